@@ -0,0 +1,574 @@
+use std::borrow::Cow;
+
+use egui::epaint::Primitive;
+
+/// Samples on each side of the center in each direction. Must match `MAX_SAMPLES` in the shader.
+const MAX_SAMPLES: f32 = 24.0;
+
+/// Uniform buffer for one draw call of the blur shader.
+#[derive(Clone, Copy, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct BlurUniforms {
+    screen_size: [f32; 2],
+    step: [f32; 2],
+    rect: [f32; 4],
+    sample_rect: [f32; 4],
+    mask_rect: [f32; 4],
+    mask_rounding: [f32; 4],
+    sigma: f32,
+    _padding: [f32; 3],
+}
+
+/// A rectangle in whole physical pixels.
+#[derive(Clone, Copy, Debug)]
+struct PixelRect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl PixelRect {
+    /// The pixels touched by `rect`, which must be within the screen.
+    fn covering(rect: egui::Rect) -> Self {
+        let (min, max) = (rect.min.floor(), rect.max.ceil());
+        Self {
+            x: min.x as u32,
+            y: min.y as u32,
+            width: (max.x - min.x) as u32,
+            height: (max.y - min.y) as u32,
+        }
+    }
+}
+
+/// What to do for one [`egui::BackdropBlur`].
+struct BlurJob {
+    /// What is copied from the offscreen image, and blurred horizontally.
+    sample_rect: PixelRect,
+
+    /// Where the result is painted.
+    paint_rect: PixelRect,
+
+    /// Offsets into the uniform buffer for the horizontal and the vertical pass.
+    uniform_offsets: [u32; 2],
+}
+
+/// The offscreen image, and the textures the blur goes through.
+struct Textures {
+    size: [u32; 2],
+
+    /// Where everything is painted when there are blurs, before going on the screen.
+    scene: wgpu::Texture,
+    scene_view: wgpu::TextureView,
+    scene_bind_group: wgpu::BindGroup,
+
+    /// What was behind the blur.
+    backdrop: wgpu::Texture,
+    backdrop_bind_group: wgpu::BindGroup,
+
+    /// That blurred horizontally.
+    horizontal_view: wgpu::TextureView,
+    horizontal_bind_group: wgpu::BindGroup,
+}
+
+/// Paints [`egui::BackdropBlur`]s, by blurring what has already been painted.
+///
+/// Since the target of [`crate::renderer::RenderPass::execute`] can't be read from,
+/// everything is painted to an offscreen image first when there are any blurs,
+/// which is then painted onto the target.
+///
+/// For each blur, the part of the offscreen image that is needed is copied to a texture,
+/// blurred horizontally into another texture,
+/// and then blurred vertically back onto the offscreen image, within the rounded rectangle.
+pub(crate) struct BackdropBlurPass {
+    format: wgpu::TextureFormat,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+
+    /// Blurs horizontally, replacing what is in the target.
+    blur_pipeline: wgpu::RenderPipeline,
+
+    /// Blurs vertically, blending with what is in the target.
+    blend_blur_pipeline: wgpu::RenderPipeline,
+
+    /// Puts the offscreen image on the target.
+    present_pipeline: wgpu::RenderPipeline,
+
+    uniform_buffer: wgpu::Buffer,
+    uniform_buffer_size: u64,
+    uniform_stride: u64,
+
+    textures: Option<Textures>,
+
+    /// One for each [`Primitive::BackdropBlur`] of the frame, in order.
+    /// `None` for the ones that paint nothing.
+    jobs: Vec<Option<BlurJob>>,
+}
+
+impl BackdropBlurPass {
+    pub(crate) fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("egui_backdrop_blur_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("backdrop_blur.wgsl"))),
+        });
+
+        let uniforms_size = std::mem::size_of::<BlurUniforms>() as u64;
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("egui_backdrop_blur_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(uniforms_size),
+                        ty: wgpu::BufferBindingType::Uniform,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("egui_backdrop_blur_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // The same blending as for the egui meshes:
+        let premultiplied_alpha = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+        };
+
+        let create_render_pipeline = |label, fragment_entry_point, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: fragment_entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+        let blur_pipeline = create_render_pipeline("egui_backdrop_blur_pipeline", "fs_blur", None);
+        let blend_blur_pipeline = create_render_pipeline(
+            "egui_backdrop_blur_blend_pipeline",
+            "fs_blur",
+            Some(premultiplied_alpha),
+        );
+        let present_pipeline = create_render_pipeline(
+            "egui_backdrop_blur_present_pipeline",
+            "fs_copy",
+            Some(premultiplied_alpha),
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("egui_backdrop_blur_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let uniform_stride = (uniforms_size + alignment - 1) / alignment * alignment;
+        let uniform_buffer_size = 3 * uniform_stride;
+        let uniform_buffer = Self::create_uniform_buffer(device, uniform_buffer_size);
+
+        Self {
+            format,
+            bind_group_layout,
+            sampler,
+            blur_pipeline,
+            blend_blur_pipeline,
+            present_pipeline,
+            uniform_buffer,
+            uniform_buffer_size,
+            uniform_stride,
+            textures: None,
+            jobs: Vec::new(),
+        }
+    }
+
+    fn create_uniform_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("egui_backdrop_blur_uniform_buffer"),
+            size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Are there any blurs to paint this frame?
+    pub(crate) fn is_active(&self) -> bool {
+        self.textures.is_some() && self.jobs.iter().any(Option::is_some)
+    }
+
+    /// Work out what to do for each blur of the frame, and upload it.
+    pub(crate) fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        paint_jobs: &[egui::epaint::ClippedPrimitive],
+        [width, height]: [u32; 2],
+        pixels_per_point: f32,
+    ) {
+        let screen_size = [width as f32, height as f32];
+        let screen =
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width as f32, height as f32));
+        let to_pixels = |rect: egui::Rect| {
+            egui::Rect::from_min_max(
+                (pixels_per_point * rect.min.to_vec2()).to_pos2(),
+                (pixels_per_point * rect.max.to_vec2()).to_pos2(),
+            )
+        };
+
+        // The first uniforms are for putting the offscreen image on the screen:
+        let mut uniforms = vec![BlurUniforms {
+            screen_size,
+            rect: [0.0, 0.0, screen_size[0], screen_size[1]],
+            ..Default::default()
+        }];
+
+        self.jobs.clear();
+        for egui::ClippedPrimitive {
+            clip_rect,
+            primitive,
+            ..
+        } in paint_jobs
+        {
+            let blur = match primitive {
+                Primitive::BackdropBlur(blur) => blur,
+                _ => continue,
+            };
+
+            let rect = to_pixels(blur.rect);
+            // Clip to whole pixels, like the meshes are:
+            let clip_rect = to_pixels(*clip_rect);
+            let clip_rect = egui::Rect::from_min_max(clip_rect.min.round(), clip_rect.max.round());
+            let paint_rect = rect.intersect(clip_rect).intersect(screen);
+
+            let sigma = 0.5 * blur.blur * pixels_per_point;
+            if sigma < 0.1 || !paint_rect.is_positive() {
+                self.jobs.push(None);
+                continue;
+            }
+
+            // Everything we need to sample, in whole pixels:
+            let sample_rect = paint_rect.expand(3.0 * sigma).intersect(screen);
+            let sample_rect =
+                egui::Rect::from_min_max(sample_rect.min.floor(), sample_rect.max.ceil());
+            let paint_rect =
+                egui::Rect::from_min_max(paint_rect.min.floor(), paint_rect.max.ceil());
+
+            // Large blurs skip pixels, so we don't need too many samples:
+            let (step, sigma_in_samples) = if 3.0 * sigma <= MAX_SAMPLES {
+                (1.0, sigma)
+            } else {
+                (3.0 * sigma / MAX_SAMPLES, MAX_SAMPLES / 3.0)
+            };
+
+            // Only sample the centers of the pixels we copied:
+            let inner = sample_rect.shrink(0.5);
+            let inner = [inner.min.x, inner.min.y, inner.max.x, inner.max.y];
+
+            // Blur horizontally everywhere we will sample next, without any rounded rectangle yet:
+            let no_mask = sample_rect.expand(1.0);
+            uniforms.push(BlurUniforms {
+                screen_size,
+                step: [step, 0.0],
+                rect: [
+                    sample_rect.min.x,
+                    sample_rect.min.y,
+                    sample_rect.max.x,
+                    sample_rect.max.y,
+                ],
+                sample_rect: inner,
+                mask_rect: [
+                    no_mask.center().x,
+                    no_mask.center().y,
+                    0.5 * no_mask.width(),
+                    0.5 * no_mask.height(),
+                ],
+                mask_rounding: [0.0; 4],
+                sigma: sigma_in_samples,
+                _padding: [0.0; 3],
+            });
+
+            // Then vertically, within the rounded rectangle:
+            let egui::epaint::Rounding { nw, ne, sw, se } = blur.rounding;
+            uniforms.push(BlurUniforms {
+                screen_size,
+                step: [0.0, step],
+                rect: [
+                    paint_rect.min.x,
+                    paint_rect.min.y,
+                    paint_rect.max.x,
+                    paint_rect.max.y,
+                ],
+                sample_rect: inner,
+                mask_rect: [
+                    rect.center().x,
+                    rect.center().y,
+                    0.5 * rect.width(),
+                    0.5 * rect.height(),
+                ],
+                mask_rounding: [nw, ne, sw, se].map(|radius| pixels_per_point * radius),
+                sigma: sigma_in_samples,
+                _padding: [0.0; 3],
+            });
+
+            let offset = |index: usize| (index as u64 * self.uniform_stride) as u32;
+            self.jobs.push(Some(BlurJob {
+                sample_rect: PixelRect::covering(sample_rect),
+                paint_rect: PixelRect::covering(paint_rect),
+                uniform_offsets: [offset(uniforms.len() - 2), offset(uniforms.len() - 1)],
+            }));
+        }
+
+        if !self.jobs.iter().any(Option::is_some) {
+            return;
+        }
+
+        let mut data = vec![0_u8; uniforms.len() * self.uniform_stride as usize];
+        for (chunk, uniforms) in data
+            .chunks_exact_mut(self.uniform_stride as usize)
+            .zip(&uniforms)
+        {
+            let bytes = bytemuck::bytes_of(uniforms);
+            chunk[..bytes.len()].copy_from_slice(bytes);
+        }
+        if data.len() as u64 > self.uniform_buffer_size {
+            self.uniform_buffer_size = data.len() as u64;
+            self.uniform_buffer = Self::create_uniform_buffer(device, self.uniform_buffer_size);
+            // The bind groups refer to the old buffer:
+            self.textures = None;
+        }
+        queue.write_buffer(&self.uniform_buffer, 0, &data);
+
+        if self
+            .textures
+            .as_ref()
+            .map_or(true, |textures| textures.size != [width, height])
+        {
+            self.textures = Some(self.create_textures(device, [width, height]));
+        }
+    }
+
+    fn create_textures(&self, device: &wgpu::Device, [width, height]: [u32; 2]) -> Textures {
+        let create_texture = |label, usage| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.format,
+                usage: usage | wgpu::TextureUsages::TEXTURE_BINDING,
+            })
+        };
+        let create_bind_group = |label, view: &wgpu::TextureView| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(label),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer: &self.uniform_buffer,
+                            offset: 0,
+                            size: wgpu::BufferSize::new(std::mem::size_of::<BlurUniforms>() as u64),
+                        }),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            })
+        };
+
+        let scene = create_texture(
+            "egui_backdrop_blur_scene",
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        );
+        let scene_view = scene.create_view(&wgpu::TextureViewDescriptor::default());
+        let scene_bind_group =
+            create_bind_group("egui_backdrop_blur_scene_bind_group", &scene_view);
+
+        let backdrop = create_texture("egui_backdrop_blur_backdrop", wgpu::TextureUsages::COPY_DST);
+        let backdrop_bind_group = create_bind_group(
+            "egui_backdrop_blur_backdrop_bind_group",
+            &backdrop.create_view(&wgpu::TextureViewDescriptor::default()),
+        );
+
+        let horizontal = create_texture(
+            "egui_backdrop_blur_horizontal",
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let horizontal_view = horizontal.create_view(&wgpu::TextureViewDescriptor::default());
+        let horizontal_bind_group =
+            create_bind_group("egui_backdrop_blur_horizontal_bind_group", &horizontal_view);
+
+        Textures {
+            size: [width, height],
+            scene,
+            scene_view,
+            scene_bind_group,
+            backdrop,
+            backdrop_bind_group,
+            horizontal_view,
+            horizontal_bind_group,
+        }
+    }
+
+    /// Where to paint everything before going on the screen.
+    ///
+    /// Only call this if [`Self::is_active`].
+    pub(crate) fn scene_view(&self) -> &wgpu::TextureView {
+        &self.textures.as_ref().unwrap().scene_view
+    }
+
+    /// Blur the offscreen image for the `index`:th [`Primitive::BackdropBlur`] of the frame.
+    pub(crate) fn blur(&self, encoder: &mut wgpu::CommandEncoder, index: usize) {
+        let (textures, job) = match (&self.textures, self.jobs.get(index)) {
+            (Some(textures), Some(Some(job))) => (textures, job),
+            _ => return,
+        };
+
+        // Copy what is behind the blur:
+        let PixelRect {
+            x,
+            y,
+            width,
+            height,
+        } = job.sample_rect;
+        let origin = wgpu::Origin3d { x, y, z: 0 };
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                texture: &textures.scene,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyTexture {
+                texture: &textures.backdrop,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        let passes = [
+            (
+                &textures.horizontal_view,
+                &self.blur_pipeline,
+                &textures.backdrop_bind_group,
+                job.sample_rect,
+            ),
+            (
+                &textures.scene_view,
+                &self.blend_blur_pipeline,
+                &textures.horizontal_bind_group,
+                job.paint_rect,
+            ),
+        ];
+        for ((view, pipeline, bind_group, rect), uniform_offset) in
+            passes.into_iter().zip(job.uniform_offsets)
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                label: Some("egui backdrop blur render pass"),
+            });
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, bind_group, &[uniform_offset]);
+            rpass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+            rpass.draw(0..6, 0..1);
+        }
+    }
+
+    /// Paint the offscreen image onto `color_attachment`.
+    pub(crate) fn present(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) {
+        let textures = match &self.textures {
+            Some(textures) => textures,
+            None => return,
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_attachment,
+                resolve_target: None,
+                ops: wgpu::Operations { load, store: true },
+            })],
+            depth_stencil_attachment: None,
+            label: Some("egui backdrop blur present render pass"),
+        });
+        rpass.set_pipeline(&self.present_pipeline);
+        rpass.set_bind_group(0, &textures.scene_bind_group, &[0]);
+        rpass.draw(0..6, 0..1);
+    }
+}
@@ -0,0 +1,87 @@
+// Blurs what has already been painted, for `epaint::BackdropBlur`.
+// Everything is in physical pixels from the top left.
+
+struct VertexOutput {
+    @location(0) pos: vec2<f32>,
+    @builtin(position) position: vec4<f32>,
+};
+
+struct Locals {
+    screen_size: vec2<f32>,
+    // From one sample to the next:
+    step: vec2<f32>,
+    // Where to paint: min x, min y, max x, max y
+    rect: vec4<f32>,
+    // The pixels we may sample: min x, min y, max x, max y
+    sample_rect: vec4<f32>,
+    // The rounded rectangle to paint: center x, center y, half width, half height
+    mask_rect: vec4<f32>,
+    // Corner radii: top left, top right, bottom left, bottom right
+    mask_rounding: vec4<f32>,
+    // Standard deviation of the gaussian, in samples
+    sigma: f32,
+    _padding: vec3<f32>,
+};
+@group(0) @binding(0) var<uniform> r_locals: Locals;
+@group(0) @binding(1) var r_tex_color: texture_2d<f32>;
+@group(0) @binding(2) var r_tex_sampler: sampler;
+
+// Samples on each side of the center. Must match `MAX_SAMPLES` in `backdrop_blur.rs`.
+let MAX_SAMPLES: i32 = 24;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Two triangles covering `rect`:
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    var out: VertexOutput;
+    out.pos = mix(r_locals.rect.xy, r_locals.rect.zw, corner);
+    out.position = vec4<f32>(
+        2.0 * out.pos.x / r_locals.screen_size.x - 1.0,
+        1.0 - 2.0 * out.pos.y / r_locals.screen_size.y,
+        0.0,
+        1.0,
+    );
+    return out;
+}
+
+// How much of the pixel at `pos` is inside the rounded rectangle.
+fn mask(pos: vec2<f32>) -> f32 {
+    let p = pos - r_locals.mask_rect.xy;
+    let rounding = r_locals.mask_rounding;
+    let radius = select(
+        select(rounding.w, rounding.z, p.x < 0.0),
+        select(rounding.y, rounding.x, p.x < 0.0),
+        p.y < 0.0,
+    );
+    let q = abs(p) - r_locals.mask_rect.zw + radius;
+    let distance = min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - radius;
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    var sum = vec4<f32>(0.0);
+    var weight_sum = 0.0;
+    for (var i = -MAX_SAMPLES; i <= MAX_SAMPLES; i = i + 1) {
+        let x = f32(i);
+        let weight = exp(-0.5 * x * x / (r_locals.sigma * r_locals.sigma));
+        let pos = clamp(in.pos + x * r_locals.step, r_locals.sample_rect.xy, r_locals.sample_rect.zw);
+        sum = sum + weight * textureSampleLevel(r_tex_color, r_tex_sampler, pos / r_locals.screen_size, 0.0);
+        weight_sum = weight_sum + weight;
+    }
+    return mask(in.pos) * sum / weight_sum;
+}
+
+// Paints the whole texture, for putting the offscreen image on the screen.
+@fragment
+fn fs_copy(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSampleLevel(r_tex_color, r_tex_sampler, in.pos / r_locals.screen_size, 0.0);
+}
@@ -78,3 +78,15 @@ fn vs_conv_main(
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     return in.color * textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
 }
+
+// For textures that are signed distance fields (`epaint::FontImage::sdf`):
+// the distance is in alpha, with the edge at 0.5, and the color is not premultiplied.
+// The distance texels are white. Color glyphs (e.g. emoji) have a blue below 1.0, and are painted as they are.
+@fragment
+fn fs_main_sdf(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
+    let half_width = max(0.5 * fwidth(texel.a), 0.0001);
+    let is_color = texel.b < 0.999;
+    let coverage = select(smoothstep(0.5 - half_width, 0.5 + half_width, texel.a), texel.a, is_color);
+    return in.color * vec4<f32>(texel.rgb * coverage, coverage);
+}
@@ -8,6 +8,9 @@
 
 pub use wgpu;
 
+mod backdrop_blur;
+mod mipmaps;
+
 /// Low-level painting of [`egui`] on [`wgpu`].
 pub mod renderer;
 pub use renderer::CallbackFn;
@@ -1,12 +1,18 @@
 #![allow(unsafe_code)]
 
-use std::{borrow::Cow, collections::HashMap, num::NonZeroU32};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+};
 
 use egui::{epaint::Primitive, NumExt, PaintCallbackInfo};
 use type_map::concurrent::TypeMap;
 use wgpu;
 use wgpu::util::DeviceExt as _;
 
+use crate::{backdrop_blur::BackdropBlurPass, mipmaps::MipmapGenerator};
+
 /// A callback function that can be used to compose an [`egui::PaintCallback`] for custom WGPU
 /// rendering.
 ///
@@ -120,7 +126,8 @@ struct SizedBuffer {
 
 /// Render pass to render a egui based GUI.
 pub struct RenderPass {
-    render_pipeline: wgpu::RenderPipeline,
+    /// For each [`egui::BlendMode`], and whether the texture is a signed distance field.
+    render_pipelines: HashMap<(egui::BlendMode, bool), wgpu::RenderPipeline>,
     index_buffers: Vec<SizedBuffer>,
     vertex_buffers: Vec<SizedBuffer>,
     uniform_buffer: SizedBuffer,
@@ -130,7 +137,14 @@ pub struct RenderPass {
     /// sampler). The texture may be None if the TextureId is just a handle to a user-provided
     /// sampler.
     textures: HashMap<egui::TextureId, (Option<wgpu::Texture>, wgpu::BindGroup)>,
+    /// Textures that are signed distance fields (see [`egui::FontImage::sdf`]).
+    sdf_textures: HashSet<egui::TextureId>,
+    /// How many mip levels the textures with mipmaps have (see [`egui::TextureOptions::mipmap_mode`]).
+    mip_level_counts: HashMap<egui::TextureId, u32>,
+    mipmap_generator: MipmapGenerator,
     next_user_texture_id: u64,
+    /// For [`egui::BackdropBlur`]. Not supported with multisampling.
+    backdrop_blur: Option<BackdropBlurPass>,
     /// Storage for use by [`egui::PaintCallback`]'s that need to store resources such as render
     /// pipelines that must have the lifetime of the renderpass.
     pub paint_callback_resources: TypeMap,
@@ -221,78 +235,93 @@ impl RenderPass {
             push_constant_ranges: &[],
         });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("egui_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                entry_point: if output_format.describe().srgb {
-                    "vs_main"
-                } else {
-                    "vs_conv_main"
+        let create_render_pipeline = |label, fragment_entry_point, blend_mode| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    entry_point: if output_format.describe().srgb {
+                        "vs_main"
+                    } else {
+                        "vs_conv_main"
+                    },
+                    module: &module,
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: 5 * 4,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        // 0: vec2 position
+                        // 1: vec2 texture coordinates
+                        // 2: uint color
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Uint32],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    unclipped_depth: false,
+                    conservative: false,
+                    cull_mode: None,
+                    front_face: wgpu::FrontFace::default(),
+                    polygon_mode: wgpu::PolygonMode::default(),
+                    strip_index_format: None,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    alpha_to_coverage_enabled: false,
+                    count: msaa_samples,
+                    mask: !0,
                 },
-                module: &module,
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: 5 * 4,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    // 0: vec2 position
-                    // 1: vec2 texture coordinates
-                    // 2: uint color
-                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Uint32],
-                }],
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                unclipped_depth: false,
-                conservative: false,
-                cull_mode: None,
-                front_face: wgpu::FrontFace::default(),
-                polygon_mode: wgpu::PolygonMode::default(),
-                strip_index_format: None,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                alpha_to_coverage_enabled: false,
-                count: msaa_samples,
-                mask: !0,
-            },
 
-            fragment: Some(wgpu::FragmentState {
-                module: &module,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: output_format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            multiview: None,
-        });
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: fragment_entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_format,
+                        blend: Some(blend_state(blend_mode)),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+        let render_pipelines = egui::BlendMode::ALL
+            .into_iter()
+            .flat_map(|blend_mode| {
+                [
+                    (
+                        (blend_mode, false),
+                        create_render_pipeline("egui_pipeline", "fs_main", blend_mode),
+                    ),
+                    (
+                        (blend_mode, true),
+                        create_render_pipeline("egui_sdf_pipeline", "fs_main_sdf", blend_mode),
+                    ),
+                ]
+            })
+            .collect();
 
         Self {
-            render_pipeline,
+            render_pipelines,
             vertex_buffers: Vec::with_capacity(64),
             index_buffers: Vec::with_capacity(64),
             uniform_buffer,
             uniform_bind_group,
             texture_bind_group_layout,
             textures: HashMap::new(),
+            sdf_textures: HashSet::new(),
+            mip_level_counts: HashMap::new(),
+            mipmap_generator: MipmapGenerator::new(device, wgpu::TextureFormat::Rgba8UnormSrgb),
             next_user_texture_id: 0,
+            backdrop_blur: (msaa_samples == 1)
+                .then(|| BackdropBlurPass::new(device, output_format)),
             paint_callback_resources: TypeMap::default(),
         }
     }
 
     /// Executes the egui render pass.
+    ///
+    /// If there are any [`egui::BackdropBlur`]s, everything is first painted to an offscreen image,
+    /// which is then painted onto `color_attachment`. Without a `clear_color`, the blurs
+    /// then only blur what egui paints, and not what is already in `color_attachment`.
     pub fn execute(
         &self,
         encoder: &mut wgpu::CommandEncoder,
@@ -301,6 +330,20 @@ impl RenderPass {
         screen_descriptor: &ScreenDescriptor,
         clear_color: Option<wgpu::Color>,
     ) {
+        if let Some(backdrop_blur) = &self.backdrop_blur {
+            if backdrop_blur.is_active() {
+                self.execute_with_backdrop_blur(
+                    backdrop_blur,
+                    encoder,
+                    color_attachment,
+                    paint_jobs,
+                    screen_descriptor,
+                    clear_color,
+                );
+                return;
+            }
+        }
+
         let load_operation = if let Some(color) = clear_color {
             wgpu::LoadOp::Clear(color)
         } else {
@@ -326,12 +369,83 @@ impl RenderPass {
         rpass.pop_debug_group();
     }
 
+    /// Paints into the offscreen image of the [`BackdropBlurPass`],
+    /// with a new render pass after each blur.
+    fn execute_with_backdrop_blur(
+        &self,
+        backdrop_blur: &BackdropBlurPass,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        paint_jobs: &[egui::epaint::ClippedPrimitive],
+        screen_descriptor: &ScreenDescriptor,
+        clear_color: Option<wgpu::Color>,
+    ) {
+        let mut load_operation =
+            wgpu::LoadOp::Clear(clear_color.unwrap_or(wgpu::Color::TRANSPARENT));
+        let mut first_mesh = 0;
+        let mut blur_index = 0;
+
+        for paint_jobs in
+            paint_jobs.split_inclusive(|job| matches!(job.primitive, Primitive::BackdropBlur(_)))
+        {
+            {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: backdrop_blur.scene_view(),
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: load_operation,
+                            store: true,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    label: Some("egui main render pass"),
+                });
+                rpass.push_debug_group("egui_pass");
+                self.paint_primitives(&mut rpass, paint_jobs, first_mesh, screen_descriptor);
+                rpass.pop_debug_group();
+            }
+            load_operation = wgpu::LoadOp::Load;
+            first_mesh += paint_jobs
+                .iter()
+                .filter(|job| matches!(job.primitive, Primitive::Mesh(_)))
+                .count();
+
+            if let Some(Primitive::BackdropBlur(_)) = paint_jobs.last().map(|job| &job.primitive) {
+                backdrop_blur.blur(encoder, blur_index);
+                blur_index += 1;
+            }
+        }
+
+        // The offscreen image already has the clear color:
+        let load_operation = if clear_color.is_some() {
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+        } else {
+            wgpu::LoadOp::Load
+        };
+        backdrop_blur.present(encoder, color_attachment, load_operation);
+    }
+
     /// Executes the egui render pass onto an existing wgpu renderpass.
+    ///
+    /// [`egui::BackdropBlur`]s are not painted, since that needs more than one render pass.
     pub fn execute_with_renderpass<'rpass>(
         &'rpass self,
         rpass: &mut wgpu::RenderPass<'rpass>,
         paint_jobs: &[egui::epaint::ClippedPrimitive],
         screen_descriptor: &ScreenDescriptor,
+    ) {
+        self.paint_primitives(rpass, paint_jobs, 0, screen_descriptor);
+    }
+
+    /// Paints `paint_jobs`, the first mesh of which has the index `first_mesh` among the meshes
+    /// given to [`Self::update_buffers`].
+    fn paint_primitives<'rpass>(
+        &'rpass self,
+        rpass: &mut wgpu::RenderPass<'rpass>,
+        paint_jobs: &[egui::epaint::ClippedPrimitive],
+        first_mesh: usize,
+        screen_descriptor: &ScreenDescriptor,
     ) {
         let pixels_per_point = screen_descriptor.pixels_per_point;
         let size_in_pixels = screen_descriptor.size_in_pixels;
@@ -340,12 +454,16 @@ impl RenderPass {
         // run.
         let mut needs_reset = true;
 
-        let mut index_buffers = self.index_buffers.iter();
-        let mut vertex_buffers = self.vertex_buffers.iter();
+        // The blend mode of the pipeline that is set, and whether it is the one for signed distance fields.
+        let mut current_pipeline = (egui::BlendMode::Normal, false);
+
+        let mut index_buffers = self.index_buffers[first_mesh..].iter();
+        let mut vertex_buffers = self.vertex_buffers[first_mesh..].iter();
 
         for egui::ClippedPrimitive {
             clip_rect,
             primitive,
+            blend_mode,
         } in paint_jobs
         {
             if needs_reset {
@@ -357,7 +475,8 @@ impl RenderPass {
                     0.0,
                     1.0,
                 );
-                rpass.set_pipeline(&self.render_pipeline);
+                current_pipeline = (egui::BlendMode::Normal, false);
+                rpass.set_pipeline(&self.render_pipelines[&current_pipeline]);
                 rpass.set_bind_group(0, &self.uniform_bind_group, &[]);
                 needs_reset = false;
             }
@@ -384,6 +503,11 @@ impl RenderPass {
                     let vertex_buffer = vertex_buffers.next().unwrap();
 
                     if let Some((_texture, bind_group)) = self.textures.get(&mesh.texture_id) {
+                        let pipeline = (*blend_mode, self.sdf_textures.contains(&mesh.texture_id));
+                        if pipeline != current_pipeline {
+                            rpass.set_pipeline(&self.render_pipelines[&pipeline]);
+                            current_pipeline = pipeline;
+                        }
                         rpass.set_bind_group(1, bind_group, &[]);
                         rpass.set_index_buffer(
                             index_buffer.buffer.slice(..),
@@ -441,6 +565,9 @@ impl RenderPass {
                         );
                     }
                 }
+                Primitive::BackdropBlur(_) => {
+                    // Painted between render passes by `execute`.
+                }
             }
         }
 
@@ -466,6 +593,7 @@ impl RenderPass {
 
         let data_color32 = match &image_delta.image {
             egui::ImageData::Color(image) => {
+                self.sdf_textures.remove(&id);
                 assert_eq!(
                     width as usize * height as usize,
                     image.pixels.len(),
@@ -479,6 +607,11 @@ impl RenderPass {
                     image.pixels.len(),
                     "Mismatch between texture size and texel count"
                 );
+                if image.sdf {
+                    self.sdf_textures.insert(id);
+                } else {
+                    self.sdf_textures.remove(&id);
+                }
                 Cow::Owned(image.srgba_pixels(1.0).collect::<Vec<_>>())
             }
         };
@@ -513,29 +646,50 @@ impl RenderPass {
                 y: pos[1] as u32,
                 z: 0,
             };
-            queue_write_data_to_texture(
-                texture.as_ref().expect("Tried to update user texture."),
-                origin,
-            );
+            let texture = texture.as_ref().expect("Tried to update user texture.");
+            queue_write_data_to_texture(texture, origin);
+            if let Some(&mip_level_count) = self.mip_level_counts.get(&id) {
+                self.mipmap_generator
+                    .generate(device, queue, texture, mip_level_count);
+            }
         } else {
             // allocate a new texture
+            let options = image_delta.options;
+            let mip_level_count = if options.mipmap_mode.is_some() {
+                MipmapGenerator::mip_level_count(size)
+            } else {
+                1
+            };
+            let mut usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+            if mip_level_count > 1 {
+                // The mip levels are rendered from each other:
+                usage |= wgpu::TextureUsages::RENDER_ATTACHMENT;
+            }
             let texture = device.create_texture(&wgpu::TextureDescriptor {
                 label: None,
                 size,
-                mip_level_count: 1,
+                mip_level_count,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
                 format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                usage,
             });
-            let filter = match image_delta.filter {
+            let filter_mode = |filter| match filter {
                 egui::TextureFilter::Nearest => wgpu::FilterMode::Nearest,
                 egui::TextureFilter::Linear => wgpu::FilterMode::Linear,
             };
+            let address_mode = match options.wrap_mode {
+                egui::TextureWrapMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+                egui::TextureWrapMode::Repeat => wgpu::AddressMode::Repeat,
+                egui::TextureWrapMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+            };
             let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
                 label: None,
-                mag_filter: filter,
-                min_filter: filter,
+                address_mode_u: address_mode,
+                address_mode_v: address_mode,
+                mag_filter: filter_mode(options.magnification),
+                min_filter: filter_mode(options.minification),
+                mipmap_filter: filter_mode(options.mipmap_mode.unwrap_or_default()),
                 ..Default::default()
             });
             let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -556,11 +710,20 @@ impl RenderPass {
             });
             let origin = wgpu::Origin3d::ZERO;
             queue_write_data_to_texture(&texture, origin);
+            if mip_level_count > 1 {
+                self.mipmap_generator
+                    .generate(device, queue, &texture, mip_level_count);
+                self.mip_level_counts.insert(id, mip_level_count);
+            } else {
+                self.mip_level_counts.remove(&id);
+            }
             self.textures.insert(id, (Some(texture), bind_group));
         };
     }
 
     pub fn free_texture(&mut self, id: &egui::TextureId) {
+        self.sdf_textures.remove(id);
+        self.mip_level_counts.remove(id);
         self.textures.remove(id);
     }
 
@@ -724,8 +887,19 @@ impl RenderPass {
 
                     (cbfn.prepare)(device, queue, &mut self.paint_callback_resources);
                 }
+                Primitive::BackdropBlur(_) => {}
             }
         }
+
+        if let Some(backdrop_blur) = &mut self.backdrop_blur {
+            backdrop_blur.prepare(
+                device,
+                queue,
+                paint_jobs,
+                screen_descriptor.size_in_pixels,
+                screen_descriptor.pixels_per_point,
+            );
+        }
     }
 
     /// Updates the buffers used by egui. Will properly re-size the buffers if needed.
@@ -808,6 +982,28 @@ impl ScissorRect {
     }
 }
 
+/// egui outputs colors with premultiplied alpha.
+fn blend_state(blend_mode: egui::BlendMode) -> wgpu::BlendState {
+    let (src_factor, dst_factor) = match blend_mode {
+        egui::BlendMode::Normal => (wgpu::BlendFactor::One, wgpu::BlendFactor::OneMinusSrcAlpha),
+        egui::BlendMode::Multiply => (wgpu::BlendFactor::Dst, wgpu::BlendFactor::OneMinusSrcAlpha),
+        egui::BlendMode::Screen => (wgpu::BlendFactor::OneMinusDst, wgpu::BlendFactor::One),
+        egui::BlendMode::Additive => (wgpu::BlendFactor::One, wgpu::BlendFactor::One),
+    };
+    wgpu::BlendState {
+        color: wgpu::BlendComponent {
+            src_factor,
+            dst_factor,
+            operation: wgpu::BlendOperation::Add,
+        },
+        alpha: wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        },
+    }
+}
+
 #[test]
 fn render_pass_impl_send_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
@@ -306,7 +306,7 @@ impl Prepared {
             bounds.max.at_least(self.state.pos + Vec2::splat(32.0)),
         );
 
-        let shadow_radius = ctx.style().visuals.window_shadow.extrusion; // hacky
+        let shadow_radius = ctx.style().visuals.window_shadow.margin(); // hacky
         let clip_rect_margin = ctx.style().visuals.clip_rect_margin.max(shadow_radius);
 
         let clip_rect = Rect::from_min_max(self.state.pos, bounds.max)
@@ -356,7 +356,7 @@ impl Prepared {
         );
 
         if move_response.dragged() && movable {
-            state.pos += ctx.input().pointer.delta();
+            state.pos += move_response.drag_delta();
         }
 
         // Important check - don't try to move e.g. a combobox popup!
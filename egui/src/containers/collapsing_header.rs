@@ -549,13 +549,8 @@ impl CollapsingHeader {
             let visuals = ui.style().interact_selectable(&header_response, selected);
 
             if ui.visuals().collapsing_header_frame || show_background {
-                ui.painter().add(epaint::RectShape {
-                    rect: header_response.rect.expand(visuals.expansion),
-                    rounding: visuals.rounding,
-                    fill: visuals.bg_fill,
-                    stroke: visuals.bg_stroke,
-                    // stroke: Default::default(),
-                });
+                ui.painter()
+                    .add(visuals.bg_shape(header_response.rect.expand(visuals.expansion)));
             }
 
             if selected || selectable && (header_response.hovered() || header_response.has_focus())
@@ -302,12 +302,7 @@ fn button_frame(
 
         ui.painter().set(
             where_to_put_background,
-            epaint::RectShape {
-                rect: outer_rect.expand(visuals.expansion),
-                rounding: visuals.rounding,
-                fill: visuals.bg_fill,
-                stroke: visuals.bg_stroke,
-            },
+            visuals.bg_shape(outer_rect.expand(visuals.expansion)),
         );
     }
 
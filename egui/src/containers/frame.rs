@@ -23,8 +23,18 @@ pub struct Frame {
     pub outer_margin: Margin,
     pub rounding: Rounding,
     pub shadow: Shadow,
-    pub fill: Color32,
+
+    /// Blur what is behind the frame by this much (see [`BackdropBlur`]).
+    pub backdrop_blur: f32,
+
+    pub fill: Brush,
     pub stroke: Stroke,
+
+    /// If set, the frame is painted with this texture instead of [`Self::fill`] and [`Self::rounding`].
+    ///
+    /// The nine-slice is always a rectangle, so any rounded corners must be in the texture.
+    /// [`Self::rounding`] is then only used for the [`Self::shadow`] and the [`Self::backdrop_blur`].
+    pub nine_slice: Option<NineSlice>,
 }
 
 impl Frame {
@@ -46,8 +56,9 @@ impl Frame {
         Self {
             inner_margin: Margin::symmetric(8.0, 2.0),
             rounding: Rounding::none(),
-            fill: style.visuals.window_fill(),
+            fill: style.visuals.window_brush(),
             stroke: style.visuals.window_stroke(),
+            nine_slice: style.visuals.widgets.noninteractive.bg_nine_slice,
             ..Default::default()
         }
     }
@@ -56,8 +67,9 @@ impl Frame {
         Self {
             inner_margin: Margin::same(8.0),
             rounding: Rounding::none(),
-            fill: style.visuals.window_fill(),
+            fill: style.visuals.window_brush(),
             stroke: Default::default(),
+            nine_slice: style.visuals.widgets.noninteractive.bg_nine_slice,
             ..Default::default()
         }
     }
@@ -67,8 +79,9 @@ impl Frame {
             inner_margin: style.spacing.window_margin,
             rounding: style.visuals.window_rounding,
             shadow: style.visuals.window_shadow,
-            fill: style.visuals.window_fill(),
+            fill: style.visuals.window_brush(),
             stroke: style.visuals.window_stroke(),
+            nine_slice: style.visuals.widgets.noninteractive.bg_nine_slice,
             ..Default::default()
         }
     }
@@ -78,8 +91,9 @@ impl Frame {
             inner_margin: Margin::same(1.0),
             rounding: style.visuals.widgets.noninteractive.rounding,
             shadow: style.visuals.popup_shadow,
-            fill: style.visuals.window_fill(),
+            fill: style.visuals.window_brush(),
             stroke: style.visuals.window_stroke(),
+            nine_slice: style.visuals.widgets.noninteractive.bg_nine_slice,
             ..Default::default()
         }
     }
@@ -89,8 +103,9 @@ impl Frame {
             inner_margin: style.spacing.window_margin,
             rounding: style.visuals.widgets.noninteractive.rounding,
             shadow: style.visuals.popup_shadow,
-            fill: style.visuals.window_fill(),
+            fill: style.visuals.window_brush(),
             stroke: style.visuals.window_stroke(),
+            nine_slice: style.visuals.widgets.noninteractive.bg_nine_slice,
             ..Default::default()
         }
     }
@@ -103,7 +118,7 @@ impl Frame {
         Self {
             inner_margin: Margin::same(2.0),
             rounding: style.visuals.widgets.noninteractive.rounding,
-            fill: style.visuals.extreme_bg_color,
+            fill: style.visuals.extreme_bg_color.into(),
             stroke: style.visuals.window_stroke(),
             ..Default::default()
         }
@@ -112,15 +127,16 @@ impl Frame {
     /// A dark canvas to draw on.
     pub fn dark_canvas(style: &Style) -> Self {
         Self {
-            fill: Color32::from_black_alpha(250),
+            fill: Color32::from_black_alpha(250).into(),
             ..Self::canvas(style)
         }
     }
 }
 
 impl Frame {
-    pub fn fill(mut self, fill: Color32) -> Self {
-        self.fill = fill;
+    /// A color, or a gradient (see [`Brush`]).
+    pub fn fill(mut self, fill: impl Into<Brush>) -> Self {
+        self.fill = fill.into();
         self
     }
 
@@ -134,6 +150,15 @@ impl Frame {
         self
     }
 
+    /// Paint the frame with a texture that has a border, instead of with [`Self::fill`] and [`Self::rounding`].
+    ///
+    /// The frame is always a rectangle then, so any rounded corners must be in the texture.
+    /// The [`Self::stroke`] is still painted around it (without rounding), so you may want to remove it.
+    pub fn nine_slice(mut self, nine_slice: NineSlice) -> Self {
+        self.nine_slice = Some(nine_slice);
+        self
+    }
+
     /// Margin within the painted frame.
     pub fn inner_margin(mut self, inner_margin: impl Into<Margin>) -> Self {
         self.inner_margin = inner_margin.into();
@@ -156,10 +181,22 @@ impl Frame {
         self
     }
 
+    /// Blur what is behind the frame, for a frosted glass look.
+    ///
+    /// Use it with a semi-transparent [`Self::fill`].
+    /// The blur is done by the rendering backend (see [`BackdropBlur`]).
+    pub fn backdrop_blur(mut self, blur: f32) -> Self {
+        self.backdrop_blur = blur;
+        self
+    }
+
     pub fn multiply_with_opacity(mut self, opacity: f32) -> Self {
         self.fill = self.fill.linear_multiply(opacity);
         self.stroke.color = self.stroke.color.linear_multiply(opacity);
         self.shadow.color = self.shadow.color.linear_multiply(opacity);
+        if let Some(nine_slice) = &mut self.nine_slice {
+            nine_slice.tint = nine_slice.tint.linear_multiply(opacity);
+        }
         self
     }
 }
@@ -215,23 +252,43 @@ impl Frame {
             outer_margin: _,
             rounding,
             shadow,
+            backdrop_blur,
             fill,
             stroke,
+            nine_slice,
         } = *self;
 
-        let frame_shape = Shape::Rect(epaint::RectShape {
-            rect: outer_rect,
-            rounding,
-            fill,
-            stroke,
-        });
+        let frame_shape = if let Some(nine_slice) = nine_slice {
+            if stroke.is_empty() {
+                Shape::nine_slice(outer_rect, nine_slice)
+            } else {
+                Shape::Vec(vec![
+                    Shape::nine_slice(outer_rect, nine_slice),
+                    Shape::rect_stroke(outer_rect, 0.0, stroke),
+                ])
+            }
+        } else {
+            Shape::Rect(epaint::RectShape {
+                rect: outer_rect,
+                rounding,
+                fill,
+                stroke,
+            })
+        };
+
+        let mut shapes = vec![];
+        if shadow != Default::default() {
+            shapes.push(Shape::Mesh(shadow.tessellate(outer_rect, rounding)));
+        }
+        if backdrop_blur > 0.0 {
+            shapes.push(BackdropBlur::new(outer_rect, rounding, backdrop_blur).into());
+        }
 
-        if shadow == Default::default() {
+        if shapes.is_empty() {
             frame_shape
         } else {
-            let shadow = shadow.tessellate(outer_rect, rounding);
-            let shadow = Shape::Mesh(shadow);
-            Shape::Vec(vec![shadow, frame_shape])
+            shapes.push(frame_shape);
+            Shape::Vec(shapes)
         }
     }
 }
@@ -208,6 +208,7 @@ impl SidePanel {
                     .ctx()
                     .layer_id_at(pointer)
                     .map_or(true, |top_layer_id| top_layer_id == ui.layer_id());
+                let pointer = ui.ctx().layer_pos_from_global(ui.layer_id(), pointer);
 
                 let resize_x = side.opposite().side_x(panel_rect);
                 let mouse_over_resize_line = we_are_on_top
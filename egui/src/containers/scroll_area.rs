@@ -616,10 +616,14 @@ impl Prepared {
             let content_response = ui.interact(inner_rect, id.with("area"), sense);
 
             if content_response.dragged() {
+                let scaling = ui
+                    .ctx()
+                    .layer_transform_to_global(ui.layer_id())
+                    .map_or(1.0, |transform| transform.scaling);
                 for d in 0..2 {
                     if has_bar[d] {
-                        state.offset[d] -= ui.input().pointer.delta()[d];
-                        state.vel[d] = ui.input().pointer.velocity()[d];
+                        state.offset[d] -= content_response.drag_delta()[d];
+                        state.vel[d] = ui.input().pointer.velocity()[d] / scaling;
                         state.scroll_stuck_to_end[d] = false;
                     } else {
                         state.vel[d] = 0.0;
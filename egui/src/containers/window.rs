@@ -531,7 +531,8 @@ fn move_and_resize_window(ctx: &Context, window_interaction: &WindowInteraction)
         return None;
     }
 
-    let pointer_pos = ctx.input().pointer.interact_pos()?;
+    let layer_id = window_interaction.area_layer_id;
+    let pointer_pos = ctx.layer_pos_from_global(layer_id, ctx.input().pointer.interact_pos()?);
     let mut rect = window_interaction.start_rect; // prevent drift
 
     if window_interaction.is_resize() {
@@ -554,7 +555,8 @@ fn move_and_resize_window(ctx: &Context, window_interaction: &WindowInteraction)
         // the drag from us. It is therefor important not to move the window the first frame,
         // but instead let other widgets to the steal. HACK.
         if !ctx.input().pointer.any_pressed() {
-            let press_origin = ctx.input().pointer.press_origin()?;
+            let press_origin =
+                ctx.layer_pos_from_global(layer_id, ctx.input().pointer.press_origin()?);
             let delta = pointer_pos - press_origin;
             rect = rect.translate(delta);
         }
@@ -622,6 +624,8 @@ fn resize_hover(
         }
     }
 
+    let pointer = ctx.layer_pos_from_global(area_layer_id, pointer);
+
     if ctx.memory().interaction.drag_interest {
         // Another widget will become active if we drag here
         return None;
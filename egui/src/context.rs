@@ -5,7 +5,7 @@ use crate::{
     animation_manager::AnimationManager, data::output::PlatformOutput, frame_state::FrameState,
     input_state::*, layers::GraphicLayers, memory::Options, output::FullOutput, TextureHandle, *,
 };
-use epaint::{mutex::*, stats::*, text::Fonts, textures::TextureFilter, TessellationOptions, *};
+use epaint::{mutex::*, stats::*, text::Fonts, textures::TextureOptions, TessellationOptions, *};
 
 // ----------------------------------------------------------------------------
 
@@ -36,6 +36,10 @@ struct ContextImpl {
     animation_manager: AnimationManager,
     tex_manager: WrappedTextureManager,
 
+    /// The textures of the font atlas pages after the first one.
+    /// Allocated when the atlas first needs them, and reused when the fonts are recreated.
+    font_page_textures: Vec<TextureId>,
+
     input: InputState,
 
     /// State that is collected during a frame and then cleared
@@ -426,6 +430,11 @@ impl Context {
 
         if response.is_pointer_button_down_on {
             response.interact_pointer_pos = input.pointer.interact_pos();
+            if let Some(transform) = memory.layer_transforms.get(&layer_id) {
+                response.interact_pointer_pos = response
+                    .interact_pointer_pos
+                    .map(|pos| transform.inverse() * pos);
+            }
         }
 
         if input.pointer.any_down() {
@@ -743,12 +752,15 @@ impl Context {
     /// }
     /// ```
     ///
+    /// The `options` can be just a [`crate::TextureFilter`], or [`TextureOptions`] for tiling
+    /// the texture, or for mipmaps.
+    ///
     /// Se also [`crate::ImageData`], [`crate::Ui::image`] and [`crate::ImageButton`].
     pub fn load_texture(
         &self,
         name: impl Into<String>,
         image: impl Into<ImageData>,
-        filter: TextureFilter,
+        options: impl Into<TextureOptions>,
     ) -> TextureHandle {
         let name = name.into();
         let image = image.into();
@@ -762,7 +774,7 @@ impl Context {
             max_texture_side
         );
         let tex_mngr = self.tex_manager();
-        let tex_id = tex_mngr.write().alloc(name, image, filter);
+        let tex_id = tex_mngr.write().alloc(name, image, options.into());
         TextureHandle::new(tex_mngr, tex_id)
     }
 
@@ -827,13 +839,43 @@ impl Context {
                 .memory
                 .end_frame(&ctx_impl.input, &ctx_impl.frame_state.used_ids);
 
-            let font_image_delta = ctx_impl.fonts.as_ref().unwrap().font_image_delta();
-            if let Some(font_image_delta) = font_image_delta {
+            // Forget the transforms of layers that are no longer painted on,
+            // like the graphics layers themselves are forgotten:
+            let graphics = &ctx_impl.graphics;
+            ctx_impl
+                .memory
+                .layer_transforms
+                .retain(|layer_id, _| graphics.is_painted(*layer_id));
+
+            // Before the font textures are updated, since scaled text may need new glyphs:
+            let fonts = ctx_impl.fonts.as_ref().unwrap();
+            for (layer_id, transform) in &ctx_impl.memory.layer_transforms {
+                ctx_impl
+                    .graphics
+                    .list(*layer_id)
+                    .transform(*transform, fonts);
+            }
+
+            let tex_manager = &ctx_impl.tex_manager.0;
+            let font_page_textures = &mut ctx_impl.font_page_textures;
+            let font_image_deltas = ctx_impl.fonts.as_ref().unwrap().font_image_deltas(|page| {
+                // The first page is `TextureId::default()`, so we never get asked for it.
+                while font_page_textures.len() < page {
+                    let texture_id = tex_manager.write().alloc(
+                        format!("egui_font_texture_page_{}", font_page_textures.len() + 1),
+                        epaint::FontImage::new([1, 1]).into(),
+                        Default::default(),
+                    );
+                    font_page_textures.push(texture_id);
+                }
+                font_page_textures[page - 1]
+            });
+            for (texture_id, font_image_delta) in font_image_deltas {
                 ctx_impl
                     .tex_manager
                     .0
                     .write()
-                    .set(TextureId::default(), font_image_delta);
+                    .set(texture_id, font_image_delta);
             }
 
             textures_delta = ctx_impl.tex_manager.0.write().take_delta();
@@ -868,6 +910,21 @@ impl Context {
 
     fn drain_paint_lists(&self) -> Vec<ClippedShape> {
         let ctx_impl = &mut *self.write();
+        let memory = &ctx_impl.memory;
+        let grouped_layers: ahash::AHashSet<LayerId> = memory
+            .layer_opacities
+            .keys()
+            .chain(memory.layer_blend_modes.keys())
+            .copied()
+            .collect();
+        for layer_id in grouped_layers {
+            let opacity = memory.layer_opacities.get(&layer_id).copied();
+            let blend_mode = memory.layer_blend_modes.get(&layer_id).copied();
+            ctx_impl
+                .graphics
+                .list(layer_id)
+                .group(opacity.unwrap_or(1.0), blend_mode.unwrap_or_default());
+        }
         ctx_impl
             .graphics
             .drain(ctx_impl.memory.areas.order())
@@ -885,6 +942,7 @@ impl Context {
         let texture_atlas = self.fonts().texture_atlas();
         let font_tex_size = texture_atlas.lock().size();
         let prepared_discs = texture_atlas.lock().prepared_discs();
+        let font_atlas_stats = texture_atlas.lock().stats();
 
         let paint_stats = PaintStats::from_shapes(&shapes);
         let clipped_primitives = tessellator::tessellate_shapes(
@@ -894,7 +952,9 @@ impl Context {
             prepared_discs,
             shapes,
         );
-        self.write().paint_stats = paint_stats.with_clipped_primitives(&clipped_primitives);
+        self.write().paint_stats = paint_stats
+            .with_clipped_primitives(&clipped_primitives)
+            .with_font_atlas(font_atlas_stats);
         clipped_primitives
     }
 
@@ -996,6 +1056,82 @@ impl Context {
         }
     }
 
+    /// Scale and translate everything painted on the given layer, and the input to it.
+    ///
+    /// The transform goes from the coordinates the layer is laid out and painted in
+    /// to screen coordinates, so pointer positions are transformed the other way,
+    /// and the widgets on a zoomed or panned layer can still be interacted with.
+    ///
+    /// The transform is kept until you change it, or until a frame where nothing is painted
+    /// on the layer. Set it to [`TSTransform::IDENTITY`] to remove it.
+    /// Its [`TSTransform::scaling`] must be positive.
+    ///
+    /// This is useful for zoomable canvases, like node graphs.
+    ///
+    /// [`Response::interact_pointer_pos`], [`Response::hover_pos`] and [`Response::drag_delta`]
+    /// are in the coordinates of the layer, as are the built-in widgets and containers.
+    /// But `ctx.input().pointer` is always in screen coordinates,
+    /// so use [`Self::layer_pos_from_global`] if you read it directly.
+    pub fn set_transform_layer(&self, layer_id: LayerId, transform: TSTransform) {
+        let layer_transforms = &mut self.memory().layer_transforms;
+        if transform == TSTransform::IDENTITY {
+            layer_transforms.remove(&layer_id);
+        } else {
+            layer_transforms.insert(layer_id, transform);
+        }
+    }
+
+    /// Fade everything painted on the given layer, e.g. a whole [`Area`] or [`Window`] including its images.
+    ///
+    /// From `0.0` (invisible) to `1.0` (unchanged). The opacity is kept until you change it.
+    ///
+    /// Each shape is faded on its own (see [`GroupShape`]),
+    /// so where shapes overlap you can see a bit of the ones below.
+    pub fn set_layer_opacity(&self, layer_id: LayerId, opacity: f32) {
+        let layer_opacities = &mut self.memory().layer_opacities;
+        if opacity >= 1.0 {
+            layer_opacities.remove(&layer_id);
+        } else {
+            layer_opacities.insert(layer_id, opacity.max(0.0));
+        }
+    }
+
+    /// How everything painted on the given layer is blended with what is behind it.
+    ///
+    /// The blend mode is kept until you change it. Set it to [`BlendMode::Normal`] to remove it.
+    ///
+    /// A [`GroupShape`] on the layer with a blend mode of its own uses that one instead.
+    pub fn set_layer_blend_mode(&self, layer_id: LayerId, blend_mode: BlendMode) {
+        let layer_blend_modes = &mut self.memory().layer_blend_modes;
+        if blend_mode == BlendMode::Normal {
+            layer_blend_modes.remove(&layer_id);
+        } else {
+            layer_blend_modes.insert(layer_id, blend_mode);
+        }
+    }
+
+    /// The transform from the coordinates of the given layer to screen coordinates,
+    /// as set with [`Self::set_transform_layer`].
+    pub fn layer_transform_to_global(&self, layer_id: LayerId) -> Option<TSTransform> {
+        self.memory().layer_transforms.get(&layer_id).copied()
+    }
+
+    /// The transform from screen coordinates to the coordinates of the given layer,
+    /// i.e. the inverse of [`Self::layer_transform_to_global`].
+    pub fn layer_transform_from_global(&self, layer_id: LayerId) -> Option<TSTransform> {
+        self.layer_transform_to_global(layer_id)
+            .map(|transform| transform.inverse())
+    }
+
+    /// Transform a position in screen coordinates (like those in `ctx.input().pointer`)
+    /// to the coordinates of the given layer.
+    pub fn layer_pos_from_global(&self, layer_id: LayerId, pos: Pos2) -> Pos2 {
+        match self.layer_transform_from_global(layer_id) {
+            Some(transform) => transform * pos,
+            None => pos,
+        }
+    }
+
     /// Top-most layer at the given position.
     pub fn layer_id_at(&self, pos: Pos2) -> Option<LayerId> {
         let resize_grab_radius_side = self.style().interaction.resize_grab_radius_side;
@@ -1011,7 +1147,8 @@ impl Context {
     pub(crate) fn rect_contains_pointer(&self, layer_id: LayerId, rect: Rect) -> bool {
         let pointer_pos = self.input().pointer.interact_pos();
         if let Some(pointer_pos) = pointer_pos {
-            rect.contains(pointer_pos) && self.layer_id_at(pointer_pos) == Some(layer_id)
+            rect.contains(self.layer_pos_from_global(layer_id, pointer_pos))
+                && self.layer_id_at(pointer_pos) == Some(layer_id)
         } else {
             false
         }
@@ -1355,3 +1492,20 @@ fn context_impl_send_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
     assert_send_sync::<Context>();
 }
+
+#[test]
+fn layer_transform_is_forgotten_when_not_painted() {
+    let ctx = Context::default();
+    let layer_id = LayerId::new(Order::Foreground, Id::new("canvas"));
+    let transform = TSTransform::from_scaling(2.0);
+
+    let _ = ctx.run(RawInput::default(), |ctx| {
+        ctx.set_transform_layer(layer_id, transform);
+        ctx.layer_painter(layer_id)
+            .circle_filled(Pos2::ZERO, 1.0, Color32::RED);
+    });
+    assert_eq!(ctx.layer_transform_to_global(layer_id), Some(transform));
+
+    let _ = ctx.run(RawInput::default(), |_| {});
+    assert_eq!(ctx.layer_transform_to_global(layer_id), None);
+}
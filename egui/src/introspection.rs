@@ -97,6 +97,7 @@ impl Widget for &epaint::stats::PaintStats {
                 clipped_primitives,
                 vertices,
                 indices,
+                font_atlas,
             } = self;
 
             ui.label("Intermediate:");
@@ -124,6 +125,20 @@ impl Widget for &epaint::stats::PaintStats {
             label(ui, indices, "indices").on_hover_text("Three 32-bit indices per triangles");
             ui.add_space(10.0);
 
+            ui.label("Font atlas:");
+            ui.label(format!(
+                "{:6} / {} pages",
+                font_atlas.num_pages, font_atlas.max_pages
+            ))
+            .on_hover_text("Each page is a separate texture");
+            ui.label(format!(
+                "{:5.1}% full (current page)",
+                100.0 * font_atlas.fill_ratio
+            ));
+            ui.label(format!("{:6} evictions", font_atlas.num_evictions))
+                .on_hover_text("How many times a page was cleared to make room for new glyphs");
+            ui.add_space(10.0);
+
             // ui.label("Total:");
             // ui.label(self.total().format(""));
         })
@@ -148,6 +163,7 @@ impl Widget for &mut epaint::TessellationOptions {
                 debug_paint_text_rects,
                 debug_ignore_clip_rects,
                 bezier_tolerance,
+                ellipse_tolerance,
                 epsilon: _,
             } = self;
 
@@ -167,6 +183,12 @@ impl Widget for &mut epaint::TessellationOptions {
                     .show_value(true)
                     .text("Spline Tolerance"),
             );
+            ui.add(
+                crate::widgets::Slider::new(ellipse_tolerance, 0.0001..=10.0)
+                    .logarithmic(true)
+                    .show_value(true)
+                    .text("Ellipse Tolerance"),
+            );
             ui.collapsing("debug", |ui| {
                 ui.checkbox(
                     coarse_tessellation_culling,
@@ -122,6 +122,17 @@ impl PaintList {
         self.0.is_empty()
     }
 
+    /// The number of [`Shape`]s added so far.
+    #[inline(always)]
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The [`Shape`]s added since the list had length `start`.
+    pub(crate) fn shapes_since(&self, start: usize) -> &[ClippedShape] {
+        &self.0[start.min(self.0.len())..]
+    }
+
     /// Returns the index of the new [`Shape`] that can be used with `PaintList::set`.
     #[inline(always)]
     pub fn add(&mut self, clip_rect: Rect, shape: Shape) -> ShapeIdx {
@@ -154,6 +165,27 @@ impl PaintList {
             shape.translate(delta);
         }
     }
+
+    /// Scale and translate each [`Shape`] and clip rectangle, in-place.
+    ///
+    /// Text is laid out again at the scaled size, so it stays sharp.
+    pub fn transform(&mut self, transform: TSTransform, fonts: &epaint::text::Fonts) {
+        for ClippedShape(clip_rect, shape) in &mut self.0 {
+            *clip_rect = transform * *clip_rect;
+            shape.transform_with_fonts(transform, fonts);
+        }
+    }
+
+    /// Put each [`Shape`] in a [`Shape::Group`] with this opacity and blend mode.
+    pub fn group(&mut self, opacity: f32, blend_mode: BlendMode) {
+        for ClippedShape(_, shape) in &mut self.0 {
+            let shapes = vec![std::mem::replace(shape, Shape::Noop)];
+            *shape = GroupShape::new(shapes)
+                .with_opacity(opacity)
+                .with_blend_mode(blend_mode)
+                .into();
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -166,6 +198,13 @@ impl GraphicLayers {
             .or_default()
     }
 
+    /// Has anything been painted on the layer this frame?
+    pub fn is_painted(&self, layer_id: LayerId) -> bool {
+        self.0[layer_id.order as usize]
+            .get(&layer_id.id)
+            .map_or(false, |list| !list.is_empty())
+    }
+
     pub fn drain(&mut self, area_order: &[LayerId]) -> impl ExactSizeIterator<Item = ClippedShape> {
         let mut all_shapes: Vec<_> = Default::default();
 
@@ -325,14 +325,20 @@ pub mod widgets;
 pub use epaint;
 pub use epaint::emath;
 
-pub use emath::{lerp, pos2, remap, remap_clamp, vec2, Align, Align2, NumExt, Pos2, Rect, Vec2};
+pub use emath::{
+    lerp, pos2, remap, remap_clamp, vec2, Align, Align2, NumExt, Pos2, Rect, TSTransform, Vec2,
+};
 #[cfg(feature = "color-hex")]
 pub use epaint::hex_color;
 pub use epaint::{
     color, mutex,
-    text::{FontData, FontDefinitions, FontFamily, FontId, FontTweak},
-    textures::{TextureFilter, TexturesDelta},
-    ClippedPrimitive, Color32, ColorImage, FontImage, ImageData, Mesh, PaintCallback,
+    text::{
+        FontData, FontDefinitions, FontFamily, FontId, FontStretch, FontStyle, FontTweak,
+        FontWeight,
+    },
+    textures::{TextureFilter, TextureOptions, TextureWrapMode, TexturesDelta},
+    BackdropBlur, BlendMode, Brush, ClippedPrimitive, Color32, ColorImage, ColorStops, DashPattern,
+    FontImage, GroupShape, ImageData, LineCap, LineJoin, Mesh, NineSlice, PaintCallback,
     PaintCallbackInfo, Rgba, Rounding, Shape, Stroke, TextureHandle, TextureId,
 };
 
@@ -340,7 +346,7 @@ pub mod text {
     pub use crate::text_edit::CCursorRange;
     pub use epaint::text::{
         cursor::CCursor, FontData, FontDefinitions, FontFamily, Fonts, Galley, LayoutJob,
-        LayoutSection, TextFormat, TAB_SIZE,
+        LayoutSection, TextFormat, UnderlineStyle, TAB_SIZE,
     };
 }
 
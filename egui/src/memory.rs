@@ -1,6 +1,8 @@
-use epaint::ahash::AHashSet;
+use epaint::ahash::{AHashMap, AHashSet};
 
-use crate::{area, window, Id, IdMap, InputState, LayerId, Pos2, Rect, Style};
+use crate::{
+    area, window, BlendMode, Id, IdMap, InputState, LayerId, Pos2, Rect, Style, TSTransform,
+};
 
 // ----------------------------------------------------------------------------
 
@@ -81,6 +83,19 @@ pub struct Memory {
 
     pub(crate) areas: Areas,
 
+    /// Transforms of layers, from layer coordinates to screen coordinates.
+    /// See [`crate::Context::set_transform_layer`].
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) layer_transforms: AHashMap<LayerId, TSTransform>,
+
+    /// See [`crate::Context::set_layer_opacity`].
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) layer_opacities: AHashMap<LayerId, f32>,
+
+    /// See [`crate::Context::set_layer_blend_mode`].
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) layer_blend_modes: AHashMap<LayerId, BlendMode>,
+
     /// Which popup-window is open (if any)?
     /// Could be a combo box, color picker, menu etc.
     #[cfg_attr(feature = "persistence", serde(skip))]
@@ -327,7 +342,8 @@ impl Memory {
 
     /// Top-most layer at the given position.
     pub fn layer_id_at(&self, pos: Pos2, resize_interact_radius_side: f32) -> Option<LayerId> {
-        self.areas.layer_id_at(pos, resize_interact_radius_side)
+        self.areas
+            .layer_id_at(pos, resize_interact_radius_side, &self.layer_transforms)
     }
 
     /// An iterator over all layers. Back-to-front. Top is last.
@@ -524,11 +540,19 @@ impl Areas {
     }
 
     /// Top-most layer at the given position.
-    pub fn layer_id_at(&self, pos: Pos2, resize_interact_radius_side: f32) -> Option<LayerId> {
+    pub fn layer_id_at(
+        &self,
+        pos: Pos2,
+        resize_interact_radius_side: f32,
+        layer_transforms: &AHashMap<LayerId, TSTransform>,
+    ) -> Option<LayerId> {
         for layer in self.order.iter().rev() {
             if self.is_visible(layer) {
                 if let Some(state) = self.areas.get(&layer.id) {
                     let mut rect = state.rect();
+                    if let Some(transform) = layer_transforms.get(layer) {
+                        rect = *transform * rect;
+                    }
                     if state.interactable {
                         // Allow us to resize by dragging just outside the window:
                         rect = rect.expand(resize_interact_radius_side);
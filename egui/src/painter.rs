@@ -9,7 +9,7 @@ use crate::{
 use epaint::{
     mutex::{RwLockReadGuard, RwLockWriteGuard},
     text::{Fonts, Galley},
-    CircleShape, RectShape, Rounding, Shape, Stroke,
+    Brush, CircleShape, RectShape, Rounding, Shape, Stroke,
 };
 
 /// Helper to paint shapes and text to a specific region on a specific layer.
@@ -269,7 +269,7 @@ impl Painter {
         &self,
         center: Pos2,
         radius: f32,
-        fill_color: impl Into<Color32>,
+        fill_color: impl Into<Brush>,
         stroke: impl Into<Stroke>,
     ) {
         self.add(CircleShape {
@@ -280,7 +280,7 @@ impl Painter {
         });
     }
 
-    pub fn circle_filled(&self, center: Pos2, radius: f32, fill_color: impl Into<Color32>) {
+    pub fn circle_filled(&self, center: Pos2, radius: f32, fill_color: impl Into<Brush>) {
         self.add(CircleShape {
             center,
             radius,
@@ -302,7 +302,7 @@ impl Painter {
         &self,
         rect: Rect,
         rounding: impl Into<Rounding>,
-        fill_color: impl Into<Color32>,
+        fill_color: impl Into<Brush>,
         stroke: impl Into<Stroke>,
     ) {
         self.add(RectShape {
@@ -317,7 +317,7 @@ impl Painter {
         &self,
         rect: Rect,
         rounding: impl Into<Rounding>,
-        fill_color: impl Into<Color32>,
+        fill_color: impl Into<Brush>,
     ) {
         self.add(RectShape {
             rect,
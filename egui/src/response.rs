@@ -173,6 +173,7 @@ impl Response {
         // We do not use self.clicked(), because we want to catch all clicks within our frame,
         // even if we aren't clickable (or even enabled).
         // This is important for windows and such that should close then the user clicks elsewhere.
+        let transform = self.ctx.layer_transform_from_global(self.layer_id);
         let pointer = &self.ctx.input().pointer;
 
         if pointer.any_click() {
@@ -183,6 +184,10 @@ impl Response {
             if self.hovered() {
                 false
             } else if let Some(pos) = pointer.interact_pos() {
+                let pos = match transform {
+                    Some(transform) => transform * pos,
+                    None => pos,
+                };
                 !self.rect.contains(pos)
             } else {
                 false // clicked without a pointer, weird
@@ -286,7 +291,11 @@ impl Response {
     /// If dragged, how many points were we dragged and in what direction?
     pub fn drag_delta(&self) -> Vec2 {
         if self.dragged() {
-            self.ctx.input().pointer.delta()
+            let delta = self.ctx.input().pointer.delta();
+            match self.ctx.layer_transform_to_global(self.layer_id) {
+                Some(transform) => delta / transform.scaling,
+                None => delta,
+            }
         } else {
             Vec2::ZERO
         }
@@ -302,7 +311,11 @@ impl Response {
     /// None if the pointer is outside the response area.
     pub fn hover_pos(&self) -> Option<Pos2> {
         if self.hovered() {
-            self.ctx.input().pointer.hover_pos()
+            let pos = self.ctx.input().pointer.hover_pos()?;
+            match self.ctx.layer_transform_from_global(self.layer_id) {
+                Some(transform) => Some(transform * pos),
+                None => Some(pos),
+            }
         } else {
             None
         }
@@ -3,7 +3,7 @@
 #![allow(clippy::if_same_then_else)]
 
 use crate::{color::*, emath::*, FontFamily, FontId, Response, RichText, WidgetText};
-use epaint::{Rounding, Shadow, Stroke};
+use epaint::{Brush, NineSlice, Rounding, Shadow, Shape, Stroke};
 use std::collections::BTreeMap;
 
 // ----------------------------------------------------------------------------
@@ -500,6 +500,12 @@ impl Visuals {
         self.widgets.noninteractive.bg_fill
     }
 
+    /// Window background, which can be a gradient (see [`WidgetVisuals::bg_brush`]).
+    #[inline(always)]
+    pub fn window_brush(&self) -> Brush {
+        self.widgets.noninteractive.fill_brush()
+    }
+
     #[inline(always)]
     pub fn window_stroke(&self) -> Stroke {
         self.widgets.noninteractive.bg_stroke
@@ -560,6 +566,16 @@ pub struct WidgetVisuals {
     /// Background color of widget.
     pub bg_fill: Color32,
 
+    /// If set, this is used to fill the background instead of [`Self::bg_fill`],
+    /// e.g. to give buttons a gradient.
+    pub bg_brush: Option<Brush>,
+
+    /// If set, the background of buttons, windows etc. is painted with this texture
+    /// instead of [`Self::fill_brush`] and [`Self::rounding`], e.g. to skin a game UI.
+    ///
+    /// [`Self::rounding`] is ignored then, so any rounded corners must be in the texture.
+    pub bg_nine_slice: Option<NineSlice>,
+
     /// For surrounding rectangle of things that need it,
     /// like buttons, the box of the checkbox, etc.
     /// Should maybe be called `frame_stroke`.
@@ -580,6 +596,34 @@ impl WidgetVisuals {
     pub fn text_color(&self) -> Color32 {
         self.fg_stroke.color
     }
+
+    /// What to fill the background with: [`Self::bg_brush`] if set, else [`Self::bg_fill`].
+    #[inline(always)]
+    pub fn fill_brush(&self) -> Brush {
+        self.bg_brush.unwrap_or_else(|| self.bg_fill.into())
+    }
+
+    /// The background of a widget covering `rect`, with the [`Self::bg_stroke`] around it:
+    /// [`Self::bg_nine_slice`] if set, else a rectangle filled with [`Self::fill_brush`].
+    pub fn bg_shape(&self, rect: Rect) -> Shape {
+        if let Some(nine_slice) = self.bg_nine_slice {
+            if self.bg_stroke.is_empty() {
+                Shape::nine_slice(rect, nine_slice)
+            } else {
+                Shape::Vec(vec![
+                    Shape::nine_slice(rect, nine_slice),
+                    Shape::rect_stroke(rect, 0.0, self.bg_stroke),
+                ])
+            }
+        } else {
+            Shape::Rect(epaint::RectShape {
+                rect,
+                rounding: self.rounding,
+                fill: self.fill_brush(),
+                stroke: self.bg_stroke,
+            })
+        }
+    }
 }
 
 /// Options for help debug egui by adding extra visualization
@@ -746,6 +790,8 @@ impl Widgets {
         Self {
             noninteractive: WidgetVisuals {
                 bg_fill: Color32::from_gray(27), // window background
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::from_gray(60)), // separators, indentation lines, windows outlines
                 fg_stroke: Stroke::new(1.0, Color32::from_gray(140)), // normal text color
                 rounding: Rounding::same(2.0),
@@ -753,6 +799,8 @@ impl Widgets {
             },
             inactive: WidgetVisuals {
                 bg_fill: Color32::from_gray(60), // button background
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Default::default(),
                 fg_stroke: Stroke::new(1.0, Color32::from_gray(180)), // button text
                 rounding: Rounding::same(2.0),
@@ -760,6 +808,8 @@ impl Widgets {
             },
             hovered: WidgetVisuals {
                 bg_fill: Color32::from_gray(70),
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::from_gray(150)), // e.g. hover over window edge or button
                 fg_stroke: Stroke::new(1.5, Color32::from_gray(240)),
                 rounding: Rounding::same(3.0),
@@ -767,6 +817,8 @@ impl Widgets {
             },
             active: WidgetVisuals {
                 bg_fill: Color32::from_gray(55),
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::WHITE),
                 fg_stroke: Stroke::new(2.0, Color32::WHITE),
                 rounding: Rounding::same(2.0),
@@ -774,6 +826,8 @@ impl Widgets {
             },
             open: WidgetVisuals {
                 bg_fill: Color32::from_gray(27),
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::from_gray(60)),
                 fg_stroke: Stroke::new(1.0, Color32::from_gray(210)),
                 rounding: Rounding::same(2.0),
@@ -786,6 +840,8 @@ impl Widgets {
         Self {
             noninteractive: WidgetVisuals {
                 bg_fill: Color32::from_gray(248), // window background - should be distinct from TextEdit background
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::from_gray(190)), // separators, indentation lines, windows outlines
                 fg_stroke: Stroke::new(1.0, Color32::from_gray(80)),  // normal text color
                 rounding: Rounding::same(2.0),
@@ -793,6 +849,8 @@ impl Widgets {
             },
             inactive: WidgetVisuals {
                 bg_fill: Color32::from_gray(230), // button background
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Default::default(),
                 fg_stroke: Stroke::new(1.0, Color32::from_gray(60)), // button text
                 rounding: Rounding::same(2.0),
@@ -800,6 +858,8 @@ impl Widgets {
             },
             hovered: WidgetVisuals {
                 bg_fill: Color32::from_gray(220),
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::from_gray(105)), // e.g. hover over window edge or button
                 fg_stroke: Stroke::new(1.5, Color32::BLACK),
                 rounding: Rounding::same(3.0),
@@ -807,6 +867,8 @@ impl Widgets {
             },
             active: WidgetVisuals {
                 bg_fill: Color32::from_gray(165),
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::BLACK),
                 fg_stroke: Stroke::new(2.0, Color32::BLACK),
                 rounding: Rounding::same(2.0),
@@ -814,6 +876,8 @@ impl Widgets {
             },
             open: WidgetVisuals {
                 bg_fill: Color32::from_gray(220),
+                bg_brush: None,
+                bg_nine_slice: None,
                 bg_stroke: Stroke::new(1.0, Color32::from_gray(160)),
                 fg_stroke: Stroke::new(1.0, Color32::BLACK),
                 rounding: Rounding::same(2.0),
@@ -1097,6 +1161,8 @@ impl WidgetVisuals {
     pub fn ui(&mut self, ui: &mut crate::Ui) {
         let Self {
             bg_fill,
+            bg_brush: _,
+            bg_nine_slice: _,
             bg_stroke,
             rounding,
             fg_stroke,
@@ -2,8 +2,8 @@ use std::borrow::Cow;
 use std::sync::Arc;
 
 use crate::{
-    style::WidgetVisuals, text::LayoutJob, Align, Color32, FontFamily, FontSelection, Galley, Pos2,
-    Style, TextStyle, Ui, Visuals,
+    style::WidgetVisuals, text::LayoutJob, Align, Color32, FontFamily, FontSelection, FontStretch,
+    FontStyle, FontWeight, Galley, Pos2, Style, TextStyle, Ui, Visuals,
 };
 
 /// Text and optional style choices for it.
@@ -26,6 +26,9 @@ pub struct RichText {
     text: String,
     size: Option<f32>,
     family: Option<FontFamily>,
+    weight: Option<FontWeight>,
+    stretch: Option<FontStretch>,
+    font_style: Option<FontStyle>,
     text_style: Option<TextStyle>,
     background_color: Color32,
     text_color: Option<Color32>,
@@ -111,13 +114,53 @@ impl RichText {
         self
     }
 
-    /// Select the font and size.
+    /// Select the font weight, e.g. [`FontWeight::BOLD`].
+    ///
+    /// This needs a bold face in [`crate::FontDefinitions::faces`], or a variable font.
+    #[inline]
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Use [`FontWeight::BOLD`].
+    #[inline]
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::BOLD)
+    }
+
+    /// Select how wide the font is, e.g. [`FontStretch::CONDENSED`].
+    #[inline]
+    pub fn stretch(mut self, stretch: FontStretch) -> Self {
+        self.stretch = Some(stretch);
+        self
+    }
+
+    /// Select an italic face of the font.
+    ///
+    /// Unlike [`Self::italics`], this uses the italic design of the font if there is one.
+    #[inline]
+    pub fn font_style(mut self, font_style: FontStyle) -> Self {
+        self.font_style = Some(font_style);
+        self
+    }
+
+    /// Select the font, size, weight, stretch and style.
     /// This overrides the value from [`Self::text_style`].
     #[inline]
     pub fn font(mut self, font_id: crate::FontId) -> Self {
-        let crate::FontId { size, family } = font_id;
+        let crate::FontId {
+            size,
+            family,
+            weight,
+            stretch,
+            style,
+        } = font_id;
         self.size = Some(size);
         self.family = Some(family);
+        self.weight = Some(weight);
+        self.stretch = Some(stretch);
+        self.font_style = Some(style);
         self
     }
 
@@ -239,6 +282,15 @@ impl RichText {
         if let Some(family) = &self.family {
             font_id.family = family.clone();
         }
+        if let Some(weight) = self.weight {
+            font_id.weight = weight;
+        }
+        if let Some(stretch) = self.stretch {
+            font_id.stretch = stretch;
+        }
+        if let Some(font_style) = self.font_style {
+            font_id.style = font_style;
+        }
         fonts.row_height(&font_id)
     }
 
@@ -254,6 +306,9 @@ impl RichText {
             text,
             size,
             family,
+            weight,
+            stretch,
+            font_style,
             text_style,
             background_color,
             text_color: _, // already used by `get_text_color`
@@ -283,6 +338,15 @@ impl RichText {
             if let Some(family) = family {
                 font_id.family = family;
             }
+            if let Some(weight) = weight {
+                font_id.weight = weight;
+            }
+            if let Some(stretch) = stretch {
+                font_id.stretch = stretch;
+            }
+            if let Some(font_style) = font_style {
+                font_id.style = font_style;
+            }
             font_id
         };
 
@@ -315,6 +379,7 @@ impl RichText {
             underline,
             strikethrough,
             valign,
+            ..Default::default()
         };
 
         let job = LayoutJob::single_section(text, text_format);
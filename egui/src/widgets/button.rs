@@ -23,7 +23,7 @@ pub struct Button {
     text: WidgetText,
     wrap: Option<bool>,
     /// None means default for interact
-    fill: Option<Color32>,
+    fill: Option<Brush>,
     stroke: Option<Stroke>,
     sense: Sense,
     small: bool,
@@ -80,9 +80,9 @@ impl Button {
         self
     }
 
-    /// Override background fill color. Note that this will override any on-hover effects.
+    /// Override background fill color (or gradient). Note that this will override any on-hover effects.
     /// Calling this will also turn on the frame.
-    pub fn fill(mut self, fill: impl Into<Color32>) -> Self {
+    pub fn fill(mut self, fill: impl Into<Brush>) -> Self {
         self.fill = Some(fill.into());
         self.frame = Some(true);
         self
@@ -176,14 +176,14 @@ impl Widget for Button {
             };
 
             if frame {
-                let fill = fill.unwrap_or(visuals.bg_fill);
-                let stroke = stroke.unwrap_or(visuals.bg_stroke);
-                ui.painter().rect(
-                    rect.expand(visuals.expansion),
-                    visuals.rounding,
-                    fill,
-                    stroke,
-                );
+                let rect = rect.expand(visuals.expansion);
+                if fill.is_none() && stroke.is_none() {
+                    ui.painter().add(visuals.bg_shape(rect));
+                } else {
+                    let fill = fill.unwrap_or_else(|| visuals.fill_brush());
+                    let stroke = stroke.unwrap_or(visuals.bg_stroke);
+                    ui.painter().rect(rect, visuals.rounding, fill, stroke);
+                }
             }
 
             text.paint_with_visuals(ui.painter(), text_pos, visuals);
@@ -273,12 +273,8 @@ impl<'a> Widget for Checkbox<'a> {
             // let visuals = ui.style().interact_selectable(&response, *checked); // too colorful
             let visuals = ui.style().interact(&response);
             let (small_icon_rect, big_icon_rect) = ui.spacing().icon_rectangles(rect);
-            ui.painter().add(epaint::RectShape {
-                rect: big_icon_rect.expand(visuals.expansion),
-                rounding: visuals.rounding,
-                fill: visuals.bg_fill,
-                stroke: visuals.bg_stroke,
-            });
+            ui.painter()
+                .add(visuals.bg_shape(big_icon_rect.expand(visuals.expansion)));
 
             if *checked {
                 // Check mark:
@@ -385,7 +381,7 @@ impl Widget for RadioButton {
             painter.add(epaint::CircleShape {
                 center: big_icon_rect.center(),
                 radius: big_icon_rect.width() / 2.0 + visuals.expansion,
-                fill: visuals.bg_fill,
+                fill: visuals.fill_brush(),
                 stroke: visuals.bg_stroke,
             });
 
@@ -393,7 +389,7 @@ impl Widget for RadioButton {
                 painter.add(epaint::CircleShape {
                     center: small_icon_rect.center(),
                     radius: small_icon_rect.width() / 3.0,
-                    fill: visuals.fg_stroke.color, // Intentional to use stroke and not fill
+                    fill: visuals.fg_stroke.color.into(), // Intentional to use stroke and not fill
                     // fill: ui.visuals().selection.stroke.color, // too much color
                     stroke: Default::default(),
                 });
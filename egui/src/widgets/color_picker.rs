@@ -202,7 +202,7 @@ fn color_slider_2d(
         ui.painter().add(epaint::CircleShape {
             center: pos2(x, y),
             radius: rect.width() / 12.0,
-            fill: picked_color,
+            fill: picked_color.into(),
             stroke: Stroke::new(visuals.fg_stroke.width, contrast_color(picked_color)),
         });
     }
@@ -42,6 +42,7 @@ pub struct Image {
     tint: Color32,
     sense: Sense,
     rotation: Option<(Rot2, Vec2)>,
+    nine_slice: Option<(Vec2, style::Margin)>,
 }
 
 impl Image {
@@ -54,6 +55,7 @@ impl Image {
             tint: Color32::WHITE,
             sense: Sense::hover(),
             rotation: None,
+            nine_slice: None,
         }
     }
 
@@ -93,6 +95,19 @@ impl Image {
         self.rotation = Some((Rot2::from_angle(angle), origin));
         self
     }
+
+    /// Stretch the image to its size without distorting its border, e.g. for skinned buttons and frames.
+    ///
+    /// `texture_size` is the size of the whole texture, and `insets` how wide its border is
+    /// on each side, both in texels. See [`NineSlice`].
+    pub fn nine_slice(
+        mut self,
+        texture_size: impl Into<Vec2>,
+        insets: impl Into<style::Margin>,
+    ) -> Self {
+        self.nine_slice = Some((texture_size.into(), insets.into()));
+        self
+    }
 }
 
 impl Image {
@@ -111,6 +126,7 @@ impl Image {
                 tint,
                 sense: _,
                 rotation,
+                nine_slice,
             } = self;
 
             if *bg_fill != Default::default() {
@@ -121,8 +137,17 @@ impl Image {
 
             {
                 // TODO(emilk): builder pattern for Mesh
-                let mut mesh = Mesh::with_texture(*texture_id);
-                mesh.add_rect_with_uv(rect, *uv, *tint);
+                let mut mesh = if let Some((texture_size, insets)) = nine_slice {
+                    NineSlice::new(*texture_id, *texture_size, 0.0)
+                        .with_insets(insets.left, insets.right, insets.top, insets.bottom)
+                        .with_uv(*uv)
+                        .with_tint(*tint)
+                        .tessellate(rect)
+                } else {
+                    let mut mesh = Mesh::with_texture(*texture_id);
+                    mesh.add_rect_with_uv(rect, *uv, *tint);
+                    mesh
+                };
                 if let Some((rot, origin)) = rotation {
                     mesh.rotate(*rot, rect.min + *origin * *size);
                 }
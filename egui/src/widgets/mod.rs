@@ -109,8 +109,8 @@ pub fn reset_button_with<T: PartialEq>(ui: &mut Ui, value: &mut T, reset_value:
 // ----------------------------------------------------------------------------
 
 pub fn stroke_ui(ui: &mut crate::Ui, stroke: &mut epaint::Stroke, text: &str) {
-    let epaint::Stroke { width, color } = stroke;
     ui.horizontal(|ui| {
+        let epaint::Stroke { width, color, .. } = stroke;
         ui.add(DragValue::new(width).speed(0.1).clamp_range(0.0..=5.0))
             .on_hover_text("Width");
         ui.color_edit_button_srgba(color);
@@ -120,20 +120,27 @@ pub fn stroke_ui(ui: &mut crate::Ui, stroke: &mut epaint::Stroke, text: &str) {
         let (_id, stroke_rect) = ui.allocate_space(ui.spacing().interact_size);
         let left = stroke_rect.left_center();
         let right = stroke_rect.right_center();
-        ui.painter().line_segment([left, right], (*width, *color));
+        ui.painter().line_segment([left, right], *stroke);
     });
 }
 
 pub(crate) fn shadow_ui(ui: &mut Ui, shadow: &mut epaint::Shadow, text: &str) {
-    let epaint::Shadow { extrusion, color } = shadow;
+    let epaint::Shadow {
+        offset,
+        blur,
+        spread,
+        color,
+    } = shadow;
     ui.horizontal(|ui| {
         ui.label(text);
-        ui.add(
-            DragValue::new(extrusion)
-                .speed(1.0)
-                .clamp_range(0.0..=100.0),
-        )
-        .on_hover_text("Extrusion");
+        ui.add(DragValue::new(&mut offset.x).speed(1.0).prefix("x: "))
+            .on_hover_text("Offset");
+        ui.add(DragValue::new(&mut offset.y).speed(1.0).prefix("y: "))
+            .on_hover_text("Offset");
+        ui.add(DragValue::new(blur).speed(1.0).clamp_range(0.0..=100.0))
+            .on_hover_text("Blur");
+        ui.add(DragValue::new(spread).speed(1.0).clamp_range(0.0..=100.0))
+            .on_hover_text("Spread");
         ui.color_edit_button_srgba(color);
     });
 }
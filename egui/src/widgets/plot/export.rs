@@ -0,0 +1,889 @@
+//! Exporting a [`super::Plot`] as SVG or as an image.
+
+use std::fmt::Write as _;
+
+use epaint::text::{FontDefinitions, Fonts};
+use epaint::{ClippedShape, FontImage, Primitive, TessellationOptions, TextShape, Vertex};
+
+use crate::*;
+
+/// Everything a [`super::Plot`] painted during one frame: background, grid, axes, items and legend.
+///
+/// Enable it with [`super::Plot::export`] and get it from [`super::PlotResponse::export`].
+///
+/// The output resolution is chosen independently of the on-screen size of the plot
+/// via the `pixels_per_point` argument of [`Self::to_svg`] and [`Self::to_color_image`].
+///
+/// Shapes using user textures (e.g. [`super::PlotImage`]), [`Shape::Callback`]s and
+/// [`Shape::BackdropBlur`]s are skipped, since only the backend knows how to draw them.
+#[derive(Clone)]
+pub struct PlotExport {
+    rect: Rect,
+    shapes: Vec<ClippedShape>,
+    font_definitions: FontDefinitions,
+    max_texture_side: usize,
+    tessellation_options: TessellationOptions,
+}
+
+impl PlotExport {
+    pub(super) fn new(ctx: &Context, rect: Rect, shapes: Vec<ClippedShape>) -> Self {
+        let (font_definitions, max_texture_side) = {
+            let fonts = ctx.fonts();
+            let font_definitions = fonts.lock().fonts.definitions().clone();
+            (font_definitions, fonts.max_texture_side())
+        };
+        let tessellation_options = *ctx.tessellation_options();
+        Self {
+            rect,
+            shapes,
+            font_definitions,
+            max_texture_side,
+            tessellation_options,
+        }
+    }
+
+    /// The area covered by the plot, in points.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The captured shapes, in points.
+    pub fn shapes(&self) -> &[ClippedShape] {
+        &self.shapes
+    }
+
+    /// Size in pixels of the exported image for the given scale.
+    pub fn size_in_pixels(&self, pixels_per_point: f32) -> [usize; 2] {
+        [
+            (self.rect.width() * pixels_per_point).round().at_least(1.0) as usize,
+            (self.rect.height() * pixels_per_point)
+                .round()
+                .at_least(1.0) as usize,
+        ]
+    }
+
+    /// Serialize the plot as an SVG document.
+    ///
+    /// The document is `pixels_per_point` times the on-screen size of the plot.
+    /// Text is written as SVG `<text>`, so the viewer may pick a slightly different font.
+    pub fn to_svg(&self, pixels_per_point: f32) -> String {
+        let rect = self.rect;
+        let [width, height] = self.size_in_pixels(pixels_per_point);
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            width,
+            height,
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height()
+        );
+
+        let mut current_clip_rect = None;
+        for (i, ClippedShape(clip_rect, shape)) in self.shapes.iter().enumerate() {
+            if current_clip_rect != Some(*clip_rect) {
+                if current_clip_rect.is_some() {
+                    svg.push_str("</g>\n");
+                }
+                let clip = clip_rect.intersect(rect);
+                let _ = writeln!(
+                    svg,
+                    r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                    i,
+                    clip.min.x,
+                    clip.min.y,
+                    clip.width().at_least(0.0),
+                    clip.height().at_least(0.0)
+                );
+                let _ = writeln!(svg, r#"<g clip-path="url(#clip{})">"#, i);
+                current_clip_rect = Some(*clip_rect);
+            }
+            write_svg_shape(&mut svg, shape);
+        }
+        if current_clip_rect.is_some() {
+            svg.push_str("</g>\n");
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render the plot on the CPU.
+    ///
+    /// The image is `pixels_per_point` times the on-screen size of the plot.
+    /// Use e.g. `egui_extras::image::encode_png` to turn it into a PNG file.
+    ///
+    /// All text is laid out again with fonts rasterized at `pixels_per_point`, so it stays sharp.
+    /// This loads the fonts anew, so it takes a while.
+    pub fn to_color_image(&self, pixels_per_point: f32) -> ColorImage {
+        let size = self.size_in_pixels(pixels_per_point);
+        let mut canvas = Canvas {
+            size,
+            pixels: vec![Rgba::TRANSPARENT; size[0] * size[1]],
+        };
+
+        let fonts = Fonts::new(
+            pixels_per_point,
+            self.max_texture_side,
+            self.font_definitions.clone(),
+        );
+        let shapes = self
+            .shapes
+            .iter()
+            .map(|ClippedShape(clip_rect, shape)| {
+                ClippedShape(*clip_rect, relayout_text(&fonts, shape.clone()))
+            })
+            .collect();
+
+        let atlas = fonts.texture_atlas();
+        let atlas = atlas.lock();
+        let primitives = epaint::tessellate_shapes(
+            pixels_per_point,
+            self.tessellation_options,
+            atlas.size(),
+            atlas.prepared_discs(),
+            shapes,
+        );
+
+        let offset = self.rect.min.to_vec2();
+        let to_pixels = |pos: Pos2| ((pos - offset).to_vec2() * pixels_per_point).to_pos2();
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+            blend_mode,
+        } in primitives
+        {
+            if let Primitive::Mesh(mesh) = primitive {
+                let font_image = match atlas.page_from_texture_id(mesh.texture_id) {
+                    Some(page) => atlas.page_image(page),
+                    _ => continue, // A user texture we don't have access to.
+                };
+                let clip_rect =
+                    Rect::from_min_max(to_pixels(clip_rect.min), to_pixels(clip_rect.max));
+                for triangle in mesh.indices.chunks_exact(3) {
+                    let [a, b, c] = [0, 1, 2].map(|i| {
+                        let vertex = mesh.vertices[triangle[i] as usize];
+                        Vertex {
+                            pos: to_pixels(vertex.pos),
+                            ..vertex
+                        }
+                    });
+                    canvas.fill_triangle(&[a, b, c], clip_rect, font_image, blend_mode);
+                }
+            }
+        }
+
+        let pixels = canvas
+            .pixels
+            .iter()
+            .map(|rgba| {
+                let [r, g, b, a] = rgba
+                    .to_array()
+                    .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                Color32::from_rgba_premultiplied(r, g, b, a)
+            })
+            .collect();
+        ColorImage { size, pixels }
+    }
+}
+
+/// Lay out all text in `shape` again with `fonts`, to use glyphs rasterized at their resolution.
+fn relayout_text(fonts: &Fonts, shape: Shape) -> Shape {
+    match shape {
+        Shape::Text(mut text_shape) => {
+            text_shape.galley = fonts.layout_job((*text_shape.galley.job).clone());
+            Shape::Text(text_shape)
+        }
+        Shape::Vec(shapes) => Shape::Vec(
+            shapes
+                .into_iter()
+                .map(|shape| relayout_text(fonts, shape))
+                .collect(),
+        ),
+        Shape::Group(mut group) => {
+            group.shapes = group
+                .shapes
+                .into_iter()
+                .map(|shape| relayout_text(fonts, shape))
+                .collect();
+            Shape::Group(group)
+        }
+        shape => shape,
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Premultiplied sRGBA pixels, blended the same way the egui backends do.
+struct Canvas {
+    size: [usize; 2],
+    pixels: Vec<Rgba>,
+}
+
+impl Canvas {
+    fn fill_triangle(
+        &mut self,
+        [a, b, c]: &[Vertex; 3],
+        clip_rect: Rect,
+        font_image: &FontImage,
+        blend_mode: BlendMode,
+    ) {
+        let area = edge(a.pos, b.pos, c.pos);
+        if area == 0.0 {
+            return;
+        }
+
+        let bounds = Rect::from_min_max(a.pos.min(b.pos).min(c.pos), a.pos.max(b.pos).max(c.pos))
+            .intersect(clip_rect)
+            .intersect(Rect::from_min_size(
+                Pos2::ZERO,
+                vec2(self.size[0] as f32, self.size[1] as f32),
+            ));
+        if !bounds.is_positive() {
+            return;
+        }
+
+        let colors = [a, b, c].map(|v| {
+            let [r, g, b, a] = v.color.to_array().map(|c| c as f32 / 255.0);
+            Rgba::from_rgba_premultiplied(r, g, b, a)
+        });
+
+        let weights = |p: Pos2| {
+            [
+                edge(b.pos, c.pos, p) / area,
+                edge(c.pos, a.pos, p) / area,
+                edge(a.pos, b.pos, p) / area,
+            ]
+        };
+        let uv_at = |w: [f32; 3]| {
+            (a.uv.to_vec2() * w[0] + b.uv.to_vec2() * w[1] + c.uv.to_vec2() * w[2]).to_pos2()
+        };
+
+        for y in bounds.min.y.floor() as usize..bounds.max.y.ceil() as usize {
+            for x in bounds.min.x.floor() as usize..bounds.max.x.ceil() as usize {
+                let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+                if !bounds.contains(p) {
+                    continue;
+                }
+                let w = weights(p);
+                if w.iter().any(|w| *w < 0.0) {
+                    continue;
+                }
+
+                let mut texel = sample_font_image(font_image, uv_at(w));
+                if font_image.sdf {
+                    // Like the shaders do it, with the change to the neighboring pixels as `fwidth`:
+                    let distance_at =
+                        |p: Pos2| sample_font_image(font_image, uv_at(weights(p))).a();
+                    let half_width = 0.5
+                        * ((distance_at(p + vec2(1.0, 0.0)) - texel.a()).abs()
+                            + (distance_at(p + vec2(0.0, 1.0)) - texel.a()).abs());
+                    texel = from_sdf(texel, half_width);
+                }
+                let src = (colors[0] * w[0] + colors[1] * w[1] + colors[2] * w[2]) * texel;
+
+                let dst = &mut self.pixels[y * self.size[0] + x];
+                *dst = blend_mode.blend(src, *dst);
+            }
+        }
+    }
+}
+
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b - a).x * (p - a).y - (b - a).y * (p - a).x
+}
+
+/// Bilinear sample of the font atlas, mapped like [`FontImage::srgba_pixels`] with `gamma = 1.0`.
+///
+/// For a signed distance field the color is unmultiplied, with the distance in alpha.
+fn sample_font_image(font_image: &FontImage, uv: Pos2) -> Rgba {
+    let [w, h] = font_image.size;
+    let x = (uv.x * w as f32 - 0.5).clamp(0.0, (w - 1) as f32);
+    let y = (uv.y * h as f32 - 0.5).clamp(0.0, (h - 1) as f32);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+    let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+    let texel = |x: usize, y: usize| {
+        let i = y * w + x;
+        match font_image.colors.get(i) {
+            Some(&color) if color != Color32::TRANSPARENT => {
+                let color = if font_image.sdf {
+                    color.to_srgba_unmultiplied()
+                } else {
+                    color.to_array()
+                };
+                let [r, g, b, a] = color.map(|c| c as f32 / 255.0);
+                Rgba::from_rgba_premultiplied(r, g, b, a)
+            }
+            _ if font_image.sdf => {
+                Rgba::from_rgba_premultiplied(1.0, 1.0, 1.0, font_image.pixels[i])
+            }
+            _ => Rgba::from_white_alpha(font_image.pixels[i].powf(1.0 / 2.2)),
+        }
+    };
+    let top = texel(x0, y0) * (1.0 - tx) + texel(x1, y0) * tx;
+    let bottom = texel(x0, y1) * (1.0 - tx) + texel(x1, y1) * tx;
+    top * (1.0 - ty) + bottom * ty
+}
+
+/// Coverage from a texel of a signed distance field, with a smooth edge `2 * half_width` wide.
+fn from_sdf(texel: Rgba, half_width: f32) -> Rgba {
+    let half_width = half_width.max(1e-4);
+    let t = remap_clamp(
+        texel.a(),
+        (0.5 - half_width)..=(0.5 + half_width),
+        0.0..=1.0,
+    );
+    let coverage = t * t * (3.0 - 2.0 * t);
+    Rgba::from_rgba_premultiplied(
+        texel.r() * coverage,
+        texel.g() * coverage,
+        texel.b() * coverage,
+        coverage,
+    )
+}
+
+// ----------------------------------------------------------------------------
+
+fn write_svg_shape(svg: &mut String, shape: &Shape) {
+    match shape {
+        Shape::Noop | Shape::Callback(_) | Shape::BackdropBlur(_) => {}
+        Shape::Vec(shapes) => {
+            for shape in shapes {
+                write_svg_shape(svg, shape);
+            }
+        }
+        Shape::Group(group) => {
+            let blend_mode = match group.blend_mode {
+                BlendMode::Normal => "",
+                BlendMode::Multiply => r#" style="mix-blend-mode:multiply""#,
+                BlendMode::Screen => r#" style="mix-blend-mode:screen""#,
+                BlendMode::Additive => r#" style="mix-blend-mode:plus-lighter""#,
+            };
+            let _ = writeln!(svg, r#"<g opacity="{}"{}>"#, group.opacity, blend_mode);
+            for shape in &group.shapes {
+                write_svg_shape(svg, shape);
+            }
+            svg.push_str("</g>\n");
+        }
+        Shape::Circle(circle) => {
+            let fill = fill_attr(svg, circle.fill);
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{}"{}{}/>"#,
+                circle.center.x,
+                circle.center.y,
+                circle.radius,
+                fill,
+                stroke_attr(circle.stroke)
+            );
+        }
+        Shape::Ellipse(ellipse) => {
+            let fill = fill_attr(svg, ellipse.fill);
+            let _ = writeln!(
+                svg,
+                r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"{}{}/>"#,
+                ellipse.center.x,
+                ellipse.center.y,
+                ellipse.radius.x,
+                ellipse.radius.y,
+                fill,
+                stroke_attr(ellipse.stroke)
+            );
+        }
+        Shape::Arc(arc) => write_svg_arc(svg, arc),
+        Shape::LineSegment { points, stroke } => {
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                points[0].x,
+                points[0].y,
+                points[1].x,
+                points[1].y,
+                stroke_attr(*stroke)
+            );
+        }
+        Shape::Path(path) if path.fill_mode != epaint::FillMode::Convex => {
+            let fill_rule = if path.fill_mode == epaint::FillMode::EvenOdd {
+                "evenodd"
+            } else {
+                "nonzero"
+            };
+            let d: Vec<String> = std::iter::once(&path.points)
+                .chain(&path.holes)
+                .filter(|outline| !outline.is_empty())
+                .map(|outline| {
+                    let points: Vec<String> =
+                        outline.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                    format!("M{}Z", points.join(" L"))
+                })
+                .collect();
+            let fill = fill_attr(svg, path.fill);
+            let _ = writeln!(
+                svg,
+                r#"<path d="{}" fill-rule="{}"{}{}/>"#,
+                d.join(" "),
+                fill_rule,
+                fill,
+                stroke_attr(path.stroke)
+            );
+        }
+        Shape::Path(path) => {
+            let (element, fill) = if path.closed {
+                ("polygon", path.fill)
+            } else {
+                ("polyline", Brush::default())
+            };
+            let fill = fill_attr(svg, fill);
+            let _ = writeln!(
+                svg,
+                r#"<{} points="{}"{}{}/>"#,
+                element,
+                points_attr(&path.points),
+                fill,
+                stroke_attr(path.stroke)
+            );
+        }
+        Shape::Rect(rect_shape) => {
+            let rect = rect_shape.rect;
+            let fill = fill_attr(svg, rect_shape.fill);
+            let _ = writeln!(
+                svg,
+                r#"<path d="{}"{}{}/>"#,
+                rounded_rect_path(rect, rect_shape.rounding),
+                fill,
+                stroke_attr(rect_shape.stroke)
+            );
+        }
+        Shape::Text(text_shape) => write_svg_text(svg, text_shape),
+        Shape::Mesh(mesh) => {
+            if mesh.texture_id != TextureId::default() {
+                return;
+            }
+            for triangle in mesh.indices.chunks_exact(3) {
+                let vertices = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+                let _ = writeln!(
+                    svg,
+                    r#"<polygon points="{}"{}/>"#,
+                    points_attr(&vertices.map(|v| v.pos)),
+                    color_attr("fill", vertices[0].color)
+                );
+            }
+        }
+        Shape::QuadraticBezier(bezier) => {
+            let [p0, p1, p2] = bezier.points;
+            write_svg_bezier(
+                svg,
+                &format!("M{} {} Q{} {} {} {}", p0.x, p0.y, p1.x, p1.y, p2.x, p2.y),
+                bezier.closed,
+                bezier.fill,
+                bezier.stroke,
+            );
+        }
+        Shape::CubicBezier(bezier) => {
+            let [p0, p1, p2, p3] = bezier.points;
+            write_svg_bezier(
+                svg,
+                &format!(
+                    "M{} {} C{} {} {} {} {} {}",
+                    p0.x, p0.y, p1.x, p1.y, p2.x, p2.y, p3.x, p3.y
+                ),
+                bezier.closed,
+                bezier.fill,
+                bezier.stroke,
+            );
+        }
+    }
+}
+
+fn write_svg_bezier(svg: &mut String, d: &str, closed: bool, fill: Brush, stroke: Stroke) {
+    let (close, fill) = if closed {
+        (" Z", fill)
+    } else {
+        ("", Brush::default())
+    };
+    let fill = fill_attr(svg, fill);
+    let _ = writeln!(
+        svg,
+        r#"<path d="{}{}"{}{}/>"#,
+        d,
+        close,
+        fill,
+        stroke_attr(stroke)
+    );
+}
+
+fn write_svg_arc(svg: &mut String, arc: &epaint::ArcShape) {
+    if arc.sweep_angle() == 0.0 {
+        return;
+    }
+
+    // An SVG arc can't go all the way around, so we draw it in two halves:
+    let angles = arc.clockwise_angles();
+    let [start, middle, end] = [
+        *angles.start(),
+        0.5 * (*angles.start() + *angles.end()),
+        *angles.end(),
+    ]
+    .map(|angle| arc.point_at(angle));
+
+    let mut d = if arc.sector {
+        format!(
+            "M{} {} L{} {}",
+            arc.center.x, arc.center.y, start.x, start.y
+        )
+    } else {
+        format!("M{} {}", start.x, start.y)
+    };
+    for p in [middle, end] {
+        let _ = write!(
+            d,
+            " A{} {} 0 0 1 {} {}",
+            arc.radius.x, arc.radius.y, p.x, p.y
+        );
+    }
+    if arc.sector || arc.is_full_turn() {
+        d.push_str(" Z");
+    }
+
+    // Open paths are filled as if they were closed, which is what we want:
+    let fill = fill_attr(svg, arc.fill);
+    let _ = writeln!(
+        svg,
+        r#"<path d="{}"{}{}/>"#,
+        d,
+        fill,
+        stroke_attr(arc.stroke)
+    );
+}
+
+fn write_svg_text(svg: &mut String, text_shape: &TextShape) {
+    let TextShape {
+        pos,
+        galley,
+        underline,
+        override_text_color,
+        angle,
+    } = text_shape;
+
+    if *angle == 0.0 {
+        svg.push_str("<g>\n");
+    } else {
+        let _ = writeln!(
+            svg,
+            r#"<g transform="rotate({} {} {})">"#,
+            angle.to_degrees(),
+            pos.x,
+            pos.y
+        );
+    }
+
+    for row in &galley.rows {
+        let mut glyphs = &row.glyphs[..];
+        while let Some(first) = glyphs.first() {
+            let run_len = glyphs
+                .iter()
+                .take_while(|glyph| glyph.section_index == first.section_index)
+                .count();
+            let (run, rest) = glyphs.split_at(run_len);
+            glyphs = rest;
+
+            let format = &galley.job.sections[first.section_index as usize].format;
+            let family = match format.font_id.family {
+                FontFamily::Monospace => "monospace",
+                FontFamily::Proportional | FontFamily::Name(_) => "sans-serif",
+            };
+            let color = override_text_color.unwrap_or(format.color);
+            let xs: Vec<String> = run
+                .iter()
+                .map(|glyph| (pos.x + glyph.pos.x).to_string())
+                .collect();
+            let text: String = run.iter().map(|glyph| glyph.chr).collect();
+            // `Glyph::pos` is the top of the glyph box, but SVG positions text by its baseline:
+            let baseline = pos.y + first.pos.y + 0.8 * first.size.y;
+            let _ = writeln!(
+                svg,
+                r#"<text xml:space="preserve" x="{}" y="{}" font-family="{}" font-size="{}"{}{}>{}</text>"#,
+                xs.join(" "),
+                baseline,
+                family,
+                format.font_id.size,
+                if format.italics {
+                    r#" font-style="italic""#
+                } else {
+                    ""
+                },
+                color_attr("fill", color),
+                escape_xml(&text)
+            );
+        }
+
+        if *underline != Stroke::none() {
+            let left = *pos + row.rect.left_bottom().to_vec2();
+            let right = *pos + row.rect.right_bottom().to_vec2();
+            write_svg_shape(svg, &Shape::line_segment([left, right], *underline));
+        }
+    }
+
+    svg.push_str("</g>\n");
+}
+
+fn rounded_rect_path(rect: Rect, rounding: Rounding) -> String {
+    let max = 0.5 * rect.width().min(rect.height());
+    let Rounding { nw, ne, sw, se } = rounding;
+    let [nw, ne, sw, se] = [nw, ne, sw, se].map(|r| r.clamp(0.0, max));
+    let (left, top, right, bottom) = (rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+    format!(
+        "M{} {} H{} A{} {} 0 0 1 {} {} V{} A{} {} 0 0 1 {} {} H{} A{} {} 0 0 1 {} {} V{} A{} {} 0 0 1 {} {} Z",
+        left + nw,
+        top,
+        right - ne,
+        ne,
+        ne,
+        right,
+        top + ne,
+        bottom - se,
+        se,
+        se,
+        right - se,
+        bottom,
+        left + sw,
+        sw,
+        sw,
+        left,
+        bottom - sw,
+        top + nw,
+        nw,
+        nw,
+        left + nw,
+        top
+    )
+}
+
+fn points_attr(points: &[Pos2]) -> String {
+    let points: Vec<String> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+    points.join(" ")
+}
+
+fn color_attr(attr: &str, color: Color32) -> String {
+    if color.a() == 0 {
+        return format!(r#" {}="none""#, attr);
+    }
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let mut s = format!(r##" {}="#{:02x}{:02x}{:02x}""##, attr, r, g, b);
+    if a < 255 {
+        let _ = write!(s, r#" {}-opacity="{:.3}""#, attr, a as f32 / 255.0);
+    }
+    s
+}
+
+/// The `fill` attribute for a [`Brush`].
+///
+/// Gradients are written to `svg` as a definition that the attribute refers to.
+fn fill_attr(svg: &mut String, brush: Brush) -> String {
+    let (element, geometry, stops) = match brush {
+        Brush::Solid(color) => return color_attr("fill", color),
+        _ if brush.is_transparent() => return color_attr("fill", Color32::TRANSPARENT),
+        Brush::LinearGradient { start, end, stops } => (
+            "linearGradient",
+            format!(
+                r#"x1="{}" y1="{}" x2="{}" y2="{}""#,
+                start.x, start.y, end.x, end.y
+            ),
+            stops,
+        ),
+        Brush::RadialGradient {
+            center,
+            radius,
+            stops,
+        } => {
+            let mut geometry = format!(r#"cx="{}" cy="{}" r="{}""#, center.x, center.y, radius.x);
+            if radius.x > 0.0 && radius.y != radius.x {
+                // Stretch the circle vertically, keeping the center in place:
+                let scale = radius.y / radius.x;
+                let _ = write!(
+                    geometry,
+                    r#" gradientTransform="translate(0 {}) scale(1 {})""#,
+                    center.y * (1.0 - scale),
+                    scale
+                );
+            }
+            ("radialGradient", geometry, stops)
+        }
+    };
+
+    // The position in the document is unique, so it makes a good id:
+    let id = format!("gradient{}", svg.len());
+    let _ = writeln!(
+        svg,
+        r#"<defs><{} id="{}" gradientUnits="objectBoundingBox" {}>"#,
+        element, id, geometry
+    );
+    for &(offset, color) in stops.as_slice() {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let _ = writeln!(
+            svg,
+            r##"<stop offset="{}" stop-color="#{:02x}{:02x}{:02x}" stop-opacity="{:.3}"/>"##,
+            offset,
+            r,
+            g,
+            b,
+            a as f32 / 255.0
+        );
+    }
+    let _ = writeln!(svg, "</{}></defs>", element);
+
+    format!(r#" fill="url(#{})""#, id)
+}
+
+fn stroke_attr(stroke: Stroke) -> String {
+    if stroke.width <= 0.0 || stroke.color.a() == 0 {
+        r#" stroke="none""#.to_owned()
+    } else {
+        let mut attr = format!(
+            r#"{} stroke-width="{}""#,
+            color_attr("stroke", stroke.color),
+            stroke.width
+        );
+        match stroke.cap {
+            LineCap::Butt => {}
+            LineCap::Round => attr += r#" stroke-linecap="round""#,
+            LineCap::Square => attr += r#" stroke-linecap="square""#,
+        }
+        match stroke.join {
+            LineJoin::Miter => {}
+            LineJoin::Round => attr += r#" stroke-linejoin="round""#,
+            LineJoin::Bevel => attr += r#" stroke-linejoin="bevel""#,
+        }
+        // Our default miter limit is the same as in SVG:
+        if stroke.miter_limit != Stroke::DEFAULT_MITER_LIMIT {
+            let _ = write!(attr, r#" stroke-miterlimit="{}""#, stroke.miter_limit);
+        }
+        if !stroke.dash.is_solid() {
+            let lengths: Vec<String> = stroke.dash.lengths().iter().map(f32::to_string).collect();
+            let _ = write!(attr, r#" stroke-dasharray="{}""#, lengths.join(" "));
+            if stroke.dash.offset != 0.0 {
+                let _ = write!(attr, r#" stroke-dashoffset="{}""#, stroke.dash.offset);
+            }
+        }
+        attr
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for chr in text.chars() {
+        match chr {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(chr),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plot::{Plot, PlotPoint, Polygon, Text};
+
+    /// A plot with the right half filled red and a text in the left half.
+    fn export() -> PlotExport {
+        let ctx = Context::default();
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(300.0, 200.0))),
+            ..Default::default()
+        };
+        let mut export = None;
+        let _ = ctx.run(raw_input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                let response = Plot::new("plot").export(true).show(ui, |plot_ui| {
+                    let right_half = vec![[0.0, -10.0], [10.0, -10.0], [10.0, 10.0], [0.0, 10.0]];
+                    plot_ui.polygon(Polygon::new(right_half).color(Color32::RED).fill_alpha(1.0));
+                    plot_ui.text(Text::new(PlotPoint::new(-5.0, 0.0), "Hello"));
+                });
+                export = response.export;
+            });
+        });
+        export.unwrap()
+    }
+
+    fn texts(shape: &Shape, out: &mut Vec<TextShape>) {
+        match shape {
+            Shape::Text(text_shape) => out.push(text_shape.clone()),
+            Shape::Vec(shapes) => shapes.iter().for_each(|shape| texts(shape, out)),
+            Shape::Group(group) => group.shapes.iter().for_each(|shape| texts(shape, out)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn svg() {
+        let export = export();
+        let svg = export.to_svg(2.0);
+        let [width, height] = export.size_in_pixels(2.0);
+        assert_eq!(width, (export.rect().width() * 2.0).round() as usize);
+        assert!(svg.starts_with(&format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}""#,
+            width, height
+        )));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains(r##"fill="#ff0000""##));
+        assert!(svg.contains(">Hello</text>"));
+    }
+
+    #[test]
+    fn color_image() {
+        let export = export();
+        let image = export.to_color_image(2.0);
+        assert_eq!(image.size, export.size_in_pixels(2.0));
+
+        let [width, height] = image.size;
+        let pixel = |x: f32, y: f32| {
+            image.pixels[(y * height as f32) as usize * width + (x * width as f32) as usize]
+        };
+        assert_eq!(pixel(0.75, 0.25), Color32::RED);
+        assert_ne!(pixel(0.25, 0.1), Color32::RED);
+    }
+
+    #[test]
+    fn text_is_laid_out_at_export_resolution() {
+        let export = export();
+        let mut shapes = vec![];
+        for ClippedShape(_, shape) in export.shapes() {
+            texts(shape, &mut shapes);
+        }
+        let hello = shapes
+            .into_iter()
+            .find(|text_shape| text_shape.galley.job.text == "Hello")
+            .unwrap();
+
+        let pixels_per_point = 3.0;
+        let fonts = Fonts::new(pixels_per_point, 2048, export.font_definitions.clone());
+        let mut relaid = vec![];
+        texts(
+            &relayout_text(&fonts, Shape::Text(hello.clone())),
+            &mut relaid,
+        );
+        let relaid = &relaid[0].galley;
+        // Glyph advances are rounded to whole pixels, so the width may change a little:
+        assert_eq!(relaid.size().y, hello.galley.size().y);
+        assert!((relaid.size().x - hello.galley.size().x).abs() < 2.0);
+
+        let glyph = &relaid.rows[0].glyphs[0];
+        let texels = glyph.uv_rect.max[1] - glyph.uv_rect.min[1];
+        let points = glyph.uv_rect.size.y;
+        assert!(
+            (texels as f32 - points * pixels_per_point).abs() <= 2.0,
+            "{} texels for {} points",
+            texels,
+            points
+        );
+    }
+}
@@ -130,7 +130,7 @@ impl Bar {
         let rect = Shape::Rect(RectShape {
             rect,
             rounding: Rounding::none(),
-            fill,
+            fill: fill.into(),
             stroke,
         });
 
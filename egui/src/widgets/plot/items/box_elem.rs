@@ -153,7 +153,7 @@ impl BoxElem {
         let rect = Shape::Rect(RectShape {
             rect,
             rounding: Rounding::none(),
-            fill,
+            fill: fill.into(),
             stroke,
         });
         shapes.push(rect);
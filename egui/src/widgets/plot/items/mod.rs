@@ -7,13 +7,16 @@ use epaint::Mesh;
 
 use crate::*;
 
-use super::{LabelFormatter, PlotBounds, ScreenTransform};
+use super::{ItemDrag, LabelFormatter, PlotBounds, ScreenTransform};
 use rect_elem::*;
 use values::{ClosestElem, PlotGeometry};
 
 pub use bar::Bar;
 pub use box_elem::{BoxElem, BoxSpread};
-pub use values::{LineStyle, MarkerShape, Orientation, PlotPoint, PlotPoints};
+pub use values::{
+    ErrorBars, LineStyle, MarkerShape, Orientation, PlotPoint, PlotPoints, PlotPointsBuffer,
+    PlotPointsRef,
+};
 
 mod bar;
 mod box_elem;
@@ -48,6 +51,40 @@ pub(super) trait PlotItem {
 
     fn get_bounds(&self) -> PlotBounds;
 
+    /// The id of this item, if the user is allowed to drag it.
+    fn drag_id(&self) -> Option<Id> {
+        None
+    }
+
+    /// Find the part of a draggable item closest to `point`, e.g. a point of a [`Line`].
+    fn find_drag_handle(&self, _point: Pos2, _transform: &ScreenTransform) -> Option<ClosestElem> {
+        None
+    }
+
+    /// Move the part found by [`Self::find_drag_handle`] to `value`.
+    fn drag_handle(&mut self, _index: usize, _value: PlotPoint) -> Option<ItemDrag> {
+        None
+    }
+
+    /// The cursor to show when hovering or dragging a handle of this item.
+    fn drag_cursor(&self) -> CursorIcon {
+        CursorIcon::Grab
+    }
+
+    /// The per-point errors of this item, shown in the hover label.
+    fn error_bars(&self) -> Option<&ErrorBars> {
+        None
+    }
+
+    /// The y value of this item at `x`, interpolated between its points.
+    /// Used by [`super::Plot::all_series_tooltip`].
+    fn value_at_x(&self, x: f64) -> Option<f64> {
+        match self.geometry() {
+            PlotGeometry::Points(points) => interpolate_y(&points, x),
+            PlotGeometry::None | PlotGeometry::Rects => None,
+        }
+    }
+
     fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
         match self.geometry() {
             PlotGeometry::None => None,
@@ -96,7 +133,18 @@ pub(super) trait PlotItem {
         let pointer = plot.transform.position_from_point(&value);
         shapes.push(Shape::circle_filled(pointer, 3.0, line_color));
 
-        rulers_at_value(pointer, value, self.name(), plot, shapes, label_formatter);
+        let errors = self
+            .error_bars()
+            .map_or([None; 2], |errors| errors.get(elem.index));
+        rulers_at_value(
+            pointer,
+            value,
+            errors,
+            self.name(),
+            plot,
+            shapes,
+            label_formatter,
+        );
     }
 }
 
@@ -110,6 +158,8 @@ pub struct HLine {
     pub(super) name: String,
     pub(super) highlight: bool,
     pub(super) style: LineStyle,
+    pub(super) id: Option<Id>,
+    pub(super) draggable: bool,
 }
 
 impl HLine {
@@ -120,6 +170,8 @@ impl HLine {
             name: String::default(),
             highlight: false,
             style: LineStyle::Solid,
+            id: None,
+            draggable: false,
         }
     }
 
@@ -153,6 +205,22 @@ impl HLine {
         self
     }
 
+    /// Identifies this line among the draggable items of the plot.
+    /// Defaults to an id derived from [`Self::name`].
+    pub fn id(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id_source));
+        self
+    }
+
+    /// Allow the user to drag this line with [`super::Plot::drag_pointer_button`].
+    ///
+    /// The new position is reported by [`super::PlotResponse::dragged_hline`]
+    /// and should be stored and passed to [`Self::new`] next frame. Default: `false`.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
     /// Name of this horizontal line.
     ///
     /// This name will show up in the plot legend, if legends are turned on.
@@ -210,6 +278,33 @@ impl PlotItem for HLine {
         bounds.max[1] = self.y;
         bounds
     }
+
+    fn drag_id(&self) -> Option<Id> {
+        self.draggable
+            .then(|| self.id.unwrap_or_else(|| Id::new(&self.name)))
+    }
+
+    fn find_drag_handle(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        let y = transform
+            .position_from_point(&PlotPoint::new(0.0, self.y))
+            .y;
+        Some(ClosestElem {
+            index: 0,
+            dist_sq: (point.y - y).powi(2),
+        })
+    }
+
+    fn drag_handle(&mut self, _index: usize, value: PlotPoint) -> Option<ItemDrag> {
+        self.y = value.y;
+        Some(ItemDrag::HLine {
+            id: self.drag_id()?,
+            y: self.y,
+        })
+    }
+
+    fn drag_cursor(&self) -> CursorIcon {
+        CursorIcon::ResizeVertical
+    }
 }
 
 /// A vertical line in a plot, filling the full width
@@ -220,6 +315,8 @@ pub struct VLine {
     pub(super) name: String,
     pub(super) highlight: bool,
     pub(super) style: LineStyle,
+    pub(super) id: Option<Id>,
+    pub(super) draggable: bool,
 }
 
 impl VLine {
@@ -230,6 +327,8 @@ impl VLine {
             name: String::default(),
             highlight: false,
             style: LineStyle::Solid,
+            id: None,
+            draggable: false,
         }
     }
 
@@ -263,6 +362,22 @@ impl VLine {
         self
     }
 
+    /// Identifies this line among the draggable items of the plot.
+    /// Defaults to an id derived from [`Self::name`].
+    pub fn id(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id_source));
+        self
+    }
+
+    /// Allow the user to drag this line with [`super::Plot::drag_pointer_button`].
+    ///
+    /// The new position is reported by [`super::PlotResponse::dragged_vline`]
+    /// and should be stored and passed to [`Self::new`] next frame. Default: `false`.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
     /// Name of this vertical line.
     ///
     /// This name will show up in the plot legend, if legends are turned on.
@@ -320,6 +435,33 @@ impl PlotItem for VLine {
         bounds.max[0] = self.x;
         bounds
     }
+
+    fn drag_id(&self) -> Option<Id> {
+        self.draggable
+            .then(|| self.id.unwrap_or_else(|| Id::new(&self.name)))
+    }
+
+    fn find_drag_handle(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        let x = transform
+            .position_from_point(&PlotPoint::new(self.x, 0.0))
+            .x;
+        Some(ClosestElem {
+            index: 0,
+            dist_sq: (point.x - x).powi(2),
+        })
+    }
+
+    fn drag_handle(&mut self, _index: usize, value: PlotPoint) -> Option<ItemDrag> {
+        self.x = value.x;
+        Some(ItemDrag::VLine {
+            id: self.drag_id()?,
+            x: self.x,
+        })
+    }
+
+    fn drag_cursor(&self) -> CursorIcon {
+        CursorIcon::ResizeHorizontal
+    }
 }
 
 /// A series of values forming a path.
@@ -330,6 +472,9 @@ pub struct Line {
     pub(super) highlight: bool,
     pub(super) fill: Option<f32>,
     pub(super) style: LineStyle,
+    pub(super) id: Option<Id>,
+    pub(super) draggable_points: bool,
+    pub(super) error_bars: Option<ErrorBars>,
 }
 
 impl Line {
@@ -341,6 +486,9 @@ impl Line {
             highlight: false,
             fill: None,
             style: LineStyle::Solid,
+            id: None,
+            draggable_points: false,
+            error_bars: None,
         }
     }
 
@@ -380,6 +528,30 @@ impl Line {
         self
     }
 
+    /// Draw error bars around the points of this line, in the color of the line.
+    pub fn error_bars(mut self, error_bars: ErrorBars) -> Self {
+        self.error_bars = Some(error_bars);
+        self
+    }
+
+    /// Identifies this line among the draggable items of the plot.
+    /// Defaults to an id derived from [`Self::name`].
+    pub fn id(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id_source));
+        self
+    }
+
+    /// Allow the user to drag the individual points of this line with
+    /// [`super::Plot::drag_pointer_button`], e.g. to edit a curve.
+    ///
+    /// Only works for owned points, not for generated or shared ones.
+    /// The moved point is reported by [`super::PlotResponse::dragged_line_point`]
+    /// and should be stored by you. Default: `false`.
+    pub fn draggable_points(mut self, draggable: bool) -> Self {
+        self.draggable_points = draggable;
+        self
+    }
+
     /// Name of this line.
     ///
     /// This name will show up in the plot legend, if legends are turned on.
@@ -408,11 +580,12 @@ impl PlotItem for Line {
             highlight,
             mut fill,
             style,
+            error_bars,
             ..
         } = self;
 
-        let values_tf: Vec<_> = series
-            .points()
+        let points = series.read();
+        let values_tf: Vec<_> = points
             .iter()
             .map(|v| transform.position_from_point(v))
             .collect();
@@ -457,6 +630,13 @@ impl PlotItem for Line {
             mesh.colored_vertex(pos2(last.x, y), fill_color);
             shapes.push(Shape::Mesh(mesh));
         }
+        if let Some(error_bars) = error_bars {
+            let mut error_stroke = *stroke;
+            if *highlight {
+                error_stroke.width *= 2.0;
+            }
+            error_bar_shapes(&points, error_bars, transform, error_stroke, shapes);
+        }
         style.style_line(values_tf, *stroke, *highlight, shapes);
     }
 
@@ -481,11 +661,40 @@ impl PlotItem for Line {
     }
 
     fn geometry(&self) -> PlotGeometry<'_> {
-        PlotGeometry::Points(self.series.points())
+        PlotGeometry::Points(self.series.read())
     }
 
     fn get_bounds(&self) -> PlotBounds {
-        self.series.get_bounds()
+        let mut bounds = self.series.get_bounds();
+        if let Some(error_bars) = &self.error_bars {
+            bounds.merge(&error_bars.get_bounds(&self.series.read()));
+        }
+        bounds
+    }
+
+    fn error_bars(&self) -> Option<&ErrorBars> {
+        self.error_bars.as_ref()
+    }
+
+    fn drag_id(&self) -> Option<Id> {
+        (self.draggable_points && matches!(self.series, PlotPoints::Owned(_)))
+            .then(|| self.id.unwrap_or_else(|| Id::new(&self.name)))
+    }
+
+    fn find_drag_handle(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        self.find_closest(point, transform)
+    }
+
+    fn drag_handle(&mut self, index: usize, value: PlotPoint) -> Option<ItemDrag> {
+        let id = self.drag_id()?;
+        if let PlotPoints::Owned(points) = &mut self.series {
+            *points.get_mut(index)? = value;
+        }
+        Some(ItemDrag::LinePoint {
+            id,
+            index,
+            point: value,
+        })
     }
 }
 
@@ -577,7 +786,7 @@ impl PlotItem for Polygon {
         }
 
         let mut values_tf: Vec<_> = series
-            .points()
+            .read()
             .iter()
             .map(|v| transform.position_from_point(v))
             .collect();
@@ -611,12 +820,241 @@ impl PlotItem for Polygon {
     }
 
     fn geometry(&self) -> PlotGeometry<'_> {
-        PlotGeometry::Points(self.series.points())
+        PlotGeometry::Points(self.series.read())
     }
 
     fn get_bounds(&self) -> PlotBounds {
         self.series.get_bounds()
     }
+
+    fn value_at_x(&self, _x: f64) -> Option<f64> {
+        None // A polygon is not a function of x.
+    }
+}
+
+/// The filled area between two series, e.g. a confidence band around a [`Line`].
+///
+/// The points of `lower` and `upper` are paired by index, so both should have the same number of
+/// points, usually at the same x values.
+pub struct Band {
+    pub(super) lower: PlotPoints,
+    pub(super) upper: PlotPoints,
+    pub(super) stroke: Stroke,
+    pub(super) name: String,
+    pub(super) highlight: bool,
+    pub(super) fill_alpha: f32,
+    pub(super) style: LineStyle,
+}
+
+impl Band {
+    pub fn new(lower: impl Into<PlotPoints>, upper: impl Into<PlotPoints>) -> Self {
+        Self {
+            lower: lower.into(),
+            upper: upper.into(),
+            stroke: Stroke::new(0.0, Color32::TRANSPARENT),
+            name: Default::default(),
+            highlight: false,
+            fill_alpha: 4.0 * DEFAULT_FILL_ALPHA,
+            style: LineStyle::Solid,
+        }
+    }
+
+    /// Highlight this band in the plot by scaling up the stroke and reducing the fill
+    /// transparency.
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Add a stroke along the lower and upper bounds. Default: no stroke.
+    pub fn stroke(mut self, stroke: impl Into<Stroke>) -> Self {
+        self.stroke = stroke.into();
+        self
+    }
+
+    /// Stroke width of the bounds. Default: `0.0`, i.e. only the area is filled.
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.stroke.width = width.into();
+        self
+    }
+
+    /// Color of the band. Default is `Color32::TRANSPARENT` which means a color will be auto-assigned.
+    ///
+    /// Use the color of a [`Line`] to show the band as part of it.
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.stroke.color = color.into();
+        self
+    }
+
+    /// Alpha of the filled area.
+    pub fn fill_alpha(mut self, alpha: impl Into<f32>) -> Self {
+        self.fill_alpha = alpha.into();
+        self
+    }
+
+    /// Set the style of the bounds. Default is `LineStyle::Solid`.
+    pub fn style(mut self, style: LineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Name of this band.
+    ///
+    /// This name will show up in the plot legend, if legends are turned on.
+    ///
+    /// Multiple plot items may share the same name, in which case they will also share an entry in
+    /// the legend.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+impl PlotItem for Band {
+    fn get_shapes(&self, _ui: &mut Ui, transform: &ScreenTransform, shapes: &mut Vec<Shape>) {
+        let Self {
+            lower,
+            upper,
+            stroke,
+            highlight,
+            mut fill_alpha,
+            style,
+            ..
+        } = self;
+
+        if *highlight {
+            fill_alpha = (2.0 * fill_alpha).at_most(1.0);
+        }
+
+        let lower_tf: Vec<_> = lower
+            .read()
+            .iter()
+            .map(|v| transform.position_from_point(v))
+            .collect();
+        let upper_tf: Vec<_> = upper
+            .read()
+            .iter()
+            .map(|v| transform.position_from_point(v))
+            .collect();
+        let n_values = lower_tf.len().min(upper_tf.len());
+
+        let fill_color = Rgba::from(stroke.color)
+            .to_opaque()
+            .multiply(fill_alpha)
+            .into();
+        let mut mesh = Mesh::default();
+        mesh.reserve_triangles(2 * n_values.saturating_sub(1));
+        mesh.reserve_vertices(2 * n_values);
+        for i in 0..n_values {
+            mesh.colored_vertex(lower_tf[i], fill_color);
+            mesh.colored_vertex(upper_tf[i], fill_color);
+            if i > 0 {
+                let idx = 2 * i as u32;
+                mesh.add_triangle(idx - 2, idx - 1, idx);
+                mesh.add_triangle(idx - 1, idx, idx + 1);
+            }
+        }
+        shapes.push(Shape::Mesh(mesh));
+
+        if stroke.width > 0.0 {
+            style.style_line(lower_tf, *stroke, *highlight, shapes);
+            style.style_line(upper_tf, *stroke, *highlight, shapes);
+        }
+    }
+
+    fn initialize(&mut self, x_range: RangeInclusive<f64>) {
+        self.lower.generate_points(x_range.clone());
+        self.upper.generate_points(x_range);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn color(&self) -> Color32 {
+        self.stroke.color
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::None
+    }
+
+    fn get_bounds(&self) -> PlotBounds {
+        let mut bounds = self.lower.get_bounds();
+        bounds.merge(&self.upper.get_bounds());
+        bounds
+    }
+
+    /// The points of `lower` come first, followed by the points of `upper`.
+    fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        let closest = |points: &[PlotPoint], offset: usize| {
+            points
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let pos = transform.position_from_point(value);
+                    let dist_sq = point.distance_sq(pos);
+                    ClosestElem {
+                        index: offset + index,
+                        dist_sq,
+                    }
+                })
+                .min_by_key(|e| e.dist_sq.ord())
+        };
+
+        // Only lock one series at a time: both may be the same `PlotPointsBuffer`,
+        // which a writer could lock in between.
+        let (closest_lower, lower_len) = {
+            let lower = self.lower.read();
+            (closest(&lower, 0), lower.len())
+        };
+        let closest_upper = closest(&self.upper.read(), lower_len);
+        closest_lower
+            .into_iter()
+            .chain(closest_upper)
+            .min_by_key(|e| e.dist_sq.ord())
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        // Only lock one series at a time, see `find_closest`.
+        let (lower_value, lower_len) = {
+            let lower = self.lower.read();
+            (lower.get(elem.index).copied(), lower.len())
+        };
+        let value =
+            match lower_value.or_else(|| self.upper.read().get(elem.index - lower_len).copied()) {
+                Some(value) => value,
+                None => return,
+            };
+
+        let pointer = plot.transform.position_from_point(&value);
+        shapes.push(Shape::circle_filled(pointer, 3.0, rulers_color(plot.ui)));
+
+        rulers_at_value(
+            pointer,
+            value,
+            [None; 2],
+            self.name(),
+            plot,
+            shapes,
+            label_formatter,
+        );
+    }
 }
 
 /// Text inside the plot.
@@ -748,6 +1186,7 @@ pub struct Points {
     pub(super) name: String,
     pub(super) highlight: bool,
     pub(super) stems: Option<f32>,
+    pub(super) error_bars: Option<ErrorBars>,
 }
 
 impl Points {
@@ -761,6 +1200,7 @@ impl Points {
             name: Default::default(),
             highlight: false,
             stems: None,
+            error_bars: None,
         }
     }
 
@@ -800,6 +1240,12 @@ impl Points {
         self
     }
 
+    /// Draw error bars around the points, in the color of the markers.
+    pub fn error_bars(mut self, error_bars: ErrorBars) -> Self {
+        self.error_bars = Some(error_bars);
+        self
+    }
+
     /// Name of this set of points.
     ///
     /// This name will show up in the plot legend, if legends are turned on.
@@ -827,6 +1273,7 @@ impl PlotItem for Points {
             mut radius,
             highlight,
             stems,
+            error_bars,
             ..
         } = self;
 
@@ -846,8 +1293,19 @@ impl PlotItem for Points {
 
         let y_reference = stems.map(|y| transform.position_from_point(&PlotPoint::new(0.0, y)).y);
 
-        series
-            .points()
+        let points = series.read();
+        if let Some(error_bars) = error_bars {
+            let width = if *highlight { 2.0 } else { 1.0 };
+            error_bar_shapes(
+                &points,
+                error_bars,
+                transform,
+                Stroke::new(width, *color),
+                shapes,
+            );
+        }
+
+        points
             .iter()
             .map(|value| transform.position_from_point(value))
             .for_each(|center| {
@@ -863,7 +1321,7 @@ impl PlotItem for Points {
                         shapes.push(Shape::Circle(epaint::CircleShape {
                             center,
                             radius,
-                            fill,
+                            fill: fill.into(),
                             stroke,
                         }));
                     }
@@ -962,11 +1420,19 @@ impl PlotItem for Points {
     }
 
     fn geometry(&self) -> PlotGeometry<'_> {
-        PlotGeometry::Points(self.series.points())
+        PlotGeometry::Points(self.series.read())
     }
 
     fn get_bounds(&self) -> PlotBounds {
-        self.series.get_bounds()
+        let mut bounds = self.series.get_bounds();
+        if let Some(error_bars) = &self.error_bars {
+            bounds.merge(&error_bars.get_bounds(&self.series.read()));
+        }
+        bounds
+    }
+
+    fn error_bars(&self) -> Option<&ErrorBars> {
+        self.error_bars.as_ref()
     }
 }
 
@@ -1026,10 +1492,12 @@ impl PlotItem for Arrows {
             ..
         } = self;
         let stroke = Stroke::new(if *highlight { 2.0 } else { 1.0 }, *color);
+        // Copy one side, so we never hold two locks at once (they may be the same buffer):
+        let tips = tips.read().to_vec();
         origins
-            .points()
+            .read()
             .iter()
-            .zip(tips.points().iter())
+            .zip(tips.iter())
             .map(|(origin, tip)| {
                 (
                     transform.position_from_point(origin),
@@ -1077,7 +1545,7 @@ impl PlotItem for Arrows {
     }
 
     fn geometry(&self) -> PlotGeometry<'_> {
-        PlotGeometry::Points(self.origins.points())
+        PlotGeometry::Points(self.origins.read())
     }
 
     fn get_bounds(&self) -> PlotBounds {
@@ -1547,7 +2015,7 @@ impl PlotItem for BoxPlot {
 // ----------------------------------------------------------------------------
 // Helper functions
 
-fn rulers_color(ui: &Ui) -> Color32 {
+pub(super) fn rulers_color(ui: &Ui) -> Color32 {
     if ui.visuals().dark_mode {
         Color32::from_gray(100).additive()
     } else {
@@ -1653,6 +2121,7 @@ fn add_rulers_and_text(
 pub(super) fn rulers_at_value(
     pointer: Pos2,
     value: PlotPoint,
+    errors: [Option<[f64; 2]>; 2],
     name: &str,
     plot: &PlotConfig<'_>,
     shapes: &mut Vec<Shape>,
@@ -1676,17 +2145,16 @@ pub(super) fn rulers_at_value(
         let scale = plot.transform.dvalue_dpos();
         let x_decimals = ((-scale[0].abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
         let y_decimals = ((-scale[1].abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
+        let x = format_with_error(value.x, errors[0], x_decimals);
+        let y = format_with_error(value.y, errors[1], y_decimals);
         if let Some(custom_label) = label_formatter {
             custom_label(name, &value)
         } else if plot.show_x && plot.show_y {
-            format!(
-                "{}x = {:.*}\ny = {:.*}",
-                prefix, x_decimals, value.x, y_decimals, value.y
-            )
+            format!("{}x = {}\ny = {}", prefix, x, y)
         } else if plot.show_x {
-            format!("{}x = {:.*}", prefix, x_decimals, value.x)
+            format!("{}x = {}", prefix, x)
         } else if plot.show_y {
-            format!("{}y = {:.*}", prefix, y_decimals, value.y)
+            format!("{}y = {}", prefix, y)
         } else {
             unreachable!()
         }
@@ -1704,6 +2172,95 @@ pub(super) fn rulers_at_value(
     ));
 }
 
+/// Linear interpolation of the y value at `x`, on the first segment of `points` that spans `x`.
+fn interpolate_y(points: &[PlotPoint], x: f64) -> Option<f64> {
+    if let [point] = points {
+        return (point.x == x).then(|| point.y);
+    }
+    points.windows(2).find_map(|segment| {
+        let (a, b) = (segment[0], segment[1]);
+        if a.x.min(b.x) <= x && x <= a.x.max(b.x) {
+            if a.x == b.x {
+                Some(a.y)
+            } else {
+                Some(a.y + (b.y - a.y) * (x - a.x) / (b.x - a.x))
+            }
+        } else {
+            None
+        }
+    })
+}
+
+fn format_with_error(value: f64, error: Option<[f64; 2]>, decimals: usize) -> String {
+    match error {
+        None => format!("{:.*}", decimals, value),
+        Some([below, above]) if below == above => {
+            format!("{:.*} ± {:.*}", decimals, value, decimals, above)
+        }
+        Some([below, above]) => format!(
+            "{:.*} -{:.*} +{:.*}",
+            decimals, value, decimals, below, decimals, above
+        ),
+    }
+}
+
+/// Draws whiskers with caps for the points that have an error in `errors`.
+fn error_bar_shapes(
+    points: &[PlotPoint],
+    errors: &ErrorBars,
+    transform: &ScreenTransform,
+    stroke: Stroke,
+    shapes: &mut Vec<Shape>,
+) {
+    let half_cap = 0.5 * errors.cap_width;
+    for (index, point) in points.iter().enumerate() {
+        let [x, y] = errors.get(index);
+        if let Some([below, above]) = x {
+            let left = transform.position_from_point(&PlotPoint::new(point.x - below, point.y));
+            let right = transform.position_from_point(&PlotPoint::new(point.x + above, point.y));
+            shapes.push(Shape::line_segment([left, right], stroke));
+            if half_cap > 0.0 {
+                for end in [left, right] {
+                    let cap = [end - vec2(0.0, half_cap), end + vec2(0.0, half_cap)];
+                    shapes.push(Shape::line_segment(cap, stroke));
+                }
+            }
+        }
+        if let Some([below, above]) = y {
+            let bottom = transform.position_from_point(&PlotPoint::new(point.x, point.y - below));
+            let top = transform.position_from_point(&PlotPoint::new(point.x, point.y + above));
+            shapes.push(Shape::line_segment([bottom, top], stroke));
+            if half_cap > 0.0 {
+                for end in [bottom, top] {
+                    let cap = [end - vec2(half_cap, 0.0), end + vec2(half_cap, 0.0)];
+                    shapes.push(Shape::line_segment(cap, stroke));
+                }
+            }
+        }
+    }
+}
+
+/// The draggable item part closest to `pointer`, if it is close enough to be grabbed.
+pub(super) fn find_drag_handle(
+    items: &[Box<dyn PlotItem>],
+    pointer: Pos2,
+    transform: &ScreenTransform,
+    grab_radius: f32,
+) -> Option<(Id, usize)> {
+    let grab_radius_sq = grab_radius * grab_radius;
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let id = item.drag_id()?;
+            let elem = item.find_drag_handle(pointer, transform)?;
+            Some((id, elem))
+        })
+        .min_by_key(|(_, elem)| elem.dist_sq.ord())
+        .filter(|(_, elem)| elem.dist_sq <= grab_radius_sq)
+        .map(|(id, elem)| (id, elem.index))
+}
+
 fn find_closest_rect<'a, T>(
     rects: impl IntoIterator<Item = &'a T>,
     point: Pos2,
@@ -1723,3 +2280,112 @@ where
         })
         .min_by_key(|e| e.dist_sq.ord())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps `x, y ∈ [0, 10]` to a 100 × 100 points square.
+    fn transform() -> ScreenTransform {
+        let frame = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+        let bounds = PlotBounds {
+            min: [0.0, 0.0],
+            max: [10.0, 10.0],
+        };
+        ScreenTransform::new(frame, bounds, false, false)
+    }
+
+    #[test]
+    fn error_bars_extend_bounds() {
+        let errors = ErrorBars::new()
+            .x_asymmetric([[0.1, 0.2], [0.0, 0.3]])
+            .y([0.5]);
+        let line = Line::new(vec![[0.0, 0.0], [1.0, 1.0]]).error_bars(errors);
+        let bounds = line.get_bounds();
+        assert_eq!(bounds.min(), [-0.1, -0.5]);
+        assert_eq!(bounds.max(), [1.3, 1.0]);
+    }
+
+    #[test]
+    fn error_bar_whiskers() {
+        let points = [PlotPoint::new(1.0, 1.0), PlotPoint::new(2.0, 2.0)];
+        let errors = ErrorBars::new().x([0.5, 0.5]).y([1.0]);
+
+        let mut shapes = vec![];
+        error_bar_shapes(
+            &points,
+            &errors,
+            &transform(),
+            Stroke::default(),
+            &mut shapes,
+        );
+        assert_eq!(shapes.len(), 3 * 3, "a line and two caps per whisker");
+
+        let mut shapes = vec![];
+        let errors = errors.cap_width(0.0);
+        error_bar_shapes(
+            &points,
+            &errors,
+            &transform(),
+            Stroke::default(),
+            &mut shapes,
+        );
+        assert_eq!(shapes.len(), 3);
+        let expected = [pos2(5.0, 90.0), pos2(15.0, 90.0)];
+        assert!(
+            matches!(shapes[0], Shape::LineSegment { points, .. } if points == expected),
+            "the x whisker of the first point spans ±0.5"
+        );
+    }
+
+    #[test]
+    fn interpolate_y_between_points() {
+        let points = [
+            PlotPoint::new(0.0, 0.0),
+            PlotPoint::new(2.0, 4.0),
+            PlotPoint::new(1.0, 10.0),
+            PlotPoint::new(1.0, 20.0),
+        ];
+        assert_eq!(interpolate_y(&points, 0.5), Some(1.0));
+        assert_eq!(interpolate_y(&points, 2.0), Some(4.0));
+        assert_eq!(interpolate_y(&points, 3.0), None);
+        assert_eq!(interpolate_y(&points, -0.1), None);
+        // The first segment that spans `x` wins, also when going backwards:
+        assert_eq!(interpolate_y(&points[1..], 1.5), Some(7.0));
+        // A vertical segment gives its first point:
+        assert_eq!(interpolate_y(&points[2..], 1.0), Some(10.0));
+
+        assert_eq!(interpolate_y(&points[..1], 0.0), Some(0.0));
+        assert_eq!(interpolate_y(&points[..1], 1.0), None);
+        assert_eq!(interpolate_y(&[], 0.0), None);
+    }
+
+    #[test]
+    fn band_find_closest() {
+        let band = Band::new(
+            vec![[0.0, 0.0], [5.0, 0.0], [10.0, 0.0]],
+            vec![[0.0, 10.0], [5.0, 10.0], [10.0, 10.0]],
+        );
+        let transform = transform();
+        let near_upper_middle = transform.position_from_point(&PlotPoint::new(5.0, 9.0));
+        let closest = band.find_closest(near_upper_middle, &transform).unwrap();
+        assert_eq!(
+            closest.index, 4,
+            "the points of upper come after the points of lower"
+        );
+        assert_eq!(band.get_bounds().max(), [10.0, 10.0]);
+    }
+
+    #[test]
+    fn band_of_one_buffer() {
+        // Both sides may be the same buffer, e.g. for a band that is only a line.
+        let buffer = PlotPointsBuffer::new(4);
+        buffer.extend([[0.0, 1.0], [10.0, 2.0]]);
+        let band = Band::new(&buffer, &buffer);
+        let transform = transform();
+        let pointer = transform.position_from_point(&PlotPoint::new(10.0, 2.0));
+        let closest = band.find_closest(pointer, &transform).unwrap();
+        assert_eq!(closest.index, 1);
+        assert_eq!(closest.dist_sq, 0.0);
+    }
+}
@@ -1,5 +1,7 @@
+use epaint::mutex::{RwLock, RwLockReadGuard};
 use epaint::{Pos2, Shape, Stroke, Vec2};
-use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::ops::{Bound, Deref, RangeBounds, RangeInclusive};
+use std::sync::Arc;
 
 use crate::plot::transform::PlotBounds;
 
@@ -8,6 +10,7 @@ use crate::plot::transform::PlotBounds;
 /// Uses f64 for improved accuracy to enable plotting
 /// large values (e.g. unix time on x axis).
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlotPoint {
     /// This is often something monotonically increasing, such as time, but doesn't have to be.
     /// Goes from left to right.
@@ -150,10 +153,13 @@ impl Default for Orientation {
 
 /// Represents many [`PlotPoint`]s.
 ///
-/// These can be an owned `Vec` or generated with a function.
+/// These can be an owned `Vec`, generated with a function, or borrowed from a shared
+/// [`PlotPointsBuffer`].
 pub enum PlotPoints {
     Owned(Vec<PlotPoint>),
     Generator(ExplicitGenerator),
+    /// Points streamed into a ring buffer. They are read in place, without copying.
+    Shared(PlotPointsBuffer),
     // Borrowed(&[PlotPoint]), // TODO: Lifetimes are tricky in this case.
 }
 
@@ -175,6 +181,18 @@ impl From<Vec<[f64; 2]>> for PlotPoints {
     }
 }
 
+impl From<PlotPointsBuffer> for PlotPoints {
+    fn from(buffer: PlotPointsBuffer) -> Self {
+        Self::Shared(buffer)
+    }
+}
+
+impl From<&PlotPointsBuffer> for PlotPoints {
+    fn from(buffer: &PlotPointsBuffer) -> Self {
+        Self::Shared(buffer.clone())
+    }
+}
+
 impl FromIterator<[f64; 2]> for PlotPoints {
     fn from_iter<T: IntoIterator<Item = [f64; 2]>>(iter: T) -> Self {
         Self::Owned(iter.into_iter().map(|point| point.into()).collect())
@@ -186,10 +204,27 @@ impl PlotPoints {
         Self::from_iter(points)
     }
 
+    /// The owned points.
+    ///
+    /// Returns an empty slice for points that are generated by a function (before the plot is
+    /// shown) or stored in a [`PlotPointsBuffer`].
+    #[deprecated = "Use PlotPoints::read, which also gives the points of a PlotPointsBuffer"]
     pub fn points(&self) -> &[PlotPoint] {
         match self {
             PlotPoints::Owned(points) => points.as_slice(),
-            PlotPoints::Generator(_) => &[],
+            PlotPoints::Generator(_) | PlotPoints::Shared(_) => &[],
+        }
+    }
+
+    /// Access all available points, locking the buffer if they are shared.
+    ///
+    /// Points that are generated by a function are empty until the plot is shown.
+    /// Don't hold on to the returned value, since it blocks [`PlotPointsBuffer::push`].
+    pub fn read(&self) -> PlotPointsRef<'_> {
+        match self {
+            PlotPoints::Owned(points) => PlotPointsRef::Slice(points),
+            PlotPoints::Generator(_) => PlotPointsRef::Slice(&[]),
+            PlotPoints::Shared(buffer) => PlotPointsRef::Shared(buffer.read()),
         }
     }
 
@@ -269,6 +304,7 @@ impl PlotPoints {
         match self {
             PlotPoints::Owned(points) => points.is_empty(),
             PlotPoints::Generator(_) => false,
+            PlotPoints::Shared(buffer) => buffer.is_empty(),
         }
     }
 
@@ -304,18 +340,250 @@ impl PlotPoints {
 
     pub(super) fn get_bounds(&self) -> PlotBounds {
         match self {
-            PlotPoints::Owned(points) => {
+            PlotPoints::Generator(generator) => generator.estimate_bounds(),
+            _ => {
                 let mut bounds = PlotBounds::NOTHING;
-                for point in points {
+                for point in self.read().iter() {
                     bounds.extend_with(point);
                 }
                 bounds
             }
-            PlotPoints::Generator(generator) => generator.estimate_bounds(),
         }
     }
 }
 
+/// Borrowed access to the points of a [`PlotPoints`], see [`PlotPoints::read`].
+///
+/// Dereferences to a slice of the points.
+pub enum PlotPointsRef<'a> {
+    Slice(&'a [PlotPoint]),
+    Shared(RwLockReadGuard<'a, [PlotPoint]>),
+}
+
+impl Deref for PlotPointsRef<'_> {
+    type Target = [PlotPoint];
+
+    fn deref(&self) -> &[PlotPoint] {
+        match self {
+            Self::Slice(points) => points,
+            Self::Shared(points) => points,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A fixed-capacity ring buffer of [`PlotPoint`]s for streaming data into a plot.
+///
+/// Cloning a [`PlotPointsBuffer`] gives another handle to the same data, so one handle can be
+/// moved to a producer thread which calls [`Self::push`], while another is passed to
+/// [`super::Line`] or [`super::Points`] every frame. The plot reads the points in place
+/// without copying them. Once the buffer is full, the oldest points are dropped.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{Line, Plot, PlotPointsBuffer};
+/// let buffer = PlotPointsBuffer::new(1000);
+/// for i in 0..100 {
+///     let x = i as f64 * 0.1;
+///     buffer.push([x, x.sin()]);
+/// }
+/// Plot::new("telemetry")
+///     .follow_latest(5.0)
+///     .show(ui, |plot_ui| plot_ui.line(Line::new(&buffer)));
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct PlotPointsBuffer {
+    ring: Arc<RwLock<RingBuffer>>,
+}
+
+impl PlotPointsBuffer {
+    /// Create an empty buffer holding at most `capacity` points.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ring: Arc::new(RwLock::new(RingBuffer::new(capacity))),
+        }
+    }
+
+    /// Append a point, dropping the oldest one if the buffer is full.
+    pub fn push(&self, point: impl Into<PlotPoint>) {
+        self.ring.write().push(point.into());
+    }
+
+    /// Append many points while only locking the buffer once.
+    pub fn extend<P: Into<PlotPoint>>(&self, points: impl IntoIterator<Item = P>) {
+        let mut ring = self.ring.write();
+        for point in points {
+            ring.push(point.into());
+        }
+    }
+
+    /// Remove all points.
+    pub fn clear(&self) {
+        self.ring.write().clear();
+    }
+
+    /// The number of points currently stored.
+    pub fn len(&self) -> usize {
+        self.ring.read().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of points kept.
+    pub fn capacity(&self) -> usize {
+        self.ring.read().capacity
+    }
+
+    /// The most recently pushed point, if any.
+    pub fn latest(&self) -> Option<PlotPoint> {
+        self.read().last().copied()
+    }
+
+    /// Lock the buffer and access the points, oldest first.
+    ///
+    /// The producer will block on [`Self::push`] while the returned guard is alive.
+    pub fn read(&self) -> RwLockReadGuard<'_, [PlotPoint]> {
+        RwLockReadGuard::map(self.ring.read(), |ring| ring.as_slice())
+    }
+}
+
+/// Each point is written twice, `capacity` apart, so that the stored points
+/// always form one contiguous slice.
+struct RingBuffer {
+    data: Vec<PlotPoint>,
+    capacity: usize,
+    start: usize,
+    len: usize,
+}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: vec![PlotPoint::new(0.0, 0.0); 2 * capacity],
+            capacity,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, point: PlotPoint) {
+        if self.capacity == 0 {
+            return;
+        }
+        let index = (self.start + self.len) % self.capacity;
+        self.data[index] = point;
+        self.data[index + self.capacity] = point;
+        if self.len < self.capacity {
+            self.len += 1;
+        } else {
+            self.start = (self.start + 1) % self.capacity;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    fn as_slice(&self) -> &[PlotPoint] {
+        &self.data[self.start..self.start + self.len]
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Per-point errors, drawn as whiskers by [`super::Line::error_bars`] and [`super::Points::error_bars`].
+///
+/// The errors are matched to the points of the series by index.
+/// Points without a matching error get no whisker.
+///
+/// ```
+/// # use egui::plot::ErrorBars;
+/// let errors = ErrorBars::new()
+///     .y([0.1, 0.2, 0.1])
+///     .x_asymmetric([[0.5, 1.0], [0.5, 1.0], [0.5, 1.0]]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorBars {
+    /// Distance below and above each x value.
+    pub(crate) x: Vec<[f64; 2]>,
+    /// Distance below and above each y value.
+    pub(crate) y: Vec<[f64; 2]>,
+    pub(crate) cap_width: f32,
+}
+
+impl Default for ErrorBars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorBars {
+    pub fn new() -> Self {
+        Self {
+            x: Vec::new(),
+            y: Vec::new(),
+            cap_width: 6.0,
+        }
+    }
+
+    /// Symmetric errors along x, one per point.
+    pub fn x(mut self, errors: impl IntoIterator<Item = f64>) -> Self {
+        self.x = errors.into_iter().map(|e| [e, e]).collect();
+        self
+    }
+
+    /// Asymmetric errors along x, one `[below, above]` pair per point.
+    pub fn x_asymmetric(mut self, errors: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        self.x = errors.into_iter().collect();
+        self
+    }
+
+    /// Symmetric errors along y, one per point.
+    pub fn y(mut self, errors: impl IntoIterator<Item = f64>) -> Self {
+        self.y = errors.into_iter().map(|e| [e, e]).collect();
+        self
+    }
+
+    /// Asymmetric errors along y, one `[below, above]` pair per point.
+    pub fn y_asymmetric(mut self, errors: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        self.y = errors.into_iter().collect();
+        self
+    }
+
+    /// Width of the caps at the ends of the whiskers, in points. Default: `6.0`.
+    pub fn cap_width(mut self, width: impl Into<f32>) -> Self {
+        self.cap_width = width.into();
+        self
+    }
+
+    /// The `[below, above]` errors along x and y of the point with the given index.
+    pub(crate) fn get(&self, index: usize) -> [Option<[f64; 2]>; 2] {
+        [self.x.get(index).copied(), self.y.get(index).copied()]
+    }
+
+    /// The bounds of the whiskers around the given points.
+    pub(crate) fn get_bounds(&self, points: &[PlotPoint]) -> PlotBounds {
+        let mut bounds = PlotBounds::NOTHING;
+        for (index, point) in points.iter().enumerate() {
+            let [x, y] = self.get(index);
+            if let Some([below, above]) = x {
+                bounds.extend_with_x(point.x - below);
+                bounds.extend_with_x(point.x + above);
+            }
+            if let Some([below, above]) = y {
+                bounds.extend_with_y(point.y - below);
+                bounds.extend_with_y(point.y + above);
+            }
+        }
+        bounds
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -360,7 +628,7 @@ pub(crate) enum PlotGeometry<'a> {
     None,
 
     /// Point values (X-Y graphs)
-    Points(&'a [PlotPoint]),
+    Points(PlotPointsRef<'a>),
 
     /// Rectangles (examples: boxes or bars)
     // Has currently no data, as it would require copying rects or iterating a list of pointers.
@@ -430,3 +698,49 @@ pub(crate) struct ClosestElem {
     /// Squared distance from the mouse cursor (needed to compare against other PlotItems, which might be nearer)
     pub dist_sq: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_latest_points_in_order() {
+        let buffer = PlotPointsBuffer::new(3);
+        assert!(buffer.is_empty());
+
+        buffer.extend((0..5).map(|i| [i as f64, 0.0]));
+        let xs: Vec<f64> = buffer.read().iter().map(|p| p.x).collect();
+        assert_eq!(xs, vec![2.0, 3.0, 4.0]);
+        assert_eq!(buffer.latest(), Some(PlotPoint::new(4.0, 0.0)));
+
+        buffer.clear();
+        buffer.push([7.0, 1.0]);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.read()[0], PlotPoint::new(7.0, 1.0));
+    }
+
+    #[test]
+    fn shared_points_are_read_in_place() {
+        let buffer = PlotPointsBuffer::new(4);
+        buffer.extend([[1.0, 2.0], [3.0, 4.0]]);
+        let points = PlotPoints::from(&buffer);
+        assert_eq!(
+            &*points.read(),
+            &[PlotPoint::new(1.0, 2.0), PlotPoint::new(3.0, 4.0)]
+        );
+    }
+
+    #[test]
+    fn follow_latest_without_data() {
+        let mut bounds = PlotPoints::from(&PlotPointsBuffer::new(4)).get_bounds();
+        bounds.follow_latest_x(5.0);
+        assert_eq!(bounds.min(), PlotBounds::NOTHING.min());
+
+        let buffer = PlotPointsBuffer::new(4);
+        buffer.extend([[1.0, 0.0], [10.0, 1.0]]);
+        let mut bounds = PlotPoints::from(&buffer).get_bounds();
+        bounds.follow_latest_x(5.0);
+        assert_eq!(bounds.min()[0], 5.0);
+        assert_eq!(bounds.max()[0], 10.0);
+    }
+}
@@ -121,7 +121,7 @@ impl LegendEntry {
         painter.add(epaint::CircleShape {
             center: icon_rect.center(),
             radius: icon_size * 0.5,
-            fill: visuals.bg_fill,
+            fill: visuals.fill_brush(),
             stroke: visuals.bg_stroke,
         });
 
@@ -242,7 +242,7 @@ impl Widget for &mut LegendWidget {
                     inner_margin: vec2(8.0, 4.0).into(),
                     rounding: ui.style().visuals.window_rounding,
                     shadow: epaint::Shadow::default(),
-                    fill: ui.style().visuals.extreme_bg_color,
+                    fill: ui.style().visuals.extreme_bg_color.into(),
                     stroke: ui.style().visuals.window_stroke(),
                     ..Default::default()
                 }
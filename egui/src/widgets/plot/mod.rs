@@ -11,13 +11,16 @@ use items::PlotItem;
 use legend::LegendWidget;
 use transform::ScreenTransform;
 
+pub use export::PlotExport;
 pub use items::{
-    Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, HLine, Line, LineStyle, MarkerShape,
-    Orientation, PlotImage, PlotPoint, PlotPoints, Points, Polygon, Text, VLine,
+    Arrows, Band, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, ErrorBars, HLine, Line, LineStyle,
+    MarkerShape, Orientation, PlotImage, PlotPoint, PlotPoints, PlotPointsBuffer, PlotPointsRef,
+    Points, Polygon, Text, VLine,
 };
 pub use legend::{Corner, Legend};
 pub use transform::PlotBounds;
 
+mod export;
 mod items;
 mod legend;
 mod transform;
@@ -101,6 +104,11 @@ struct PlotMemory {
     last_screen_transform: ScreenTransform,
     /// Allows to remember the first click position when performing a boxed zoom
     last_click_pos_for_zoom: Option<Pos2>,
+    /// The item and the part of it (e.g. point index) currently being dragged by the user.
+    dragged_item: Option<(Id, usize)>,
+    /// Where the current range selection started, in plot coordinates.
+    selection_start: Option<PlotPoint>,
+    selection: Option<PlotBounds>,
 }
 
 impl PlotMemory {
@@ -115,6 +123,16 @@ impl PlotMemory {
 
 // ----------------------------------------------------------------------------
 
+/// The hovered plot of a [`LinkedAxisGroup`], to find the x value under the pointer.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct LinkedCursor {
+    id: Id,
+    /// Where the plot is on screen.
+    frame: Rect,
+    /// The x-range shown in [`Self::frame`].
+    x_range: [f64; 2],
+}
+
 /// Defines how multiple plots share the same range for one or both of their axes. Can be added while building
 /// a plot with [`Plot::link_axis`]. Contains an internal state, meaning that this object should be stored by
 /// the user between frames.
@@ -122,7 +140,9 @@ impl PlotMemory {
 pub struct LinkedAxisGroup {
     pub(crate) link_x: bool,
     pub(crate) link_y: bool,
+    pub(crate) link_cursor: bool,
     pub(crate) bounds: Rc<Cell<Option<PlotBounds>>>,
+    pub(crate) cursor: Rc<Cell<Option<LinkedCursor>>>,
 }
 
 impl LinkedAxisGroup {
@@ -130,7 +150,9 @@ impl LinkedAxisGroup {
         Self {
             link_x,
             link_y,
+            link_cursor: false,
             bounds: Rc::new(Cell::new(None)),
+            cursor: Rc::new(Cell::new(None)),
         }
     }
 
@@ -161,6 +183,12 @@ impl LinkedAxisGroup {
         self.link_y = link;
     }
 
+    /// Change whether hovering one plot of this group shows a cursor at the same x value in the other plots.
+    /// Default: `false`.
+    pub fn set_link_cursor(&mut self, link: bool) {
+        self.link_cursor = link;
+    }
+
     fn get(&self) -> Option<PlotBounds> {
         self.bounds.get()
     }
@@ -168,6 +196,144 @@ impl LinkedAxisGroup {
     fn set(&self, bounds: PlotBounds) {
         self.bounds.set(Some(bounds));
     }
+
+    /// The x value under the `pointer` in another plot of this group.
+    ///
+    /// Uses the current pointer position, so plots shown before the hovered one don't lag behind.
+    fn cursor_for(&self, plot_id: Id, pointer: Option<Pos2>) -> Option<f64> {
+        match self.cursor.get() {
+            Some(cursor) if self.link_cursor && cursor.id != plot_id => {
+                let frame = cursor.frame;
+                let [min_x, max_x] = cursor.x_range;
+                let pointer = pointer.filter(|pointer| frame.contains(*pointer))?;
+                Some(remap(
+                    pointer.x as f64,
+                    (frame.left() as f64)..=(frame.right() as f64),
+                    min_x..=max_x,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn set_cursor(&self, plot_id: Id, hovered: bool, transform: &ScreenTransform) {
+        if hovered {
+            let bounds = transform.bounds();
+            self.cursor.set(Some(LinkedCursor {
+                id: plot_id,
+                frame: *transform.frame(),
+                x_range: [bounds.min[0], bounds.max[0]],
+            }));
+        } else if matches!(self.cursor.get(), Some(cursor) if cursor.id == plot_id) {
+            self.cursor.set(None);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Which axes a range selected by the user spans. See [`Plot::selection`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Select a range of x values, spanning all y values.
+    X,
+
+    /// Select a range of y values, spanning all x values.
+    Y,
+
+    /// Select a rectangular region.
+    XY,
+}
+
+impl SelectionMode {
+    fn bounds(self, start: PlotPoint, end: PlotPoint) -> PlotBounds {
+        let mut bounds = PlotBounds::NOTHING;
+        bounds.extend_with(&start);
+        bounds.extend_with(&end);
+        match self {
+            Self::X => {
+                bounds.min[1] = f64::NEG_INFINITY;
+                bounds.max[1] = f64::INFINITY;
+            }
+            Self::Y => {
+                bounds.min[0] = f64::NEG_INFINITY;
+                bounds.max[0] = f64::INFINITY;
+            }
+            Self::XY => {}
+        }
+        bounds
+    }
+}
+
+/// A plot item that was dragged by the user this frame. See [`PlotResponse::dragged_item`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemDrag {
+    /// A draggable [`HLine`] was moved to `y`.
+    HLine { id: Id, y: f64 },
+
+    /// A draggable [`VLine`] was moved to `x`.
+    VLine { id: Id, x: f64 },
+
+    /// The point at `index` of a [`Line`] with draggable points was moved to `point`.
+    LinePoint {
+        id: Id,
+        index: usize,
+        point: PlotPoint,
+    },
+}
+
+/// What [`Plot::show`] returns.
+pub struct PlotResponse<R> {
+    /// What the user closure returned.
+    pub inner: R,
+
+    /// The response of the plot area.
+    pub response: Response,
+
+    /// The item the user is dragging this frame, if any.
+    ///
+    /// See [`HLine::draggable`], [`VLine::draggable`] and [`Line::draggable_points`].
+    pub dragged_item: Option<ItemDrag>,
+
+    /// The range selected by the user, if any. See [`Plot::selection`].
+    ///
+    /// Axes that are not part of the [`SelectionMode`] span from negative to positive infinity.
+    pub selection: Option<PlotBounds>,
+
+    /// Everything the plot painted this frame, if [`Plot::export`] is enabled.
+    pub export: Option<PlotExport>,
+}
+
+impl<R> PlotResponse<R> {
+    /// The new y value, if the draggable [`HLine`] with this id was dragged this frame.
+    pub fn dragged_hline(&self, id_source: impl std::hash::Hash) -> Option<f64> {
+        match self.dragged_item {
+            Some(ItemDrag::HLine { id, y }) if id == Id::new(id_source) => Some(y),
+            _ => None,
+        }
+    }
+
+    /// The new x value, if the draggable [`VLine`] with this id was dragged this frame.
+    pub fn dragged_vline(&self, id_source: impl std::hash::Hash) -> Option<f64> {
+        match self.dragged_item {
+            Some(ItemDrag::VLine { id, x }) if id == Id::new(id_source) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// The index and new position of the point, if a point of the [`Line`] with this id was
+    /// dragged this frame.
+    pub fn dragged_line_point(
+        &self,
+        id_source: impl std::hash::Hash,
+    ) -> Option<(usize, PlotPoint)> {
+        match self.dragged_item {
+            Some(ItemDrag::LinePoint { id, index, point }) if id == Id::new(id_source) => {
+                Some((index, point))
+            }
+            _ => None,
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -194,11 +360,15 @@ pub struct Plot {
     center_y_axis: bool,
     allow_zoom: bool,
     allow_drag: bool,
+    drag_pointer_button: PointerButton,
     allow_scroll: bool,
     min_auto_bounds: PlotBounds,
     margin_fraction: Vec2,
+    follow_latest: Option<f64>,
     allow_boxed_zoom: bool,
     boxed_zoom_pointer_button: PointerButton,
+    selection_mode: Option<SelectionMode>,
+    selection_pointer_button: PointerButton,
     linked_axes: Option<LinkedAxisGroup>,
 
     min_size: Vec2,
@@ -216,6 +386,8 @@ pub struct Plot {
     show_background: bool,
     show_axes: [bool; 2],
     grid_spacers: [GridSpacer; 2],
+    all_series_tooltip: bool,
+    export: bool,
 }
 
 impl Plot {
@@ -228,11 +400,15 @@ impl Plot {
             center_y_axis: false,
             allow_zoom: true,
             allow_drag: true,
+            drag_pointer_button: PointerButton::Primary,
             allow_scroll: true,
             min_auto_bounds: PlotBounds::NOTHING,
             margin_fraction: Vec2::splat(0.05),
+            follow_latest: None,
             allow_boxed_zoom: true,
             boxed_zoom_pointer_button: PointerButton::Secondary,
+            selection_mode: None,
+            selection_pointer_button: PointerButton::Primary,
             linked_axes: None,
 
             min_size: Vec2::splat(64.0),
@@ -250,6 +426,8 @@ impl Plot {
             show_background: true,
             show_axes: [true; 2],
             grid_spacers: [log_grid_spacer(10), log_grid_spacer(10)],
+            all_series_tooltip: false,
+            export: false,
         }
     }
 
@@ -335,6 +513,19 @@ impl Plot {
         self
     }
 
+    /// While the x-axis is automatically bounded, only show the last `x_width` plot units,
+    /// ending at the largest x value of all items.
+    ///
+    /// This scrolls the plot along with incoming data, e.g. from a [`PlotPointsBuffer`].
+    /// Dragging or zooming the plot stops following; double-click to resume.
+    /// You need to call [`Context::request_repaint`] when new data arrives.
+    ///
+    /// Default: `None` (show all data).
+    pub fn follow_latest(mut self, x_width: impl Into<f64>) -> Self {
+        self.follow_latest = Some(x_width.into());
+        self
+    }
+
     /// Whether to allow zooming in the plot by dragging out a box with the secondary mouse button.
     ///
     /// Default: `true`.
@@ -349,12 +540,40 @@ impl Plot {
         self
     }
 
+    /// Allow the user to select a range by dragging with
+    /// [`Self::selection_pointer_button`] ("brushing").
+    ///
+    /// The selection is reported by [`PlotResponse::selection`] until the user clicks the plot.
+    /// Dragging with the selection button no longer moves the plot. Default: `None`.
+    pub fn selection(mut self, mode: SelectionMode) -> Self {
+        self.selection_mode = Some(mode);
+        self
+    }
+
+    /// Config the button pointer to use for selecting a range. Default: [`Primary`](PointerButton::Primary)
+    ///
+    /// If this is the same button as [`Self::boxed_zoom_pointer_button`], dragging selects a range
+    /// and boxed zooming is disabled while [`Self::selection`] is on.
+    pub fn selection_pointer_button(mut self, selection_pointer_button: PointerButton) -> Self {
+        self.selection_pointer_button = selection_pointer_button;
+        self
+    }
+
     /// Whether to allow dragging in the plot to move the bounds. Default: `true`.
     pub fn allow_drag(mut self, on: bool) -> Self {
         self.allow_drag = on;
         self
     }
 
+    /// Config the button pointer to use for moving the bounds and dragging plot items.
+    /// Default: [`Primary`](PointerButton::Primary)
+    ///
+    /// Items can be grabbed within [`style::Interaction::resize_grab_radius_side`] of their handle.
+    pub fn drag_pointer_button(mut self, drag_pointer_button: PointerButton) -> Self {
+        self.drag_pointer_button = drag_pointer_button;
+        self
+    }
+
     /// Provide a function to customize the on-hover label for the x and y axis
     ///
     /// ```
@@ -510,8 +729,28 @@ impl Plot {
         self
     }
 
+    /// When hovering the plot, show the values of all series at the x value under the pointer,
+    /// instead of describing only the closest item.
+    ///
+    /// The values are interpolated between the points of each series.
+    /// With [`LinkedAxisGroup::set_link_cursor`] the values are also shown in the other plots of the group.
+    /// Default: `false`.
+    pub fn all_series_tooltip(mut self, on: bool) -> Self {
+        self.all_series_tooltip = on;
+        self
+    }
+
+    /// Capture everything the plot paints this frame, so it can be saved as SVG or PNG.
+    ///
+    /// The result is returned in [`PlotResponse::export`].
+    /// Default: `false`.
+    pub fn export(mut self, export: bool) -> Self {
+        self.export = export;
+        self
+    }
+
     /// Interact with and add items to the plot and finally draw it.
-    pub fn show<R>(self, ui: &mut Ui, build_fn: impl FnOnce(&mut PlotUi) -> R) -> InnerResponse<R> {
+    pub fn show<R>(self, ui: &mut Ui, build_fn: impl FnOnce(&mut PlotUi) -> R) -> PlotResponse<R> {
         self.show_dyn(ui, Box::new(build_fn))
     }
 
@@ -519,7 +758,7 @@ impl Plot {
         self,
         ui: &mut Ui,
         build_fn: Box<dyn FnOnce(&mut PlotUi) -> R + 'a>,
-    ) -> InnerResponse<R> {
+    ) -> PlotResponse<R> {
         let Self {
             id_source,
             center_x_axis,
@@ -527,10 +766,14 @@ impl Plot {
             allow_zoom,
             allow_scroll,
             allow_drag,
-            allow_boxed_zoom,
+            drag_pointer_button,
+            mut allow_boxed_zoom,
             boxed_zoom_pointer_button: boxed_zoom_pointer,
+            selection_mode,
+            selection_pointer_button,
             min_auto_bounds,
             margin_fraction,
+            follow_latest,
             width,
             height,
             min_size,
@@ -546,6 +789,8 @@ impl Plot {
             show_axes,
             linked_axes,
             grid_spacers,
+            all_series_tooltip,
+            export,
         } = self;
 
         // Determine the size of the plot in the UI
@@ -590,6 +835,9 @@ impl Plot {
                 center_y_axis,
             ),
             last_click_pos_for_zoom: None,
+            dragged_item: None,
+            selection_start: None,
+            selection: None,
         });
 
         // If the min bounds changed, recalculate everything.
@@ -609,6 +857,9 @@ impl Plot {
             mut hidden_items,
             last_screen_transform,
             mut last_click_pos_for_zoom,
+            mut dragged_item,
+            mut selection_start,
+            mut selection,
             ..
         } = memory;
 
@@ -628,12 +879,14 @@ impl Plot {
             ..
         } = plot_ui;
 
+        let first_shape_idx = ui.ctx().graphics().list(ui.layer_id()).len();
+
         // Background
         if show_background {
             ui.painter().with_clip_rect(rect).add(epaint::RectShape {
                 rect,
                 rounding: Rounding::same(2.0),
-                fill: ui.visuals().extreme_bg_color,
+                fill: ui.visuals().extreme_bg_color.into(),
                 stroke: ui.visuals().widgets.noninteractive.bg_stroke,
             });
         }
@@ -708,6 +961,9 @@ impl Plot {
             }
 
             if auto_bounds.x {
+                if let Some(x_width) = follow_latest {
+                    bounds.follow_latest_x(x_width);
+                }
                 bounds.add_relative_margin_x(margin_fraction);
             }
 
@@ -728,8 +984,75 @@ impl Plot {
             }
         }
 
+        // Dragging of plot items
+        let mut item_drag = None;
+        let mut item_cursor = None;
+        let grab_radius = ui.style().interaction.resize_grab_radius_side;
+        if response.drag_started() && response.dragged_by(drag_pointer_button) {
+            dragged_item = response.interact_pointer_pos().and_then(|pointer| {
+                items::find_drag_handle(&items, pointer, &transform, grab_radius)
+            });
+        } else if !response.dragged_by(drag_pointer_button) {
+            dragged_item = None;
+        }
+        if let Some((id, index)) = dragged_item {
+            if let Some(item) = items.iter_mut().find(|item| item.drag_id() == Some(id)) {
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    item_drag = item.drag_handle(index, transform.value_from_position(pointer));
+                }
+                item.highlight();
+                item_cursor = Some(item.drag_cursor());
+            }
+            // Don't let the bounds follow the item we are dragging.
+            auto_bounds = false.into();
+        } else if let Some(hover_pos) = response.hover_pos() {
+            if let Some((id, _)) =
+                items::find_drag_handle(&items, hover_pos, &transform, grab_radius)
+            {
+                item_cursor = items
+                    .iter()
+                    .find(|item| item.drag_id() == Some(id))
+                    .map(|item| item.drag_cursor());
+            }
+        }
+
+        // Range selection
+        if let Some(selection_mode) = selection_mode {
+            // The same drag can't both select a range and zoom.
+            allow_boxed_zoom &= boxed_zoom_pointer != selection_pointer_button;
+
+            if dragged_item.is_none()
+                && response.drag_started()
+                && response.dragged_by(selection_pointer_button)
+            {
+                selection_start = response
+                    .interact_pointer_pos()
+                    .map(|pointer| transform.value_from_position(pointer));
+            }
+            if let Some(start) = selection_start {
+                if response.dragged_by(selection_pointer_button) {
+                    if let Some(pointer) = response.interact_pointer_pos() {
+                        let end = transform.value_from_position(pointer);
+                        selection = Some(selection_mode.bounds(start, end));
+                    }
+                } else {
+                    selection_start = None;
+                }
+            }
+            if response.hovered() && ui.input().pointer.button_clicked(selection_pointer_button) {
+                selection = None;
+            }
+        } else {
+            selection_start = None;
+            selection = None;
+        }
+
         // Dragging
-        if allow_drag && response.dragged_by(PointerButton::Primary) {
+        if allow_drag
+            && dragged_item.is_none()
+            && selection_start.is_none()
+            && response.dragged_by(drag_pointer_button)
+        {
             response = response.on_hover_cursor(CursorIcon::Grabbing);
             transform.translate_bounds(-response.drag_delta());
             auto_bounds = false.into();
@@ -809,6 +1132,13 @@ impl Plot {
             item.initialize(transform.bounds().range_x());
         }
 
+        let linked_cursor = linked_axes.as_ref().and_then(|group| {
+            group.set_cursor(plot_id, response.hovered(), &transform);
+            let pointer = ui.input().pointer.hover_pos();
+            let pointer = pointer.map(|pos| ui.ctx().layer_pos_from_global(ui.layer_id(), pos));
+            group.cursor_for(plot_id, pointer)
+        });
+
         let prepared = PreparedPlot {
             items,
             show_x,
@@ -819,9 +1149,30 @@ impl Plot {
             show_axes,
             transform: transform.clone(),
             grid_spacers,
+            all_series_tooltip,
+            linked_cursor,
         };
         prepared.ui(ui, &response);
 
+        if let Some(selection) = selection {
+            let bounds = transform.bounds();
+            let min = PlotPoint::new(
+                selection.min[0].max(bounds.min[0]),
+                selection.min[1].max(bounds.min[1]),
+            );
+            let max = PlotPoint::new(
+                selection.max[0].min(bounds.max[0]),
+                selection.max[1].min(bounds.max[1]),
+            );
+            let color = ui.visuals().selection.bg_fill;
+            ui.painter().with_clip_rect(rect).add(epaint::RectShape {
+                rect: transform.rect_from_values(&min, &max),
+                rounding: Rounding::none(),
+                fill: color.linear_multiply(0.25).into(),
+                stroke: Stroke::new(1.0, color),
+            });
+        }
+
         if let Some(boxed_zoom_rect) = boxed_zoom_rect {
             ui.painter().with_clip_rect(rect).add(boxed_zoom_rect.0);
             ui.painter().with_clip_rect(rect).add(boxed_zoom_rect.1);
@@ -837,6 +1188,16 @@ impl Plot {
             group.set(*transform.bounds());
         }
 
+        let export = export.then(|| {
+            let shapes = ui
+                .ctx()
+                .graphics()
+                .list(ui.layer_id())
+                .shapes_since(first_shape_idx)
+                .to_vec();
+            PlotExport::new(ui.ctx(), rect, shapes)
+        });
+
         let memory = PlotMemory {
             auto_bounds,
             hovered_entry,
@@ -844,16 +1205,27 @@ impl Plot {
             min_auto_bounds,
             last_screen_transform: transform,
             last_click_pos_for_zoom,
+            dragged_item,
+            selection_start,
+            selection,
         };
         memory.store(ui.ctx(), plot_id);
 
-        let response = if show_x || show_y {
+        let response = if let Some(cursor) = item_cursor {
+            response.on_hover_cursor(cursor)
+        } else if show_x || show_y {
             response.on_hover_cursor(CursorIcon::Crosshair)
         } else {
             response
         };
 
-        InnerResponse { inner, response }
+        PlotResponse {
+            inner,
+            response,
+            dragged_item: item_drag,
+            selection,
+            export,
+        }
     }
 }
 
@@ -948,6 +1320,19 @@ impl PlotUi {
         self.items.push(Box::new(polygon));
     }
 
+    /// Add a band, e.g. to show the confidence interval of a line.
+    pub fn band(&mut self, mut band: Band) {
+        if band.lower.is_empty() || band.upper.is_empty() {
+            return;
+        };
+
+        // Give the band an automatic color if no color has been assigned.
+        if band.stroke.color == Color32::TRANSPARENT {
+            band.stroke.color = self.auto_color();
+        }
+        self.items.push(Box::new(band));
+    }
+
     /// Add a text.
     pub fn text(&mut self, text: Text) {
         if text.text.is_empty() {
@@ -1119,6 +1504,9 @@ struct PreparedPlot {
     show_axes: [bool; 2],
     transform: ScreenTransform,
     grid_spacers: [GridSpacer; 2],
+    all_series_tooltip: bool,
+    /// The x value of the cursor of another plot in the same [`LinkedAxisGroup`].
+    linked_cursor: Option<f64>,
 }
 
 impl PreparedPlot {
@@ -1140,7 +1528,23 @@ impl PreparedPlot {
         }
 
         if let Some(pointer) = response.hover_pos() {
-            self.hover(ui, pointer, &mut shapes);
+            if self.all_series_tooltip {
+                let x = transform.value_from_position(pointer).x;
+                self.hover_all_series(ui, x, Some(pointer), &mut shapes);
+            } else {
+                self.hover(ui, pointer, &mut shapes);
+            }
+        } else if let Some(x) = self.linked_cursor {
+            if self.all_series_tooltip {
+                self.hover_all_series(ui, x, None, &mut shapes);
+            } else {
+                let frame = transform.frame();
+                let screen_x = transform.position_from_point(&PlotPoint::new(x, 0.0)).x;
+                shapes.push(Shape::line_segment(
+                    [pos2(screen_x, frame.top()), pos2(screen_x, frame.bottom())],
+                    (1.0, items::rulers_color(ui)),
+                ));
+            }
         }
 
         let painter = ui.painter().with_clip_rect(*transform.frame());
@@ -1292,7 +1696,114 @@ impl PreparedPlot {
             item.on_hover(elem, shapes, &plot, label_formatter);
         } else {
             let value = transform.value_from_position(pointer);
-            items::rulers_at_value(pointer, value, "", &plot, shapes, label_formatter);
+            items::rulers_at_value(
+                pointer,
+                value,
+                [None; 2],
+                "",
+                &plot,
+                shapes,
+                label_formatter,
+            );
+        }
+    }
+
+    /// Marks the values of all series at `x` and lists them next to the `pointer`, or at the top of
+    /// the plot for a linked cursor.
+    fn hover_all_series(&self, ui: &Ui, x: f64, pointer: Option<Pos2>, shapes: &mut Vec<Shape>) {
+        let Self {
+            transform,
+            label_formatter,
+            items,
+            ..
+        } = self;
+
+        let frame = transform.frame();
+        let screen_x = transform.position_from_point(&PlotPoint::new(x, 0.0)).x;
+        if screen_x < frame.left() || frame.right() < screen_x {
+            return;
+        }
+        shapes.push(Shape::line_segment(
+            [pos2(screen_x, frame.top()), pos2(screen_x, frame.bottom())],
+            (1.0, items::rulers_color(ui)),
+        ));
+
+        let scale = transform.dvalue_dpos();
+        let x_decimals = ((-scale[0].abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
+        let y_decimals = ((-scale[1].abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
+
+        // Each row has the color of its marker, if any:
+        let mut rows = vec![(format!("x = {:.*}", x_decimals, x), None)];
+        let marker_radius = 3.0;
+        for item in items {
+            if let Some(y) = item.value_at_x(x) {
+                let value = PlotPoint::new(x, y);
+                let pos = transform.position_from_point(&value);
+                shapes.push(Shape::circle_filled(pos, marker_radius, item.color()));
+
+                let text = if let Some(custom_label) = label_formatter {
+                    custom_label(item.name(), &value)
+                } else if item.name().is_empty() {
+                    format!("{:.*}", y_decimals, y)
+                } else {
+                    format!("{}: {:.*}", item.name(), y_decimals, y)
+                };
+                rows.push((text, Some(item.color())));
+            }
+        }
+
+        let font_id = TextStyle::Body.resolve(ui.style());
+        let text_color = ui.visuals().text_color();
+        let marker_width = 4.0 * marker_radius;
+        let rows: Vec<_> = rows
+            .into_iter()
+            .map(|(text, color)| {
+                let galley = ui.fonts().layout_no_wrap(text, font_id.clone(), text_color);
+                (galley, color)
+            })
+            .collect();
+        let text_size = rows.iter().fold(Vec2::ZERO, |size, (galley, color)| {
+            let indent = if color.is_some() { marker_width } else { 0.0 };
+            vec2(
+                size.x.max(indent + galley.size().x),
+                size.y + galley.size().y,
+            )
+        });
+
+        let padding = vec2(4.0, 2.0);
+        let size = text_size + 2.0 * padding;
+        let mut pos = match pointer {
+            Some(pointer) => pointer + vec2(12.0, 12.0),
+            None => pos2(screen_x + 8.0, frame.top() + 4.0),
+        };
+        if pos.x + size.x > frame.right() {
+            pos.x = screen_x - 8.0 - size.x;
+        }
+        pos.y = pos.y.at_most(frame.bottom() - size.y);
+        let rect = Rect::from_min_size(pos, size);
+
+        shapes.push(Shape::Rect(epaint::RectShape {
+            rect,
+            rounding: Rounding::same(2.0),
+            fill: ui.visuals().window_brush(),
+            stroke: ui.visuals().window_stroke(),
+        }));
+        let mut text_pos = rect.min + padding;
+        for (galley, color) in rows {
+            let height = galley.size().y;
+            if let Some(color) = color {
+                if let Some(first_row) = galley.rows.first() {
+                    let center = pos2(
+                        text_pos.x + 1.5 * marker_radius,
+                        text_pos.y + first_row.rect.center().y,
+                    );
+                    shapes.push(Shape::circle_filled(center, marker_radius, color));
+                }
+                shapes.push(Shape::galley(text_pos + vec2(marker_width, 0.0), galley));
+            } else {
+                shapes.push(Shape::galley(text_pos, galley));
+            }
+            text_pos.y += height;
         }
     }
 }
@@ -1331,3 +1842,160 @@ fn fill_marks_between(out: &mut Vec<GridMark>, step_size: f64, (min, max): (f64,
     });
     out.extend(marks_iter);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: PointerButton = PointerButton::Primary;
+    const SECONDARY: PointerButton = PointerButton::Secondary;
+
+    fn pointer(pos: Pos2, button: PointerButton, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos,
+            button,
+            pressed,
+            modifiers: Default::default(),
+        }
+    }
+
+    /// Run one frame of a plot of `y ∈ [-1, 1]` with a draggable [`HLine`] at `y = 0`,
+    /// which is painted through the middle of the plot. Returns the bounds at the end of the frame.
+    fn run_frame(
+        ctx: &Context,
+        events: Vec<Event>,
+        plot: fn() -> Plot,
+    ) -> PlotResponse<PlotBounds> {
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0))),
+            events,
+            ..Default::default()
+        };
+        let mut plot_response = None;
+        let _ = ctx.run(raw_input, |ctx| {
+            crate::CentralPanel::default().show(ctx, |ui| {
+                let response = plot().include_y(-1.0).include_y(1.0).show(ui, |plot_ui| {
+                    plot_ui.hline(HLine::new(0.0).id("h").draggable(true));
+                    plot_ui.plot_bounds()
+                });
+                plot_response = Some(response);
+            });
+        });
+        plot_response.unwrap()
+    }
+
+    /// Drag from the middle of the plot to 40 points below and to the right of it, returning the last frame.
+    fn drag(ctx: &Context, button: PointerButton, plot: fn() -> Plot) -> PlotResponse<PlotBounds> {
+        let center = run_frame(ctx, vec![], plot).response.rect.center();
+        let end = center + vec2(40.0, 40.0);
+        run_frame(
+            ctx,
+            vec![Event::PointerMoved(center), pointer(center, button, true)],
+            plot,
+        );
+        run_frame(ctx, vec![Event::PointerMoved(end)], plot)
+    }
+
+    #[test]
+    fn drag_hline() {
+        let ctx = Context::default();
+        let response = drag(&ctx, PRIMARY, || Plot::new("plot"));
+        let y = response.dragged_hline("h").unwrap();
+        assert!(y < 0.0, "dragged down, got {}", y);
+        assert!(response.selection.is_none());
+    }
+
+    #[test]
+    fn drag_hline_with_other_button() {
+        let ctx = Context::default();
+        let plot = || {
+            Plot::new("plot")
+                .drag_pointer_button(SECONDARY)
+                .allow_boxed_zoom(false)
+        };
+        assert_eq!(drag(&ctx, PRIMARY, plot).dragged_item, None);
+
+        let ctx = Context::default();
+        assert!(drag(&ctx, SECONDARY, plot).dragged_hline("h").is_some());
+    }
+
+    #[test]
+    fn hline_out_of_grab_radius() {
+        let ctx = Context::default();
+        let plot = || Plot::new("plot");
+        let rect = run_frame(&ctx, vec![], plot).response.rect;
+        let start =
+            rect.center() + vec2(0.0, ctx.style().interaction.resize_grab_radius_side + 1.0);
+        run_frame(
+            &ctx,
+            vec![Event::PointerMoved(start), pointer(start, PRIMARY, true)],
+            plot,
+        );
+        let response = run_frame(
+            &ctx,
+            vec![Event::PointerMoved(start + vec2(0.0, 40.0))],
+            plot,
+        );
+        assert_eq!(response.dragged_item, None);
+    }
+
+    #[test]
+    fn select_range() {
+        let ctx = Context::default();
+        let plot = || {
+            Plot::new("plot")
+                .selection(SelectionMode::Y)
+                .selection_pointer_button(SECONDARY)
+        };
+        let response = drag(&ctx, SECONDARY, plot);
+        assert_eq!(response.dragged_item, None);
+        let selection = response.selection.unwrap();
+        assert!(selection.min()[1] < 0.0 && selection.max()[1] > -1e-6);
+        assert_eq!(selection.min()[0], f64::NEG_INFINITY);
+        assert_eq!(selection.max()[0], f64::INFINITY);
+
+        // Releasing the button keeps the selection, and boxed zoom, which shares the button, doesn't
+        // change the bounds.
+        let before = response.inner;
+        let end = response.response.rect.center() + vec2(40.0, 40.0);
+        let response = run_frame(&ctx, vec![pointer(end, SECONDARY, false)], plot);
+        assert_eq!(response.selection, Some(selection));
+        let response = run_frame(&ctx, vec![], plot);
+        assert_eq!(response.inner, before);
+    }
+
+    #[test]
+    fn linked_cursor_follows_pointer() {
+        let mut group = LinkedAxisGroup::x();
+        group.set_link_cursor(true);
+        let [a, b] = [Id::new("a"), Id::new("b")];
+        let frame = Rect::from_min_size(pos2(100.0, 0.0), vec2(100.0, 50.0));
+        let bounds = PlotBounds {
+            min: [0.0, 0.0],
+            max: [10.0, 1.0],
+        };
+        let transform = ScreenTransform::new(frame, bounds, false, false);
+
+        group.set_cursor(b, true, &transform);
+        assert_eq!(group.cursor_for(a, Some(pos2(150.0, 10.0))), Some(5.0));
+        assert_eq!(group.cursor_for(a, Some(pos2(175.0, 10.0))), Some(7.5));
+        assert_eq!(group.cursor_for(a, Some(pos2(50.0, 10.0))), None);
+        assert_eq!(group.cursor_for(b, Some(pos2(150.0, 10.0))), None);
+
+        group.set_cursor(a, false, &transform);
+        assert!(group.cursor_for(a, Some(pos2(150.0, 10.0))).is_some());
+        group.set_cursor(b, false, &transform);
+        assert_eq!(group.cursor_for(a, Some(pos2(150.0, 10.0))), None);
+    }
+
+    #[test]
+    fn selection_bounds() {
+        let start = PlotPoint::new(3.0, -1.0);
+        let end = PlotPoint::new(1.0, 2.0);
+        let xy = SelectionMode::XY.bounds(start, end);
+        assert_eq!((xy.min(), xy.max()), ([1.0, -1.0], [3.0, 2.0]));
+        let x = SelectionMode::X.bounds(start, end);
+        assert_eq!(x.min(), [1.0, f64::NEG_INFINITY]);
+        assert_eq!(x.max(), [3.0, f64::INFINITY]);
+    }
+}
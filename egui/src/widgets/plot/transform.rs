@@ -103,6 +103,15 @@ impl PlotBounds {
         self.max[0] = other.max[0];
     }
 
+    /// Only show the last `x_width` of the x-range, for [`super::Plot::follow_latest`].
+    ///
+    /// Does nothing if there is no data yet, i.e. the end of the x-range is not finite.
+    pub(crate) fn follow_latest_x(&mut self, x_width: f64) {
+        if self.max[0].is_finite() {
+            self.min[0] = self.max[0] - x_width;
+        }
+    }
+
     pub(crate) fn set_y(&mut self, other: &PlotBounds) {
         self.min[1] = other.min[1];
         self.max[1] = other.max[1];
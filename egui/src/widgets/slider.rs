@@ -404,7 +404,7 @@ impl<'a> Slider<'a> {
             ui.painter().add(epaint::RectShape {
                 rect: rail_rect,
                 rounding: ui.visuals().widgets.inactive.rounding,
-                fill: ui.visuals().widgets.inactive.bg_fill,
+                fill: ui.visuals().widgets.inactive.bg_fill.into(),
                 // fill: visuals.bg_fill,
                 // fill: ui.visuals().extreme_bg_color,
                 stroke: Default::default(),
@@ -417,7 +417,7 @@ impl<'a> Slider<'a> {
             ui.painter().add(epaint::CircleShape {
                 center,
                 radius: self.handle_radius(rect) + visuals.expansion,
-                fill: visuals.bg_fill,
+                fill: visuals.fill_brush(),
                 stroke: visuals.fg_stroke,
             });
         }
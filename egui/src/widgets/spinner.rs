@@ -1,4 +1,4 @@
-use epaint::{emath::lerp, vec2, Pos2, Shape, Stroke};
+use epaint::{vec2, Shape, Stroke};
 
 use crate::{Response, Sense, Ui, Widget};
 
@@ -37,18 +37,13 @@ impl Widget for Spinner {
             ui.ctx().request_repaint();
 
             let radius = (rect.height() / 2.0) - 2.0;
-            let n_points = 20;
-            let start_angle = ui.input().time * std::f64::consts::TAU;
-            let end_angle = start_angle + 240f64.to_radians() * ui.input().time.sin();
-            let points: Vec<Pos2> = (0..n_points)
-                .map(|i| {
-                    let angle = lerp(start_angle..=end_angle, i as f64 / n_points as f64);
-                    let (sin, cos) = angle.sin_cos();
-                    rect.center() + radius * vec2(cos as f32, sin as f32)
-                })
-                .collect();
-            ui.painter().add(Shape::line(
-                points,
+            let time = ui.input().time;
+            let start_angle = (time.fract() * std::f64::consts::TAU) as f32;
+            let end_angle = start_angle + 240f32.to_radians() * time.sin() as f32;
+            ui.painter().add(Shape::arc(
+                rect.center(),
+                radius,
+                start_angle..=end_angle,
                 Stroke::new(3.0, ui.visuals().strong_text_color()),
             ));
         }
@@ -4,7 +4,10 @@ use epaint::text::{cursor::*, Galley, LayoutJob};
 
 use crate::{output::OutputEvent, *};
 
-use super::{CCursorRange, CursorRange, TextEditOutput, TextEditState};
+use super::{
+    cursor_range::{cursor_left_one_character, cursor_right_one_character},
+    CCursorRange, CursorRange, TextEditOutput, TextEditState,
+};
 
 /// A text region that the user can edit the contents of.
 ///
@@ -324,14 +327,14 @@ impl<'t> TextEdit<'t> {
                         rect: frame_rect,
                         rounding: visuals.rounding,
                         // fill: ui.visuals().selection.bg_fill,
-                        fill: ui.visuals().extreme_bg_color,
+                        fill: ui.visuals().extreme_bg_color.into(),
                         stroke: ui.visuals().selection.stroke,
                     }
                 } else {
                     epaint::RectShape {
                         rect: frame_rect,
                         rounding: visuals.rounding,
-                        fill: ui.visuals().extreme_bg_color,
+                        fill: ui.visuals().extreme_bg_color.into(),
                         stroke: visuals.bg_stroke, // TODO(emilk): we want to show something here, or a text-edit field doesn't "pop".
                     }
                 }
@@ -342,7 +345,7 @@ impl<'t> TextEdit<'t> {
                     rounding: visuals.rounding,
                     // fill: ui.visuals().extreme_bg_color,
                     // fill: visuals.bg_fill,
-                    fill: Color32::TRANSPARENT,
+                    fill: Color32::TRANSPARENT.into(),
                     stroke: visuals.bg_stroke, // TODO(emilk): we want to show something here, or a text-edit field doesn't "pop".
                 }
             };
@@ -445,7 +448,10 @@ impl<'t> TextEdit<'t> {
         let painter = ui.painter_at(text_clip_rect.expand(1.0)); // expand to avoid clipping cursor
 
         if interactive {
-            if let Some(pointer_pos) = ui.ctx().pointer_interact_pos() {
+            if let Some(pointer_pos) = response
+                .interact_pointer_pos()
+                .or_else(|| response.hover_pos())
+            {
                 if response.hovered() && text.is_mutable() {
                     ui.output().mutable_text_under_cursor = true;
                 }
@@ -890,26 +896,34 @@ fn paint_cursor_selection(
 
     for ri in min.row..=max.row {
         let row = &galley.rows[ri];
-        let left = if ri == min.row {
-            row.x_offset(min.column)
+        let min_column = if ri == min.row { min.column } else { 0 };
+        let max_column = if ri == max.row {
+            max.column
         } else {
-            row.rect.left()
+            row.char_count_excluding_newline()
         };
-        let right = if ri == max.row {
-            row.x_offset(max.column)
-        } else {
-            let newline_size = if row.ends_with_newline {
-                row.height() / 2.0 // visualize that we select the newline
-            } else {
-                0.0
-            };
-            row.rect.right() + newline_size
-        };
-        let rect = Rect::from_min_max(
-            pos + vec2(left, row.min_y()),
-            pos + vec2(right, row.max_y()),
-        );
-        painter.rect_filled(rect, 0.0, color);
+
+        // With mixed left-to-right and right-to-left text the selection can be split up:
+        let mut x_ranges = row.x_ranges(min_column..max_column);
+
+        if ri != max.row && row.ends_with_newline {
+            let newline_size = row.height() / 2.0; // visualize that we select the newline
+            if row.rtl {
+                if let Some(first) = x_ranges.first_mut() {
+                    *first = first.start() - newline_size..=*first.end();
+                }
+            } else if let Some(last) = x_ranges.last_mut() {
+                *last = *last.start()..=last.end() + newline_size;
+            }
+        }
+
+        for x_range in x_ranges {
+            let rect = Rect::from_min_max(
+                pos + vec2(*x_range.start(), row.min_y()),
+                pos + vec2(*x_range.end(), row.max_y()),
+            );
+            painter.rect_filled(rect, 0.0, color);
+        }
     }
 }
 
@@ -1150,8 +1164,8 @@ fn move_single_cursor(cursor: &mut Cursor, galley: &Galley, key: Key, modifiers:
             Key::E => *cursor = galley.cursor_end_of_row(cursor),
             Key::P => *cursor = galley.cursor_up_one_row(cursor),
             Key::N => *cursor = galley.cursor_down_one_row(cursor),
-            Key::B => *cursor = galley.cursor_left_one_character(cursor),
-            Key::F => *cursor = galley.cursor_right_one_character(cursor),
+            Key::B => *cursor = cursor_left_one_character(galley, cursor),
+            Key::F => *cursor = cursor_right_one_character(galley, cursor),
             _ => (),
         }
         return;
@@ -1164,7 +1178,7 @@ fn move_single_cursor(cursor: &mut Cursor, galley: &Galley, key: Key, modifiers:
             } else if modifiers.mac_cmd {
                 *cursor = galley.cursor_begin_of_row(cursor);
             } else {
-                *cursor = galley.cursor_left_one_character(cursor);
+                *cursor = cursor_left_one_character(galley, cursor);
             }
         }
         Key::ArrowRight => {
@@ -1174,7 +1188,7 @@ fn move_single_cursor(cursor: &mut Cursor, galley: &Galley, key: Key, modifiers:
             } else if modifiers.mac_cmd {
                 *cursor = galley.cursor_end_of_row(cursor);
             } else {
-                *cursor = galley.cursor_right_one_character(cursor);
+                *cursor = cursor_right_one_character(galley, cursor);
             }
         }
         Key::ArrowUp => {
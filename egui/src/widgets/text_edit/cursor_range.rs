@@ -1,4 +1,4 @@
-use epaint::text::cursor::*;
+use epaint::text::{cursor::*, Galley};
 
 /// A selected text range (could be a range of length zero).
 #[derive(Clone, Copy, Debug, Default)]
@@ -148,3 +148,140 @@ pub struct PCursorRange {
     /// This part of the cursor does not move when shift is down.
     pub secondary: PCursor,
 }
+
+// ----------------------------------------------------------------------------
+
+/// Move the cursor one character to the left on screen.
+///
+/// In right-to-left text that means moving forward in the text.
+pub(crate) fn cursor_left_one_character(galley: &Galley, cursor: &Cursor) -> Cursor {
+    if let Some(cursor) = cursor_visual_step(galley, cursor, false) {
+        cursor
+    } else if is_rtl_row(galley, cursor) {
+        galley.cursor_right_one_character(cursor)
+    } else {
+        galley.cursor_left_one_character(cursor)
+    }
+}
+
+/// Move the cursor one character to the right on screen.
+///
+/// In right-to-left text that means moving backwards in the text.
+pub(crate) fn cursor_right_one_character(galley: &Galley, cursor: &Cursor) -> Cursor {
+    if let Some(cursor) = cursor_visual_step(galley, cursor, true) {
+        cursor
+    } else if is_rtl_row(galley, cursor) {
+        galley.cursor_left_one_character(cursor)
+    } else {
+        galley.cursor_right_one_character(cursor)
+    }
+}
+
+fn is_rtl_row(galley: &Galley, cursor: &Cursor) -> bool {
+    galley
+        .rows
+        .get(cursor.rcursor.row)
+        .map_or(false, |row| row.rtl)
+}
+
+/// Step to the closest cursor position to the left or right within a row with right-to-left text.
+///
+/// Returns `None` if the row has no right-to-left text,
+/// or if the cursor is already at that edge of the row.
+fn cursor_visual_step(galley: &Galley, cursor: &Cursor, step_right: bool) -> Option<Cursor> {
+    let row = galley.rows.get(cursor.rcursor.row)?;
+    if !row.is_bidi() {
+        return None;
+    }
+
+    let num_columns = row.char_count_excluding_newline();
+    let mut stops: Vec<(f32, usize)> = (0..=num_columns)
+        .map(|column| (row.x_offset(column), column))
+        .collect();
+    stops.sort_by(|a, b| {
+        let by_column = if row.rtl {
+            b.1.cmp(&a.1)
+        } else {
+            a.1.cmp(&b.1)
+        };
+        a.0.partial_cmp(&b.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(by_column)
+    });
+
+    let column = cursor.rcursor.column.min(num_columns);
+    let index = stops.iter().position(|&(_, c)| c == column)?;
+    let new_index = if step_right {
+        index + 1
+    } else {
+        index.checked_sub(1)?
+    };
+    let &(_, new_column) = stops.get(new_index)?;
+
+    Some(galley.from_rcursor(RCursor {
+        row: cursor.rcursor.row,
+        column: new_column,
+    }))
+}
+
+#[cfg(all(test, feature = "bidi"))]
+mod tests {
+    use super::*;
+    use epaint::{
+        text::{FontDefinitions, Fonts},
+        Color32, FontId,
+    };
+
+    fn galley(text: &str) -> std::sync::Arc<Galley> {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        fonts.layout_no_wrap(text.to_owned(), FontId::default(), Color32::WHITE)
+    }
+
+    /// Press an arrow key until the cursor stops moving, and return the character indices visited.
+    fn walk(galley: &Galley, start: Cursor, step: fn(&Galley, &Cursor) -> Cursor) -> Vec<usize> {
+        let mut cursor = start;
+        let mut visited = vec![cursor.ccursor.index];
+        loop {
+            let next = step(galley, &cursor);
+            if next.ccursor.index == cursor.ccursor.index {
+                return visited;
+            }
+            cursor = next;
+            visited.push(cursor.ccursor.index);
+        }
+    }
+
+    #[test]
+    fn move_across_ltr_rtl_boundary() {
+        // A left-to-right paragraph ending in Hebrew, shown as "ab םולש":
+        let galley = galley("ab \u{5E9}\u{5DC}\u{5D5}\u{5DD}");
+
+        let right = walk(&galley, Cursor::default(), cursor_right_one_character);
+        assert_eq!(right, [0, 1, 2, 3, 6, 5, 4, 7]);
+
+        let left = walk(&galley, galley.end(), cursor_left_one_character);
+        assert_eq!(left, [7, 4, 5, 6, 3, 2, 1, 0]);
+
+        // Every step moves right on screen:
+        let x: Vec<f32> = right
+            .iter()
+            .map(|&index| {
+                galley
+                    .pos_from_cursor(&galley.from_ccursor(CCursor::new(index)))
+                    .min
+                    .x
+            })
+            .collect();
+        assert!(x.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", x);
+    }
+
+    #[test]
+    fn move_in_rtl_paragraph() {
+        // A right-to-left paragraph, so the text starts at the right edge:
+        let galley = galley("\u{5E9}\u{5DC}\u{5D5}\u{5DD}");
+        let left = walk(&galley, Cursor::default(), cursor_left_one_character);
+        assert_eq!(left, [0, 1, 2, 3, 4]);
+        let right = walk(&galley, galley.end(), cursor_right_one_character);
+        assert_eq!(right, [4, 3, 2, 1, 0]);
+    }
+}
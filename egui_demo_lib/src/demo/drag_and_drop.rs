@@ -67,7 +67,7 @@ pub fn drop_target<R>(
         where_to_put_background,
         epaint::RectShape {
             rounding: style.rounding,
-            fill,
+            fill: fill.into(),
             stroke,
             rect,
         },
@@ -18,6 +18,16 @@ pub struct MiscDemoWindow {
     tree: Tree,
     box_painting: BoxPainting,
 
+    /// For showing a repeating texture.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tile_texture: Option<TextureHandle>,
+
+    /// A texture with a border, for the nine-slice images.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    nine_slice_texture: Option<TextureHandle>,
+
+    window_opacity: f32,
+
     dummy_bool: bool,
     dummy_usize: usize,
 }
@@ -37,6 +47,12 @@ impl Default for MiscDemoWindow {
             tree: Tree::demo(),
             box_painting: Default::default(),
 
+            tile_texture: None,
+
+            nine_slice_texture: None,
+
+            window_opacity: 1.0,
+
             dummy_bool: false,
             dummy_usize: 0,
         }
@@ -159,6 +175,39 @@ impl View for MiscDemoWindow {
                 });
             });
 
+        CollapsingHeader::new("Gradients")
+            .default_open(false)
+            .show(ui, gradients_ui);
+
+        CollapsingHeader::new("Polygons")
+            .default_open(false)
+            .show(ui, polygons_ui);
+
+        CollapsingHeader::new("Stroke styles")
+            .default_open(false)
+            .show(ui, stroke_styles_ui);
+
+        CollapsingHeader::new("Tiled texture")
+            .default_open(false)
+            .show(ui, |ui| tiled_texture_ui(ui, &mut self.tile_texture));
+
+        CollapsingHeader::new("Nine-slice images")
+            .default_open(false)
+            .show(ui, |ui| nine_slice_ui(ui, &mut self.nine_slice_texture));
+
+        CollapsingHeader::new("Shadows and blur")
+            .default_open(false)
+            .show(ui, shadows_ui);
+
+        CollapsingHeader::new("Blend modes and opacity")
+            .default_open(false)
+            .show(ui, |ui| blending_ui(ui, &mut self.window_opacity));
+
+        #[cfg(feature = "svg")]
+        CollapsingHeader::new("SVG")
+            .default_open(false)
+            .show(ui, svg_ui);
+
         CollapsingHeader::new("Many circles of different sizes")
             .default_open(false)
             .show(ui, |ui| {
@@ -473,6 +522,377 @@ impl Tree {
 
 // ----------------------------------------------------------------------------
 
+fn gradients_ui(ui: &mut Ui) {
+    let sunset = Brush::LinearGradient {
+        start: pos2(0.0, 0.0),
+        end: pos2(0.0, 1.0),
+        stops: ColorStops::new(&[
+            (0.0, Color32::from_rgb(40, 60, 160)),
+            (0.6, Color32::from_rgb(230, 100, 80)),
+            (1.0, Color32::from_rgb(250, 200, 90)),
+        ]),
+    };
+
+    Frame::group(ui.style()).fill(sunset).show(ui, |ui| {
+        ui.colored_label(Color32::WHITE, "A frame filled with a linear gradient");
+    });
+
+    ui.horizontal(|ui| {
+        let shine = Brush::linear_gradient(
+            pos2(0.0, 0.0),
+            pos2(0.0, 1.0),
+            ui.visuals().widgets.inactive.bg_fill.linear_multiply(0.5),
+            ui.visuals().widgets.inactive.bg_fill,
+        );
+        let _ = ui.add(Button::new("A button with a gradient").fill(shine));
+
+        let (rect, _response) = ui.allocate_exact_size(Vec2::splat(32.0), Sense::hover());
+        ui.painter().circle_filled(
+            rect.center(),
+            16.0,
+            Brush::radial_gradient(Color32::YELLOW, Color32::RED),
+        );
+    });
+
+    ui.label("Widgets can also get a gradient from the style with `WidgetVisuals::bg_brush`.");
+}
+
+// ----------------------------------------------------------------------------
+
+fn polygons_ui(ui: &mut Ui) {
+    use std::f32::consts::TAU;
+
+    ui.label("Concave and self-intersecting polygons, and polygons with holes:");
+    ui.horizontal(|ui| {
+        let color = ui.visuals().text_color();
+        let stroke = Stroke::new(1.0, ui.visuals().strong_text_color());
+        let size = Vec2::splat(64.0);
+
+        for fill_mode in [epaint::FillMode::NonZero, epaint::FillMode::EvenOdd] {
+            let (rect, _response) = ui.allocate_exact_size(size, Sense::hover());
+            let star = (0..5)
+                .map(|i| {
+                    let angle = TAU * (2 * i) as f32 / 5.0 - TAU / 4.0;
+                    rect.center() + 0.45 * size.x * Vec2::angled(angle)
+                })
+                .collect();
+            ui.painter().add(epaint::PathShape {
+                fill_mode,
+                ..epaint::PathShape::polygon(star, color, stroke)
+            });
+        }
+
+        let (rect, _response) = ui.allocate_exact_size(size, Sense::hover());
+        let corners = |rect: Rect| {
+            vec![
+                rect.left_top(),
+                rect.right_top(),
+                rect.right_bottom(),
+                rect.left_bottom(),
+            ]
+        };
+        let holes = vec![
+            corners(Rect::from_center_size(
+                rect.center() - vec2(12.0, 12.0),
+                Vec2::splat(16.0),
+            )),
+            corners(Rect::from_center_size(
+                rect.center() + vec2(12.0, 12.0),
+                Vec2::splat(16.0),
+            )),
+        ];
+        ui.painter().add(Shape::polygon_with_holes(
+            corners(rect.shrink(4.0)),
+            holes,
+            color,
+            stroke,
+        ));
+    });
+}
+
+fn stroke_styles_ui(ui: &mut Ui) {
+    let color = ui.visuals().text_color();
+    let size = vec2(64.0, 48.0);
+
+    ui.label("Caps: butt, round and square");
+    ui.horizontal(|ui| {
+        for cap in [LineCap::Butt, LineCap::Round, LineCap::Square] {
+            let (rect, _response) = ui.allocate_exact_size(size, Sense::hover());
+            let rect = rect.shrink(12.0);
+            let stroke = Stroke::new(12.0, color).with_cap(cap);
+            ui.painter()
+                .line_segment([rect.left_center(), rect.right_center()], stroke);
+        }
+    });
+
+    ui.label("Joins: miter, round and bevel");
+    ui.horizontal(|ui| {
+        for join in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            let (rect, _response) = ui.allocate_exact_size(size, Sense::hover());
+            let rect = rect.shrink(12.0);
+            let points = vec![rect.left_bottom(), rect.center_top(), rect.right_bottom()];
+            let stroke = Stroke::new(8.0, color).with_join(join);
+            ui.painter().add(Shape::line(points, stroke));
+        }
+    });
+
+    ui.label("Dashes along lines and curves:");
+    ui.horizontal(|ui| {
+        let (rect, _response) = ui.allocate_exact_size(vec2(3.0 * size.x, size.y), Sense::hover());
+        let rect = rect.shrink(8.0);
+        let stroke = Stroke::new(3.0, color)
+            .with_cap(LineCap::Round)
+            .with_dash(DashPattern::new(&[12.0, 6.0, 0.0, 6.0]));
+        ui.painter()
+            .add(epaint::CubicBezierShape::from_points_stroke(
+                [
+                    rect.left_bottom(),
+                    rect.left_top(),
+                    rect.right_bottom(),
+                    rect.right_top(),
+                ],
+                false,
+                Color32::TRANSPARENT,
+                stroke,
+            ));
+
+        let (rect, _response) = ui.allocate_exact_size(size, Sense::hover());
+        let stroke = Stroke::new(4.0, color)
+            .with_cap(LineCap::Round)
+            .with_dash(DashPattern::dotted(8.0));
+        ui.painter().circle_stroke(rect.center(), 18.0, stroke);
+    });
+}
+
+fn tiled_texture_ui(ui: &mut Ui, tile_texture: &mut Option<TextureHandle>) {
+    let texture = tile_texture.get_or_insert_with(|| {
+        // A brick wall:
+        let size = 16;
+        let pixels = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                let x = if y < size / 2 { x } else { x + size / 2 };
+                if y % (size / 2) == 0 || x % size == 0 {
+                    Color32::from_gray(200)
+                } else {
+                    Color32::from_rgb(160, 60, 40)
+                }
+            })
+            .collect();
+        ui.ctx().load_texture(
+            "tile",
+            ColorImage {
+                size: [size, size],
+                pixels,
+            },
+            TextureOptions::NEAREST.with_wrap_mode(TextureWrapMode::Repeat),
+        )
+    });
+
+    ui.label("A small texture that repeats, by using uv-coordinates outside of [0, 1]:");
+    let tiles = vec2(12.0, 4.0);
+    ui.add(
+        Image::new(texture.id(), 24.0 * tiles).uv(Rect::from_min_max(Pos2::ZERO, tiles.to_pos2())),
+    );
+}
+
+fn nine_slice_ui(ui: &mut Ui, nine_slice_texture: &mut Option<TextureHandle>) {
+    let (size, inset) = (24, 8);
+    let texture = nine_slice_texture.get_or_insert_with(|| {
+        // A golden outline with studs in the corners around a dark center:
+        let pixels = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                let edge_distance = x.min(y).min(size - 1 - x).min(size - 1 - y);
+                let in_corner =
+                    (x < inset || x >= size - inset) && (y < inset || y >= size - inset);
+                let corner_center = |c: usize| {
+                    if c < inset {
+                        inset / 2
+                    } else {
+                        size - inset / 2
+                    }
+                };
+                let stud_distance = vec2(
+                    x as f32 + 0.5 - corner_center(x) as f32,
+                    y as f32 + 0.5 - corner_center(y) as f32,
+                )
+                .length();
+                if edge_distance < 2 || (in_corner && stud_distance < 2.5) {
+                    Color32::from_rgb(220, 170, 60)
+                } else if edge_distance < inset {
+                    Color32::from_rgb(110, 70, 40)
+                } else {
+                    Color32::from_rgb(60, 60, 70)
+                }
+            })
+            .collect();
+        ui.ctx().load_texture(
+            "nine_slice",
+            ColorImage {
+                size: [size, size],
+                pixels,
+            },
+            TextureOptions::NEAREST,
+        )
+    });
+    let texture_size = vec2(size as f32, size as f32);
+    let nine_slice = NineSlice::new(texture.id(), texture_size, inset as f32);
+
+    ui.label("A small texture with a border, stretched without distorting the border:");
+    ui.horizontal(|ui| {
+        for image_size in [vec2(48.0, 48.0), vec2(160.0, 48.0), vec2(64.0, 120.0)] {
+            ui.add(Image::new(texture.id(), image_size).nine_slice(texture_size, inset as f32));
+        }
+    });
+
+    Frame::none()
+        .nine_slice(nine_slice)
+        .inner_margin(16.0)
+        .show(ui, |ui| {
+            ui.colored_label(Color32::WHITE, "A frame painted with the same texture");
+        });
+
+    ui.scope(|ui| {
+        let widgets = &mut ui.visuals_mut().widgets;
+        for (visuals, tint) in [
+            (&mut widgets.inactive, Color32::WHITE),
+            (&mut widgets.hovered, Color32::from_rgb(255, 230, 190)),
+            (&mut widgets.active, Color32::from_gray(190)),
+        ] {
+            visuals.bg_nine_slice = Some(nine_slice.with_tint(tint));
+            visuals.bg_stroke = Stroke::none();
+            visuals.fg_stroke.color = Color32::WHITE;
+        }
+        ui.spacing_mut().button_padding = vec2(12.0, 8.0);
+        ui.horizontal(|ui| {
+            let _ = ui.button("Skinned button");
+            let _ = ui.button("Another one");
+        });
+    });
+}
+
+fn shadows_ui(ui: &mut Ui) {
+    ui.label("Frames with shadows that are offset and spread out:");
+    ui.horizontal(|ui| {
+        for (offset, spread) in [(Vec2::ZERO, 0.0), (vec2(6.0, 6.0), 0.0), (Vec2::ZERO, 6.0)] {
+            Frame::none()
+                .inner_margin(12.0)
+                .outer_margin(16.0)
+                .rounding(8.0)
+                .fill(ui.visuals().window_fill())
+                .shadow(epaint::Shadow {
+                    offset,
+                    blur: 16.0,
+                    spread,
+                    color: Color32::from_black_alpha(128),
+                })
+                .show(ui, |ui| ui.label("Shadow"));
+        }
+    });
+
+    ui.label("A frame that blurs what is behind it (only with the glow and wgpu backends):");
+    let (rect, _response) = ui.allocate_exact_size(vec2(320.0, 120.0), Sense::hover());
+    let stripes = 16;
+    for i in 0..stripes {
+        let t = i as f32 / stripes as f32;
+        let x = lerp(rect.x_range(), t);
+        let stripe = Rect::from_x_y_ranges(x..=x + rect.width() / stripes as f32, rect.y_range());
+        let color = Color32::from(Hsva::new(t, 0.8, 0.9, 1.0));
+        ui.painter().rect_filled(stripe, 0.0, color);
+    }
+    ui.allocate_ui_at_rect(rect.shrink(24.0), |ui| {
+        Frame::none()
+            .inner_margin(12.0)
+            .rounding(12.0)
+            .fill(ui.visuals().window_fill().linear_multiply(0.5))
+            .backdrop_blur(12.0)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.label("Frosted glass");
+            });
+    });
+}
+
+fn blending_ui(ui: &mut Ui, window_opacity: &mut f32) {
+    ui.add(Slider::new(window_opacity, 0.2..=1.0).text("Opacity of this window"));
+    ui.ctx().set_layer_opacity(ui.layer_id(), *window_opacity);
+
+    ui.label("Overlapping red, green and blue circles in each blend mode:");
+    ui.horizontal(|ui| {
+        for blend_mode in BlendMode::ALL {
+            ui.vertical(|ui| {
+                let (rect, _response) = ui.allocate_exact_size(Vec2::splat(64.0), Sense::hover());
+                let painter = ui.painter();
+                painter.rect_filled(rect, 4.0, Color32::from_gray(128));
+                let circles = [Color32::RED, Color32::GREEN, Color32::BLUE]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &color)| {
+                        let angle = std::f32::consts::TAU * (i as f32 / 3.0 - 0.25);
+                        Shape::circle_filled(
+                            rect.center() + 10.0 * Vec2::angled(angle),
+                            16.0,
+                            color,
+                        )
+                    })
+                    .collect();
+                painter.add(GroupShape::new(circles).with_blend_mode(blend_mode));
+                ui.label(format!("{:?}", blend_mode));
+            });
+        }
+    });
+}
+
+#[cfg(feature = "svg")]
+fn svg_ui(ui: &mut Ui) {
+    const ICON: &str = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+            <defs>
+                <linearGradient id="sky" x1="0" y1="0" x2="0" y2="1">
+                    <stop offset="0" stop-color="#4af"/>
+                    <stop offset="1" stop-color="#a4f"/>
+                </linearGradient>
+            </defs>
+            <rect x="1" y="1" width="22" height="22" rx="4" fill="url(#sky)"/>
+            <circle cx="16" cy="8" r="3" fill="#fd4"/>
+            <path d="M3 20 L9 11 L13 16 L16 13 L21 20 Z" fill="#264" stroke="#fff"
+                stroke-width="1" stroke-linejoin="round"/>
+        </svg>"##;
+
+    let svg = match epaint::svg::Svg::parse(ICON) {
+        Ok(svg) => svg,
+        Err(err) => {
+            ui.colored_label(Color32::RED, err);
+            return;
+        }
+    };
+
+    ui.label("The same SVG at different sizes, as shapes rather than an image:");
+    ui.horizontal(|ui| {
+        for size in [16.0, 32.0, 64.0, 128.0] {
+            let (rect, _response) = ui.allocate_exact_size(Vec2::splat(size), Sense::hover());
+            ui.painter().extend(svg.shapes(rect));
+        }
+    });
+
+    ui.label("Recolored with the text color:");
+    ui.horizontal(|ui| {
+        let (rect, _response) = ui.allocate_exact_size(Vec2::splat(64.0), Sense::hover());
+        let text_color = ui.visuals().text_color();
+        let mut shapes = svg.shapes(rect);
+        for shape in &mut shapes {
+            epaint::shape_transform::adjust_colors(shape, &|color| {
+                let luminance = color.r().max(color.g()).max(color.b()) as f32 / 255.0;
+                *color = text_color.linear_multiply(luminance * color.a() as f32 / 255.0);
+            });
+        }
+        ui.painter().extend(shapes);
+    });
+}
+
+// ----------------------------------------------------------------------------
+
 fn text_layout_ui(
     ui: &mut egui::Ui,
     max_rows: &mut usize,
@@ -630,6 +1050,85 @@ fn text_layout_ui(
             ..Default::default()
         },
     );
+    job.append(
+        " There are also ",
+        0.0,
+        TextFormat {
+            color: default_color,
+            ..Default::default()
+        },
+    );
+    job.append(
+        "wavy",
+        0.0,
+        TextFormat {
+            color: default_color,
+            underline: Stroke::new(1.0, Color32::RED),
+            underline_style: text::UnderlineStyle::Wavy,
+            ..Default::default()
+        },
+    );
+    job.append(
+        " and ",
+        0.0,
+        TextFormat {
+            color: default_color,
+            ..Default::default()
+        },
+    );
+    job.append(
+        "dotted",
+        0.0,
+        TextFormat {
+            color: default_color,
+            underline: Stroke::new(1.5, Color32::LIGHT_BLUE),
+            underline_style: text::UnderlineStyle::Dotted,
+            ..Default::default()
+        },
+    );
+    job.append(
+        " underlines, ",
+        0.0,
+        TextFormat {
+            color: default_color,
+            ..Default::default()
+        },
+    );
+    job.append(
+        "overlines",
+        0.0,
+        TextFormat {
+            color: default_color,
+            overline: Stroke::new(1.0, strong_color),
+            ..Default::default()
+        },
+    );
+    job.append(
+        " and ",
+        0.0,
+        TextFormat {
+            color: default_color,
+            ..Default::default()
+        },
+    );
+    job.append(
+        "highlights that continue when the text wraps",
+        0.0,
+        TextFormat {
+            color: strong_color,
+            highlight: Color32::from_rgb(0, 80, 160),
+            highlight_rounding: 4.0,
+            ..Default::default()
+        },
+    );
+    job.append(
+        ".",
+        0.0,
+        TextFormat {
+            color: default_color,
+            ..Default::default()
+        },
+    );
 
     ui.label(job);
 
@@ -0,0 +1,114 @@
+use egui::*;
+
+/// Widgets on layers that are scaled and translated with [`Context::set_transform_layer`].
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PanZoom {
+    /// From the top left of the canvas to screen coordinates.
+    transform: TSTransform,
+    value: f32,
+    text: String,
+}
+
+impl Default for PanZoom {
+    fn default() -> Self {
+        Self {
+            transform: TSTransform::IDENTITY,
+            value: 0.5,
+            text: "Edit me".to_owned(),
+        }
+    }
+}
+
+impl super::Demo for PanZoom {
+    fn name(&self) -> &'static str {
+        "🔍 Pan Zoom"
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        use super::View as _;
+        Window::new(self.name())
+            .open(open)
+            .default_size(vec2(512.0, 512.0))
+            .vscroll(false)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl super::View for PanZoom {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.label("Pan by dragging or scrolling, and zoom with ctrl + scroll or by pinching. Double-click to reset.");
+        ui.separator();
+
+        let (id, rect) = ui.allocate_space(ui.available_size());
+        let response = ui.interact(rect, id, Sense::click_and_drag());
+
+        if response.double_clicked() {
+            self.transform = TSTransform::IDENTITY;
+        }
+        if response.dragged() {
+            self.transform.translation += response.drag_delta();
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let (zoom_delta, scroll_delta) = {
+                let input = ui.input();
+                (input.zoom_delta(), input.scroll_delta)
+            };
+
+            // Zoom in on the pointer, so that what is under it stays there:
+            let to_screen = TSTransform::from_translation(rect.min.to_vec2()) * self.transform;
+            let pointer_in_layer = (to_screen.inverse() * pointer).to_vec2();
+            self.transform = self.transform
+                * TSTransform::from_translation(pointer_in_layer)
+                * TSTransform::from_scaling(zoom_delta)
+                * TSTransform::from_translation(-pointer_in_layer);
+
+            self.transform = TSTransform::from_translation(scroll_delta) * self.transform;
+        }
+
+        let to_screen = TSTransform::from_translation(rect.min.to_vec2()) * self.transform;
+
+        for (i, pos) in [pos2(16.0, 16.0), pos2(160.0, 96.0), pos2(48.0, 208.0)]
+            .into_iter()
+            .enumerate()
+        {
+            // NOTE: the areas are in the foreground so that they are painted on top of the window.
+            let layer_id = Area::new(id.with(("pan_zoom_area", i)))
+                .order(Order::Foreground)
+                .default_pos(pos)
+                .drag_bounds(Rect::EVERYTHING)
+                .show(ui.ctx(), |ui| {
+                    ui.set_clip_rect(to_screen.inverse() * rect);
+                    Frame::popup(ui.style()).show(ui, |ui| match i {
+                        0 => {
+                            ui.label("Drag me around!");
+                            ui.add(Slider::new(&mut self.value, 0.0..=1.0).text("value"));
+                            if ui.button("Reset").clicked() {
+                                self.value = 0.5;
+                            }
+                        }
+                        1 => {
+                            ui.text_edit_singleline(&mut self.text);
+                        }
+                        _ => {
+                            let (rect, _) =
+                                ui.allocate_exact_size(Vec2::splat(64.0), Sense::hover());
+                            let stroke = Stroke::new(2.0, ui.visuals().text_color());
+                            ui.painter().circle_stroke(rect.center(), 30.0, stroke);
+                            ui.painter().text(
+                                rect.center(),
+                                Align2::CENTER_CENTER,
+                                format!("{:.0}%", 100.0 * self.transform.scaling),
+                                TextStyle::Body.resolve(ui.style()),
+                                ui.visuals().text_color(),
+                            );
+                        }
+                    });
+                })
+                .response
+                .layer_id;
+
+            ui.ctx().set_transform_layer(layer_id, to_screen);
+        }
+    }
+}
@@ -4,9 +4,9 @@ use std::ops::RangeInclusive;
 use egui::plot::{GridInput, GridMark};
 use egui::*;
 use plot::{
-    Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, CoordinatesFormatter, Corner, HLine,
-    Legend, Line, LineStyle, MarkerShape, Plot, PlotImage, PlotPoint, PlotPoints, Points, Polygon,
-    Text, VLine,
+    Arrows, Band, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, CoordinatesFormatter, Corner,
+    ErrorBars, HLine, ItemDrag, Legend, Line, LineStyle, MarkerShape, Plot, PlotImage, PlotPoint,
+    PlotPoints, PlotResponse, Points, Polygon, SelectionMode, Text, VLine,
 };
 
 // ----------------------------------------------------------------------------
@@ -458,6 +458,8 @@ impl CustomAxisDemo {
 struct LinkedAxisDemo {
     link_x: bool,
     link_y: bool,
+    link_cursor: bool,
+    all_series_tooltip: bool,
     group: plot::LinkedAxisGroup,
 }
 
@@ -468,6 +470,8 @@ impl Default for LinkedAxisDemo {
         Self {
             link_x,
             link_y,
+            link_cursor: true,
+            all_series_tooltip: true,
             group: plot::LinkedAxisGroup::new(link_x, link_y),
         }
     }
@@ -499,11 +503,11 @@ impl LinkedAxisDemo {
     }
 
     fn configure_plot(plot_ui: &mut plot::PlotUi) {
-        plot_ui.line(LinkedAxisDemo::line_with_slope(0.5));
-        plot_ui.line(LinkedAxisDemo::line_with_slope(1.0));
-        plot_ui.line(LinkedAxisDemo::line_with_slope(2.0));
-        plot_ui.line(LinkedAxisDemo::sin());
-        plot_ui.line(LinkedAxisDemo::cos());
+        plot_ui.line(LinkedAxisDemo::line_with_slope(0.5).name("0.5 x"));
+        plot_ui.line(LinkedAxisDemo::line_with_slope(1.0).name("x"));
+        plot_ui.line(LinkedAxisDemo::line_with_slope(2.0).name("2 x"));
+        plot_ui.line(LinkedAxisDemo::sin().name("sin(x)"));
+        plot_ui.line(LinkedAxisDemo::cos().name("cos(x)"));
     }
 
     fn ui(&mut self, ui: &mut Ui) -> Response {
@@ -511,17 +515,22 @@ impl LinkedAxisDemo {
             ui.label("Linked axes:");
             ui.checkbox(&mut self.link_x, "X");
             ui.checkbox(&mut self.link_y, "Y");
+            ui.checkbox(&mut self.link_cursor, "Cursor");
         });
+        ui.checkbox(&mut self.all_series_tooltip, "Show all series on hover");
         self.group.set_link_x(self.link_x);
         self.group.set_link_y(self.link_y);
+        self.group.set_link_cursor(self.link_cursor);
         ui.horizontal(|ui| {
             Plot::new("linked_axis_1")
+                .all_series_tooltip(self.all_series_tooltip)
                 .data_aspect(1.0)
                 .width(250.0)
                 .height(250.0)
                 .link_axis(self.group.clone())
                 .show(ui, LinkedAxisDemo::configure_plot);
             Plot::new("linked_axis_2")
+                .all_series_tooltip(self.all_series_tooltip)
                 .data_aspect(2.0)
                 .width(150.0)
                 .height(250.0)
@@ -529,6 +538,7 @@ impl LinkedAxisDemo {
                 .show(ui, LinkedAxisDemo::configure_plot);
         });
         Plot::new("linked_axis_3")
+            .all_series_tooltip(self.all_series_tooltip)
             .data_aspect(0.5)
             .width(250.0)
             .height(150.0)
@@ -561,6 +571,22 @@ impl ItemsDemo {
         ));
         let points = Points::new(sin_values).stems(-1.5).radius(1.0);
 
+        let measurements = Points::new(
+            (0..5)
+                .map(|i| [i as f64 - 6.0, -5.5 + 0.2 * i as f64])
+                .collect::<Vec<_>>(),
+        )
+        .radius(3.0)
+        .error_bars(
+            ErrorBars::new()
+                .y([0.3, 0.5, 0.2, 0.6, 0.4])
+                .x_asymmetric([[0.1, 0.3]; 5]),
+        );
+        let band = Band::new(
+            PlotPoints::from_explicit_callback(|x| -8.0 + 0.3 * x.sin(), 1.0..8.0, 50),
+            PlotPoints::from_explicit_callback(|x| -7.0 + 0.5 * x.sin(), 1.0..8.0, 50),
+        );
+
         let arrows = {
             let pos_radius = 8.0;
             let tip_radius = 7.0;
@@ -581,7 +607,8 @@ impl ItemsDemo {
             ui.ctx().load_texture(
                 "plot_demo",
                 egui::ColorImage::example(),
-                egui::TextureFilter::Linear,
+                // Mipmaps, so it still looks good when zoomed out:
+                egui::TextureOptions::LINEAR.with_mipmap_mode(Some(egui::TextureFilter::Linear)),
             )
         });
         let image = PlotImage::new(
@@ -603,6 +630,8 @@ impl ItemsDemo {
             plot_ui.line(line.name("Line with fill"));
             plot_ui.polygon(polygon.name("Convex polygon"));
             plot_ui.points(points.name("Points with stems"));
+            plot_ui.points(measurements.name("Points with error bars"));
+            plot_ui.band(band.name("Band"));
             plot_ui.text(Text::new(PlotPoint::new(-3.0, -3.0), "wow").name("Text"));
             plot_ui.text(Text::new(PlotPoint::new(-2.0, 2.5), "so graph").name("Text"));
             plot_ui.text(Text::new(PlotPoint::new(3.0, 3.0), "much color").name("Text"));
@@ -616,18 +645,46 @@ impl ItemsDemo {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Default, PartialEq)]
-struct InteractionDemo {}
+#[derive(PartialEq)]
+struct InteractionDemo {
+    threshold: f64,
+    marker: f64,
+    curve: Vec<[f64; 2]>,
+}
+
+impl Default for InteractionDemo {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            marker: 1.0,
+            curve: (0..=8).map(|i| [i as f64 * 0.5, 0.0]).collect(),
+        }
+    }
+}
 
 impl InteractionDemo {
-    #[allow(clippy::unused_self)]
     fn ui(&mut self, ui: &mut Ui) -> Response {
-        let plot = Plot::new("interaction_demo").height(300.0);
+        ui.label("Drag the lines or the points of the curve.");
+        ui.label("Drag the background to select a range of x values, click to clear it.");
 
-        let InnerResponse {
+        let plot = Plot::new("interaction_demo")
+            .height(300.0)
+            .selection(SelectionMode::X);
+
+        let PlotResponse {
             response,
             inner: (screen_pos, pointer_coordinate, pointer_coordinate_drag_delta, bounds, hovered),
+            dragged_item,
+            selection,
+            ..
         } = plot.show(ui, |plot_ui| {
+            plot_ui.hline(HLine::new(self.threshold).id("threshold").draggable(true));
+            plot_ui.vline(VLine::new(self.marker).id("marker").draggable(true));
+            plot_ui.line(
+                Line::new(self.curve.clone())
+                    .id("curve")
+                    .draggable_points(true),
+            );
             (
                 plot_ui.screen_from_plot(PlotPoint::new(0.0, 0.0)),
                 plot_ui.pointer_coordinate(),
@@ -637,6 +694,22 @@ impl InteractionDemo {
             )
         });
 
+        match dragged_item {
+            Some(ItemDrag::HLine { y, .. }) => self.threshold = y,
+            Some(ItemDrag::VLine { x, .. }) => self.marker = x,
+            Some(ItemDrag::LinePoint { index, point, .. }) => {
+                self.curve[index] = [point.x, point.y];
+            }
+            None => {}
+        }
+
+        if let Some(selection) = selection {
+            ui.label(format!(
+                "selected x range: {:.02} to {:.02}",
+                selection.min()[0],
+                selection.max()[0]
+            ));
+        }
         ui.label(format!(
             "plot bounds: min: {:.02?}, max: {:.02?}",
             bounds.min(),
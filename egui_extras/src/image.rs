@@ -1,5 +1,5 @@
 use egui::mutex::Mutex;
-use egui::TextureFilter;
+use egui::{TextureFilter, TextureOptions};
 
 /// An image to be shown in egui.
 ///
@@ -13,7 +13,7 @@ pub struct RetainedImage {
     image: Mutex<egui::ColorImage>,
     /// Lazily loaded when we have an egui context.
     texture: Mutex<Option<egui::TextureHandle>>,
-    filter: TextureFilter,
+    options: TextureOptions,
 }
 
 impl RetainedImage {
@@ -23,7 +23,7 @@ impl RetainedImage {
             size: image.size,
             image: Mutex::new(image),
             texture: Default::default(),
-            filter: Default::default(),
+            options: Default::default(),
         }
     }
 
@@ -86,7 +86,8 @@ impl RetainedImage {
     ///     .with_texture_filter(TextureFilter::Nearest);
     /// ```
     pub fn with_texture_filter(mut self, filter: TextureFilter) -> Self {
-        self.filter = filter;
+        self.options.magnification = filter;
+        self.options.minification = filter;
 
         // If the texture has already been uploaded, this will force it to be re-uploaded with the
         // updated filter.
@@ -95,6 +96,20 @@ impl RetainedImage {
         self
     }
 
+    /// Set how the texture is sampled: the filters, the wrap mode, and whether it has mipmaps.
+    ///
+    /// **Note:** If the texture has already been uploaded to the GPU, this will require
+    /// re-uploading the texture with the updated options.
+    pub fn with_options(mut self, options: TextureOptions) -> Self {
+        self.options = options;
+
+        // If the texture has already been uploaded, this will force it to be re-uploaded with the
+        // updated options.
+        *self.texture.lock() = None;
+
+        self
+    }
+
     /// The size of the image data (number of pixels wide/high).
     pub fn size(&self) -> [usize; 2] {
         self.size
@@ -128,7 +143,7 @@ impl RetainedImage {
             .get_or_insert_with(|| {
                 let image: &mut ColorImage = &mut self.image.lock();
                 let image = std::mem::take(image);
-                ctx.load_texture(&self.debug_name, image, self.filter)
+                ctx.load_texture(&self.debug_name, image, self.options)
             })
             .id()
     }
@@ -183,6 +198,33 @@ pub fn load_image_bytes(image_bytes: &[u8]) -> Result<egui::ColorImage, String>
     ))
 }
 
+/// Encode an image as a PNG file, e.g. one from [`egui::widgets::plot::PlotExport::to_color_image`].
+///
+/// Requires the "image" feature. You must also opt-in to the png format
+/// with e.g. `image = { version = "0.24", features = ["png"] }`.
+///
+/// # Errors
+/// If the png format is not enabled.
+#[cfg(feature = "image")]
+pub fn encode_png(image: &egui::ColorImage) -> Result<Vec<u8>, String> {
+    let pixels: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|color| color.to_srgba_unmultiplied())
+        .collect();
+    let mut png_bytes = std::io::Cursor::new(Vec::new());
+    image::write_buffer_with_format(
+        &mut png_bytes,
+        &pixels,
+        image.width() as _,
+        image.height() as _,
+        image::ColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(png_bytes.into_inner())
+}
+
 /// Load an SVG and rasterize it into an egui image.
 ///
 /// Requires the "svg" feature.
@@ -1,7 +1,7 @@
 #![allow(deprecated)] // legacy implement_vertex macro
 #![allow(semicolon_in_expressions_from_macros)] // glium::program! macro
 
-use egui::epaint::Primitive;
+use egui::{epaint::Primitive, TextureFilter, TextureOptions, TextureWrapMode};
 
 use {
     ahash::AHashMap,
@@ -12,16 +12,40 @@ use {
         program,
         texture::{self, srgb_texture2d::SrgbTexture2d},
         uniform,
-        uniforms::{MagnifySamplerFilter, SamplerWrapFunction},
+        uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerWrapFunction},
     },
     std::rc::Rc,
 };
 
+/// A texture, and how to sample it.
+struct EguiTexture {
+    texture: Rc<SrgbTexture2d>,
+    options: TextureOptions,
+
+    /// Is this a signed distance field (see [`egui::FontImage::sdf`])?
+    sdf: bool,
+
+    /// For textures with mipmaps, a copy of the whole image,
+    /// since the mipmaps are made from it again after partial updates.
+    mipmapped_image: Option<egui::ColorImage>,
+}
+
+impl EguiTexture {
+    fn native(texture: Rc<SrgbTexture2d>) -> Self {
+        Self {
+            texture,
+            options: Default::default(),
+            sdf: false,
+            mipmapped_image: None,
+        }
+    }
+}
+
 pub struct Painter {
     max_texture_side: usize,
     program: glium::Program,
 
-    textures: AHashMap<egui::TextureId, Rc<SrgbTexture2d>>,
+    textures: AHashMap<egui::TextureId, EguiTexture>,
 
     /// [`egui::TextureId::User`] index
     next_native_tex_id: u64,
@@ -97,15 +121,26 @@ impl Painter {
         for egui::ClippedPrimitive {
             clip_rect,
             primitive,
+            blend_mode,
         } in clipped_primitives
         {
             match primitive {
                 Primitive::Mesh(mesh) => {
-                    self.paint_mesh(target, display, pixels_per_point, clip_rect, mesh);
+                    self.paint_mesh(
+                        target,
+                        display,
+                        pixels_per_point,
+                        clip_rect,
+                        *blend_mode,
+                        mesh,
+                    );
                 }
                 Primitive::Callback(_) => {
                     panic!("Custom rendering callbacks are not implemented in egui_glium");
                 }
+                Primitive::BackdropBlur(_) => {
+                    // Not supported by egui_glium, so nothing is painted.
+                }
             }
         }
     }
@@ -117,6 +152,7 @@ impl Painter {
         display: &glium::Display,
         pixels_per_point: f32,
         clip_rect: &Rect,
+        blend_mode: egui::BlendMode,
         mesh: &Mesh,
     ) {
         debug_assert!(mesh.is_valid());
@@ -146,19 +182,66 @@ impl Painter {
         let height_in_points = height_in_pixels as f32 / pixels_per_point;
 
         if let Some(texture) = self.get_texture(mesh.texture_id) {
-            // The texture coordinates for text are so that both nearest and linear should work with the egui font texture.
-            // For user textures linear sampling is more likely to be the right choice.
-            let filter = MagnifySamplerFilter::Linear;
+            let options = texture.options;
+            let magnify_filter = match options.magnification {
+                TextureFilter::Nearest => MagnifySamplerFilter::Nearest,
+                TextureFilter::Linear => MagnifySamplerFilter::Linear,
+            };
+            let minify_filter = match (options.minification, options.mipmap_mode) {
+                (TextureFilter::Nearest, None) => MinifySamplerFilter::Nearest,
+                (TextureFilter::Linear, None) => MinifySamplerFilter::Linear,
+                (TextureFilter::Nearest, Some(TextureFilter::Nearest)) => {
+                    MinifySamplerFilter::NearestMipmapNearest
+                }
+                (TextureFilter::Nearest, Some(TextureFilter::Linear)) => {
+                    MinifySamplerFilter::NearestMipmapLinear
+                }
+                (TextureFilter::Linear, Some(TextureFilter::Nearest)) => {
+                    MinifySamplerFilter::LinearMipmapNearest
+                }
+                (TextureFilter::Linear, Some(TextureFilter::Linear)) => {
+                    MinifySamplerFilter::LinearMipmapLinear
+                }
+            };
+            let wrap_function = match options.wrap_mode {
+                TextureWrapMode::ClampToEdge => SamplerWrapFunction::Clamp,
+                TextureWrapMode::Repeat => SamplerWrapFunction::Repeat,
+                TextureWrapMode::MirroredRepeat => SamplerWrapFunction::Mirror,
+            };
 
             let uniforms = uniform! {
                 u_screen_size: [width_in_points, height_in_points],
-                u_sampler: texture.sampled().magnify_filter(filter).wrap_function(SamplerWrapFunction::Clamp),
+                u_sampler: texture
+                    .texture
+                    .sampled()
+                    .magnify_filter(magnify_filter)
+                    .minify_filter(minify_filter)
+                    .wrap_function(wrap_function),
+                u_sdf: texture.sdf,
             };
 
             // egui outputs colors with premultiplied alpha:
+            let (source, destination) = match blend_mode {
+                egui::BlendMode::Normal => (
+                    glium::LinearBlendingFactor::One,
+                    glium::LinearBlendingFactor::OneMinusSourceAlpha,
+                ),
+                egui::BlendMode::Multiply => (
+                    glium::LinearBlendingFactor::DestinationColor,
+                    glium::LinearBlendingFactor::OneMinusSourceAlpha,
+                ),
+                egui::BlendMode::Screen => (
+                    glium::LinearBlendingFactor::OneMinusDestinationColor,
+                    glium::LinearBlendingFactor::One,
+                ),
+                egui::BlendMode::Additive => (
+                    glium::LinearBlendingFactor::One,
+                    glium::LinearBlendingFactor::One,
+                ),
+            };
             let color_blend_func = glium::BlendingFunction::Addition {
-                source: glium::LinearBlendingFactor::One,
-                destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+                source,
+                destination,
             };
 
             // Less important, but this is technically the correct alpha blend function
@@ -226,47 +309,88 @@ impl Painter {
         tex_id: egui::TextureId,
         delta: &egui::epaint::ImageDelta,
     ) {
-        let pixels: Vec<(u8, u8, u8, u8)> = match &delta.image {
+        let sdf = matches!(&delta.image, egui::ImageData::Font(image) if image.sdf);
+        let image = match &delta.image {
             egui::ImageData::Color(image) => {
                 assert_eq!(
                     image.width() * image.height(),
                     image.pixels.len(),
                     "Mismatch between texture size and texel count"
                 );
-                image.pixels.iter().map(|color| color.to_tuple()).collect()
+                image.clone()
             }
             egui::ImageData::Font(image) => {
                 let gamma = 1.0;
-                image
-                    .srgba_pixels(gamma)
-                    .map(|color| color.to_tuple())
-                    .collect()
+                egui::ColorImage {
+                    size: image.size,
+                    pixels: image.srgba_pixels(gamma).collect(),
+                }
             }
         };
-        let glium_image = glium::texture::RawImage2d {
-            data: std::borrow::Cow::Owned(pixels),
-            width: delta.image.width() as _,
-            height: delta.image.height() as _,
-            format: glium::texture::ClientFormat::U8U8U8U8,
-        };
-        let format = texture::SrgbFormat::U8U8U8U8;
-        let mipmaps = texture::MipmapsOption::NoMipmap;
 
         if let Some(pos) = delta.pos {
             // update a sub-region
-            if let Some(gl_texture) = self.textures.get(&tex_id) {
-                let rect = glium::Rect {
-                    left: pos[0] as _,
-                    bottom: pos[1] as _,
-                    width: glium_image.width,
-                    height: glium_image.height,
-                };
-                gl_texture.main_level().write(rect, glium_image);
+            if let Some(texture) = self.textures.get_mut(&tex_id) {
+                if let Some(whole_image) = &mut texture.mipmapped_image {
+                    let [x, y] = pos;
+                    let row_length = image.width();
+                    for (row, y) in image.pixels.chunks_exact(row_length).zip(y..) {
+                        let start = y * whole_image.width() + x;
+                        whole_image.pixels[start..start + row_length].copy_from_slice(row);
+                    }
+                    texture.texture = Self::create_texture(facade, whole_image, true).into();
+                } else {
+                    let rect = glium::Rect {
+                        left: pos[0] as _,
+                        bottom: pos[1] as _,
+                        width: image.width() as _,
+                        height: image.height() as _,
+                    };
+                    texture
+                        .texture
+                        .main_level()
+                        .write(rect, Self::raw_image(&image));
+                }
             }
         } else {
-            let gl_texture =
-                SrgbTexture2d::with_format(facade, glium_image, format, mipmaps).unwrap();
-            self.textures.insert(tex_id, gl_texture.into());
+            let has_mipmaps = delta.options.mipmap_mode.is_some();
+            let texture = Self::create_texture(facade, &image, has_mipmaps);
+            self.textures.insert(
+                tex_id,
+                EguiTexture {
+                    texture: texture.into(),
+                    options: delta.options,
+                    sdf,
+                    mipmapped_image: has_mipmaps.then(|| image),
+                },
+            );
+        }
+    }
+
+    fn create_texture(
+        facade: &dyn glium::backend::Facade,
+        image: &egui::ColorImage,
+        has_mipmaps: bool,
+    ) -> SrgbTexture2d {
+        let format = texture::SrgbFormat::U8U8U8U8;
+        let mipmaps = if has_mipmaps {
+            texture::MipmapsOption::AutoGeneratedMipmaps
+        } else {
+            texture::MipmapsOption::NoMipmap
+        };
+        SrgbTexture2d::with_format(facade, Self::raw_image(image), format, mipmaps).unwrap()
+    }
+
+    fn raw_image(
+        image: &egui::ColorImage,
+    ) -> glium::texture::RawImage2d<'static, (u8, u8, u8, u8)> {
+        let pixels: Vec<(u8, u8, u8, u8)> =
+            image.pixels.iter().map(|color| color.to_tuple()).collect();
+        glium::texture::RawImage2d {
+            data: std::borrow::Cow::Owned(pixels),
+            width: image.width() as _,
+            height: image.height() as _,
+            format: glium::texture::ClientFormat::U8U8U8U8,
         }
     }
 
@@ -274,18 +398,18 @@ impl Painter {
         self.textures.remove(&tex_id);
     }
 
-    fn get_texture(&self, texture_id: egui::TextureId) -> Option<&SrgbTexture2d> {
-        self.textures.get(&texture_id).map(|rc| rc.as_ref())
+    fn get_texture(&self, texture_id: egui::TextureId) -> Option<&EguiTexture> {
+        self.textures.get(&texture_id)
     }
 
     pub fn register_native_texture(&mut self, native: Rc<SrgbTexture2d>) -> egui::TextureId {
         let id = egui::TextureId::User(self.next_native_tex_id);
         self.next_native_tex_id += 1;
-        self.textures.insert(id, native);
+        self.textures.insert(id, EguiTexture::native(native));
         id
     }
 
     pub fn replace_native_texture(&mut self, id: egui::TextureId, replacing: Rc<SrgbTexture2d>) {
-        self.textures.insert(id, replacing);
+        self.textures.insert(id, EguiTexture::native(replacing));
     }
 }
@@ -1,10 +1,36 @@
 #version 100
 
+#ifdef GL_OES_standard_derivatives
+    // For `fwidth`:
+    #extension GL_OES_standard_derivatives : enable
+#endif
+
 precision mediump float;
 uniform sampler2D u_sampler;
 varying vec4 v_rgba;
 varying vec2 v_tc;
 
+// Is the texture a signed distance field (`epaint::FontImage::sdf`)?
+uniform bool u_sdf;
+
+// The distance is in alpha, with the edge at 0.5, and the color is not premultiplied.
+// Returns the premultiplied color.
+vec4 from_sdf(vec4 texel) {
+#ifdef GL_OES_standard_derivatives
+    float half_width = max(0.5 * fwidth(texel.a), 0.0001);
+#else
+    float half_width = 0.05; // No derivatives, so guess how much the distance changes per pixel.
+#endif
+    float coverage = smoothstep(0.5 - half_width, 0.5 + half_width, texel.a);
+
+    // The distance texels are white. Color glyphs (e.g. emoji) have a blue below 1.0,
+    // and are painted as they are:
+    if (texel.b < 0.999) {
+        coverage = texel.a;
+    }
+    return vec4(texel.rgb * coverage, coverage);
+}
+
 // 0-255 sRGB  from  0-1 linear
 vec3 srgb_from_linear(vec3 rgb) {
     bvec3 cutoff = lessThan(rgb, vec3(0.0031308));
@@ -32,6 +58,9 @@ vec4 linear_from_srgba(vec4 srgba) {
 void main() {
     // We must decode the colors, since WebGL doesn't come with sRGBA textures:
     vec4 texture_rgba = linear_from_srgba(texture2D(u_sampler, v_tc) * 255.0);
+    if (u_sdf) {
+        texture_rgba = from_sdf(texture_rgba);
+    }
 
     /// Multiply vertex color with texture color (in linear space).
     gl_FragColor = v_rgba * texture_rgba;
@@ -5,8 +5,29 @@ in vec4 v_rgba;
 in vec2 v_tc;
 out vec4 f_color;
 
+// Is the texture a signed distance field (`epaint::FontImage::sdf`)?
+uniform bool u_sdf;
+
+// The distance is in alpha, with the edge at 0.5, and the color is not premultiplied.
+// Returns the premultiplied color.
+vec4 from_sdf(vec4 texel) {
+    float half_width = max(0.5 * fwidth(texel.a), 0.0001);
+    float coverage = smoothstep(0.5 - half_width, 0.5 + half_width, texel.a);
+
+    // The distance texels are white. Color glyphs (e.g. emoji) have a blue below 1.0,
+    // and are painted as they are:
+    if (texel.b < 0.999) {
+        coverage = texel.a;
+    }
+    return vec4(texel.rgb * coverage, coverage);
+}
+
 void main() {
     // The texture sampler is sRGB aware, and glium already expects linear rgba output
     // so no need for any sRGB conversions here:
-    f_color = v_rgba * texture(u_sampler, v_tc);
+    vec4 texture_rgba = texture(u_sampler, v_tc);
+    if (u_sdf) {
+        texture_rgba = from_sdf(texture_rgba);
+    }
+    f_color = v_rgba * texture_rgba;
 }
@@ -5,6 +5,23 @@ uniform sampler2D u_sampler;
 varying vec4 v_rgba;
 varying vec2 v_tc;
 
+// Is the texture a signed distance field (`epaint::FontImage::sdf`)?
+uniform bool u_sdf;
+
+// The distance is in alpha, with the edge at 0.5, and the color is not premultiplied.
+// Returns the premultiplied color.
+vec4 from_sdf(vec4 texel) {
+    float half_width = max(0.5 * fwidth(texel.a), 0.0001);
+    float coverage = smoothstep(0.5 - half_width, 0.5 + half_width, texel.a);
+
+    // The distance texels are white. Color glyphs (e.g. emoji) have a blue below 1.0,
+    // and are painted as they are:
+    if (texel.b < 0.999) {
+        coverage = texel.a;
+    }
+    return vec4(texel.rgb * coverage, coverage);
+}
+
 // 0-255 sRGB  from  0-1 linear
 vec3 srgb_from_linear(vec3 rgb) {
     bvec3 cutoff = lessThan(rgb, vec3(0.0031308));
@@ -20,6 +37,9 @@ vec4 srgba_from_linear(vec4 rgba) {
 void main() {
     // The texture is set up with `SRGB8_ALPHA8`, so no need to decode here!
     vec4 texture_rgba = texture2D(u_sampler, v_tc);
+    if (u_sdf) {
+        texture_rgba = from_sdf(texture_rgba);
+    }
 
     /// Multiply vertex color with texture color (in linear space).
     gl_FragColor = v_rgba * texture_rgba;
@@ -0,0 +1,359 @@
+#![allow(unsafe_code)]
+use crate::check_for_gl_error;
+use crate::misc_util::{compile_shader, link_program};
+use crate::vao::BufferInfo;
+use glow::HasContext as _;
+
+/// Samples on each side of the center in each direction. Must match `MAX_SAMPLES` in the shader.
+const MAX_SAMPLES: f32 = 24.0;
+
+/// Paints [`egui::BackdropBlur`]s, by blurring what has already been painted to the framebuffer.
+///
+/// The part of the framebuffer that is needed is copied to a texture,
+/// blurred horizontally into another texture,
+/// and then blurred vertically back onto the framebuffer, within the rounded rectangle.
+pub(crate) struct BackdropBlurPass {
+    gl: std::sync::Arc<glow::Context>,
+    pos_buffer: glow::Buffer,
+    index_buffer: glow::Buffer,
+    vao: crate::vao::VertexArrayObject,
+    is_webgl_1: bool,
+    srgb_support: bool,
+
+    /// What was behind the blur, and that blurred horizontally.
+    textures: [glow::Texture; 2],
+    texture_size: (i32, i32),
+
+    /// For rendering to `textures[1]`.
+    fbo: glow::Framebuffer,
+
+    program: glow::Program,
+    u_sampler: glow::UniformLocation,
+    u_screen_size: glow::UniformLocation,
+    u_rect: glow::UniformLocation,
+    u_step: glow::UniformLocation,
+    u_sigma: glow::UniformLocation,
+    u_sample_rect: glow::UniformLocation,
+    u_mask_rect: glow::UniformLocation,
+    u_mask_rounding: glow::UniformLocation,
+}
+
+impl BackdropBlurPass {
+    pub(crate) unsafe fn new(
+        gl: std::sync::Arc<glow::Context>,
+        shader_prefix: &str,
+        is_webgl_1: bool,
+        srgb_support: bool,
+    ) -> Result<BackdropBlurPass, String> {
+        let vert_shader = compile_shader(
+            &gl,
+            glow::VERTEX_SHADER,
+            &format!(
+                "{}\n{}",
+                shader_prefix,
+                include_str!("shader/blur_vertex_100es.glsl")
+            ),
+        )?;
+        let frag_shader = compile_shader(
+            &gl,
+            glow::FRAGMENT_SHADER,
+            &format!(
+                "{}\n{}",
+                shader_prefix,
+                include_str!("shader/blur_fragment_100es.glsl")
+            ),
+        )?;
+        let program = link_program(&gl, [vert_shader, frag_shader].iter())?;
+        gl.detach_shader(program, vert_shader);
+        gl.detach_shader(program, frag_shader);
+        gl.delete_shader(vert_shader);
+        gl.delete_shader(frag_shader);
+
+        let uniform = |name: &str| {
+            gl.get_uniform_location(program, name)
+                .ok_or_else(|| format!("failed to get location of {}", name))
+        };
+        let u_sampler = uniform("u_sampler")?;
+        let u_screen_size = uniform("u_screen_size")?;
+        let u_rect = uniform("u_rect")?;
+        let u_step = uniform("u_step")?;
+        let u_sigma = uniform("u_sigma")?;
+        let u_sample_rect = uniform("u_sample_rect")?;
+        let u_mask_rect = uniform("u_mask_rect")?;
+        let u_mask_rounding = uniform("u_mask_rounding")?;
+
+        let positions: Vec<f32> = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+
+        let indices: Vec<u8> = vec![0, 1, 2, 1, 2, 3];
+
+        let pos_buffer = gl.create_buffer()?;
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(pos_buffer));
+        gl.buffer_data_u8_slice(
+            glow::ARRAY_BUFFER,
+            bytemuck::cast_slice(&positions),
+            glow::STATIC_DRAW,
+        );
+
+        let a_pos_loc = gl
+            .get_attrib_location(program, "a_pos")
+            .ok_or_else(|| "failed to get location of a_pos".to_owned())?;
+        let vao = crate::vao::VertexArrayObject::new(
+            &gl,
+            pos_buffer,
+            vec![BufferInfo {
+                location: a_pos_loc,
+                vector_size: 2,
+                data_type: glow::FLOAT,
+                normalized: false,
+                stride: 0,
+                offset: 0,
+            }],
+        );
+
+        let index_buffer = gl.create_buffer()?;
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(index_buffer));
+        gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, &indices, glow::STATIC_DRAW);
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, None);
+
+        let textures = [gl.create_texture()?, gl.create_texture()?];
+        for texture in textures {
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            for (parameter, value) in [
+                (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+                (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+                (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+            ] {
+                gl.tex_parameter_i32(glow::TEXTURE_2D, parameter, value as i32);
+            }
+        }
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        let fbo = gl.create_framebuffer()?;
+
+        crate::check_for_gl_error_even_in_release!(&gl, "backdrop blur initialization");
+
+        Ok(BackdropBlurPass {
+            gl,
+            pos_buffer,
+            index_buffer,
+            vao,
+            is_webgl_1,
+            srgb_support,
+            textures,
+            texture_size: (0, 0),
+            fbo,
+            program,
+            u_sampler,
+            u_screen_size,
+            u_rect,
+            u_step,
+            u_sigma,
+            u_sample_rect,
+            u_mask_rect,
+            u_mask_rounding,
+        })
+    }
+
+    /// Make the textures as large as the screen.
+    unsafe fn resize(&mut self, width: i32, height: i32) {
+        if (width, height) == self.texture_size {
+            return;
+        }
+
+        // The same format as the egui textures, so colors are blended the same way:
+        let (internal_format, format) = if self.is_webgl_1 {
+            let format = if self.srgb_support {
+                glow::SRGB_ALPHA
+            } else {
+                glow::RGBA
+            };
+            (format, format)
+        } else {
+            (glow::SRGB8_ALPHA8, glow::RGBA)
+        };
+
+        for texture in self.textures {
+            self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            self.gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                internal_format as i32,
+                width,
+                height,
+                0,
+                format,
+                glow::UNSIGNED_BYTE,
+                None,
+            );
+        }
+        self.gl.bind_texture(glow::TEXTURE_2D, None);
+
+        self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.fbo));
+        self.gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(self.textures[1]),
+            0,
+        );
+
+        self.texture_size = (width, height);
+
+        check_for_gl_error!(&self.gl, "BackdropBlurPass::resize");
+    }
+
+    /// Blur the part of `target` (`None` for the screen) that `blur` covers.
+    ///
+    /// Leaves `target` bound, but the program, vertex array and viewport are changed,
+    /// so the painting state needs to be restored.
+    pub(crate) unsafe fn paint(
+        &mut self,
+        blur: &egui::BackdropBlur,
+        clip_rect: egui::Rect,
+        [width, height]: [u32; 2],
+        pixels_per_point: f32,
+        target: Option<glow::Framebuffer>,
+    ) {
+        let (width, height) = (width as f32, height as f32);
+
+        // In pixels from the bottom left, like OpenGL:
+        let to_pixels = |rect: egui::Rect| {
+            egui::Rect::from_min_max(
+                egui::pos2(
+                    pixels_per_point * rect.min.x,
+                    height - pixels_per_point * rect.max.y,
+                ),
+                egui::pos2(
+                    pixels_per_point * rect.max.x,
+                    height - pixels_per_point * rect.min.y,
+                ),
+            )
+        };
+        let screen = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width, height));
+        let rect = to_pixels(blur.rect);
+        let paint_rect = rect.intersect(to_pixels(clip_rect)).intersect(screen);
+
+        let sigma = 0.5 * blur.blur * pixels_per_point;
+        if sigma < 0.1 || !paint_rect.is_positive() {
+            return;
+        }
+
+        // Everything we need to sample, in whole pixels:
+        let sample_rect = paint_rect.expand(3.0 * sigma).intersect(screen);
+        let sample_rect = egui::Rect::from_min_max(sample_rect.min.floor(), sample_rect.max.ceil());
+        let [x, y] = [sample_rect.min.x as i32, sample_rect.min.y as i32];
+        let [w, h] = [sample_rect.width() as i32, sample_rect.height() as i32];
+
+        // Large blurs skip pixels, so we don't need too many samples:
+        let (step, sigma_in_samples) = if 3.0 * sigma <= MAX_SAMPLES {
+            (1.0, sigma)
+        } else {
+            (3.0 * sigma / MAX_SAMPLES, MAX_SAMPLES / 3.0)
+        };
+
+        self.resize(width as i32, height as i32);
+
+        // Copy what is behind the blur:
+        self.gl.bind_framebuffer(glow::FRAMEBUFFER, target);
+        self.gl
+            .bind_texture(glow::TEXTURE_2D, Some(self.textures[0]));
+        self.gl
+            .copy_tex_sub_image_2d(glow::TEXTURE_2D, 0, x, y, x, y, w, h);
+        check_for_gl_error!(&self.gl, "backdrop blur copy");
+
+        self.gl.use_program(Some(self.program));
+        self.gl.active_texture(glow::TEXTURE0);
+        self.gl.uniform_1_i32(Some(&self.u_sampler), 0);
+        self.gl
+            .uniform_2_f32(Some(&self.u_screen_size), width, height);
+        self.gl.uniform_1_f32(Some(&self.u_sigma), sigma_in_samples);
+        // Only sample the centers of the pixels we copied:
+        let inner = sample_rect.shrink(0.5);
+        self.gl.uniform_4_f32(
+            Some(&self.u_sample_rect),
+            inner.min.x,
+            inner.min.y,
+            inner.max.x,
+            inner.max.y,
+        );
+        self.gl.viewport(0, 0, width as i32, height as i32);
+        self.vao.bind(&self.gl);
+        self.gl
+            .bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.index_buffer));
+
+        // Blur horizontally into the other texture, everywhere we will sample next:
+        self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.fbo));
+        self.gl.disable(glow::SCISSOR_TEST);
+        self.gl.disable(glow::BLEND);
+        self.set_rect(sample_rect);
+        self.gl.uniform_2_f32(Some(&self.u_step), step, 0.0);
+        // No rounded rectangle yet, just somewhere larger than what we paint:
+        let no_mask = sample_rect.expand(1.0);
+        self.gl.uniform_4_f32(
+            Some(&self.u_mask_rect),
+            no_mask.center().x,
+            no_mask.center().y,
+            0.5 * no_mask.width(),
+            0.5 * no_mask.height(),
+        );
+        self.gl
+            .uniform_4_f32(Some(&self.u_mask_rounding), 0.0, 0.0, 0.0, 0.0);
+        self.gl
+            .draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_BYTE, 0);
+
+        // Then vertically, onto the target, within the rounded rectangle:
+        self.gl.bind_framebuffer(glow::FRAMEBUFFER, target);
+        self.gl.enable(glow::SCISSOR_TEST);
+        self.gl.enable(glow::BLEND);
+        self.gl
+            .bind_texture(glow::TEXTURE_2D, Some(self.textures[1]));
+        self.set_rect(paint_rect);
+        self.gl.uniform_2_f32(Some(&self.u_step), 0.0, step);
+        let egui::epaint::Rounding { nw, ne, sw, se } = blur.rounding;
+        let center = rect.center();
+        let half_size = 0.5 * rect.size();
+        self.gl.uniform_4_f32(
+            Some(&self.u_mask_rect),
+            center.x,
+            center.y,
+            half_size.x,
+            half_size.y,
+        );
+        self.gl.uniform_4_f32(
+            Some(&self.u_mask_rounding),
+            pixels_per_point * nw,
+            pixels_per_point * ne,
+            pixels_per_point * sw,
+            pixels_per_point * se,
+        );
+        self.gl
+            .draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_BYTE, 0);
+
+        self.vao.unbind(&self.gl);
+        self.gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, None);
+        self.gl.bind_texture(glow::TEXTURE_2D, None);
+
+        check_for_gl_error!(&self.gl, "backdrop blur");
+    }
+
+    unsafe fn set_rect(&self, rect: egui::Rect) {
+        self.gl.uniform_4_f32(
+            Some(&self.u_rect),
+            rect.min.x,
+            rect.min.y,
+            rect.max.x,
+            rect.max.y,
+        );
+    }
+
+    pub(crate) unsafe fn destroy(&self) {
+        self.gl.delete_buffer(self.pos_buffer);
+        self.gl.delete_buffer(self.index_buffer);
+        self.gl.delete_program(self.program);
+        self.gl.delete_framebuffer(self.fbo);
+        for texture in self.textures {
+            self.gl.delete_texture(texture);
+        }
+    }
+}
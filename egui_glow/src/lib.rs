@@ -14,6 +14,7 @@
 pub mod painter;
 pub use glow;
 pub use painter::{CallbackFn, Painter};
+mod backdrop_blur;
 mod misc_util;
 mod post_process;
 mod shader_version;
@@ -1,6 +1,9 @@
 #![allow(unsafe_code)]
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use egui::{
     emath::Rect,
@@ -9,6 +12,7 @@ use egui::{
 use glow::HasContext as _;
 use memoffset::offset_of;
 
+use crate::backdrop_blur::BackdropBlurPass;
 use crate::check_for_gl_error;
 use crate::misc_util::{compile_shader, link_program};
 use crate::post_process::PostProcess;
@@ -35,6 +39,48 @@ impl TextureFilterExt for TextureFilter {
     }
 }
 
+trait TextureOptionsExt {
+    fn min_filter_glow_code(&self) -> u32;
+    fn wrap_glow_code(&self) -> u32;
+}
+
+impl TextureOptionsExt for egui::TextureOptions {
+    fn min_filter_glow_code(&self) -> u32 {
+        match (self.minification, self.mipmap_mode) {
+            (minification, None) => minification.glow_code(),
+            (TextureFilter::Nearest, Some(TextureFilter::Nearest)) => glow::NEAREST_MIPMAP_NEAREST,
+            (TextureFilter::Nearest, Some(TextureFilter::Linear)) => glow::NEAREST_MIPMAP_LINEAR,
+            (TextureFilter::Linear, Some(TextureFilter::Nearest)) => glow::LINEAR_MIPMAP_NEAREST,
+            (TextureFilter::Linear, Some(TextureFilter::Linear)) => glow::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    fn wrap_glow_code(&self) -> u32 {
+        match self.wrap_mode {
+            egui::TextureWrapMode::ClampToEdge => glow::CLAMP_TO_EDGE,
+            egui::TextureWrapMode::Repeat => glow::REPEAT,
+            egui::TextureWrapMode::MirroredRepeat => glow::MIRRORED_REPEAT,
+        }
+    }
+}
+
+trait BlendModeExt {
+    /// Source and destination factors for the color channels.
+    fn glow_blend_func(&self) -> (u32, u32);
+}
+
+impl BlendModeExt for egui::BlendMode {
+    fn glow_blend_func(&self) -> (u32, u32) {
+        // egui outputs colors with premultiplied alpha:
+        match self {
+            egui::BlendMode::Normal => (glow::ONE, glow::ONE_MINUS_SRC_ALPHA),
+            egui::BlendMode::Multiply => (glow::DST_COLOR, glow::ONE_MINUS_SRC_ALPHA),
+            egui::BlendMode::Screen => (glow::ONE_MINUS_DST_COLOR, glow::ONE),
+            egui::BlendMode::Additive => (glow::ONE, glow::ONE),
+        }
+    }
+}
+
 /// An OpenGL painter using [`glow`].
 ///
 /// This is responsible for painting egui and managing egui textures.
@@ -50,16 +96,27 @@ pub struct Painter {
     program: glow::Program,
     u_screen_size: glow::UniformLocation,
     u_sampler: glow::UniformLocation,
+    u_sdf: glow::UniformLocation,
     is_webgl_1: bool,
     is_embedded: bool,
     vao: crate::vao::VertexArrayObject,
     srgb_support: bool,
     post_process: Option<PostProcess>,
+
+    /// For [`egui::BackdropBlur`], if the shaders for it work.
+    backdrop_blur: Option<BackdropBlurPass>,
+
     vbo: glow::Buffer,
     element_array_buffer: glow::Buffer,
 
     textures: HashMap<egui::TextureId, glow::Texture>,
 
+    /// Textures that are signed distance fields (see [`egui::FontImage::sdf`]).
+    sdf_textures: HashSet<egui::TextureId>,
+
+    /// Size of each texture, so that partial updates know the size of the whole texture.
+    texture_sizes: HashMap<egui::TextureId, [usize; 2]>,
+
     next_native_tex_id: u64,
 
     /// Stores outdated OpenGL textures that are yet to be deleted
@@ -178,6 +235,7 @@ impl Painter {
             gl.delete_shader(frag);
             let u_screen_size = gl.get_uniform_location(program, "u_screen_size").unwrap();
             let u_sampler = gl.get_uniform_location(program, "u_sampler").unwrap();
+            let u_sdf = gl.get_uniform_location(program, "u_sdf").unwrap();
 
             let vbo = gl.create_buffer()?;
 
@@ -216,6 +274,15 @@ impl Painter {
 
             let element_array_buffer = gl.create_buffer()?;
 
+            let backdrop_blur =
+                match BackdropBlurPass::new(gl.clone(), shader_prefix, is_webgl_1, srgb_support) {
+                    Ok(backdrop_blur) => Some(backdrop_blur),
+                    Err(err) => {
+                        tracing::warn!("Backdrop blur is not supported: {}", err);
+                        None
+                    }
+                };
+
             crate::check_for_gl_error_even_in_release!(&gl, "after Painter::new");
 
             Ok(Painter {
@@ -224,14 +291,18 @@ impl Painter {
                 program,
                 u_screen_size,
                 u_sampler,
+                u_sdf,
                 is_webgl_1,
                 is_embedded: matches!(shader_version, ShaderVersion::Es100 | ShaderVersion::Es300),
                 vao,
                 srgb_support,
                 post_process,
+                backdrop_blur,
                 vbo,
                 element_array_buffer,
                 textures: Default::default(),
+                sdf_textures: Default::default(),
+                texture_sizes: Default::default(),
                 next_native_tex_id: 1 << 32,
                 textures_to_destroy: Vec::new(),
                 destroyed: false,
@@ -261,6 +332,18 @@ impl Painter {
         self.post_process.as_ref().map(|pp| pp.fbo())
     }
 
+    unsafe fn set_blend_mode(&self, blend_mode: egui::BlendMode) {
+        let (src, dst) = blend_mode.glow_blend_func();
+        self.gl.blend_func_separate(
+            src,
+            dst,
+            // Less important, but this is technically the correct alpha blend function
+            // when you want to make use of the framebuffer alpha (for screenshots, compositing, etc).
+            glow::ONE_MINUS_DST_ALPHA,
+            glow::ONE,
+        );
+    }
+
     unsafe fn prepare_painting(
         &mut self,
         [width_in_pixels, height_in_pixels]: [u32; 2],
@@ -276,15 +359,7 @@ impl Painter {
         self.gl.enable(glow::BLEND);
         self.gl
             .blend_equation_separate(glow::FUNC_ADD, glow::FUNC_ADD);
-        self.gl.blend_func_separate(
-            // egui outputs colors with premultiplied alpha:
-            glow::ONE,
-            glow::ONE_MINUS_SRC_ALPHA,
-            // Less important, but this is technically the correct alpha blend function
-            // when you want to make use of the framebuffer alpha (for screenshots, compositing, etc).
-            glow::ONE_MINUS_DST_ALPHA,
-            glow::ONE,
-        );
+        self.set_blend_mode(egui::BlendMode::Normal);
 
         if !cfg!(target_arch = "wasm32") {
             self.gl.enable(glow::FRAMEBUFFER_SRGB);
@@ -374,15 +449,21 @@ impl Painter {
         }
         let size_in_pixels = unsafe { self.prepare_painting(screen_size_px, pixels_per_point) };
 
+        let mut current_blend_mode = egui::BlendMode::Normal;
         for egui::ClippedPrimitive {
             clip_rect,
             primitive,
+            blend_mode,
         } in clipped_primitives
         {
             set_clip_rect(&self.gl, size_in_pixels, pixels_per_point, *clip_rect);
 
             match primitive {
                 Primitive::Mesh(mesh) => {
+                    if *blend_mode != current_blend_mode {
+                        unsafe { self.set_blend_mode(*blend_mode) };
+                        current_blend_mode = *blend_mode;
+                    }
                     self.paint_mesh(mesh);
                 }
                 Primitive::Callback(callback) => {
@@ -430,6 +511,24 @@ impl Painter {
                             }
                             self.prepare_painting(screen_size_px, pixels_per_point)
                         };
+                        current_blend_mode = egui::BlendMode::Normal;
+                    }
+                }
+                Primitive::BackdropBlur(blur) => {
+                    let target = self.intermediate_fbo();
+                    if let Some(backdrop_blur) = &mut self.backdrop_blur {
+                        crate::profile_scope!("backdrop_blur");
+                        unsafe {
+                            backdrop_blur.paint(
+                                blur,
+                                *clip_rect,
+                                screen_size_px,
+                                pixels_per_point,
+                                target,
+                            );
+                            self.prepare_painting(screen_size_px, pixels_per_point);
+                        }
+                        current_blend_mode = egui::BlendMode::Normal;
                     }
                 }
             }
@@ -470,6 +569,10 @@ impl Painter {
                 );
 
                 self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                self.gl.uniform_1_i32(
+                    Some(&self.u_sdf),
+                    self.sdf_textures.contains(&mesh.texture_id) as i32,
+                );
             }
 
             unsafe {
@@ -502,6 +605,15 @@ impl Painter {
             self.gl.bind_texture(glow::TEXTURE_2D, Some(glow_texture));
         }
 
+        if delta.is_whole() {
+            self.texture_sizes.insert(tex_id, delta.image.size());
+        }
+        let texture_size = self
+            .texture_sizes
+            .get(&tex_id)
+            .copied()
+            .unwrap_or_else(|| delta.image.size());
+
         match &delta.image {
             egui::ImageData::Color(image) => {
                 assert_eq!(
@@ -510,9 +622,11 @@ impl Painter {
                     "Mismatch between texture size and texel count"
                 );
 
+                self.sdf_textures.remove(&tex_id);
+
                 let data: &[u8] = bytemuck::cast_slice(image.pixels.as_ref());
 
-                self.upload_texture_srgb(delta.pos, image.size, delta.filter, data);
+                self.upload_texture_srgb(delta.pos, image.size, texture_size, delta.options, data);
             }
             egui::ImageData::Font(image) => {
                 assert_eq!(
@@ -521,6 +635,12 @@ impl Painter {
                     "Mismatch between texture size and texel count"
                 );
 
+                if image.sdf {
+                    self.sdf_textures.insert(tex_id);
+                } else {
+                    self.sdf_textures.remove(&tex_id);
+                }
+
                 let gamma = if self.is_embedded && self.post_process.is_none() {
                     1.0 / 2.2
                 } else {
@@ -531,7 +651,7 @@ impl Painter {
                     .flat_map(|a| a.to_array())
                     .collect();
 
-                self.upload_texture_srgb(delta.pos, image.size, delta.filter, &data);
+                self.upload_texture_srgb(delta.pos, image.size, texture_size, delta.options, &data);
             }
         };
     }
@@ -540,7 +660,8 @@ impl Painter {
         &mut self,
         pos: Option<[usize; 2]>,
         [w, h]: [usize; 2],
-        texture_filter: TextureFilter,
+        texture_size: [usize; 2],
+        options: egui::TextureOptions,
         data: &[u8],
     ) {
         assert_eq!(data.len(), w * h * 4);
@@ -558,27 +679,46 @@ impl Painter {
             self.max_texture_side
         );
 
+        // WebGL 1 can only repeat textures and make mipmaps if their sizes are powers of two,
+        // and can't make mipmaps for sRGB textures at all (`EXT_sRGB`).
+        let options = if self.is_webgl_1 {
+            let power_of_two = texture_size.iter().all(|side| side.is_power_of_two());
+            egui::TextureOptions {
+                wrap_mode: if power_of_two {
+                    options.wrap_mode
+                } else {
+                    egui::TextureWrapMode::ClampToEdge
+                },
+                mipmap_mode: options
+                    .mipmap_mode
+                    .filter(|_| power_of_two && !self.srgb_support),
+                ..options
+            }
+        } else {
+            options
+        };
+
         unsafe {
             self.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MAG_FILTER,
-                texture_filter.glow_code() as i32,
+                options.magnification.glow_code() as i32,
             );
             self.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MIN_FILTER,
-                texture_filter.glow_code() as i32,
+                options.min_filter_glow_code() as i32,
             );
 
             self.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_WRAP_S,
-                glow::CLAMP_TO_EDGE as i32,
+                options.wrap_glow_code() as i32,
             );
             self.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_WRAP_T,
-                glow::CLAMP_TO_EDGE as i32,
+                options.wrap_glow_code() as i32,
             );
             check_for_gl_error!(&self.gl, "tex_parameter");
 
@@ -624,10 +764,17 @@ impl Painter {
                 );
                 check_for_gl_error!(&self.gl, "tex_image_2d");
             }
+
+            if options.mipmap_mode.is_some() {
+                self.gl.generate_mipmap(glow::TEXTURE_2D);
+                check_for_gl_error!(&self.gl, "generate_mipmap");
+            }
         }
     }
 
     pub fn free_texture(&mut self, tex_id: egui::TextureId) {
+        self.sdf_textures.remove(&tex_id);
+        self.texture_sizes.remove(&tex_id);
         if let Some(old_tex) = self.textures.remove(&tex_id) {
             unsafe { self.gl.delete_texture(old_tex) };
         }
@@ -675,6 +822,9 @@ impl Painter {
                 if let Some(ref post_process) = self.post_process {
                     post_process.destroy();
                 }
+                if let Some(ref backdrop_blur) = self.backdrop_blur {
+                    backdrop_blur.destroy();
+                }
             }
             self.destroyed = true;
         }
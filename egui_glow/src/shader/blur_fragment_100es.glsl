@@ -0,0 +1,40 @@
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+uniform sampler2D u_sampler;
+uniform vec2 u_screen_size; // in pixels
+uniform vec2 u_step; // from one sample to the next, in pixels
+uniform float u_sigma; // standard deviation of the gaussian, in samples
+uniform vec4 u_sample_rect; // the pixels we may sample: min x, min y, max x, max y
+uniform vec4 u_mask_rect; // the rounded rectangle to paint: center x, center y, half width, half height
+uniform vec4 u_mask_rounding; // corner radii: top left, top right, bottom left, bottom right
+varying vec2 v_pos; // in pixels
+
+const int MAX_SAMPLES = 24; // on each side of the center
+
+// How much of the pixel at `pos` is inside the rounded rectangle.
+float mask(vec2 pos) {
+    vec2 p = pos - u_mask_rect.xy;
+    float radius = p.y > 0.0
+        ? (p.x < 0.0 ? u_mask_rounding.x : u_mask_rounding.y)
+        : (p.x < 0.0 ? u_mask_rounding.z : u_mask_rounding.w);
+    vec2 q = abs(p) - u_mask_rect.zw + radius;
+    float distance = min(max(q.x, q.y), 0.0) + length(max(q, 0.0)) - radius;
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
+void main() {
+    vec4 sum = vec4(0.0);
+    float weight_sum = 0.0;
+    for (int i = -MAX_SAMPLES; i <= MAX_SAMPLES; i++) {
+        float x = float(i);
+        float weight = exp(-0.5 * x * x / (u_sigma * u_sigma));
+        vec2 pos = clamp(v_pos + x * u_step, u_sample_rect.xy, u_sample_rect.zw);
+        sum += weight * texture2D(u_sampler, pos / u_screen_size);
+        weight_sum += weight;
+    }
+    gl_FragColor = mask(v_pos) * sum / weight_sum;
+}
@@ -0,0 +1,10 @@
+precision mediump float;
+uniform vec2 u_screen_size; // in pixels
+uniform vec4 u_rect; // where to paint, in pixels from the bottom left: min x, min y, max x, max y
+attribute vec2 a_pos;
+varying vec2 v_pos; // in pixels
+
+void main() {
+    v_pos = mix(u_rect.xy, u_rect.zw, a_pos);
+    gl_Position = vec4(2.0 * v_pos / u_screen_size - 1.0, 0.0, 1.0);
+}
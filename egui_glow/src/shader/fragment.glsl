@@ -1,9 +1,17 @@
+#ifdef GL_OES_standard_derivatives
+    // For `fwidth` in WebGL1:
+    #extension GL_OES_standard_derivatives : enable
+#endif
+
 #ifdef GL_ES
     precision mediump float;
 #endif
 
 uniform sampler2D u_sampler;
 
+// Is the texture a signed distance field (`epaint::FontImage::sdf`)?
+uniform bool u_sdf;
+
 #ifdef NEW_SHADER_INTERFACE
     in vec4 v_rgba;
     in vec2 v_tc;
@@ -16,11 +24,33 @@ uniform sampler2D u_sampler;
     varying vec2 v_tc;
 #endif
 
+// The distance is in alpha, with the edge at 0.5, and the color is not premultiplied.
+// Returns the premultiplied color.
+vec4 from_sdf(vec4 texel) {
+#if defined(GL_ES) && __VERSION__ < 300 && !defined(GL_OES_standard_derivatives)
+    float half_width = 0.05; // No derivatives, so guess how much the distance changes per pixel.
+#else
+    float half_width = max(0.5 * fwidth(texel.a), 0.0001);
+#endif
+    float coverage = smoothstep(0.5 - half_width, 0.5 + half_width, texel.a);
+
+    // The distance texels are white. Color glyphs (e.g. emoji) have a blue below 1.0,
+    // and are painted as they are:
+    if (texel.b < 0.999) {
+        coverage = texel.a;
+    }
+    return vec4(texel.rgb * coverage, coverage);
+}
+
 #ifdef SRGB_SUPPORTED
     void main() {
         // The texture sampler is sRGB aware, and OpenGL already expects linear rgba output
         // so no need for any sRGB conversions here:
-        gl_FragColor = v_rgba * texture2D(u_sampler, v_tc);
+        vec4 texture_rgba = texture2D(u_sampler, v_tc);
+        if (u_sdf) {
+            texture_rgba = from_sdf(texture_rgba);
+        }
+        gl_FragColor = v_rgba * texture_rgba;
     }
 #else
     // 0-255 sRGB  from  0-1 linear
@@ -50,6 +80,9 @@ uniform sampler2D u_sampler;
     void main() {
         // We must decode the colors, since WebGL1 doesn't come with sRGBA textures:
         vec4 texture_rgba = linear_from_srgba(texture2D(u_sampler, v_tc) * 255.0);
+        if (u_sdf) {
+            texture_rgba = from_sdf(texture_rgba);
+        }
         /// Multiply vertex color with texture color (in linear space).
         gl_FragColor = v_rgba * texture_rgba;
 
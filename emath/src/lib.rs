@@ -33,6 +33,7 @@ mod rect;
 mod rect_transform;
 mod rot2;
 pub mod smart_aim;
+mod ts_transform;
 mod vec2;
 
 pub use {
@@ -42,6 +43,7 @@ pub use {
     rect::*,
     rect_transform::*,
     rot2::*,
+    ts_transform::*,
     vec2::*,
 };
 
@@ -0,0 +1,223 @@
+use crate::{Pos2, Rect, Vec2};
+
+/// Linearly transforms positions via a scaling, then a translation.
+///
+/// [`TSTransform`] first scales points with the scaling origin at `0, 0`
+/// (the top left corner), then translates them.
+///
+/// This is useful for zooming and panning, e.g. of a node graph canvas.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct TSTransform {
+    /// Scaling applied first, scaled around (0, 0).
+    ///
+    /// Must be positive for the transform to be invertible.
+    pub scaling: f32,
+
+    /// Translation amount, applied after scaling.
+    pub translation: Vec2,
+}
+
+impl Default for TSTransform {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl TSTransform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec2::ZERO,
+        scaling: 1.0,
+    };
+
+    /// Creates a new transform that first scales points around
+    /// `(0, 0)`, then translates them.
+    #[inline]
+    pub fn new(translation: Vec2, scaling: f32) -> Self {
+        Self {
+            translation,
+            scaling,
+        }
+    }
+
+    #[inline]
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self::new(translation, 1.0)
+    }
+
+    #[inline]
+    pub fn from_scaling(scaling: f32) -> Self {
+        Self::new(Vec2::ZERO, scaling)
+    }
+
+    /// Inverts the transform.
+    ///
+    /// The [`Self::scaling`] must be positive.
+    ///
+    /// ```
+    /// # use emath::{pos2, vec2, TSTransform};
+    /// let p1 = pos2(2.0, 3.0);
+    /// let p2 = pos2(12.0, 5.0);
+    /// let ts = TSTransform::new(vec2(2.0, 3.0), 2.0);
+    /// let inv = ts.inverse();
+    /// assert_eq!(inv.mul_pos(p1), pos2(0.0, 0.0));
+    /// assert_eq!(inv.mul_pos(p2), pos2(5.0, 1.0));
+    ///
+    /// assert_eq!(ts.inverse().inverse(), ts);
+    /// ```
+    #[inline]
+    pub fn inverse(&self) -> Self {
+        crate::emath_assert!(
+            self.scaling > 0.0,
+            "Can't invert a TSTransform with a scaling of {}",
+            self.scaling
+        );
+        Self::new(-self.translation / self.scaling, 1.0 / self.scaling)
+    }
+
+    /// Transforms the given coordinate.
+    ///
+    /// ```
+    /// # use emath::{pos2, vec2, TSTransform};
+    /// let p1 = pos2(0.0, 0.0);
+    /// let p2 = pos2(5.0, 1.0);
+    /// let ts = TSTransform::new(vec2(2.0, 3.0), 2.0);
+    /// assert_eq!(ts.mul_pos(p1), pos2(2.0, 3.0));
+    /// assert_eq!(ts.mul_pos(p2), pos2(12.0, 5.0));
+    /// ```
+    #[inline]
+    pub fn mul_pos(&self, pos: Pos2) -> Pos2 {
+        (self.scaling * pos.to_vec2() + self.translation).to_pos2()
+    }
+
+    /// Transforms the given rectangle.
+    ///
+    /// ```
+    /// # use emath::{pos2, vec2, Rect, TSTransform};
+    /// let rect = Rect::from_min_max(pos2(5.0, 5.0), pos2(15.0, 10.0));
+    /// let ts = TSTransform::new(vec2(1.0, 0.0), 3.0);
+    /// let transformed = ts.mul_rect(rect);
+    /// assert_eq!(transformed.min, pos2(16.0, 15.0));
+    /// assert_eq!(transformed.max, pos2(46.0, 30.0));
+    /// ```
+    #[inline]
+    pub fn mul_rect(&self, rect: Rect) -> Rect {
+        Rect {
+            min: self.mul_pos(rect.min),
+            max: self.mul_pos(rect.max),
+        }
+    }
+}
+
+/// Transforms the position.
+impl std::ops::Mul<Pos2> for TSTransform {
+    type Output = Pos2;
+
+    #[inline]
+    fn mul(self, pos: Pos2) -> Pos2 {
+        self.mul_pos(pos)
+    }
+}
+
+/// Transforms the rectangle.
+impl std::ops::Mul<Rect> for TSTransform {
+    type Output = Rect;
+
+    #[inline]
+    fn mul(self, rect: Rect) -> Rect {
+        self.mul_rect(rect)
+    }
+}
+
+/// Applies the right hand side transform, then the left hand side.
+impl std::ops::Mul<Self> for TSTransform {
+    type Output = Self;
+
+    /// Applies the right hand side transform, then the left hand side.
+    ///
+    /// ```
+    /// # use emath::{TSTransform, vec2};
+    /// let ts1 = TSTransform::new(vec2(1.0, 0.0), 2.0);
+    /// let ts2 = TSTransform::new(vec2(-1.0, -1.0), 3.0);
+    /// let ts_combined = TSTransform::new(vec2(2.0, -1.0), 6.0);
+    /// assert_eq!(ts_combined, ts2 * ts1);
+    /// ```
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        // Apply rhs first.
+        Self {
+            scaling: self.scaling * rhs.scaling,
+            translation: self.translation + self.scaling * rhs.translation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TSTransform;
+    use crate::{pos2, vec2, Rect};
+
+    #[test]
+    fn test_inverse() {
+        let transforms = [
+            TSTransform::IDENTITY,
+            TSTransform::from_translation(vec2(-3.0, 4.0)),
+            TSTransform::from_scaling(0.25),
+            TSTransform::new(vec2(10.0, -20.0), 3.0),
+        ];
+        let points = [pos2(0.0, 0.0), pos2(1.5, -2.0), pos2(-100.0, 50.0)];
+        for transform in transforms {
+            for identity in [
+                transform * transform.inverse(),
+                transform.inverse() * transform,
+            ] {
+                assert!((identity.scaling - 1.0).abs() < 1e-6, "{:?}", identity);
+                assert!(identity.translation.length() < 1e-5, "{:?}", identity);
+            }
+            for point in points {
+                let round_trip = transform.inverse() * (transform * point);
+                assert!(round_trip.distance(point) < 1e-4, "{:?}", round_trip);
+            }
+        }
+    }
+
+    #[test]
+    fn test_composition() {
+        let zoom = TSTransform::from_scaling(2.0);
+        let pan = TSTransform::from_translation(vec2(5.0, -5.0));
+        let point = pos2(1.0, 2.0);
+
+        // The right hand side is applied first:
+        assert_eq!((pan * zoom) * point, pos2(7.0, -1.0));
+        assert_eq!((zoom * pan) * point, pos2(12.0, -6.0));
+        assert_eq!((pan * zoom) * point, pan * (zoom * point));
+
+        // Composition is associative:
+        let other = TSTransform::new(vec2(-1.0, 3.0), 0.5);
+        assert_eq!((pan * zoom) * other, pan * (zoom * other));
+
+        assert_eq!(TSTransform::IDENTITY * other, other);
+        assert_eq!(other * TSTransform::IDENTITY, other);
+        assert_eq!(TSTransform::default(), TSTransform::IDENTITY);
+    }
+
+    #[test]
+    fn test_rect_mapping() {
+        let transform = TSTransform::new(vec2(10.0, 20.0), 2.0);
+        let rect = Rect::from_min_max(pos2(-1.0, 0.0), pos2(3.0, 5.0));
+        let transformed = transform * rect;
+
+        assert_eq!(transformed.min, transform * rect.min);
+        assert_eq!(transformed.max, transform * rect.max);
+        assert_eq!(transformed.size(), 2.0 * rect.size());
+        assert_eq!(transformed.center(), transform * rect.center());
+        assert_eq!(transform.inverse() * transformed, rect);
+
+        // Points inside stay inside:
+        assert!(transformed.contains(transform * pos2(0.0, 1.0)));
+        assert!(!transformed.contains(transform * pos2(4.0, 1.0)));
+    }
+}
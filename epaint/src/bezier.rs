@@ -1,7 +1,7 @@
 #![allow(clippy::many_single_char_names)]
 use std::ops::Range;
 
-use crate::{shape::Shape, Color32, PathShape, Stroke};
+use crate::{shape::Shape, Brush, PathShape, Stroke};
 use emath::*;
 
 // ----------------------------------------------------------------------------
@@ -17,7 +17,7 @@ pub struct CubicBezierShape {
     pub points: [Pos2; 4],
     pub closed: bool,
 
-    pub fill: Color32,
+    pub fill: Brush,
     pub stroke: Stroke,
 }
 
@@ -29,13 +29,13 @@ impl CubicBezierShape {
     pub fn from_points_stroke(
         points: [Pos2; 4],
         closed: bool,
-        fill: Color32,
+        fill: impl Into<Brush>,
         stroke: impl Into<Stroke>,
     ) -> Self {
         Self {
             points,
             closed,
-            fill,
+            fill: fill.into(),
             stroke: stroke.into(),
         }
     }
@@ -67,6 +67,8 @@ impl CubicBezierShape {
                 points,
                 closed: self.closed,
                 fill: self.fill,
+                fill_mode: Default::default(),
+                holes: Vec::new(),
                 stroke: self.stroke,
             };
             pathshapes.push(pathshape);
@@ -76,7 +78,7 @@ impl CubicBezierShape {
 
     /// The visual bounding rectangle (includes stroke width)
     pub fn visual_bounding_rect(&self) -> Rect {
-        if self.fill == Color32::TRANSPARENT && self.stroke.is_empty() {
+        if self.fill.is_transparent() && self.stroke.is_empty() {
             Rect::NOTHING
         } else {
             self.logical_bounding_rect().expand(self.stroke.width / 2.0)
@@ -380,7 +382,7 @@ pub struct QuadraticBezierShape {
     pub points: [Pos2; 3],
     pub closed: bool,
 
-    pub fill: Color32,
+    pub fill: Brush,
     pub stroke: Stroke,
 }
 
@@ -393,13 +395,13 @@ impl QuadraticBezierShape {
     pub fn from_points_stroke(
         points: [Pos2; 3],
         closed: bool,
-        fill: Color32,
+        fill: impl Into<Brush>,
         stroke: impl Into<Stroke>,
     ) -> Self {
         QuadraticBezierShape {
             points,
             closed,
-            fill,
+            fill: fill.into(),
             stroke: stroke.into(),
         }
     }
@@ -426,13 +428,15 @@ impl QuadraticBezierShape {
             points,
             closed: self.closed,
             fill: self.fill,
+            fill_mode: Default::default(),
+            holes: Vec::new(),
             stroke: self.stroke,
         }
     }
 
     /// The visual bounding rectangle (includes stroke width)
     pub fn visual_bounding_rect(&self) -> Rect {
-        if self.fill == Color32::TRANSPARENT && self.stroke.is_empty() {
+        if self.fill.is_transparent() && self.stroke.is_empty() {
             Rect::NOTHING
         } else {
             self.logical_bounding_rect().expand(self.stroke.width / 2.0)
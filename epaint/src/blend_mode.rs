@@ -0,0 +1,51 @@
+/// How the colors of a shape are combined with what has already been painted behind it.
+///
+/// All modes work on premultiplied colors, so that a transparent part of a shape never changes what is behind it.
+///
+/// Used by [`crate::GroupShape`], and by the backends for each [`crate::ClippedPrimitive`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum BlendMode {
+    /// Paint on top of what is behind (alpha blending).
+    Normal,
+
+    /// Multiply the colors with what is behind, which makes it darker.
+    Multiply,
+
+    /// The inverse of multiplying the inverted colors, which makes it lighter.
+    Screen,
+
+    /// Add the colors to what is behind, e.g. for glows and light effects.
+    Additive,
+}
+
+impl Default for BlendMode {
+    #[inline]
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl BlendMode {
+    pub const ALL: [Self; 4] = [Self::Normal, Self::Multiply, Self::Screen, Self::Additive];
+
+    /// Blend the premultiplied color `src` onto `dst`, the way the backends do it.
+    ///
+    /// The alpha channel always uses normal alpha blending.
+    pub fn blend(self, src: crate::Rgba, dst: crate::Rgba) -> crate::Rgba {
+        let [sr, sg, sb, sa] = src.to_array();
+        let [dr, dg, db, da] = dst.to_array();
+        let color = |s: f32, d: f32| match self {
+            Self::Normal => s + d * (1.0 - sa),
+            Self::Multiply => s * d + d * (1.0 - sa),
+            Self::Screen => s * (1.0 - d) + d,
+            Self::Additive => s + d,
+        };
+        crate::Rgba::from_rgba_premultiplied(
+            color(sr, dr),
+            color(sg, dg),
+            color(sb, db),
+            sa + da * (1.0 - sa),
+        )
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    color::{Hsva, HsvaGamma},
+    Color32, Rgba,
+};
+use emath::{Pos2, Rect, Vec2};
+
+/// How to fill a shape: with a solid color, or with a gradient.
+///
+/// The positions of a gradient are relative to the bounding rectangle of the shape it fills,
+/// so that `(0, 0)` is its left top corner and `(1, 1)` its right bottom corner.
+/// That way the same [`Brush`] can be used for shapes of all sizes, e.g. in a `Frame` or in the `Visuals`.
+///
+/// ```
+/// # use epaint::*;
+/// // Top to bottom:
+/// let brush = Brush::linear_gradient(pos2(0.0, 0.0), pos2(0.0, 1.0), Color32::WHITE, Color32::BLACK);
+/// assert_eq!(brush.color_at(pos2(10.0, 10.0), Rect::from_min_size(pos2(10.0, 10.0), vec2(5.0, 5.0))), Color32::WHITE);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Brush {
+    Solid(Color32),
+
+    /// The color changes along the line from `start` to `end`,
+    /// and is the same along lines perpendicular to it.
+    LinearGradient {
+        start: Pos2,
+        end: Pos2,
+        stops: ColorStops,
+    },
+
+    /// The color changes with the distance from `center`,
+    /// reaching the last stop at `radius` (which can be different horizontally and vertically).
+    RadialGradient {
+        center: Pos2,
+        radius: Vec2,
+        stops: ColorStops,
+    },
+}
+
+impl Default for Brush {
+    #[inline]
+    fn default() -> Self {
+        Self::Solid(Color32::TRANSPARENT)
+    }
+}
+
+impl From<Color32> for Brush {
+    #[inline(always)]
+    fn from(color: Color32) -> Self {
+        Self::Solid(color)
+    }
+}
+
+impl From<Rgba> for Brush {
+    #[inline(always)]
+    fn from(color: Rgba) -> Self {
+        Self::Solid(color.into())
+    }
+}
+
+impl From<Hsva> for Brush {
+    #[inline(always)]
+    fn from(color: Hsva) -> Self {
+        Self::Solid(color.into())
+    }
+}
+
+impl From<HsvaGamma> for Brush {
+    #[inline(always)]
+    fn from(color: HsvaGamma) -> Self {
+        Self::Solid(color.into())
+    }
+}
+
+impl Brush {
+    /// A gradient from `start_color` at `start` to `end_color` at `end`.
+    pub fn linear_gradient(
+        start: Pos2,
+        end: Pos2,
+        start_color: impl Into<Color32>,
+        end_color: impl Into<Color32>,
+    ) -> Self {
+        Self::LinearGradient {
+            start,
+            end,
+            stops: ColorStops::new(&[(0.0, start_color.into()), (1.0, end_color.into())]),
+        }
+    }
+
+    /// A gradient from `center_color` in the center of the shape to `edge_color` at its edges.
+    pub fn radial_gradient(
+        center_color: impl Into<Color32>,
+        edge_color: impl Into<Color32>,
+    ) -> Self {
+        Self::RadialGradient {
+            center: Pos2::new(0.5, 0.5),
+            radius: Vec2::splat(0.5),
+            stops: ColorStops::new(&[(0.0, center_color.into()), (1.0, edge_color.into())]),
+        }
+    }
+
+    /// True if every color is transparent, so there is nothing to paint.
+    pub fn is_transparent(&self) -> bool {
+        match self {
+            Self::Solid(color) => *color == Color32::TRANSPARENT,
+            Self::LinearGradient { stops, .. } | Self::RadialGradient { stops, .. } => stops
+                .as_slice()
+                .iter()
+                .all(|(_, color)| *color == Color32::TRANSPARENT),
+        }
+    }
+
+    /// The color of a solid brush, or the average color of the stops of a gradient.
+    pub fn average_color(&self) -> Color32 {
+        match self {
+            Self::Solid(color) => *color,
+            Self::LinearGradient { stops, .. } | Self::RadialGradient { stops, .. } => {
+                let stops = stops.as_slice();
+                let sum = stops.iter().fold(Rgba::TRANSPARENT, |sum, (_, color)| {
+                    sum + Rgba::from(*color)
+                });
+                (sum * (1.0 / stops.len() as f32)).into()
+            }
+        }
+    }
+
+    /// The color at `pos`, when filling a shape with the bounding rectangle `rect`.
+    pub fn color_at(&self, pos: Pos2, rect: Rect) -> Color32 {
+        match self {
+            Self::Solid(color) => *color,
+            Self::LinearGradient { stops, .. } | Self::RadialGradient { stops, .. } => {
+                stops.color_at(self.gradient_t(pos, rect))
+            }
+        }
+    }
+
+    /// How far along the gradient `pos` is, where the first stop is at `0` and the last at `1`
+    /// (unless their offsets say otherwise).
+    pub(crate) fn gradient_t(&self, pos: Pos2, rect: Rect) -> f32 {
+        let size = rect.size();
+        match *self {
+            Self::Solid(_) => 0.0,
+            Self::LinearGradient { start, end, .. } => {
+                let start = rect.min + start.to_vec2() * size;
+                let end = rect.min + end.to_vec2() * size;
+                let dir = end - start;
+                let length_sq = dir.length_sq();
+                if length_sq > 0.0 {
+                    (pos - start).dot(dir) / length_sq
+                } else {
+                    0.0
+                }
+            }
+            Self::RadialGradient { center, radius, .. } => {
+                let center = rect.min + center.to_vec2() * size;
+                let radius = radius * size;
+                let d = pos - center;
+                Vec2::new(
+                    if radius.x > 0.0 { d.x / radius.x } else { 0.0 },
+                    if radius.y > 0.0 { d.y / radius.y } else { 0.0 },
+                )
+                .length()
+            }
+        }
+    }
+
+    /// Multiply all colors with this factor, e.g. to fade them out.
+    pub fn linear_multiply(mut self, factor: f32) -> Self {
+        self.adjust_colors(|color| *color = color.linear_multiply(factor));
+        self
+    }
+
+    /// Change every color of the brush.
+    pub fn adjust_colors(&mut self, mut adjust_color: impl FnMut(&mut Color32)) {
+        match self {
+            Self::Solid(color) => adjust_color(color),
+            Self::LinearGradient { stops, .. } | Self::RadialGradient { stops, .. } => {
+                for (_, color) in stops.as_mut_slice() {
+                    adjust_color(color);
+                }
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The colors of a gradient, and where along the gradient (from `0` to `1`) they are.
+///
+/// Holds up to [`Self::MAX`] stops, so that a [`Brush`] is `Copy`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ColorStops {
+    stops: [(f32, Color32); Self::MAX],
+    len: usize,
+}
+
+impl ColorStops {
+    /// The most stops a gradient can have.
+    pub const MAX: usize = 8;
+
+    /// Stops as `(offset, color)`, sorted by offset.
+    ///
+    /// Panics if there are no stops, or more than [`Self::MAX`].
+    pub fn new(stops: &[(f32, Color32)]) -> Self {
+        assert!(
+            !stops.is_empty() && stops.len() <= Self::MAX,
+            "A gradient needs 1-{} color stops, got {}",
+            Self::MAX,
+            stops.len()
+        );
+        let mut slf = Self {
+            stops: [(0.0, Color32::TRANSPARENT); Self::MAX],
+            len: stops.len(),
+        };
+        slf.stops[..stops.len()].copy_from_slice(stops);
+        slf.as_mut_slice()
+            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        slf
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[(f32, Color32)] {
+        &self.stops[..self.len]
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [(f32, Color32)] {
+        &mut self.stops[..self.len]
+    }
+
+    /// The color at `t`, interpolated (in linear space) between the closest stops.
+    pub fn color_at(&self, t: f32) -> Color32 {
+        let stops = self.as_slice();
+        let (first, last) = (stops[0], stops[stops.len() - 1]);
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+        for pair in stops.windows(2) {
+            let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+            if t <= t1 {
+                if t1 <= t0 {
+                    return c1;
+                }
+                let f = (t - t0) / (t1 - t0);
+                return (Rgba::from(c0) * (1.0 - f) + Rgba::from(c1) * f).into();
+            }
+        }
+        last.1
+    }
+}
+
+impl PartialEq for ColorStops {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{textures::TextureFilter, Color32};
+use crate::{textures::TextureOptions, Color32};
 
 /// An image stored in RAM.
 ///
@@ -158,11 +158,15 @@ impl From<ColorImage> for ImageData {
 
 // ----------------------------------------------------------------------------
 
-/// A single-channel image designed for the font texture.
+/// An image designed for the font texture.
 ///
 /// Each value represents "coverage", i.e. how much a texel is covered by a character.
 ///
 /// This is roughly interpreted as the opacity of a white image.
+///
+/// Color glyphs (e.g. emoji) are instead stored as colors, see [`Self::colors`].
+///
+/// With [`crate::text::GlyphRendering::Sdf`] the values are instead a signed distance field, see [`Self::sdf`].
 #[derive(Clone, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FontImage {
@@ -173,6 +177,20 @@ pub struct FontImage {
     ///
     /// Often you want to use [`Self::srgba_pixels`] instead.
     pub pixels: Vec<f32>,
+
+    /// Premultiplied colors of color glyphs.
+    ///
+    /// Either empty (there are no color glyphs), or the same length as [`Self::pixels`].
+    /// Where a color is not [`Color32::TRANSPARENT`] it is used instead of the coverage.
+    pub colors: Vec<Color32>,
+
+    /// Are the values a signed distance field rather than coverage?
+    ///
+    /// Then a value is the distance to the closest glyph outline, mapped so that
+    /// `0.5` is on the outline, `1.0` well inside and `0.0` well outside.
+    /// The painter needs to turn this into coverage (usually with `smoothstep`
+    /// over the screen space derivative of the value), so that glyphs stay sharp at any scale.
+    pub sdf: bool,
 }
 
 impl FontImage {
@@ -180,6 +198,8 @@ impl FontImage {
         Self {
             size,
             pixels: vec![0.0; size[0] * size[1]],
+            colors: vec![],
+            sdf: false,
         }
     }
 
@@ -198,8 +218,29 @@ impl FontImage {
     /// `gamma` should normally be set to 1.0.
     /// If you are having problems with text looking skinny and pixelated, try
     /// setting a lower gamma, e.g. `0.5`.
+    ///
+    /// If this is a signed distance field ([`Self::sdf`]) the pixels are instead _unmultiplied_,
+    /// with the distance in alpha, and `gamma` is ignored.
+    /// The distance texels are then white, and the texels of color glyphs have a blue of at most 254,
+    /// so that the shader can tell them apart and only threshold the distances.
     pub fn srgba_pixels(&'_ self, gamma: f32) -> impl ExactSizeIterator<Item = Color32> + '_ {
-        self.pixels.iter().map(move |coverage| {
+        self.pixels.iter().enumerate().map(move |(i, coverage)| {
+            if let Some(&color) = self.colors.get(i) {
+                if color != Color32::TRANSPARENT {
+                    if self.sdf {
+                        // The distance texels are white, so a blue below 255 marks a color texel:
+                        let [r, g, b, a] = color.to_srgba_unmultiplied();
+                        return Color32::from_rgba_premultiplied(r, g, b.min(254), a);
+                    }
+                    return color;
+                }
+            }
+
+            if self.sdf {
+                let distance = fast_round(coverage * 255.0);
+                return Color32::from_rgba_premultiplied(255, 255, 255, distance);
+            }
+
             // This is arbitrarily chosen to make text look as good as possible.
             // In particular, it looks good with gamma=1 and the default eframe backend,
             // which uses linear blending.
@@ -209,20 +250,36 @@ impl FontImage {
         })
     }
 
+    /// Set the (premultiplied) color of a texel, making it part of a color glyph.
+    pub fn set_color(&mut self, (x, y): (usize, usize), color: Color32) {
+        let [w, h] = self.size;
+        assert!(x < w && y < h);
+        if self.colors.is_empty() {
+            self.colors = vec![Color32::TRANSPARENT; self.pixels.len()];
+        }
+        self.colors[y * w + x] = color;
+    }
+
     /// Clone a sub-region as a new image.
     pub fn region(&self, [x, y]: [usize; 2], [w, h]: [usize; 2]) -> FontImage {
         assert!(x + w <= self.width());
         assert!(y + h <= self.height());
 
         let mut pixels = Vec::with_capacity(w * h);
+        let mut colors = Vec::with_capacity(if self.colors.is_empty() { 0 } else { w * h });
         for y in y..y + h {
             let offset = y * self.width() + x;
             pixels.extend(&self.pixels[offset..(offset + w)]);
+            if !self.colors.is_empty() {
+                colors.extend(&self.colors[offset..(offset + w)]);
+            }
         }
         assert_eq!(pixels.len(), w * h);
         FontImage {
             size: [w, h],
             pixels,
+            colors,
+            sdf: self.sdf,
         }
     }
 }
@@ -274,7 +331,10 @@ pub struct ImageDelta {
     /// If [`Self::pos`] is `Some`, this describes a patch of the whole image starting at [`Self::pos`].
     pub image: ImageData,
 
-    pub filter: TextureFilter,
+    /// How the texture is sampled.
+    ///
+    /// Partial updates should use the same options as the whole texture.
+    pub options: TextureOptions,
 
     /// If `None`, set the whole texture to [`Self::image`].
     ///
@@ -284,19 +344,23 @@ pub struct ImageDelta {
 
 impl ImageDelta {
     /// Update the whole texture.
-    pub fn full(image: impl Into<ImageData>, filter: TextureFilter) -> Self {
+    pub fn full(image: impl Into<ImageData>, options: impl Into<TextureOptions>) -> Self {
         Self {
             image: image.into(),
-            filter,
+            options: options.into(),
             pos: None,
         }
     }
 
     /// Update a sub-region of an existing texture.
-    pub fn partial(pos: [usize; 2], image: impl Into<ImageData>, filter: TextureFilter) -> Self {
+    pub fn partial(
+        pos: [usize; 2],
+        image: impl Into<ImageData>,
+        options: impl Into<TextureOptions>,
+    ) -> Self {
         Self {
             image: image.into(),
-            filter,
+            options: options.into(),
             pos: Some(pos),
         }
     }
@@ -307,3 +371,54 @@ impl ImageDelta {
         self.pos.is_none()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_image_colors() {
+        let mut image = FontImage::new([3, 2]);
+        assert!(image.colors.is_empty(), "no color glyphs yet");
+        image[(0, 0)] = 1.0;
+
+        let red = Color32::from_rgba_premultiplied(128, 0, 0, 128);
+        image.set_color((1, 1), red);
+        assert_eq!(image.colors.len(), image.pixels.len());
+
+        let pixels: Vec<Color32> = image.srgba_pixels(1.0).collect();
+        assert_eq!(pixels[0], Color32::WHITE, "coverage");
+        assert_eq!(pixels[1], Color32::TRANSPARENT, "nothing");
+        assert_eq!(pixels[4], red, "color glyphs are used as they are");
+
+        let region = image.region([1, 1], [2, 1]);
+        assert_eq!(region.colors, [red, Color32::TRANSPARENT]);
+        assert_eq!(image.region([0, 0], [3, 1]).colors.len(), 3);
+
+        let mut coverage_only = FontImage::new([2, 2]);
+        coverage_only[(1, 1)] = 1.0;
+        assert!(coverage_only.region([1, 1], [1, 1]).colors.is_empty());
+    }
+
+    #[test]
+    fn sdf_font_image_colors() {
+        let mut image = FontImage::new([3, 1]);
+        image.sdf = true;
+        image[(0, 0)] = 0.5;
+        let purple = Color32::from_rgba_premultiplied(100, 0, 50, 128);
+        image.set_color((1, 0), purple);
+        image.set_color((2, 0), Color32::WHITE);
+
+        let pixels: Vec<Color32> = image.srgba_pixels(1.0).collect();
+
+        // Distances are white, with the distance in alpha:
+        assert_eq!(pixels[0].to_array(), [255, 255, 255, 128]);
+
+        // Colors are unmultiplied:
+        assert_eq!(pixels[1].to_array(), purple.to_srgba_unmultiplied());
+        assert!(pixels[1].r() > purple.r());
+
+        // …and never white, so the shader can tell them apart from the distances:
+        assert_eq!(pixels[2].to_array(), [255, 255, 254, 255]);
+    }
+}
@@ -13,15 +13,20 @@
 #![allow(clippy::manual_range_contains)]
 
 mod bezier;
+mod blend_mode;
+mod brush;
 pub mod color;
 pub mod image;
 mod mesh;
 pub mod mutex;
+mod nine_slice;
 mod shadow;
 mod shape;
 pub mod shape_transform;
 pub mod stats;
 mod stroke;
+#[cfg(feature = "svg")]
+pub mod svg;
 pub mod tessellator;
 pub mod text;
 mod texture_atlas;
@@ -31,19 +36,22 @@ pub mod util;
 
 pub use {
     bezier::{CubicBezierShape, QuadraticBezierShape},
+    blend_mode::BlendMode,
+    brush::{Brush, ColorStops},
     color::{Color32, Rgba},
     image::{ColorImage, FontImage, ImageData, ImageDelta},
     mesh::{Mesh, Mesh16, Vertex},
+    nine_slice::NineSlice,
     shadow::Shadow,
     shape::{
-        CircleShape, PaintCallback, PaintCallbackInfo, PathShape, RectShape, Rounding, Shape,
-        TextShape,
+        ArcShape, BackdropBlur, CircleShape, EllipseShape, FillMode, GroupShape, PaintCallback,
+        PaintCallbackInfo, PathShape, RectShape, Rounding, Shape, TextShape,
     },
     stats::PaintStats,
-    stroke::Stroke,
+    stroke::{DashPattern, LineCap, LineJoin, Stroke},
     tessellator::{tessellate_shapes, TessellationOptions, Tessellator},
-    text::{FontFamily, FontId, Fonts, Galley},
-    texture_atlas::TextureAtlas,
+    text::{FontFamily, FontId, FontStretch, FontStyle, FontWeight, Fonts, Galley},
+    texture_atlas::{AtlasPageRef, TextureAtlas},
     texture_handle::TextureHandle,
     textures::TextureManager,
 };
@@ -97,7 +105,7 @@ pub struct ClippedShape(
     pub Shape,
 );
 
-/// A [`Mesh`] or [`PaintCallback`] within a clip rectangle.
+/// A [`Mesh`], [`PaintCallback`] or [`BackdropBlur`] within a clip rectangle.
 ///
 /// Everything is using logical points.
 #[derive(Clone, Debug)]
@@ -108,13 +116,19 @@ pub struct ClippedPrimitive {
 
     /// What to paint - either a [`Mesh`] or a [`PaintCallback`].
     pub primitive: Primitive,
+
+    /// How to blend a [`Mesh`] with what is behind it.
+    ///
+    /// Ignored for the other primitives.
+    pub blend_mode: BlendMode,
 }
 
-/// A rendering primitive - either a [`Mesh`] or a [`PaintCallback`].
+/// A rendering primitive - either a [`Mesh`], a [`PaintCallback`] or a [`BackdropBlur`].
 #[derive(Clone, Debug)]
 pub enum Primitive {
     Mesh(Mesh),
     Callback(PaintCallback),
+    BackdropBlur(BackdropBlur),
 }
 
 // ----------------------------------------------------------------------------
@@ -255,6 +255,13 @@ impl Mesh {
         }
     }
 
+    /// Scale and translate the positions of all vertices, in-place.
+    pub fn transform(&mut self, transform: TSTransform) {
+        for v in &mut self.vertices {
+            v.pos = transform * v.pos;
+        }
+    }
+
     /// Rotate by some angle about an origin, in-place.
     ///
     /// Origin is a position in screen space.
@@ -0,0 +1,213 @@
+use super::*;
+
+/// A texture with a border that can be stretched to any size without distorting the border,
+/// e.g. for skinning buttons and windows.
+///
+/// The texture is cut into nine slices by the insets [`Self::left`], [`Self::right`],
+/// [`Self::top`] and [`Self::bottom`]: the four corners keep their size, the top and bottom edges
+/// are stretched horizontally, the left and right edges vertically, and the center both ways.
+///
+/// The mesh always covers the whole rectangle: there is no rounding,
+/// so any rounded corners must be transparent in the texture.
+///
+/// ```
+/// # use epaint::*;
+/// let nine_slice = NineSlice::new(TextureId::Managed(1), vec2(48.0, 48.0), 16.0);
+/// let mesh = nine_slice.tessellate(Rect::from_min_size(pos2(0.0, 0.0), vec2(200.0, 32.0)));
+/// assert_eq!(mesh.vertices.len(), 16);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct NineSlice {
+    pub texture_id: TextureId,
+
+    /// The part of the texture to use, normally `(0, 0)` to `(1, 1)`.
+    pub uv: Rect,
+
+    /// Size of the whole texture in texels, to turn the insets into uv coordinates.
+    pub texture_size: Vec2,
+
+    /// Width of the left border, in texels.
+    pub left: f32,
+
+    /// Width of the right border, in texels.
+    pub right: f32,
+
+    /// Height of the top border, in texels.
+    pub top: f32,
+
+    /// Height of the bottom border, in texels.
+    pub bottom: f32,
+
+    /// Points per texel of the borders when painted, e.g. `0.5` for a texture made for a
+    /// `pixels_per_point` of `2`.
+    ///
+    /// If the rectangle is too small for the borders they are made thinner.
+    pub scale: f32,
+
+    /// Multiply the texture with this color.
+    pub tint: Color32,
+}
+
+impl NineSlice {
+    /// The whole texture, with insets of the same size on all sides.
+    pub fn new(texture_id: TextureId, texture_size: impl Into<Vec2>, inset: f32) -> Self {
+        Self {
+            texture_id,
+            uv: Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+            texture_size: texture_size.into(),
+            left: inset,
+            right: inset,
+            top: inset,
+            bottom: inset,
+            scale: 1.0,
+            tint: Color32::WHITE,
+        }
+    }
+
+    /// Insets in texels.
+    pub fn with_insets(mut self, left: f32, right: f32, top: f32, bottom: f32) -> Self {
+        self.left = left;
+        self.right = right;
+        self.top = top;
+        self.bottom = bottom;
+        self
+    }
+
+    /// Use only this part of the texture, e.g. one sprite of a sprite sheet.
+    pub fn with_uv(mut self, uv: Rect) -> Self {
+        self.uv = uv;
+        self
+    }
+
+    /// Points per texel of the borders when painted.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Multiply the texture with this color.
+    pub fn with_tint(mut self, tint: impl Into<Color32>) -> Self {
+        self.tint = tint.into();
+        self
+    }
+
+    /// Paint it to cover `rect`.
+    pub fn tessellate(&self, rect: Rect) -> Mesh {
+        let xs = slice(
+            [rect.min.x, rect.max.x],
+            [self.left * self.scale, self.right * self.scale],
+        );
+        let ys = slice(
+            [rect.min.y, rect.max.y],
+            [self.top * self.scale, self.bottom * self.scale],
+        );
+        let size = self.texture_size.max(Vec2::splat(1.0));
+        let us = [
+            self.uv.min.x,
+            self.uv.min.x + self.left / size.x,
+            self.uv.max.x - self.right / size.x,
+            self.uv.max.x,
+        ];
+        let vs = [
+            self.uv.min.y,
+            self.uv.min.y + self.top / size.y,
+            self.uv.max.y - self.bottom / size.y,
+            self.uv.max.y,
+        ];
+
+        let mut mesh = Mesh::with_texture(self.texture_id);
+        mesh.reserve_vertices(16);
+        mesh.reserve_triangles(18);
+        for (&y, &v) in ys.iter().zip(&vs) {
+            for (&x, &u) in xs.iter().zip(&us) {
+                mesh.vertices.push(Vertex {
+                    pos: pos2(x, y),
+                    uv: pos2(u, v),
+                    color: self.tint,
+                });
+            }
+        }
+        for row in 0..3 {
+            for col in 0..3 {
+                let top_left = (4 * row + col) as u32;
+                mesh.add_triangle(top_left, top_left + 1, top_left + 4);
+                mesh.add_triangle(top_left + 1, top_left + 4, top_left + 5);
+            }
+        }
+        mesh
+    }
+}
+
+/// Where the borders of the given widths end along `[min, max]`,
+/// making them thinner if they don't fit.
+fn slice([min, max]: [f32; 2], [start, end]: [f32; 2]) -> [f32; 4] {
+    let length = (max - min).max(0.0);
+    let fit = if start + end > length {
+        length / (start + end)
+    } else {
+        1.0
+    };
+    [min, min + start * fit, max - end * fit, max]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xs_and_ys(mesh: &Mesh) -> ([f32; 4], [f32; 4]) {
+        let v = &mesh.vertices;
+        (
+            [v[0].pos.x, v[1].pos.x, v[2].pos.x, v[3].pos.x],
+            [v[0].pos.y, v[4].pos.y, v[8].pos.y, v[12].pos.y],
+        )
+    }
+
+    #[test]
+    fn sub_rect_uv() {
+        // The right half of the top half of a 64x32 sprite sheet:
+        let uv = Rect::from_min_max(pos2(0.5, 0.0), pos2(1.0, 0.5));
+        let nine_slice = NineSlice::new(TextureId::Managed(1), vec2(64.0, 32.0), 0.0)
+            .with_uv(uv)
+            .with_insets(8.0, 8.0, 4.0, 4.0);
+        let mesh = nine_slice.tessellate(Rect::from_min_size(pos2(0.0, 0.0), vec2(100.0, 50.0)));
+        assert!(mesh.is_valid());
+
+        let v = &mesh.vertices;
+        let us = [v[0].uv.x, v[1].uv.x, v[2].uv.x, v[3].uv.x];
+        let vs = [v[0].uv.y, v[4].uv.y, v[8].uv.y, v[12].uv.y];
+        assert_eq!(us, [0.5, 0.625, 0.875, 1.0]);
+        assert_eq!(vs, [0.0, 0.125, 0.375, 0.5]);
+
+        let (xs, ys) = xs_and_ys(&mesh);
+        assert_eq!(xs, [0.0, 8.0, 92.0, 100.0]);
+        assert_eq!(ys, [0.0, 4.0, 46.0, 50.0]);
+    }
+
+    #[test]
+    fn insets_larger_than_rect() {
+        let nine_slice = NineSlice::new(TextureId::Managed(1), vec2(48.0, 48.0), 16.0);
+        let rect = Rect::from_min_size(pos2(10.0, 10.0), vec2(20.0, 10.0));
+
+        let mesh = nine_slice.tessellate(rect);
+        assert!(mesh.is_valid());
+        let (xs, ys) = xs_and_ys(&mesh);
+        assert_eq!(xs, [10.0, 20.0, 20.0, 30.0], "borders are made thinner");
+        assert_eq!(ys, [10.0, 15.0, 15.0, 20.0], "borders are made thinner");
+        assert_eq!(
+            mesh.vertices[5].uv,
+            pos2(1.0 / 3.0, 1.0 / 3.0),
+            "uvs are kept"
+        );
+
+        let mesh = nine_slice.with_scale(0.5).tessellate(rect);
+        let (xs, ys) = xs_and_ys(&mesh);
+        assert_eq!(xs, [10.0, 18.0, 22.0, 30.0], "scaled borders fit");
+        assert_eq!(ys, [10.0, 15.0, 15.0, 20.0]);
+
+        let empty = Rect::from_min_size(pos2(10.0, 10.0), Vec2::ZERO);
+        let (xs, ys) = xs_and_ys(&nine_slice.tessellate(empty));
+        assert_eq!(xs, [10.0; 4]);
+        assert_eq!(ys, [10.0; 4]);
+    }
+}
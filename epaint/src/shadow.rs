@@ -2,12 +2,25 @@ use super::*;
 
 /// The color and fuzziness of a fuzzy shape.
 /// Can be used for a rectangular shadow with a soft penumbra.
+///
+/// The shadow fades out like a gaussian blur of the rectangle, as in CSS `box-shadow`.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Shadow {
-    /// The shadow extends this much outside the rect.
-    /// The size of the fuzzy penumbra.
-    pub extrusion: f32,
+    /// Move the shadow by this much.
+    ///
+    /// For instance, a positive `y` makes it look like the light comes from above.
+    pub offset: Vec2,
+
+    /// The size of the fuzzy penumbra: the edge of the shadow fades out over about this distance
+    /// on both sides.
+    ///
+    /// The gaussian has a standard deviation of half of this, like `blur` in CSS.
+    pub blur: f32,
+
+    /// Expand the shadow by this much in all directions (before blurring it).
+    pub spread: f32,
 
     /// Color of the opaque center of the shadow.
     pub color: Color32,
@@ -17,7 +30,9 @@ impl Shadow {
     /// Tooltips, menus, …
     pub fn small_dark() -> Self {
         Self {
-            extrusion: 16.0,
+            offset: vec2(0.0, 2.0),
+            blur: 12.0,
+            spread: 0.0,
             color: Color32::from_black_alpha(96),
         }
     }
@@ -25,7 +40,9 @@ impl Shadow {
     /// Tooltips, menus, …
     pub fn small_light() -> Self {
         Self {
-            extrusion: 16.0,
+            offset: vec2(0.0, 2.0),
+            blur: 12.0,
+            spread: 0.0,
             color: Color32::from_black_alpha(32),
         }
     }
@@ -33,7 +50,9 @@ impl Shadow {
     /// Subtle and nice on dark backgrounds
     pub fn big_dark() -> Self {
         Self {
-            extrusion: 32.0,
+            offset: vec2(0.0, 4.0),
+            blur: 24.0,
+            spread: 0.0,
             color: Color32::from_black_alpha(96),
         }
     }
@@ -41,42 +60,203 @@ impl Shadow {
     /// Subtle and nice on white backgrounds
     pub fn big_light() -> Self {
         Self {
-            extrusion: 32.0,
+            offset: vec2(0.0, 4.0),
+            blur: 24.0,
+            spread: 0.0,
             color: Color32::from_black_alpha(40),
         }
     }
 
+    /// How far outside of the rectangle the shadow can be seen, at most.
+    pub fn margin(&self) -> f32 {
+        self.offset.x.abs().max(self.offset.y.abs()) + self.spread + 1.5 * self.blur
+    }
+
     pub fn tessellate(&self, rect: emath::Rect, rounding: impl Into<Rounding>) -> Mesh {
         // tessellator.clip_rect = clip_rect; // TODO(emilk): culling
 
-        let Self { extrusion, color } = *self;
+        let Self {
+            offset,
+            blur,
+            spread,
+            color,
+        } = *self;
 
+        let rect = rect.translate(offset).expand(spread);
         let rounding: Rounding = rounding.into();
-        let half_ext = 0.5 * extrusion;
-
-        let ext_rounding = Rounding {
-            nw: rounding.nw + half_ext,
-            ne: rounding.ne + half_ext,
-            sw: rounding.sw + half_ext,
-            se: rounding.se + half_ext,
+        let rounding = Rounding {
+            nw: (rounding.nw + spread).max(0.0),
+            ne: (rounding.ne + spread).max(0.0),
+            sw: (rounding.sw + spread).max(0.0),
+            se: (rounding.se + spread).max(0.0),
         };
 
-        use crate::tessellator::*;
-        let rect = RectShape::filled(rect.expand(half_ext), ext_rounding, color);
-        let pixels_per_point = 1.0; // doesn't matter here
-        let font_tex_size = [1; 2]; // unused size we are not tessellating text.
-        let mut tessellator = Tessellator::new(
-            pixels_per_point,
-            TessellationOptions {
-                feathering: true,
-                feathering_size_in_pixels: extrusion * pixels_per_point,
-                ..Default::default()
-            },
-            font_tex_size,
-            vec![],
+        let sigma = 0.5 * blur;
+        if sigma < 0.25 || !rect.is_positive() {
+            // So sharp that it is just a rectangle:
+            use crate::tessellator::*;
+            let pixels_per_point = 1.0; // doesn't matter here
+            let font_tex_size = [1; 2]; // unused size we are not tessellating text.
+            let mut tessellator =
+                Tessellator::new(pixels_per_point, Default::default(), font_tex_size, vec![]);
+            let mut mesh = Mesh::default();
+            tessellator.tessellate_rect(&RectShape::filled(rect, rounding, color), &mut mesh);
+            return mesh;
+        }
+
+        tessellate_gaussian_rect(rect, rounding, sigma, color)
+    }
+}
+
+/// A rounded rectangle blurred by a gaussian with the standard deviation `sigma`.
+///
+/// The mesh is a set of rings around the rectangle, each at some distance from its edge,
+/// with the color fading out from one ring to the next.
+fn tessellate_gaussian_rect(rect: Rect, rounding: Rounding, sigma: f32, color: Color32) -> Mesh {
+    use std::f32::consts::FRAC_PI_2;
+
+    /// Line segments per rounded corner.
+    const CORNER_SEGMENTS: usize = 8;
+
+    /// Rings from the inside to the outside of the penumbra.
+    const NUM_RINGS: usize = 17;
+
+    // The corner radii can't be larger than half the rectangle:
+    let max_radius = 0.5 * rect.width().min(rect.height());
+    let corners = [
+        // corner, direction into the rectangle, rounding, and the angle where the arc starts:
+        (rect.right_bottom(), vec2(-1.0, -1.0), rounding.se, 0.0),
+        (rect.left_bottom(), vec2(1.0, -1.0), rounding.sw, FRAC_PI_2),
+        (
+            rect.left_top(),
+            vec2(1.0, 1.0),
+            rounding.nw,
+            2.0 * FRAC_PI_2,
+        ),
+        (
+            rect.right_top(),
+            vec2(-1.0, 1.0),
+            rounding.ne,
+            3.0 * FRAC_PI_2,
+        ),
+    ]
+    .map(|(corner, inward, radius, angle)| {
+        // The center of the arc instead of the corner:
+        let radius = radius.min(max_radius);
+        (corner + radius * inward, inward, radius, angle)
+    });
+
+    // How much of the shadow there is at `pos`, at the `distance` outside the edge of the rounded rectangle.
+    // Near a small rectangle, the blur of the whole (non-rounded) rectangle is less than the blur
+    // of the nearby edge, so we use the smaller of the two.
+    let scale = 1.0 / (std::f32::consts::SQRT_2 * sigma);
+    let coverage = |pos: Pos2, distance: f32| {
+        let along =
+            |x: f32, min: f32, max: f32| 0.5 * (erf((x - min) * scale) - erf((x - max) * scale));
+        let of_rect = along(pos.x, rect.min.x, rect.max.x) * along(pos.y, rect.min.y, rect.max.y);
+        let of_edge = 0.5 * (1.0 - erf(distance * scale));
+        of_rect.min(of_edge)
+    };
+
+    let points_per_ring = 4 * (CORNER_SEGMENTS + 1);
+    let mut mesh = Mesh::default();
+    mesh.reserve_vertices(NUM_RINGS * points_per_ring + 1);
+    mesh.reserve_triangles(2 * (NUM_RINGS - 1) * points_per_ring + points_per_ring);
+
+    // We can't go further in than to the middle of the rectangle:
+    let inner_distance = -(3.0 * sigma).min(max_radius);
+    let outer_distance = 3.0 * sigma;
+
+    for ring in 0..NUM_RINGS {
+        let distance = emath::lerp(
+            inner_distance..=outer_distance,
+            ring as f32 / (NUM_RINGS - 1) as f32,
         );
-        let mut mesh = Mesh::default();
-        tessellator.tessellate_rect(&rect, &mut mesh);
-        mesh
+        for &(center, inward, radius, start_angle) in &corners {
+            for i in 0..=CORNER_SEGMENTS {
+                let pos = if radius + distance > 0.0 {
+                    let angle = start_angle + FRAC_PI_2 * i as f32 / CORNER_SEGMENTS as f32;
+                    center + (radius + distance) * Vec2::angled(angle)
+                } else {
+                    // Further in than the rounding: a sharp corner.
+                    center - (radius + distance) * inward
+                };
+                mesh.colored_vertex(pos, color.linear_multiply(coverage(pos, distance)));
+            }
+        }
+
+        if ring > 0 {
+            let inner = ((ring - 1) * points_per_ring) as u32;
+            let outer = (ring * points_per_ring) as u32;
+            for i in 0..points_per_ring as u32 {
+                let j = (i + 1) % points_per_ring as u32;
+                mesh.add_triangle(inner + i, outer + i, outer + j);
+                mesh.add_triangle(inner + i, outer + j, inner + j);
+            }
+        }
     }
+
+    // Fill in the innermost ring:
+    let center_idx = mesh.vertices.len() as u32;
+    let center = rect.center();
+    mesh.colored_vertex(center, color.linear_multiply(coverage(center, -max_radius)));
+    for i in 0..points_per_ring as u32 {
+        let j = (i + 1) % points_per_ring as u32;
+        mesh.add_triangle(center_idx, i, j);
+    }
+
+    mesh
+}
+
+/// The error function, to within `1.5e-7`.
+///
+/// From Abramowitz and Stegun, formula 7.1.26.
+fn erf(x: f32) -> f32 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_6
+            + t * (-0.284_496_74 + t * (1.421_413_7 + t * (-1.453_152_1 + t * 1.061_405_4))));
+    let y = 1.0 - polynomial * (-x * x).exp();
+    y.copysign(x)
+}
+
+#[test]
+fn test_gaussian_shadow() {
+    let rect = Rect::from_min_size(pos2(0.0, 0.0), vec2(100.0, 100.0));
+    let shadow = Shadow {
+        offset: vec2(10.0, 20.0),
+        blur: 8.0,
+        spread: 5.0,
+        color: Color32::BLACK,
+    };
+    let mesh = shadow.tessellate(rect, 8.0);
+    assert!(mesh.is_valid());
+
+    // Moved by the offset, and extended by the spread and about 1.5 times the blur:
+    let bounds = mesh.calc_bounds();
+    let expected = rect.translate(shadow.offset).expand(shadow.spread + 12.0);
+    assert!((bounds.min - expected.min).length() < 0.01, "{:?}", bounds);
+    assert!((bounds.max - expected.max).length() < 0.01, "{:?}", bounds);
+
+    // Opaque in the middle, and faded out at the outside:
+    let alpha_at = |pos: Pos2| {
+        mesh.vertices
+            .iter()
+            .min_by(|a, b| {
+                let (a, b) = (a.pos.distance_sq(pos), b.pos.distance_sq(pos));
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap()
+            .color
+            .a()
+    };
+    assert_eq!(alpha_at(pos2(60.0, 70.0)), 255);
+    assert_eq!(alpha_at(expected.right_center()), 0);
+
+    // Half way on the edge:
+    let edge_alpha = alpha_at(pos2(115.0, 70.0));
+    assert!((120..=135).contains(&edge_alpha), "{}", edge_alpha);
+
+    assert!((erf(0.5) - 0.520_499_9).abs() < 1e-6);
+    assert!((erf(-2.0) + 0.995_322_3).abs() < 1e-6);
 }
@@ -4,8 +4,8 @@ use std::ops::RangeInclusive;
 use std::{any::Any, sync::Arc};
 
 use crate::{
-    text::{FontId, Fonts, Galley},
-    Color32, Mesh, Stroke, TextureId,
+    text::{FontId, Fonts, Galley, LayoutJob},
+    BlendMode, Brush, Color32, Mesh, Stroke, TextureId,
 };
 use emath::*;
 
@@ -23,9 +23,18 @@ pub enum Shape {
     /// For performance reasons it is better to avoid it.
     Vec(Vec<Shape>),
 
+    /// Shapes painted with a common opacity and [`BlendMode`].
+    Group(GroupShape),
+
     /// Circle with optional outline and fill.
     Circle(CircleShape),
 
+    /// Ellipse with optional outline and fill.
+    Ellipse(EllipseShape),
+
+    /// Part of a circle or ellipse: an arc, or a pie slice.
+    Arc(ArcShape),
+
     /// A line between two points.
     LineSegment { points: [Pos2; 2], stroke: Stroke },
 
@@ -52,6 +61,9 @@ pub enum Shape {
 
     /// Backend-specific painting.
     Callback(PaintCallback),
+
+    /// Blur what has been painted behind a rectangle, e.g. for a frosted glass look.
+    BackdropBlur(BackdropBlur),
 }
 
 #[test]
@@ -129,6 +141,8 @@ impl Shape {
     }
 
     /// Turn a line into dashes.
+    ///
+    /// See also [`Stroke::dash`], which lets the tessellator dash any line, including curves.
     pub fn dashed_line(
         path: &[Pos2],
         stroke: impl Into<Stroke>,
@@ -158,14 +172,31 @@ impl Shape {
     #[inline]
     pub fn convex_polygon(
         points: Vec<Pos2>,
-        fill: impl Into<Color32>,
+        fill: impl Into<Brush>,
         stroke: impl Into<Stroke>,
     ) -> Self {
         Self::Path(PathShape::convex_polygon(points, fill, stroke))
     }
 
+    /// Any polygon with a fill and optional stroke. See [`PathShape::polygon`].
+    #[inline]
+    pub fn polygon(points: Vec<Pos2>, fill: impl Into<Brush>, stroke: impl Into<Stroke>) -> Self {
+        Self::Path(PathShape::polygon(points, fill, stroke))
+    }
+
+    /// A polygon with holes in it. See [`PathShape::polygon_with_holes`].
+    #[inline]
+    pub fn polygon_with_holes(
+        points: Vec<Pos2>,
+        holes: Vec<Vec<Pos2>>,
+        fill: impl Into<Brush>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self::Path(PathShape::polygon_with_holes(points, holes, fill, stroke))
+    }
+
     #[inline]
-    pub fn circle_filled(center: Pos2, radius: f32, fill_color: impl Into<Color32>) -> Self {
+    pub fn circle_filled(center: Pos2, radius: f32, fill_color: impl Into<Brush>) -> Self {
         Self::Circle(CircleShape::filled(center, radius, fill_color))
     }
 
@@ -174,11 +205,48 @@ impl Shape {
         Self::Circle(CircleShape::stroke(center, radius, stroke))
     }
 
+    #[inline]
+    pub fn ellipse_filled(center: Pos2, radius: Vec2, fill_color: impl Into<Brush>) -> Self {
+        Self::Ellipse(EllipseShape::filled(center, radius, fill_color))
+    }
+
+    #[inline]
+    pub fn ellipse_stroke(center: Pos2, radius: Vec2, stroke: impl Into<Stroke>) -> Self {
+        Self::Ellipse(EllipseShape::stroke(center, radius, stroke))
+    }
+
+    /// Part of the outline of a circle.
+    ///
+    /// See [`ArcShape`] for how the angles are measured.
+    #[inline]
+    pub fn arc(
+        center: Pos2,
+        radius: f32,
+        angles: RangeInclusive<f32>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self::Arc(ArcShape::arc(center, radius, angles, stroke))
+    }
+
+    /// A pie slice.
+    ///
+    /// See [`ArcShape`] for how the angles are measured.
+    #[inline]
+    pub fn sector(
+        center: Pos2,
+        radius: f32,
+        angles: RangeInclusive<f32>,
+        fill: impl Into<Brush>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self::Arc(ArcShape::sector(center, radius, angles, fill, stroke))
+    }
+
     #[inline]
     pub fn rect_filled(
         rect: Rect,
         rounding: impl Into<Rounding>,
-        fill_color: impl Into<Color32>,
+        fill_color: impl Into<Brush>,
     ) -> Self {
         Self::Rect(RectShape::filled(rect, rounding, fill_color))
     }
@@ -232,6 +300,11 @@ impl Shape {
         Shape::mesh(mesh)
     }
 
+    /// A texture with a border, stretched to cover `rect` without distorting the border.
+    pub fn nine_slice(rect: Rect, nine_slice: crate::NineSlice) -> Self {
+        Shape::mesh(nine_slice.tessellate(rect))
+    }
+
     /// The visual bounding rectangle (includes stroke widths)
     pub fn visual_bounding_rect(&self) -> Rect {
         match self {
@@ -243,7 +316,10 @@ impl Shape {
                 }
                 rect
             }
+            Self::Group(group) => group.visual_bounding_rect(),
             Self::Circle(circle_shape) => circle_shape.visual_bounding_rect(),
+            Self::Ellipse(ellipse_shape) => ellipse_shape.visual_bounding_rect(),
+            Self::Arc(arc_shape) => arc_shape.visual_bounding_rect(),
             Self::LineSegment { points, stroke } => {
                 if stroke.is_empty() {
                     Rect::NOTHING
@@ -258,6 +334,7 @@ impl Shape {
             Self::QuadraticBezier(bezier) => bezier.visual_bounding_rect(),
             Self::CubicBezier(bezier) => bezier.visual_bounding_rect(),
             Self::Callback(custom) => custom.rect,
+            Self::BackdropBlur(blur) => blur.rect,
         }
     }
 }
@@ -266,10 +343,10 @@ impl Shape {
 impl Shape {
     #[inline(always)]
     pub fn texture_id(&self) -> super::TextureId {
-        if let Shape::Mesh(mesh) = self {
-            mesh.texture_id
-        } else {
-            super::TextureId::default()
+        match self {
+            Shape::Mesh(mesh) => mesh.texture_id,
+            Shape::Text(text_shape) => text_shape.galley.atlas_page.texture_id(),
+            _ => super::TextureId::default(),
         }
     }
 
@@ -282,9 +359,20 @@ impl Shape {
                     shape.translate(delta);
                 }
             }
+            Shape::Group(group) => {
+                for shape in &mut group.shapes {
+                    shape.translate(delta);
+                }
+            }
             Shape::Circle(circle_shape) => {
                 circle_shape.center += delta;
             }
+            Shape::Ellipse(ellipse_shape) => {
+                ellipse_shape.center += delta;
+            }
+            Shape::Arc(arc_shape) => {
+                arc_shape.center += delta;
+            }
             Shape::LineSegment { points, .. } => {
                 for p in points {
                     *p += delta;
@@ -294,6 +382,9 @@ impl Shape {
                 for p in &mut path_shape.points {
                     *p += delta;
                 }
+                for p in path_shape.holes.iter_mut().flatten() {
+                    *p += delta;
+                }
             }
             Shape::Rect(rect_shape) => {
                 rect_shape.rect = rect_shape.rect.translate(delta);
@@ -317,8 +408,162 @@ impl Shape {
             Shape::Callback(shape) => {
                 shape.rect = shape.rect.translate(delta);
             }
+            Shape::BackdropBlur(blur) => {
+                blur.rect = blur.rect.translate(delta);
+            }
         }
     }
+
+    /// Scale and translate the shape, in-place.
+    ///
+    /// Stroke widths, radii and text are scaled too, so the shape looks
+    /// like it was painted with a zoomed in (or out) camera.
+    ///
+    /// Text is scaled by copying its [`Galley`] and stretching the glyphs,
+    /// so it gets blurry when zoomed in. Use [`Self::transform_with_fonts`] to keep it sharp.
+    pub fn transform(&mut self, transform: TSTransform) {
+        let scaling = transform.scaling;
+        match self {
+            Shape::Noop => {}
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    shape.transform(transform);
+                }
+            }
+            Shape::Group(group) => {
+                for shape in &mut group.shapes {
+                    shape.transform(transform);
+                }
+            }
+            Shape::Circle(circle_shape) => {
+                circle_shape.center = transform * circle_shape.center;
+                circle_shape.radius *= scaling;
+                circle_shape.stroke.scale(scaling);
+            }
+            Shape::Ellipse(ellipse_shape) => {
+                ellipse_shape.center = transform * ellipse_shape.center;
+                ellipse_shape.radius *= scaling;
+                ellipse_shape.stroke.scale(scaling);
+            }
+            Shape::Arc(arc_shape) => {
+                arc_shape.center = transform * arc_shape.center;
+                arc_shape.radius *= scaling;
+                arc_shape.stroke.scale(scaling);
+            }
+            Shape::LineSegment { points, stroke } => {
+                for p in points {
+                    *p = transform * *p;
+                }
+                stroke.scale(scaling);
+            }
+            Shape::Path(path_shape) => {
+                for p in &mut path_shape.points {
+                    *p = transform * *p;
+                }
+                for p in path_shape.holes.iter_mut().flatten() {
+                    *p = transform * *p;
+                }
+                path_shape.stroke.scale(scaling);
+            }
+            Shape::Rect(rect_shape) => {
+                rect_shape.rect = transform * rect_shape.rect;
+                let Rounding { nw, ne, sw, se } = &mut rect_shape.rounding;
+                for radius in [nw, ne, sw, se] {
+                    *radius *= scaling;
+                }
+                rect_shape.stroke.scale(scaling);
+            }
+            Shape::Text(text_shape) => {
+                text_shape.pos = transform * text_shape.pos;
+                text_shape.underline.scale(scaling);
+
+                // Scale the painted text (but not the glyph positions used for cursors):
+                let galley = Arc::make_mut(&mut text_shape.galley);
+                for row in &mut galley.rows {
+                    row.rect = TSTransform::from_scaling(scaling) * row.rect;
+                    row.visuals.mesh_bounds =
+                        TSTransform::from_scaling(scaling) * row.visuals.mesh_bounds;
+                    row.visuals
+                        .mesh
+                        .transform(TSTransform::from_scaling(scaling));
+                }
+                galley.rect = TSTransform::from_scaling(scaling) * galley.rect;
+                galley.mesh_bounds = TSTransform::from_scaling(scaling) * galley.mesh_bounds;
+            }
+            Shape::Mesh(mesh) => {
+                mesh.transform(transform);
+            }
+            Shape::QuadraticBezier(bezier_shape) => {
+                for p in &mut bezier_shape.points {
+                    *p = transform * *p;
+                }
+                bezier_shape.stroke.scale(scaling);
+            }
+            Shape::CubicBezier(cubic_curve) => {
+                for p in &mut cubic_curve.points {
+                    *p = transform * *p;
+                }
+                cubic_curve.stroke.scale(scaling);
+            }
+            Shape::Callback(shape) => {
+                shape.rect = transform * shape.rect;
+            }
+            Shape::BackdropBlur(blur) => {
+                blur.rect = transform * blur.rect;
+                let Rounding { nw, ne, sw, se } = &mut blur.rounding;
+                for radius in [nw, ne, sw, se] {
+                    *radius *= scaling;
+                }
+                blur.blur *= scaling;
+            }
+        }
+    }
+
+    /// Like [`Self::transform`], but text is laid out again at the scaled font size,
+    /// so that it stays sharp.
+    ///
+    /// The [`Fonts`] cache the scaled layout, so this is cheap to do every frame.
+    pub fn transform_with_fonts(&mut self, transform: TSTransform, fonts: &Fonts) {
+        match self {
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    shape.transform_with_fonts(transform, fonts);
+                }
+            }
+            Shape::Group(group) => {
+                for shape in &mut group.shapes {
+                    shape.transform_with_fonts(transform, fonts);
+                }
+            }
+            Shape::Text(text_shape) if transform.scaling != 1.0 => {
+                text_shape.pos = transform * text_shape.pos;
+                text_shape.underline.scale(transform.scaling);
+                let job = scaled_layout_job(&text_shape.galley.job, transform.scaling);
+                text_shape.galley = fonts.layout_job(job);
+            }
+            _ => self.transform(transform),
+        }
+    }
+}
+
+/// The same text with all sizes and distances scaled.
+fn scaled_layout_job(job: &LayoutJob, scaling: f32) -> LayoutJob {
+    let mut job = job.clone();
+    job.wrap.max_width *= scaling;
+    job.first_row_min_height *= scaling;
+    job.paragraph_indent *= scaling;
+    job.paragraph_spacing *= scaling;
+    for section in &mut job.sections {
+        section.leading_space *= scaling;
+        let format = &mut section.format;
+        format.font_id.size *= scaling;
+        format.underline.scale(scaling);
+        format.overline.scale(scaling);
+        format.strikethrough.scale(scaling);
+        format.highlight_rounding *= scaling;
+        format.extra_letter_spacing *= scaling;
+    }
+    job
 }
 
 // ----------------------------------------------------------------------------
@@ -329,13 +574,13 @@ impl Shape {
 pub struct CircleShape {
     pub center: Pos2,
     pub radius: f32,
-    pub fill: Color32,
+    pub fill: Brush,
     pub stroke: Stroke,
 }
 
 impl CircleShape {
     #[inline]
-    pub fn filled(center: Pos2, radius: f32, fill_color: impl Into<Color32>) -> Self {
+    pub fn filled(center: Pos2, radius: f32, fill_color: impl Into<Brush>) -> Self {
         Self {
             center,
             radius,
@@ -356,7 +601,7 @@ impl CircleShape {
 
     /// The visual bounding rectangle (includes stroke width)
     pub fn visual_bounding_rect(&self) -> Rect {
-        if self.fill == Color32::TRANSPARENT && self.stroke.is_empty() {
+        if self.fill.is_transparent() && self.stroke.is_empty() {
             Rect::NOTHING
         } else {
             Rect::from_center_size(
@@ -376,6 +621,253 @@ impl From<CircleShape> for Shape {
 
 // ----------------------------------------------------------------------------
 
+/// How to paint an ellipse.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct EllipseShape {
+    pub center: Pos2,
+
+    /// Radius along the x and y axes.
+    pub radius: Vec2,
+
+    pub fill: Brush,
+    pub stroke: Stroke,
+}
+
+impl EllipseShape {
+    #[inline]
+    pub fn filled(center: Pos2, radius: Vec2, fill_color: impl Into<Brush>) -> Self {
+        Self {
+            center,
+            radius,
+            fill: fill_color.into(),
+            stroke: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn stroke(center: Pos2, radius: Vec2, stroke: impl Into<Stroke>) -> Self {
+        Self {
+            center,
+            radius,
+            fill: Default::default(),
+            stroke: stroke.into(),
+        }
+    }
+
+    /// The visual bounding rectangle (includes stroke width)
+    pub fn visual_bounding_rect(&self) -> Rect {
+        if self.fill.is_transparent() && self.stroke.is_empty() {
+            Rect::NOTHING
+        } else {
+            Rect::from_center_size(
+                self.center,
+                self.radius * 2.0 + Vec2::splat(self.stroke.width),
+            )
+        }
+    }
+}
+
+impl From<EllipseShape> for Shape {
+    #[inline(always)]
+    fn from(shape: EllipseShape) -> Self {
+        Self::Ellipse(shape)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// How to paint part of a circle or ellipse: an arc, or a pie slice (sector).
+///
+/// Angles are in radians. Zero is to the right (positive x),
+/// and they increase clockwise (towards positive y, which is down).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ArcShape {
+    pub center: Pos2,
+
+    /// Radius along the x and y axes.
+    pub radius: Vec2,
+
+    /// Where the arc starts, in radians, clockwise from the positive x axis (as y points down).
+    ///
+    /// This is the parametric angle of the ellipse: the arc starts at
+    /// `center + radius * Vec2::angled(start_angle)` (see [`Self::point_at`]).
+    /// Unless the radii are equal, that is not the point in the direction of `start_angle` from the `center`.
+    pub start_angle: f32,
+
+    /// Where the arc ends, as a parametric angle like [`Self::start_angle`].
+    ///
+    /// If this is less than `start_angle`, the arc goes counter-clockwise.
+    /// At most one full turn is painted.
+    pub end_angle: f32,
+
+    /// If true, the ends of the arc are connected to the center, making a pie slice.
+    ///
+    /// Otherwise the fill is the area between the arc and the straight line between its ends.
+    pub sector: bool,
+
+    pub fill: Brush,
+    pub stroke: Stroke,
+}
+
+impl ArcShape {
+    /// Part of the outline of a circle.
+    #[inline]
+    pub fn arc(
+        center: Pos2,
+        radius: f32,
+        angles: RangeInclusive<f32>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self {
+            center,
+            radius: Vec2::splat(radius),
+            start_angle: *angles.start(),
+            end_angle: *angles.end(),
+            sector: false,
+            fill: Default::default(),
+            stroke: stroke.into(),
+        }
+    }
+
+    /// A pie slice of a circle.
+    #[inline]
+    pub fn sector(
+        center: Pos2,
+        radius: f32,
+        angles: RangeInclusive<f32>,
+        fill: impl Into<Brush>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self {
+            center,
+            radius: Vec2::splat(radius),
+            start_angle: *angles.start(),
+            end_angle: *angles.end(),
+            sector: true,
+            fill: fill.into(),
+            stroke: stroke.into(),
+        }
+    }
+
+    /// How far the arc goes, at most one turn in either direction.
+    #[inline]
+    pub fn sweep_angle(&self) -> f32 {
+        use std::f32::consts::TAU;
+        (self.end_angle - self.start_angle).clamp(-TAU, TAU)
+    }
+
+    /// Is this a whole circle or ellipse?
+    #[inline]
+    pub fn is_full_turn(&self) -> bool {
+        self.sweep_angle().abs() >= std::f32::consts::TAU
+    }
+
+    /// The point on the ellipse at the given parametric angle.
+    #[inline]
+    pub fn point_at(&self, angle: f32) -> Pos2 {
+        self.center + self.radius * Vec2::angled(angle)
+    }
+
+    /// The angles the arc goes between, smallest first (i.e. clockwise).
+    pub fn clockwise_angles(&self) -> RangeInclusive<f32> {
+        let end_angle = self.start_angle + self.sweep_angle();
+        self.start_angle.min(end_angle)..=self.start_angle.max(end_angle)
+    }
+
+    /// The visual bounding rectangle (includes stroke width)
+    pub fn visual_bounding_rect(&self) -> Rect {
+        use std::f32::consts::TAU;
+
+        if self.fill.is_transparent() && self.stroke.is_empty() {
+            return Rect::NOTHING;
+        }
+
+        let angles = self.clockwise_angles();
+        let mut points = vec![self.point_at(*angles.start()), self.point_at(*angles.end())];
+        if self.sector {
+            points.push(self.center);
+        }
+
+        // The ellipse is at its widest and tallest at every quarter turn:
+        let first_quarter = (*angles.start() / (TAU / 4.0)).ceil() as i32;
+        let last_quarter = (*angles.end() / (TAU / 4.0)).floor() as i32;
+        for quarter in first_quarter..=last_quarter {
+            points.push(self.point_at(quarter as f32 * TAU / 4.0));
+        }
+
+        Rect::from_points(&points).expand(self.stroke.width / 2.0)
+    }
+}
+
+impl From<ArcShape> for Shape {
+    #[inline(always)]
+    fn from(shape: ArcShape) -> Self {
+        Self::Arc(shape)
+    }
+}
+
+#[test]
+fn path_bounding_rect_includes_holes() {
+    let square = vec![
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    let outside = vec![pos2(12.0, 2.0), pos2(14.0, 2.0), pos2(14.0, 4.0)];
+    let path = PathShape::polygon_with_holes(
+        square,
+        vec![outside],
+        Color32::RED,
+        Stroke::new(2.0, Color32::WHITE),
+    );
+    assert_eq!(
+        path.visual_bounding_rect(),
+        Rect::from_min_max(pos2(-1.0, -1.0), pos2(15.0, 11.0))
+    );
+
+    // A convex fill has no holes:
+    let path = PathShape {
+        fill_mode: FillMode::Convex,
+        ..path
+    };
+    assert_eq!(
+        path.visual_bounding_rect(),
+        Rect::from_min_max(pos2(-1.0, -1.0), pos2(11.0, 11.0))
+    );
+}
+
+#[test]
+fn arc_bounding_rect() {
+    use std::f32::consts::PI;
+    let stroke = Stroke::new(2.0, Color32::WHITE);
+
+    // The bottom half, going through the bottom-most point:
+    let arc = ArcShape::arc(pos2(10.0, 10.0), 5.0, 0.0..=PI, stroke);
+    assert_eq!(
+        arc.visual_bounding_rect(),
+        Rect::from_min_max(pos2(4.0, 9.0), pos2(16.0, 16.0))
+    );
+
+    // The same half, counter-clockwise from the other end:
+    let arc = ArcShape::arc(pos2(10.0, 10.0), 5.0, PI..=0.0, stroke);
+    assert_eq!(
+        arc.visual_bounding_rect(),
+        Rect::from_min_max(pos2(4.0, 9.0), pos2(16.0, 16.0))
+    );
+
+    // A slice of the right top quarter includes the center:
+    let sector = ArcShape::sector(pos2(10.0, 10.0), 5.0, -PI / 2.0..=0.0, Color32::RED, stroke);
+    assert_eq!(
+        sector.visual_bounding_rect(),
+        Rect::from_min_max(pos2(9.0, 4.0), pos2(16.0, 11.0))
+    );
+}
+
+// ----------------------------------------------------------------------------
+
 /// A path which can be stroked and/or filled (if closed).
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -387,8 +879,17 @@ pub struct PathShape {
     /// This is required if `fill != TRANSPARENT`.
     pub closed: bool,
 
-    /// Fill is only supported for convex polygons.
-    pub fill: Color32,
+    /// With the default [`FillMode::Convex`], fill is only supported for convex polygons.
+    pub fill: Brush,
+
+    /// How to fill the path: fast for convex polygons, or with a fill rule for any polygon.
+    pub fill_mode: FillMode,
+
+    /// More closed outlines, which are filled together with `points` according to the [`FillMode`].
+    ///
+    /// These are usually holes. With [`FillMode::NonZero`] a hole must go around
+    /// the other way than `points`. They are ignored with [`FillMode::Convex`].
+    pub holes: Vec<Vec<Pos2>>,
 
     /// Color and thickness of the line.
     pub stroke: Stroke,
@@ -404,6 +905,8 @@ impl PathShape {
             points,
             closed: false,
             fill: Default::default(),
+            fill_mode: FillMode::Convex,
+            holes: Vec::new(),
             stroke: stroke.into(),
         }
     }
@@ -415,6 +918,8 @@ impl PathShape {
             points,
             closed: true,
             fill: Default::default(),
+            fill_mode: FillMode::Convex,
+            holes: Vec::new(),
             stroke: stroke.into(),
         }
     }
@@ -425,24 +930,97 @@ impl PathShape {
     #[inline]
     pub fn convex_polygon(
         points: Vec<Pos2>,
-        fill: impl Into<Color32>,
+        fill: impl Into<Brush>,
         stroke: impl Into<Stroke>,
     ) -> Self {
         PathShape {
             points,
             closed: true,
             fill: fill.into(),
+            fill_mode: FillMode::Convex,
+            holes: Vec::new(),
             stroke: stroke.into(),
         }
     }
 
+    /// Any polygon with a fill and optional stroke.
+    ///
+    /// It can be concave, and even intersect itself (see [`FillMode::NonZero`]).
+    /// This is slower to paint than a [`Self::convex_polygon`].
+    #[inline]
+    pub fn polygon(points: Vec<Pos2>, fill: impl Into<Brush>, stroke: impl Into<Stroke>) -> Self {
+        PathShape {
+            fill_mode: FillMode::NonZero,
+            ..Self::convex_polygon(points, fill, stroke)
+        }
+    }
+
+    /// A polygon with holes in it, using [`FillMode::EvenOdd`],
+    /// so it doesn't matter which way around the holes go.
+    #[inline]
+    pub fn polygon_with_holes(
+        points: Vec<Pos2>,
+        holes: Vec<Vec<Pos2>>,
+        fill: impl Into<Brush>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        PathShape {
+            fill_mode: FillMode::EvenOdd,
+            holes,
+            ..Self::convex_polygon(points, fill, stroke)
+        }
+    }
+
     /// The visual bounding rectangle (includes stroke width)
+    ///
+    /// Unless the fill is [`FillMode::Convex`], this includes the [`Self::holes`],
+    /// which are stroked too, and which fill what they go around outside the points.
     #[inline]
     pub fn visual_bounding_rect(&self) -> Rect {
-        if self.fill == Color32::TRANSPARENT && self.stroke.is_empty() {
+        if self.fill.is_transparent() && self.stroke.is_empty() {
             Rect::NOTHING
         } else {
-            Rect::from_points(&self.points).expand(self.stroke.width / 2.0)
+            let mut rect = Rect::from_points(&self.points);
+            if self.fill_mode != FillMode::Convex {
+                for hole in &self.holes {
+                    rect = rect.union(Rect::from_points(hole));
+                }
+            }
+            rect.expand(self.stroke.width / 2.0)
+        }
+    }
+}
+
+/// How to fill a closed [`PathShape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FillMode {
+    /// Fast, but only correct for convex polygons.
+    Convex,
+
+    /// A point is inside if the outlines go around it more times one way than the other,
+    /// like the `nonzero` fill rule of SVG.
+    NonZero,
+
+    /// A point is inside if the outlines go around it an odd number of times,
+    /// like the `evenodd` fill rule of SVG.
+    EvenOdd,
+}
+
+impl Default for FillMode {
+    #[inline]
+    fn default() -> Self {
+        Self::Convex
+    }
+}
+
+impl FillMode {
+    /// Is a point inside, given how many times the outlines go around it (clockwise minus counter-clockwise)?
+    #[inline]
+    pub fn is_inside(self, winding_number: i32) -> bool {
+        match self {
+            Self::Convex | Self::NonZero => winding_number != 0,
+            Self::EvenOdd => winding_number % 2 != 0,
         }
     }
 }
@@ -466,7 +1044,7 @@ pub struct RectShape {
     pub rounding: Rounding,
 
     /// How to fill the rectangle.
-    pub fill: Color32,
+    pub fill: Brush,
 
     /// The thickness and color of the outline.
     pub stroke: Stroke,
@@ -474,11 +1052,7 @@ pub struct RectShape {
 
 impl RectShape {
     #[inline]
-    pub fn filled(
-        rect: Rect,
-        rounding: impl Into<Rounding>,
-        fill_color: impl Into<Color32>,
-    ) -> Self {
+    pub fn filled(rect: Rect, rounding: impl Into<Rounding>, fill_color: impl Into<Brush>) -> Self {
         Self {
             rect,
             rounding: rounding.into(),
@@ -500,7 +1074,7 @@ impl RectShape {
     /// The visual bounding rectangle (includes stroke width)
     #[inline]
     pub fn visual_bounding_rect(&self) -> Rect {
-        if self.fill == Color32::TRANSPARENT && self.stroke.is_empty() {
+        if self.fill.is_transparent() && self.stroke.is_empty() {
             Rect::NOTHING
         } else {
             self.rect.expand(self.stroke.width / 2.0)
@@ -619,7 +1193,8 @@ pub struct TextShape {
 
     /// If set, the text color in the galley will be ignored and replaced
     /// with the given color.
-    /// This will NOT replace background color nor strikethrough/underline color.
+    /// This will NOT replace background color nor strikethrough/underline color,
+    /// nor the colors of color glyphs (e.g. emoji).
     pub override_text_color: Option<Color32>,
 
     /// Rotate text by this many radians clockwise.
@@ -829,3 +1404,107 @@ impl From<PaintCallback> for Shape {
         Self::Callback(shape)
     }
 }
+
+// ----------------------------------------------------------------------------
+
+/// Blur what has been painted behind a rectangle, before painting what comes after it
+/// (see [`Shape::BackdropBlur`]).
+///
+/// Paint something semi-transparent on top of it, e.g. the fill of a window, for a frosted glass look.
+///
+/// This is done by the rendering backend (`egui_glow` and `egui-wgpu` support it).
+/// Backends that don't just paint nothing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct BackdropBlur {
+    /// The part of the screen to blur.
+    pub rect: Rect,
+
+    /// How rounded the corners of [`Self::rect`] are.
+    pub rounding: Rounding,
+
+    /// How blurry it gets, in points.
+    ///
+    /// This is the size of the gaussian blur, with a standard deviation of half of this
+    /// (the same as [`crate::Shadow::blur`]).
+    pub blur: f32,
+}
+
+impl BackdropBlur {
+    #[inline]
+    pub fn new(rect: Rect, rounding: impl Into<Rounding>, blur: f32) -> Self {
+        Self {
+            rect,
+            rounding: rounding.into(),
+            blur,
+        }
+    }
+}
+
+impl From<BackdropBlur> for Shape {
+    #[inline(always)]
+    fn from(blur: BackdropBlur) -> Self {
+        Self::BackdropBlur(blur)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Shapes painted with a common opacity and [`BlendMode`] (see [`Shape::Group`]).
+///
+/// The opacity is applied to each of the shapes on its own,
+/// so where they overlap you can see the ones below through the ones on top.
+///
+/// Groups can be nested: the opacities are multiplied, and the innermost blend mode
+/// other than [`BlendMode::Normal`] is used.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupShape {
+    pub shapes: Vec<Shape>,
+
+    /// From `0.0` (invisible) to `1.0` (unchanged).
+    pub opacity: f32,
+
+    /// How the shapes are blended with what is behind them.
+    ///
+    /// [`BlendMode::Normal`] keeps the blend mode of the group this one is in, if any.
+    pub blend_mode: BlendMode,
+}
+
+impl GroupShape {
+    /// Fully opaque, with [`BlendMode::Normal`].
+    #[inline]
+    pub fn new(shapes: Vec<Shape>) -> Self {
+        Self {
+            shapes,
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
+        }
+    }
+
+    /// From `0.0` (invisible) to `1.0` (unchanged).
+    #[inline]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    #[inline]
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    /// The visual bounding rectangle of all the shapes.
+    pub fn visual_bounding_rect(&self) -> Rect {
+        self.shapes.iter().fold(Rect::NOTHING, |rect, shape| {
+            rect.union(shape.visual_bounding_rect())
+        })
+    }
+}
+
+impl From<GroupShape> for Shape {
+    #[inline(always)]
+    fn from(group: GroupShape) -> Self {
+        Self::Group(group)
+    }
+}
@@ -9,19 +9,32 @@ pub fn adjust_colors(shape: &mut Shape, adjust_color: &impl Fn(&mut Color32)) {
                 adjust_colors(shape, adjust_color);
             }
         }
+        Shape::Group(group) => {
+            for shape in &mut group.shapes {
+                adjust_colors(shape, adjust_color);
+            }
+        }
         Shape::Circle(circle_shape) => {
-            adjust_color(&mut circle_shape.fill);
+            circle_shape.fill.adjust_colors(adjust_color);
             adjust_color(&mut circle_shape.stroke.color);
         }
+        Shape::Ellipse(ellipse_shape) => {
+            ellipse_shape.fill.adjust_colors(adjust_color);
+            adjust_color(&mut ellipse_shape.stroke.color);
+        }
+        Shape::Arc(arc_shape) => {
+            arc_shape.fill.adjust_colors(adjust_color);
+            adjust_color(&mut arc_shape.stroke.color);
+        }
         Shape::LineSegment { stroke, .. } => {
             adjust_color(&mut stroke.color);
         }
         Shape::Path(path_shape) => {
-            adjust_color(&mut path_shape.fill);
+            path_shape.fill.adjust_colors(adjust_color);
             adjust_color(&mut path_shape.stroke.color);
         }
         Shape::Rect(rect_shape) => {
-            adjust_color(&mut rect_shape.fill);
+            rect_shape.fill.adjust_colors(adjust_color);
             adjust_color(&mut rect_shape.stroke.color);
         }
         Shape::Text(text_shape) => {
@@ -44,15 +57,16 @@ pub fn adjust_colors(shape: &mut Shape, adjust_color: &impl Fn(&mut Color32)) {
             }
         }
         Shape::QuadraticBezier(quatratic) => {
-            adjust_color(&mut quatratic.fill);
+            quatratic.fill.adjust_colors(adjust_color);
             adjust_color(&mut quatratic.stroke.color);
         }
         Shape::CubicBezier(bezier) => {
-            adjust_color(&mut bezier.fill);
+            bezier.fill.adjust_colors(adjust_color);
             adjust_color(&mut bezier.stroke.color);
         }
         Shape::Callback(_) => {
             // Can't tint user callback code
         }
+        Shape::BackdropBlur(_) => {}
     }
 }
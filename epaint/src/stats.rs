@@ -157,6 +157,22 @@ impl AllocInfo {
     }
 }
 
+/// How full the font atlas is, see [`crate::TextureAtlas::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AtlasStats {
+    /// Number of pages (textures) the atlas uses.
+    pub num_pages: usize,
+
+    /// The most pages the atlas will use before it starts clearing pages.
+    pub max_pages: usize,
+
+    /// How full the page new glyphs are put on is, in 0-1 range.
+    pub fill_ratio: f32,
+
+    /// How many times a page has been cleared to make room for new glyphs.
+    pub num_evictions: usize,
+}
+
 /// Collected allocation statistics for shapes and meshes.
 #[derive(Clone, Copy, Default)]
 pub struct PaintStats {
@@ -174,6 +190,9 @@ pub struct PaintStats {
     pub clipped_primitives: AllocInfo,
     pub vertices: AllocInfo,
     pub indices: AllocInfo,
+
+    /// How full the font atlas is.
+    pub font_atlas: AtlasStats,
 }
 
 impl PaintStats {
@@ -199,8 +218,17 @@ impl PaintStats {
                     self.add(shape);
                 }
             }
+            Shape::Group(group) => {
+                self.shapes += AllocInfo::from_slice(&group.shapes);
+                self.shape_vec += AllocInfo::from_slice(&group.shapes);
+                for shape in &group.shapes {
+                    self.add(shape);
+                }
+            }
             Shape::Noop
             | Shape::Circle { .. }
+            | Shape::Ellipse(_)
+            | Shape::Arc(_)
             | Shape::LineSegment { .. }
             | Shape::Rect { .. }
             | Shape::CubicBezier(_)
@@ -219,7 +247,7 @@ impl PaintStats {
             Shape::Mesh(mesh) => {
                 self.shape_mesh += AllocInfo::from_mesh(mesh);
             }
-            Shape::Callback(_) => {
+            Shape::Callback(_) | Shape::BackdropBlur(_) => {
                 self.num_callbacks += 1;
             }
         }
@@ -238,6 +266,11 @@ impl PaintStats {
         }
         self
     }
+
+    pub fn with_font_atlas(mut self, font_atlas: AtlasStats) -> Self {
+        self.font_atlas = font_atlas;
+        self
+    }
 }
 
 fn megabytes(size: usize) -> String {
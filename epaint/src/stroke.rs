@@ -2,17 +2,44 @@
 
 use super::*;
 
-/// Describes the width and color of a line.
+/// Describes the width and color of a line,
+/// and optionally how its ends and corners look and if it is dashed.
 ///
 /// The default stroke is the same as [`Stroke::none`].
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Stroke {
     pub width: f32,
     pub color: Color32,
+
+    /// How the ends of open lines (and of each dash) look.
+    pub cap: LineCap,
+
+    /// How the corners of lines look.
+    pub join: LineJoin,
+
+    /// A [`LineJoin::Miter`] that would reach further out from the corner
+    /// than this many times half the width is replaced with a [`LineJoin::Bevel`].
+    ///
+    /// This is the same as `stroke-miterlimit` in SVG.
+    pub miter_limit: f32,
+
+    /// Dashes and gaps along the line. Solid by default.
+    pub dash: DashPattern,
+}
+
+impl Default for Stroke {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
 }
 
 impl Stroke {
+    /// Same as in SVG.
+    pub const DEFAULT_MITER_LIMIT: f32 = 4.0;
+
     /// Same as [`Stroke::default`].
     #[inline(always)]
     pub fn none() -> Self {
@@ -24,14 +51,60 @@ impl Stroke {
         Self {
             width: width.into(),
             color: color.into(),
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: Self::DEFAULT_MITER_LIMIT,
+            dash: DashPattern::SOLID,
         }
     }
 
+    #[inline]
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    #[inline]
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// See [`Self::miter_limit`].
+    #[inline]
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    #[inline]
+    pub fn with_dash(mut self, dash: DashPattern) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    /// Scale the width and dashes, e.g. when zooming.
+    #[inline]
+    pub fn scale(&mut self, factor: f32) {
+        self.width *= factor;
+        self.dash.scale(factor);
+    }
+
     /// True if width is zero or color is transparent
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.width <= 0.0 || self.color == Color32::TRANSPARENT
     }
+
+    /// True if this is a solid line with butt caps and miter joins,
+    /// which is what a [`Stroke`] with only a width and color is.
+    #[inline]
+    pub fn has_default_style(&self) -> bool {
+        self.cap == LineCap::Butt
+            && self.join == LineJoin::Miter
+            && self.miter_limit == Self::DEFAULT_MITER_LIMIT
+            && self.dash.is_solid()
+    }
 }
 
 impl<Color> From<(f32, Color)> for Stroke
@@ -47,8 +120,239 @@ where
 impl std::hash::Hash for Stroke {
     #[inline(always)]
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        let Self { width, color } = *self;
+        let Self {
+            width,
+            color,
+            cap,
+            join,
+            miter_limit,
+            dash,
+        } = *self;
         crate::f32_hash(state, width);
         color.hash(state);
+        cap.hash(state);
+        join.hash(state);
+        crate::f32_hash(state, miter_limit);
+        for length in dash.lengths() {
+            crate::f32_hash(state, length);
+        }
+        crate::f32_hash(state, dash.offset);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// How the ends of a line look.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LineCap {
+    /// The line ends exactly at its end points.
+    Butt,
+
+    /// The line ends with a half circle around its end points.
+    Round,
+
+    /// The line ends with half a square around its end points,
+    /// i.e. it is extended by half its width.
+    Square,
+}
+
+impl Default for LineCap {
+    fn default() -> Self {
+        Self::Butt
+    }
+}
+
+/// How the corners of a line look.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet in a sharp corner,
+    /// unless that is further out than [`Stroke::miter_limit`].
+    Miter,
+
+    /// The corner is rounded off around the corner point.
+    Round,
+
+    /// The corner is cut off.
+    Bevel,
+}
+
+impl Default for LineJoin {
+    fn default() -> Self {
+        Self::Miter
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The lengths of dashes and gaps along a [`Stroke`], repeated along the whole line.
+///
+/// This is a small handle to lengths kept in a list for the whole program, so that a [`Stroke`]
+/// stays small and `Copy`. That list only grows with each different pattern given to [`Self::new`]
+/// (not when a pattern is scaled), so make a pattern once rather than with new lengths every frame.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(from = "SerializedDashPattern", into = "SerializedDashPattern")
+)]
+pub struct DashPattern {
+    /// Where the lengths are in [`DASH_LENGTHS`], counting from one, or zero if solid.
+    index: u32,
+
+    /// What the lengths are multiplied by, see [`Stroke::scale`].
+    scale: f32,
+
+    /// How far into the pattern the line starts.
+    pub offset: f32,
+}
+
+/// The lengths of every [`DashPattern`] made so far.
+#[cfg(not(target_arch = "wasm32"))]
+static DASH_LENGTHS: parking_lot::Mutex<Vec<Vec<f32>>> = parking_lot::const_mutex(Vec::new());
+
+/// The lengths of every [`DashPattern`] made so far.
+#[cfg(target_arch = "wasm32")]
+static DASH_LENGTHS: atomic_refcell::AtomicRefCell<Vec<Vec<f32>>> =
+    atomic_refcell::AtomicRefCell::new(Vec::new());
+
+fn with_dash_lengths<R>(f: impl FnOnce(&mut Vec<Vec<f32>>) -> R) -> R {
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut dash_lengths = DASH_LENGTHS.lock();
+    #[cfg(target_arch = "wasm32")]
+    let mut dash_lengths = DASH_LENGTHS.borrow_mut();
+    f(&mut dash_lengths)
+}
+
+impl Default for DashPattern {
+    fn default() -> Self {
+        Self::SOLID
+    }
+}
+
+impl DashPattern {
+    /// No dashes.
+    pub const SOLID: Self = Self {
+        index: 0,
+        scale: 1.0,
+        offset: 0.0,
+    };
+
+    /// Alternating lengths of dashes and gaps, starting with a dash.
+    ///
+    /// Like in SVG, an odd number of lengths is repeated to get an even number,
+    /// so `[5.0]` is the same as `[5.0, 5.0]`.
+    /// A dash can have zero length, which is a dot with [`LineCap::Round`].
+    ///
+    /// Negative lengths count as zero, and if all lengths are zero the line is solid.
+    pub fn new(lengths: &[f32]) -> Self {
+        let lengths: Vec<f32> = lengths.iter().map(|length| length.max(0.0)).collect();
+        let sum: f32 = lengths.iter().sum();
+        if !(sum > 0.0 && sum.is_finite()) {
+            return Self::SOLID;
+        }
+
+        let index = with_dash_lengths(|dash_lengths| {
+            dash_lengths
+                .iter()
+                .position(|existing| *existing == lengths)
+                .unwrap_or_else(|| {
+                    dash_lengths.push(lengths);
+                    dash_lengths.len() - 1
+                })
+        });
+        Self {
+            index: index as u32 + 1,
+            ..Self::SOLID
+        }
+    }
+
+    /// Dashes of `dash_length` with gaps of `gap_length` between them.
+    pub fn dashed(dash_length: f32, gap_length: f32) -> Self {
+        Self::new(&[dash_length, gap_length])
+    }
+
+    /// Dots (zero-length dashes, so use [`LineCap::Round`] or [`LineCap::Square`]) `spacing` apart.
+    pub fn dotted(spacing: f32) -> Self {
+        Self::new(&[0.0, spacing])
+    }
+
+    #[inline]
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[inline]
+    pub fn is_solid(&self) -> bool {
+        self.index == 0
+    }
+
+    /// The lengths of dashes and gaps, as given to [`Self::new`] (and scaled since).
+    pub fn lengths(&self) -> Vec<f32> {
+        if self.is_solid() {
+            return vec![];
+        }
+        with_dash_lengths(|dash_lengths| {
+            dash_lengths[self.index as usize - 1]
+                .iter()
+                .map(|length| self.scale * length)
+                .collect()
+        })
+    }
+
+    /// The length along the line after which the pattern repeats.
+    pub fn period(&self) -> f32 {
+        let lengths = self.lengths();
+        let sum: f32 = lengths.iter().sum();
+        if lengths.len() % 2 == 0 {
+            sum
+        } else {
+            2.0 * sum
+        }
+    }
+
+    /// Scale the lengths and offset.
+    #[inline]
+    pub(crate) fn scale(&mut self, factor: f32) {
+        self.scale *= factor;
+        self.offset *= factor;
+    }
+}
+
+impl PartialEq for DashPattern {
+    fn eq(&self, other: &Self) -> bool {
+        let same_lengths = if self.is_solid() || other.is_solid() {
+            self.is_solid() == other.is_solid()
+        } else {
+            (self.index, self.scale) == (other.index, other.scale)
+                || self.lengths() == other.lengths()
+        };
+        same_lengths && self.offset == other.offset
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct SerializedDashPattern {
+    lengths: Vec<f32>,
+    offset: f32,
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedDashPattern> for DashPattern {
+    fn from(pattern: SerializedDashPattern) -> Self {
+        Self::new(&pattern.lengths).with_offset(pattern.offset)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<DashPattern> for SerializedDashPattern {
+    fn from(pattern: DashPattern) -> Self {
+        Self {
+            lengths: pattern.lengths(),
+            offset: pattern.offset,
+        }
     }
 }
@@ -0,0 +1,1293 @@
+//! Import vector graphics from SVG as [`Shape`]s.
+//!
+//! Unlike rasterizing an SVG to an image, the shapes stay sharp at any size and `pixels_per_point`,
+//! and they can be recolored like any other shapes, e.g. with [`crate::shape_transform::adjust_colors`].
+//!
+//! Only what is common in icons is supported:
+//! * `<path>`, `<rect>`, `<circle>`, `<ellipse>`, `<line>`, `<polyline>` and `<polygon>`, in `<g>` groups.
+//! * Fills with colors and linear and radial gradients (without `gradientTransform` or `spreadMethod`).
+//! * Strokes with colors, widths, caps, joins and dashes. A gradient stroke is painted with its average color.
+//! * The `transform`, `opacity` and `display` attributes, and presentation attributes in `style`.
+//!
+//! Things like text, images, clipping, masks, filters and CSS style sheets are ignored.
+//! The `opacity` of a group is applied to each shape in it separately.
+
+use std::f32::consts::{PI, TAU};
+
+use crate::*;
+use emath::*;
+
+/// Vector graphics imported from an SVG document or path data.
+///
+/// The curves are kept, and only flattened when you ask for the [`Shape`]s at a certain size,
+/// so they are always smooth.
+///
+/// ```
+/// # use epaint::{*, svg::Svg};
+/// let svg = Svg::parse(r##"
+///     <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+///         <path d="M4 12 L10 18 L20 6" fill="none" stroke="#000" stroke-width="2"/>
+///     </svg>
+/// "##).unwrap();
+/// assert_eq!(svg.size(), vec2(24.0, 24.0));
+/// let shapes = svg.shapes(Rect::from_min_size(pos2(0.0, 0.0), vec2(48.0, 48.0)));
+/// assert_eq!(shapes.len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Svg {
+    size: Vec2,
+    view_box: Rect,
+    paths: Vec<SvgPath>,
+}
+
+impl Svg {
+    /// Parse an SVG document.
+    pub fn parse(svg: &str) -> Result<Self, String> {
+        let doc = roxmltree::Document::parse(svg).map_err(|err| err.to_string())?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(format!(
+                "Expected an <svg> element, found <{}>",
+                root.tag_name().name()
+            ));
+        }
+
+        let view_box = root.attribute("viewBox").and_then(|view_box| {
+            match parse_numbers(view_box).as_slice() {
+                &[x, y, width, height] if width > 0.0 && height > 0.0 => {
+                    Some(Rect::from_min_size(pos2(x, y), vec2(width, height)))
+                }
+                _ => None,
+            }
+        });
+        let width = root.attribute("width").and_then(parse_length);
+        let height = root.attribute("height").and_then(parse_length);
+        let size = match (width, height, view_box) {
+            (Some(width), Some(height), _) => vec2(width, height),
+            (Some(width), None, Some(view_box)) => {
+                vec2(width, width * view_box.height() / view_box.width())
+            }
+            (None, Some(height), Some(view_box)) => {
+                vec2(height * view_box.width() / view_box.height(), height)
+            }
+            (_, _, Some(view_box)) => view_box.size(),
+            _ => Vec2::splat(100.0),
+        };
+        let view_box = view_box.unwrap_or_else(|| Rect::from_min_size(Pos2::ZERO, size));
+
+        let mut importer = Importer {
+            doc: &doc,
+            paths: vec![],
+        };
+        importer.add_element(root, &Style::default());
+
+        Ok(Self {
+            size,
+            view_box,
+            paths: importer.paths,
+        })
+    }
+
+    /// Parse an SVG document from UTF-8 bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let svg = std::str::from_utf8(bytes).map_err(|err| err.to_string())?;
+        Self::parse(svg)
+    }
+
+    /// Import the path data of an SVG `<path>` element (its `d` attribute), e.g. `"M0 0 L10 0 L5 8 Z"`.
+    ///
+    /// The view box is the bounding box of the path. The width of the stroke is in the units of the path.
+    pub fn from_path_data(
+        path_data: &str,
+        fill: impl Into<Brush>,
+        stroke: impl Into<Stroke>,
+    ) -> Result<Self, String> {
+        let subpaths = parse_path_data(path_data)?;
+        let view_box = bounding_rect(&subpaths);
+        if !view_box.is_positive() {
+            return Err("The path is empty".to_owned());
+        }
+        Ok(Self {
+            size: view_box.size(),
+            view_box,
+            paths: vec![SvgPath {
+                subpaths,
+                fill: Some((fill.into(), FillMode::NonZero)),
+                stroke: stroke.into(),
+            }],
+        })
+    }
+
+    /// The intended size, from the `width` and `height` of the document
+    /// (or else from the view box), in pixels.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// The part of the coordinate system of the document that is shown.
+    pub fn view_box(&self) -> Rect {
+        self.view_box
+    }
+
+    /// The shapes that paint the SVG in the given rectangle.
+    ///
+    /// The view box is scaled to fit in `rect`, keeping its aspect ratio, and centered.
+    pub fn shapes(&self, rect: Rect) -> Vec<Shape> {
+        let view_box = self.view_box;
+        let scale = (rect.width() / view_box.width()).min(rect.height() / view_box.height());
+        if !(scale > 0.0 && scale.is_finite()) {
+            return vec![];
+        }
+        let to_screen = TSTransform::new(
+            rect.center().to_vec2() - scale * view_box.center().to_vec2(),
+            scale,
+        );
+
+        let mut shapes = vec![];
+        for path in &self.paths {
+            let outlines: Vec<(Vec<Pos2>, bool)> = path
+                .subpaths
+                .iter()
+                .map(|subpath| (subpath.flatten(to_screen), subpath.closed))
+                .collect();
+
+            if let Some((brush, fill_mode)) = path.fill {
+                let mut fills = outlines
+                    .iter()
+                    .filter(|(points, _)| points.len() >= 3)
+                    .map(|(points, _)| points.clone());
+                if let Some(points) = fills.next() {
+                    shapes.push(Shape::Path(PathShape {
+                        fill_mode,
+                        holes: fills.collect(),
+                        ..PathShape::polygon(points, brush, Stroke::none())
+                    }));
+                }
+            }
+
+            if !path.stroke.is_empty() {
+                let mut stroke = path.stroke;
+                stroke.scale(scale);
+                for (points, closed) in outlines {
+                    if points.len() < 2 {
+                        continue;
+                    }
+                    shapes.push(Shape::Path(if closed {
+                        PathShape::closed_line(points, stroke)
+                    } else {
+                        PathShape::line(points, stroke)
+                    }));
+                }
+            }
+        }
+        shapes
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A path with its paint, in the coordinates of the view box.
+#[derive(Clone, Debug)]
+struct SvgPath {
+    subpaths: Vec<SubPath>,
+    fill: Option<(Brush, FillMode)>,
+    stroke: Stroke,
+}
+
+/// A connected part of a path.
+#[derive(Clone, Debug)]
+struct SubPath {
+    start: Pos2,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    Line(Pos2),
+
+    /// Two control points and the end point of a cubic Bézier curve.
+    Cubic(Pos2, Pos2, Pos2),
+}
+
+impl SubPath {
+    fn new(start: Pos2) -> Self {
+        Self {
+            start,
+            segments: vec![],
+            closed: false,
+        }
+    }
+
+    fn points_mut(&mut self) -> impl Iterator<Item = &mut Pos2> {
+        std::iter::once(&mut self.start).chain(self.segments.iter_mut().flat_map(|segment| {
+            match segment {
+                Segment::Line(end) => vec![end],
+                Segment::Cubic(control1, control2, end) => vec![control1, control2, end],
+            }
+        }))
+    }
+
+    fn transform(&mut self, transform: Affine) {
+        for point in self.points_mut() {
+            *point = transform.mul_pos(*point);
+        }
+    }
+
+    /// The points along the subpath, after the transform, within `0.1` points of the curves.
+    fn flatten(&self, transform: TSTransform) -> Vec<Pos2> {
+        const TOLERANCE: f32 = 0.1;
+
+        let mut points = vec![transform * self.start];
+        for segment in &self.segments {
+            match *segment {
+                Segment::Line(end) => points.push(transform * end),
+                Segment::Cubic(control1, control2, end) => {
+                    let start = *points.last().unwrap();
+                    let [control1, control2, end] =
+                        [control1, control2, end].map(|p| transform * p);
+                    let curve = CubicBezierShape::from_points_stroke(
+                        [start, control1, control2, end],
+                        false,
+                        Color32::TRANSPARENT,
+                        Stroke::none(),
+                    );
+                    curve.for_each_flattened_with_t(TOLERANCE, &mut |p, _t| points.push(p));
+                    if points.last() != Some(&end) {
+                        points.push(end);
+                    }
+                }
+            }
+        }
+        points.dedup();
+        if self.closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        points
+    }
+}
+
+/// The bounding rectangle of the curves (not just their control points).
+fn bounding_rect(subpaths: &[SubPath]) -> Rect {
+    let mut control_rect = Rect::NOTHING;
+    for subpath in subpaths {
+        let mut subpath = subpath.clone();
+        for &mut point in subpath.points_mut() {
+            control_rect.extend_with(point);
+        }
+    }
+    if !control_rect.is_finite() {
+        return Rect::NOTHING;
+    }
+
+    // Flatten to well within a thousandth of the size:
+    let scale = 1000.0 / control_rect.size().max_elem().max(f32::EPSILON);
+    let to_fine = TSTransform::from_scaling(scale);
+    let mut rect = Rect::NOTHING;
+    for subpath in subpaths {
+        for point in subpath.flatten(to_fine) {
+            rect.extend_with(to_fine.inverse() * point);
+        }
+    }
+    rect
+}
+
+// ----------------------------------------------------------------------------
+
+/// A 2D affine transform, as in the SVG `matrix(a b c d e f)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Affine([f32; 6]);
+
+impl Affine {
+    const IDENTITY: Self = Self([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translate(x: f32, y: f32) -> Self {
+        Self([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    fn scale(x: f32, y: f32) -> Self {
+        Self([x, 0.0, 0.0, y, 0.0, 0.0])
+    }
+
+    /// Clockwise, in degrees.
+    fn rotate(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self([cos, sin, -sin, cos, 0.0, 0.0])
+    }
+
+    fn mul_pos(self, pos: Pos2) -> Pos2 {
+        let [a, b, c, d, e, f] = self.0;
+        pos2(a * pos.x + c * pos.y + e, b * pos.x + d * pos.y + f)
+    }
+
+    fn mul_vec(self, vec: Vec2) -> Vec2 {
+        let [a, b, c, d, _, _] = self.0;
+        vec2(a * vec.x + c * vec.y, b * vec.x + d * vec.y)
+    }
+
+    /// How much lengths are scaled, on average.
+    fn length_scale(self) -> f32 {
+        let [a, b, c, d, _, _] = self.0;
+        (a * d - b * c).abs().sqrt()
+    }
+}
+
+/// First `rhs`, then `self`.
+impl std::ops::Mul for Affine {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let [a, b, c, d, e, f] = self.0;
+        let [ra, rb, rc, rd, re, rf] = rhs.0;
+        Self([
+            a * ra + c * rb,
+            b * ra + d * rb,
+            a * rc + c * rd,
+            b * rc + d * rd,
+            a * re + c * rf + e,
+            b * re + d * rf + f,
+        ])
+    }
+}
+
+/// Parse the `transform` attribute, e.g. `"translate(10 20) rotate(45)"`.
+fn parse_transform(text: &str) -> Option<Affine> {
+    let mut transform = Affine::IDENTITY;
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let open = rest.find('(')?;
+        let close = rest.find(')')?;
+        let name = rest[..open].trim().trim_start_matches(',').trim();
+        let args = parse_numbers(&rest[open + 1..close]);
+        let arg = |i: usize| args.get(i).copied();
+        let next = match (name, args.len()) {
+            ("matrix", 6) => Affine([args[0], args[1], args[2], args[3], args[4], args[5]]),
+            ("translate", 1 | 2) => Affine::translate(args[0], arg(1).unwrap_or(0.0)),
+            ("scale", 1 | 2) => Affine::scale(args[0], arg(1).unwrap_or(args[0])),
+            ("rotate", 1) => Affine::rotate(args[0]),
+            ("rotate", 3) => {
+                Affine::translate(args[1], args[2])
+                    * Affine::rotate(args[0])
+                    * Affine::translate(-args[1], -args[2])
+            }
+            ("skewX", 1) => Affine([1.0, 0.0, args[0].to_radians().tan(), 1.0, 0.0, 0.0]),
+            ("skewY", 1) => Affine([1.0, args[0].to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+            _ => return None,
+        };
+        transform = transform * next;
+        rest = rest[close + 1..].trim_start();
+    }
+    Some(transform)
+}
+
+// ----------------------------------------------------------------------------
+
+/// The inherited properties that decide how to paint an element.
+#[derive(Clone, Debug)]
+struct Style {
+    transform: Affine,
+
+    /// The value of `currentColor`.
+    color: Color32,
+
+    fill: Paint,
+    fill_opacity: f32,
+    fill_rule: FillMode,
+
+    stroke: Paint,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    cap: LineCap,
+    join: LineJoin,
+    miter_limit: f32,
+    dash: DashPattern,
+
+    /// The `opacity` of the element and all the groups it is in.
+    opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            transform: Affine::IDENTITY,
+            color: Color32::BLACK,
+            fill: Paint::Color(Color32::BLACK),
+            fill_opacity: 1.0,
+            fill_rule: FillMode::NonZero,
+            stroke: Paint::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: Stroke::DEFAULT_MITER_LIMIT,
+            dash: DashPattern::SOLID,
+            opacity: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Paint {
+    None,
+    Color(Color32),
+
+    /// A gradient, by id, and what to use if there is no such gradient.
+    Url(String, Box<Paint>),
+}
+
+impl Style {
+    /// The style of `node`, which is in an element with this style.
+    ///
+    /// Returns `None` if the element isn't displayed.
+    fn of(&self, node: roxmltree::Node<'_, '_>) -> Option<Self> {
+        if property(node, "display") == Some("none") {
+            return None;
+        }
+
+        let mut style = self.clone();
+        if let Some(transform) = node.attribute("transform").and_then(parse_transform) {
+            style.transform = style.transform * transform;
+        }
+        if let Some(color) = property(node, "color").and_then(parse_color) {
+            style.color = color;
+        }
+        if let Some(fill) = property(node, "fill").and_then(|fill| style.parse_paint(fill)) {
+            style.fill = fill;
+        }
+        if let Some(stroke) = property(node, "stroke").and_then(|stroke| style.parse_paint(stroke))
+        {
+            style.stroke = stroke;
+        }
+        if let Some(opacity) = property(node, "fill-opacity").and_then(parse_opacity) {
+            style.fill_opacity = opacity;
+        }
+        if let Some(opacity) = property(node, "stroke-opacity").and_then(parse_opacity) {
+            style.stroke_opacity = opacity;
+        }
+        if let Some(opacity) = property(node, "opacity").and_then(parse_opacity) {
+            style.opacity *= opacity;
+        }
+        match property(node, "fill-rule") {
+            Some("nonzero") => style.fill_rule = FillMode::NonZero,
+            Some("evenodd") => style.fill_rule = FillMode::EvenOdd,
+            _ => {}
+        }
+        if let Some(width) = property(node, "stroke-width").and_then(parse_length) {
+            style.stroke_width = width;
+        }
+        match property(node, "stroke-linecap") {
+            Some("butt") => style.cap = LineCap::Butt,
+            Some("round") => style.cap = LineCap::Round,
+            Some("square") => style.cap = LineCap::Square,
+            _ => {}
+        }
+        match property(node, "stroke-linejoin") {
+            Some("miter" | "miter-clip" | "arcs") => style.join = LineJoin::Miter,
+            Some("round") => style.join = LineJoin::Round,
+            Some("bevel") => style.join = LineJoin::Bevel,
+            _ => {}
+        }
+        if let Some(miter_limit) = property(node, "stroke-miterlimit").and_then(parse_number) {
+            style.miter_limit = miter_limit.max(1.0);
+        }
+        if let Some(dash) = property(node, "stroke-dasharray") {
+            let mut lengths = parse_numbers(dash);
+            if lengths.len() % 2 == 1 {
+                lengths.extend_from_within(..);
+            }
+            style.dash = if lengths.iter().all(|&length| length >= 0.0) {
+                DashPattern::new(&lengths).with_offset(style.dash.offset)
+            } else {
+                DashPattern::SOLID
+            };
+        }
+        if let Some(offset) = property(node, "stroke-dashoffset").and_then(parse_length) {
+            style.dash.offset = offset;
+        }
+        Some(style)
+    }
+
+    fn parse_paint(&self, text: &str) -> Option<Paint> {
+        let text = text.trim();
+        if text == "none" {
+            Some(Paint::None)
+        } else if text == "currentColor" {
+            Some(Paint::Color(self.color))
+        } else if let Some(url) = text.strip_prefix("url(") {
+            let close = url.find(')')?;
+            let id = url[..close].trim().trim_matches(|c| c == '\'' || c == '"');
+            let fallback = self.parse_paint(&url[close + 1..]).unwrap_or(Paint::None);
+            Some(Paint::Url(
+                id.trim_start_matches('#').to_owned(),
+                Box::new(fallback),
+            ))
+        } else {
+            parse_color(text).map(Paint::Color)
+        }
+    }
+}
+
+/// A property, from the `style` attribute or else a presentation attribute.
+fn property<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').rev().find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    });
+    from_style
+        .or_else(|| node.attribute(name))
+        .filter(|&value| value != "inherit")
+}
+
+// ----------------------------------------------------------------------------
+
+struct Importer<'a> {
+    doc: &'a roxmltree::Document<'a>,
+    paths: Vec<SvgPath>,
+}
+
+impl<'a> Importer<'a> {
+    fn add_element(&mut self, node: roxmltree::Node<'_, '_>, parent_style: &Style) {
+        let style = match parent_style.of(node) {
+            Some(style) => style,
+            None => return,
+        };
+
+        let attribute = |name: &str| node.attribute(name).and_then(parse_length).unwrap_or(0.0);
+        let subpaths = match node.tag_name().name() {
+            "svg" | "g" | "a" | "switch" => {
+                for child in node.children().filter(|child| child.is_element()) {
+                    self.add_element(child, &style);
+                }
+                return;
+            }
+            "path" => match node.attribute("d").map(parse_path_data) {
+                Some(Ok(subpaths)) => subpaths,
+                _ => return,
+            },
+            "rect" => rect_subpath(
+                Rect::from_min_size(
+                    pos2(attribute("x"), attribute("y")),
+                    vec2(attribute("width"), attribute("height")),
+                ),
+                node.attribute("rx").and_then(parse_length),
+                node.attribute("ry").and_then(parse_length),
+            ),
+            "circle" => {
+                let radius = attribute("r");
+                ellipse_subpath(pos2(attribute("cx"), attribute("cy")), Vec2::splat(radius))
+            }
+            "ellipse" => ellipse_subpath(
+                pos2(attribute("cx"), attribute("cy")),
+                vec2(attribute("rx"), attribute("ry")),
+            ),
+            "line" => {
+                let mut subpath = SubPath::new(pos2(attribute("x1"), attribute("y1")));
+                subpath
+                    .segments
+                    .push(Segment::Line(pos2(attribute("x2"), attribute("y2"))));
+                let style = Style {
+                    fill: Paint::None,
+                    ..style
+                };
+                self.add_path(vec![subpath], &style);
+                return;
+            }
+            name @ ("polyline" | "polygon") => {
+                let numbers = parse_numbers(node.attribute("points").unwrap_or_default());
+                let mut points = numbers.chunks_exact(2).map(|xy| pos2(xy[0], xy[1]));
+                let mut subpath = match points.next() {
+                    Some(start) => SubPath::new(start),
+                    None => return,
+                };
+                subpath.segments.extend(points.map(Segment::Line));
+                subpath.closed = name == "polygon";
+                vec![subpath]
+            }
+            _ => return, // e.g. <defs>, which we look in when something refers to it.
+        };
+        self.add_path(subpaths, &style);
+    }
+
+    /// Add the `subpaths` (in the coordinates of the element) with the `style` of their element.
+    fn add_path(&mut self, mut subpaths: Vec<SubPath>, style: &Style) {
+        subpaths.retain(|subpath| !subpath.segments.is_empty());
+        if subpaths.is_empty() {
+            return;
+        }
+
+        let element_rect = bounding_rect(&subpaths);
+        for subpath in &mut subpaths {
+            subpath.transform(style.transform);
+        }
+        let rect = bounding_rect(&subpaths);
+
+        let fill = self
+            .brush(
+                &style.fill,
+                style.opacity * style.fill_opacity,
+                element_rect,
+                style.transform,
+                rect,
+            )
+            .map(|brush| (brush, style.fill_rule));
+
+        let stroke_brush = self.brush(
+            &style.stroke,
+            style.opacity * style.stroke_opacity,
+            element_rect,
+            style.transform,
+            rect,
+        );
+        let stroke = match stroke_brush {
+            Some(brush) if style.stroke_width > 0.0 => {
+                let mut stroke = Stroke::new(style.stroke_width, brush.average_color())
+                    .with_cap(style.cap)
+                    .with_join(style.join)
+                    .with_miter_limit(style.miter_limit)
+                    .with_dash(style.dash);
+                stroke.scale(style.transform.length_scale());
+                stroke
+            }
+            _ => Stroke::none(),
+        };
+
+        if fill.is_some() || !stroke.is_empty() {
+            self.paths.push(SvgPath {
+                subpaths,
+                fill,
+                stroke,
+            });
+        }
+    }
+
+    /// The brush of the `paint` of an element with the bounding rectangle `element_rect`
+    /// (in its own coordinates), which `transform` takes to `rect`.
+    fn brush(
+        &self,
+        paint: &Paint,
+        opacity: f32,
+        element_rect: Rect,
+        transform: Affine,
+        rect: Rect,
+    ) -> Option<Brush> {
+        match paint {
+            Paint::None => None,
+            Paint::Color(color) => Some(Brush::Solid(color.linear_multiply(opacity))),
+            Paint::Url(id, fallback) => match self.element_by_id(id) {
+                Some(gradient) => self.gradient(gradient, element_rect, transform, rect),
+                None => self.brush(fallback, opacity, element_rect, transform, rect),
+            }
+            .map(|brush| brush.linear_multiply(opacity)),
+        }
+    }
+
+    fn element_by_id(&self, id: &str) -> Option<roxmltree::Node<'a, 'a>> {
+        self.doc
+            .descendants()
+            .find(|node| node.attribute("id") == Some(id))
+    }
+
+    /// The gradient that `href` refers to, which has the attributes and stops that this one lacks.
+    fn gradient_template(&self, node: roxmltree::Node<'a, 'a>) -> Option<roxmltree::Node<'a, 'a>> {
+        let href = node
+            .attribute(("http://www.w3.org/1999/xlink", "href"))
+            .or_else(|| node.attribute("href"))?;
+        self.element_by_id(href.trim_start_matches('#'))
+    }
+
+    fn gradient(
+        &self,
+        node: roxmltree::Node<'a, 'a>,
+        element_rect: Rect,
+        transform: Affine,
+        rect: Rect,
+    ) -> Option<Brush> {
+        const MAX_TEMPLATES: usize = 8; // in case they refer to each other
+
+        let is_linear = match node.tag_name().name() {
+            "linearGradient" => true,
+            "radialGradient" => false,
+            _ => return None,
+        };
+
+        let chain: Vec<_> = std::iter::successors(Some(node), |&node| self.gradient_template(node))
+            .take(MAX_TEMPLATES)
+            .collect();
+        let attribute = |name: &str| chain.iter().find_map(|node| node.attribute(name));
+
+        let mut stops = vec![];
+        let stop_nodes = chain
+            .iter()
+            .map(|node| node.children().filter(|child| child.has_tag_name("stop")))
+            .find(|stops| stops.clone().next().is_some());
+        for stop in stop_nodes.into_iter().flatten() {
+            let offset = stop
+                .attribute("offset")
+                .and_then(parse_opacity)
+                .unwrap_or(0.0);
+            let color = property(stop, "stop-color")
+                .and_then(parse_color)
+                .unwrap_or(Color32::BLACK);
+            let opacity = property(stop, "stop-opacity")
+                .and_then(parse_opacity)
+                .unwrap_or(1.0);
+            // Offsets can't go back:
+            let offset = stops.last().map_or(offset, |&(last, _)| offset.max(last));
+            stops.push((offset, color.linear_multiply(opacity)));
+        }
+        stops.truncate(ColorStops::MAX);
+        match stops.len() {
+            0 => return None,
+            1 => return Some(Brush::Solid(stops[0].1)),
+            _ => {}
+        }
+        let stops = ColorStops::new(&stops);
+
+        if !rect.is_positive() {
+            return Some(Brush::Solid(
+                Brush::LinearGradient {
+                    start: Pos2::ZERO,
+                    end: Pos2::ZERO,
+                    stops,
+                }
+                .average_color(),
+            ));
+        }
+
+        // Gradient coordinates are fractions of the element's bounding box, unless in user space:
+        let user_space = attribute("gradientUnits") == Some("userSpaceOnUse");
+        let coordinate = |name: &str, default: f32| {
+            attribute(name)
+                .and_then(parse_coordinate)
+                .unwrap_or(default)
+        };
+        let to_element = |pos: Pos2| {
+            if user_space {
+                pos
+            } else {
+                element_rect.min + pos.to_vec2() * element_rect.size()
+            }
+        };
+        let to_brush = |pos: Pos2| {
+            let pos = transform.mul_pos(to_element(pos));
+            pos2(
+                (pos.x - rect.min.x) / rect.width(),
+                (pos.y - rect.min.y) / rect.height(),
+            )
+        };
+
+        Some(if is_linear {
+            Brush::LinearGradient {
+                start: to_brush(pos2(coordinate("x1", 0.0), coordinate("y1", 0.0))),
+                end: to_brush(pos2(coordinate("x2", 1.0), coordinate("y2", 0.0))),
+                stops,
+            }
+        } else {
+            let radius = coordinate("r", 0.5);
+            let radius = if user_space {
+                Vec2::splat(radius)
+            } else {
+                radius * element_rect.size()
+            };
+            let radius = transform.mul_vec(radius).abs();
+            Brush::RadialGradient {
+                center: to_brush(pos2(coordinate("cx", 0.5), coordinate("cy", 0.5))),
+                radius: radius / rect.size(),
+                stops,
+            }
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A rectangle, with corners rounded by `rx` and `ry`.
+fn rect_subpath(rect: Rect, rx: Option<f32>, ry: Option<f32>) -> Vec<SubPath> {
+    if !rect.is_positive() {
+        return vec![];
+    }
+    let rx = rx.or(ry).unwrap_or(0.0).clamp(0.0, rect.width() / 2.0);
+    let ry = ry
+        .or(Some(rx))
+        .unwrap_or(0.0)
+        .clamp(0.0, rect.height() / 2.0);
+
+    let mut subpath = SubPath::new(pos2(rect.min.x + rx, rect.min.y));
+    subpath.closed = true;
+    let radius = vec2(rx, ry);
+    let corners = [
+        (rect.right_top() + vec2(-rx, ry), -PI / 2.0),
+        (rect.right_bottom() + vec2(-rx, -ry), 0.0),
+        (rect.left_bottom() + vec2(rx, -ry), PI / 2.0),
+        (rect.left_top() + vec2(rx, ry), PI),
+    ];
+    for (center, start_angle) in corners {
+        let start = center + radius * Vec2::angled(start_angle);
+        subpath.segments.push(Segment::Line(start));
+        if rx > 0.0 && ry > 0.0 {
+            add_elliptic_arc(&mut subpath, center, radius, 0.0, start_angle, PI / 2.0);
+        }
+    }
+    vec![subpath]
+}
+
+fn ellipse_subpath(center: Pos2, radius: Vec2) -> Vec<SubPath> {
+    if radius.x <= 0.0 || radius.y <= 0.0 {
+        return vec![];
+    }
+    let mut subpath = SubPath::new(center + vec2(radius.x, 0.0));
+    add_elliptic_arc(&mut subpath, center, radius, 0.0, 0.0, TAU);
+    subpath.closed = true;
+    vec![subpath]
+}
+
+/// Add an arc of the ellipse (rotated by `rotation` radians) from `start_angle`, `sweep` radians around,
+/// as cubic Bézier curves.
+fn add_elliptic_arc(
+    subpath: &mut SubPath,
+    center: Pos2,
+    radius: Vec2,
+    rotation: f32,
+    start_angle: f32,
+    sweep: f32,
+) {
+    let rotation = Rot2::from_angle(rotation);
+    let point = |angle: f32| center + rotation * (radius * Vec2::angled(angle));
+    let tangent = |angle: f32| rotation * (radius * Vec2::angled(angle).rot90() * -1.0);
+
+    // At most a quarter turn per curve:
+    let count = (sweep.abs() / (PI / 2.0) - 1e-3).ceil().max(1.0);
+    let step = sweep / count;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    for i in 0..count as usize {
+        let (a0, a1) = (
+            start_angle + i as f32 * step,
+            start_angle + (i + 1) as f32 * step,
+        );
+        subpath.segments.push(Segment::Cubic(
+            point(a0) + k * tangent(a0),
+            point(a1) - k * tangent(a1),
+            point(a1),
+        ));
+    }
+}
+
+/// Add the elliptic arc of the SVG path command `A` from `from` to `to`.
+#[allow(clippy::too_many_arguments)]
+fn add_svg_arc(
+    subpath: &mut SubPath,
+    from: Pos2,
+    radius: Vec2,
+    x_axis_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Pos2,
+) {
+    if from == to {
+        return;
+    }
+    let mut radius = radius.abs();
+    if radius.x == 0.0 || radius.y == 0.0 {
+        subpath.segments.push(Segment::Line(to));
+        return;
+    }
+
+    // See https://www.w3.org/TR/SVG11/implnote.html#ArcConversionEndpointToCenter
+    let rotation = x_axis_rotation.to_radians();
+    let inverse_rotation = Rot2::from_angle(-rotation);
+    let p = inverse_rotation * ((from - to) / 2.0);
+
+    let lambda = (p.x / radius.x).powi(2) + (p.y / radius.y).powi(2);
+    if lambda > 1.0 {
+        radius *= lambda.sqrt();
+    }
+    let (rx2, ry2) = (radius.x * radius.x, radius.y * radius.y);
+    let numerator = rx2 * ry2 - rx2 * p.y * p.y - ry2 * p.x * p.x;
+    let denominator = rx2 * p.y * p.y + ry2 * p.x * p.x;
+    let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let center_prime = coefficient * vec2(radius.x * p.y / radius.y, -radius.y * p.x / radius.x);
+    let center = from + (to - from) / 2.0 + Rot2::from_angle(rotation) * center_prime;
+
+    let angle = |v: Vec2| v.y.atan2(v.x);
+    let start_angle = angle((p - center_prime) / radius);
+    let end_angle = angle((-p - center_prime) / radius);
+    let mut sweep_angle = end_angle - start_angle;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += TAU;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= TAU;
+    }
+
+    add_elliptic_arc(subpath, center, radius, rotation, start_angle, sweep_angle);
+
+    // Make sure we end exactly at `to`:
+    if let Some(Segment::Cubic(_, _, end)) = subpath.segments.last_mut() {
+        *end = to;
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Parse the path data of a `<path>` element.
+fn parse_path_data(text: &str) -> Result<Vec<SubPath>, String> {
+    let mut lexer = Lexer::new(text);
+    let mut subpaths: Vec<SubPath> = vec![];
+    let mut current = Pos2::ZERO;
+    let mut command = None;
+
+    // The last control points, which `S` and `T` mirror:
+    let mut last_cubic_control = None;
+    let mut last_quadratic_control = None;
+
+    loop {
+        lexer.skip_separators();
+        if lexer.is_at_end() {
+            break;
+        }
+        if let Some(new_command) = lexer.command() {
+            command = Some(new_command);
+        }
+        let command_char = command.ok_or_else(|| lexer.error("Expected a command"))?;
+        let relative = command_char.is_ascii_lowercase();
+        let base = if relative {
+            current.to_vec2()
+        } else {
+            Vec2::ZERO
+        };
+
+        let upper = command_char.to_ascii_uppercase();
+        if upper == 'M' {
+            current = lexer.point()? + base;
+            subpaths.push(SubPath::new(current));
+            // Following coordinates are lines:
+            command = Some(if relative { 'l' } else { 'L' });
+            last_cubic_control = None;
+            last_quadratic_control = None;
+            continue;
+        }
+
+        if subpaths.last().map_or(true, |subpath| subpath.closed) {
+            // After a close (or at the start), we continue from where the last subpath started:
+            let start = subpaths.last().map_or(current, |subpath| subpath.start);
+            subpaths.push(SubPath::new(start));
+        }
+        let subpath = subpaths.last_mut().unwrap();
+
+        let (mut cubic_control, mut quadratic_control) = (None, None);
+        match upper {
+            'L' => {
+                current = lexer.point()? + base;
+                subpath.segments.push(Segment::Line(current));
+            }
+            'H' => {
+                current.x = lexer.number()? + base.x;
+                subpath.segments.push(Segment::Line(current));
+            }
+            'V' => {
+                current.y = lexer.number()? + base.y;
+                subpath.segments.push(Segment::Line(current));
+            }
+            'C' | 'S' => {
+                let control1 = if upper == 'C' {
+                    lexer.point()? + base
+                } else {
+                    last_cubic_control
+                        .map_or(current, |control: Pos2| current + (current - control))
+                };
+                let control2 = lexer.point()? + base;
+                let end = lexer.point()? + base;
+                subpath
+                    .segments
+                    .push(Segment::Cubic(control1, control2, end));
+                cubic_control = Some(control2);
+                current = end;
+            }
+            'Q' | 'T' => {
+                let control = if upper == 'Q' {
+                    lexer.point()? + base
+                } else {
+                    last_quadratic_control
+                        .map_or(current, |control: Pos2| current + (current - control))
+                };
+                let end = lexer.point()? + base;
+                subpath.segments.push(Segment::Cubic(
+                    current + 2.0 / 3.0 * (control - current),
+                    end + 2.0 / 3.0 * (control - end),
+                    end,
+                ));
+                quadratic_control = Some(control);
+                current = end;
+            }
+            'A' => {
+                let radius = vec2(lexer.number()?, lexer.number()?);
+                let x_axis_rotation = lexer.number()?;
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let end = lexer.point()? + base;
+                add_svg_arc(
+                    subpath,
+                    current,
+                    radius,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    end,
+                );
+                current = end;
+            }
+            'Z' => {
+                subpath.closed = true;
+                current = subpath.start;
+                command = None; // must be followed by a new command
+            }
+            _ => return Err(lexer.error(&format!("Unknown command {:?}", command_char))),
+        }
+        last_cubic_control = cubic_control;
+        last_quadratic_control = quadratic_control;
+    }
+
+    Ok(subpaths)
+}
+
+/// Reads the commands and numbers of path data and other lists of numbers.
+struct Lexer<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at byte {} of {:?}", message, self.pos, self.text)
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.text.as_bytes()[self.pos..]
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r' | b',') = self.rest().first() {
+            self.pos += 1;
+        }
+    }
+
+    fn command(&mut self) -> Option<char> {
+        let c = *self.rest().first()?;
+        if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            self.pos += 1;
+            Some(c as char)
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip_separators();
+        let start = self.pos;
+        let bytes = self.rest();
+        let mut len = 0;
+        let digits = |len: &mut usize| {
+            while bytes.get(*len).map_or(false, u8::is_ascii_digit) {
+                *len += 1;
+            }
+        };
+        if let Some(b'+' | b'-') = bytes.first() {
+            len += 1;
+        }
+        digits(&mut len);
+        if bytes.get(len) == Some(&b'.') {
+            len += 1;
+            digits(&mut len);
+        }
+        if let Some(b'e' | b'E') = bytes.get(len) {
+            // Only an exponent if followed by digits (it could also be the start of `em`):
+            let mut exponent_len = len + 1;
+            if let Some(b'+' | b'-') = bytes.get(exponent_len) {
+                exponent_len += 1;
+            }
+            if bytes.get(exponent_len).map_or(false, u8::is_ascii_digit) {
+                len = exponent_len;
+                digits(&mut len);
+            }
+        }
+        self.pos += len;
+        self.text[start..self.pos]
+            .parse()
+            .map_err(|_| self.error("Expected a number"))
+    }
+
+    fn point(&mut self) -> Result<Pos2, String> {
+        Ok(pos2(self.number()?, self.number()?))
+    }
+
+    /// The flags of arcs can be written without separators, e.g. `a1 1 0 00 1 1`.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip_separators();
+        let flag = match self.rest().first() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error("Expected a flag (0 or 1)")),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+}
+
+/// All the numbers in a list like `"1, 2.5 -3"` (up until anything else).
+fn parse_numbers(text: &str) -> Vec<f32> {
+    let mut lexer = Lexer::new(text);
+    let mut numbers = vec![];
+    loop {
+        lexer.skip_separators();
+        if lexer.is_at_end() {
+            break;
+        }
+        match lexer.number() {
+            Ok(number) => numbers.push(number),
+            Err(_) => break,
+        }
+    }
+    numbers
+}
+
+fn parse_number(text: &str) -> Option<f32> {
+    text.trim().parse().ok()
+}
+
+/// A length like `12`, `12px` or `1.5em` (em counts as 16px). Other units are taken to be pixels.
+fn parse_length(text: &str) -> Option<f32> {
+    let mut lexer = Lexer::new(text.trim());
+    let number = lexer.number().ok()?;
+    match &text.trim()[lexer.pos..] {
+        "em" => Some(16.0 * number),
+        "%" => None,
+        _ => Some(number),
+    }
+}
+
+/// A number or a percentage, as a fraction.
+fn parse_coordinate(text: &str) -> Option<f32> {
+    match text.trim().strip_suffix('%') {
+        Some(percent) => parse_number(percent).map(|percent| percent / 100.0),
+        None => parse_number(text),
+    }
+}
+
+fn parse_opacity(text: &str) -> Option<f32> {
+    parse_coordinate(text).map(|opacity| opacity.clamp(0.0, 1.0))
+}
+
+/// A CSS color, like `#f80`, `#ff8800`, `rgb(255, 136, 0)` or `orange`.
+fn parse_color(text: &str) -> Option<Color32> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+        let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            3 => Some(Color32::from_rgb(
+                17 * digit(0)?,
+                17 * digit(1)?,
+                17 * digit(2)?,
+            )),
+            4 => Some(Color32::from_rgba_unmultiplied(
+                17 * digit(0)?,
+                17 * digit(1)?,
+                17 * digit(2)?,
+                17 * digit(3)?,
+            )),
+            6 => Some(Color32::from_rgb(byte(0)?, byte(2)?, byte(4)?)),
+            8 => Some(Color32::from_rgba_unmultiplied(
+                byte(0)?,
+                byte(2)?,
+                byte(4)?,
+                byte(6)?,
+            )),
+            _ => None,
+        };
+    }
+
+    if let Some(args) = text
+        .strip_prefix("rgba(")
+        .or_else(|| text.strip_prefix("rgb("))
+    {
+        let args: Vec<&str> = args.strip_suffix(')')?.split(',').map(str::trim).collect();
+        let channel = |text: &str| match text.strip_suffix('%') {
+            Some(percent) => parse_number(percent).map(|percent| percent * 2.55),
+            None => parse_number(text),
+        };
+        let [r, g, b] = [0, 1, 2].map(|i| {
+            args.get(i)
+                .and_then(|arg| channel(arg))
+                .map(|value| value.clamp(0.0, 255.0).round() as u8)
+        });
+        let alpha = args.get(3).map_or(Some(1.0), |arg| parse_opacity(arg))?;
+        return Some(Color32::from_rgba_unmultiplied(
+            r?,
+            g?,
+            b?,
+            (255.0 * alpha).round() as u8,
+        ));
+    }
+
+    let (r, g, b) = match text.to_ascii_lowercase().as_str() {
+        "transparent" => return Some(Color32::TRANSPARENT),
+        "black" => (0, 0, 0),
+        "silver" => (192, 192, 192),
+        "gray" | "grey" => (128, 128, 128),
+        "white" => (255, 255, 255),
+        "maroon" => (128, 0, 0),
+        "red" => (255, 0, 0),
+        "purple" => (128, 0, 128),
+        "fuchsia" | "magenta" => (255, 0, 255),
+        "green" => (0, 128, 0),
+        "lime" => (0, 255, 0),
+        "olive" => (128, 128, 0),
+        "yellow" => (255, 255, 0),
+        "navy" => (0, 0, 128),
+        "blue" => (0, 0, 255),
+        "teal" => (0, 128, 128),
+        "aqua" | "cyan" => (0, 255, 255),
+        "orange" => (255, 165, 0),
+        "brown" => (165, 42, 42),
+        "pink" => (255, 192, 203),
+        "gold" => (255, 215, 0),
+        "darkgray" | "darkgrey" => (169, 169, 169),
+        "lightgray" | "lightgrey" => (211, 211, 211),
+        _ => return None,
+    };
+    Some(Color32::from_rgb(r, g, b))
+}
+
+// ----------------------------------------------------------------------------
+
+#[test]
+fn test_parse_path_data() {
+    let subpaths = parse_path_data("M10,10 h10 v10 H10 z m5 5 l1-1.5.5.5 Q0 0 5 5 t1 1").unwrap();
+    assert_eq!(subpaths.len(), 2);
+    assert!(subpaths[0].closed);
+    assert_eq!(subpaths[0].segments.len(), 3);
+    assert_eq!(subpaths[1].start, pos2(15.0, 15.0));
+    assert!(!subpaths[1].closed);
+    assert_eq!(subpaths[1].segments.len(), 4);
+
+    // Arc flags without separators, and a half circle:
+    let subpaths = parse_path_data("M0 0a5 5 0 01 10 0").unwrap();
+    let rect = bounding_rect(&subpaths);
+    assert!((rect.min - pos2(0.0, -5.0)).length() < 0.01, "{:?}", rect);
+    assert!((rect.max - pos2(10.0, 0.0)).length() < 0.01, "{:?}", rect);
+
+    assert!(parse_path_data("10 10").is_err());
+    assert!(parse_path_data("M 10").is_err());
+}
@@ -5,6 +5,8 @@
 
 #![allow(clippy::identity_op)]
 
+use std::cmp::Ordering;
+
 use crate::texture_atlas::PreparedDisc;
 use crate::*;
 use emath::*;
@@ -494,8 +496,691 @@ impl Path {
     pub fn fill(&mut self, feathering: f32, color: Color32, out: &mut Mesh) {
         fill_closed_path(feathering, &mut self.0, color, out);
     }
+
+    /// Like [`Self::fill`], but with a [`Brush`], which can be a gradient.
+    ///
+    /// The gradient is relative to the bounding rectangle of the path.
+    pub fn fill_with_brush(&mut self, feathering: f32, brush: Brush, out: &mut Mesh) {
+        if let Brush::Solid(color) = brush {
+            self.fill(feathering, color, out);
+        } else if !brush.is_transparent() {
+            let rect = Rect::from_points(&self.0.iter().map(|p| p.pos).collect::<Vec<_>>());
+
+            // The distance to the center of a radial gradient changes linearly along lines from the center,
+            // so that is a good place to put the center of the triangle fan:
+            let fan_center = match brush {
+                Brush::RadialGradient { center, .. } => {
+                    Some(rect.min + center.to_vec2() * rect.size())
+                }
+                _ => None,
+            }
+            .filter(|&center| convex_path_contains(&self.0, center));
+
+            let vertex_start = out.vertices.len();
+            let index_start = out.indices.len();
+            fill_closed_path_around(feathering, &mut self.0, Color32::WHITE, fan_center, out);
+            paint_gradient(&brush, rect, vertex_start, index_start, out);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Colors the vertices added to `out` since `vertex_start` (which must be white, with coverage as alpha)
+/// with a gradient, first splitting the triangles (added since `index_start`)
+/// so that the colors are interpolated correctly.
+fn paint_gradient(
+    brush: &Brush,
+    rect: Rect,
+    vertex_start: usize,
+    index_start: usize,
+    out: &mut Mesh,
+) {
+    let triangles: Vec<[u32; 3]> = out.indices[index_start..]
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect();
+    out.indices.truncate(index_start);
+
+    let mut splitter = TriangleSplitter {
+        brush,
+        rect,
+        vertex_start,
+        ts: out.vertices[vertex_start..]
+            .iter()
+            .map(|v| brush.gradient_t(v.pos, rect))
+            .collect(),
+        mesh: out,
+    };
+
+    let triangles = match brush {
+        Brush::Solid(_) => triangles,
+        Brush::LinearGradient { stops, .. } | Brush::RadialGradient { stops, .. } => {
+            let mut triangles = triangles;
+            if matches!(brush, Brush::RadialGradient { .. }) {
+                // A radial gradient is only linear along lines from its center:
+                triangles = splitter.subdivide(triangles);
+            }
+            // The gradient is linear between the stops, and so is the interpolation of vertex colors:
+            for &(offset, _) in stops.as_slice() {
+                triangles = splitter.split_at(triangles, offset);
+            }
+            triangles
+        }
+    };
+
+    for [a, b, c] in triangles {
+        out.add_triangle(a, b, c);
+    }
+
+    for vertex in &mut out.vertices[vertex_start..] {
+        let coverage = vertex.color.a();
+        let color = brush.color_at(vertex.pos, rect);
+        vertex.color = if coverage == 255 {
+            color
+        } else {
+            color.linear_multiply(coverage as f32 / 255.0)
+        };
+    }
+}
+
+/// Splits triangles for [`paint_gradient`].
+struct TriangleSplitter<'a> {
+    brush: &'a Brush,
+    rect: Rect,
+    vertex_start: usize,
+
+    /// [`Brush::gradient_t`] of each vertex since `vertex_start`.
+    ts: Vec<f32>,
+
+    mesh: &'a mut Mesh,
+}
+
+impl<'a> TriangleSplitter<'a> {
+    fn t(&self, vertex: u32) -> f32 {
+        self.ts[vertex as usize - self.vertex_start]
+    }
+
+    /// Add a vertex between `a` and `b`.
+    fn add_vertex_between(&mut self, a: u32, b: u32, f: f32) -> u32 {
+        let va = self.mesh.vertices[a as usize];
+        let vb = self.mesh.vertices[b as usize];
+        let pos = va.pos + f * (vb.pos - va.pos);
+        let coverage = lerp(va.color.a() as f32..=vb.color.a() as f32, f);
+        let index = self.mesh.vertices.len() as u32;
+        self.mesh.vertices.push(Vertex {
+            pos,
+            uv: va.uv,
+            color: Color32::from_white_alpha(coverage.round() as u8),
+        });
+        self.ts.push(self.brush.gradient_t(pos, self.rect));
+        index
+    }
+
+    /// Split the triangles that cross the line where `t == cut`.
+    fn split_at(&mut self, triangles: Vec<[u32; 3]>, cut: f32) -> Vec<[u32; 3]> {
+        /// Vertices this close to the cut are on it, so we don't add slivers next to them.
+        const EPSILON: f32 = 1e-5;
+
+        let mut edge_vertices = ahash::AHashMap::<(u32, u32), u32>::default();
+        let mut result = Vec::with_capacity(triangles.len());
+
+        for triangle in triangles {
+            let side = triangle.map(|v| {
+                let t = self.t(v);
+                if t > cut + EPSILON {
+                    1
+                } else if t < cut - EPSILON {
+                    -1
+                } else {
+                    0
+                }
+            });
+            if !side.contains(&1) || !side.contains(&-1) {
+                result.push(triangle);
+                continue;
+            }
+
+            if let Some(on_cut) = (0..3).find(|&i| side[i] == 0) {
+                // The cut goes through a corner and the opposite edge:
+                let p = triangle[on_cut];
+                let q = triangle[(on_cut + 1) % 3];
+                let r = triangle[(on_cut + 2) % 3];
+                let x = self.vertex_at_cut(&mut edge_vertices, q, r, cut);
+                result.push([p, q, x]);
+                result.push([p, x, r]);
+                continue;
+            }
+
+            // The vertex that is alone on its side of the cut:
+            let num_above = side.iter().filter(|&&side| side == 1).count();
+            let lone = (0..3)
+                .find(|&i| (side[i] == 1) == (num_above == 1))
+                .unwrap();
+            let p = triangle[lone];
+            let q = triangle[(lone + 1) % 3];
+            let r = triangle[(lone + 2) % 3];
+
+            let x = self.vertex_at_cut(&mut edge_vertices, p, q, cut);
+            let y = self.vertex_at_cut(&mut edge_vertices, p, r, cut);
+
+            result.push([p, x, y]);
+            result.push([x, q, r]);
+            result.push([x, r, y]);
+        }
+
+        result
+    }
+
+    /// The vertex where `t == cut` on the edge from `a` to `b`, shared by the triangles on both sides of the edge.
+    fn vertex_at_cut(
+        &mut self,
+        edge_vertices: &mut ahash::AHashMap<(u32, u32), u32>,
+        a: u32,
+        b: u32,
+        cut: f32,
+    ) -> u32 {
+        let key = (a.min(b), a.max(b));
+        if let Some(&vertex) = edge_vertices.get(&key) {
+            return vertex;
+        }
+        let (ta, tb) = (self.t(a), self.t(b));
+        let f = if matches!(self.brush, Brush::RadialGradient { .. }) {
+            // `t` is not linear along the edge, but it is convex, so it only crosses the cut once:
+            let (pa, pb) = (
+                self.mesh.vertices[a as usize].pos,
+                self.mesh.vertices[b as usize].pos,
+            );
+            let (mut lo, mut hi) = (0.0, 1.0);
+            for _ in 0..16 {
+                let mid = 0.5 * (lo + hi);
+                let t = self.brush.gradient_t(pa + mid * (pb - pa), self.rect);
+                if (t < cut) == (ta < cut) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            0.5 * (lo + hi)
+        } else {
+            ((cut - ta) / (tb - ta)).clamp(0.0, 1.0)
+        };
+        let vertex = self.add_vertex_between(a, b, f);
+        edge_vertices.insert(key, vertex);
+        vertex
+    }
+
+    /// Split edges until the gradient is close to linear along each of them.
+    ///
+    /// Whether an edge is split only depends on the edge,
+    /// so neighboring triangles agree and there are no cracks between them.
+    fn subdivide(&mut self, triangles: Vec<[u32; 3]>) -> Vec<[u32; 3]> {
+        /// How far from linear `t` may be in the middle of an edge.
+        const MAX_ERROR: f32 = 1.0 / 64.0;
+
+        /// Don't split edges shorter than this (in points).
+        const MIN_LENGTH: f32 = 2.0;
+
+        let mut midpoints = ahash::AHashMap::<(u32, u32), Option<u32>>::default();
+        let mut result = Vec::with_capacity(triangles.len());
+        let mut stack = triangles;
+
+        while let Some(triangle) = stack.pop() {
+            let mut longest_split: Option<(usize, u32, f32)> = None;
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                if let Some(midpoint) =
+                    self.midpoint_if_needed(&mut midpoints, a, b, MAX_ERROR, MIN_LENGTH)
+                {
+                    let length_sq = self.mesh.vertices[a as usize]
+                        .pos
+                        .distance_sq(self.mesh.vertices[b as usize].pos);
+                    if longest_split.map_or(true, |(_, _, longest)| longest < length_sq) {
+                        longest_split = Some((i, midpoint, length_sq));
+                    }
+                }
+            }
+
+            if let Some((i, midpoint, _)) = longest_split {
+                let (a, b, c) = (triangle[i], triangle[(i + 1) % 3], triangle[(i + 2) % 3]);
+                stack.push([a, midpoint, c]);
+                stack.push([midpoint, b, c]);
+            } else {
+                result.push(triangle);
+            }
+        }
+
+        result
+    }
+
+    /// The vertex in the middle of the edge, if the gradient isn't close enough to linear along it.
+    fn midpoint_if_needed(
+        &mut self,
+        midpoints: &mut ahash::AHashMap<(u32, u32), Option<u32>>,
+        a: u32,
+        b: u32,
+        max_error: f32,
+        min_length: f32,
+    ) -> Option<u32> {
+        let key = (a.min(b), a.max(b));
+        if let Some(&midpoint) = midpoints.get(&key) {
+            return midpoint;
+        }
+
+        let (pa, pb) = (
+            self.mesh.vertices[a as usize].pos,
+            self.mesh.vertices[b as usize].pos,
+        );
+        let (ta, tb) = (self.t(a), self.t(b));
+        let t_mid = self.brush.gradient_t(pa + 0.5 * (pb - pa), self.rect);
+        let needs_split = pa.distance_sq(pb) > min_length * min_length
+            && (t_mid - 0.5 * (ta + tb)).abs() > max_error;
+
+        let midpoint = needs_split.then(|| self.add_vertex_between(a, b, 0.5));
+        midpoints.insert(key, midpoint);
+        midpoint
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Fill a polygon made of one or more closed outlines, which can be concave and intersect themselves,
+/// according to the fill rule of `fill_mode`.
+///
+/// The gradient of the `brush` is relative to the bounding rectangle of all the outlines.
+fn fill_polygon_with_brush(
+    feathering: f32,
+    outlines: &[&[Pos2]],
+    fill_mode: FillMode,
+    brush: Brush,
+    out: &mut Mesh,
+) {
+    if let Brush::Solid(color) = brush {
+        fill_polygon(feathering, outlines, fill_mode, color, out);
+    } else if !brush.is_transparent() {
+        let mut rect = Rect::NOTHING;
+        for &pos in outlines.iter().copied().flatten() {
+            rect.extend_with(pos);
+        }
+        let vertex_start = out.vertices.len();
+        let index_start = out.indices.len();
+        fill_polygon(feathering, outlines, fill_mode, Color32::WHITE, out);
+        paint_gradient(&brush, rect, vertex_start, index_start, out);
+    }
+}
+
+/// Fill a polygon made of one or more closed outlines.
+///
+/// The polygon is cut into horizontal slabs at every vertex and wherever edges cross,
+/// so that the edges in each slab are ordered left to right. The parts of a slab that are
+/// inside are trapezoids between two edges, which we join with the trapezoids between
+/// the same edges in the slabs below.
+///
+/// With feathering we do like [`fill_closed_path`]: the edges between inside and outside
+/// are moved in by half the feathering and filled from there, and faded out to half the
+/// feathering outside, with mitered corners.
+fn fill_polygon(
+    feathering: f32,
+    outlines: &[&[Pos2]],
+    fill_mode: FillMode,
+    color: Color32,
+    out: &mut Mesh,
+) {
+    if color == Color32::TRANSPARENT {
+        return;
+    }
+
+    if feathering <= 0.0 {
+        PolygonFiller::fill(outlines, fill_mode).add_trapezoids(color, out);
+        return;
+    }
+
+    let outlines: Vec<Vec<Pos2>> = outlines
+        .iter()
+        .map(|outline| {
+            let mut outline = outline.to_vec();
+            outline.dedup();
+            while outline.len() > 1 && outline.first() == outline.last() {
+                outline.pop();
+            }
+            outline
+        })
+        .collect();
+    let outline_slices: Vec<&[Pos2]> = outlines.iter().map(Vec::as_slice).collect();
+
+    // How much of each edge has the inside to its right (positive) or left (negative):
+    let filler = PolygonFiller::fill(&outline_slices, fill_mode);
+    let mut inside_to_the_right = vec![0.0; outlines.iter().map(Vec::len).sum()];
+    for trapezoid in &filler.trapezoids {
+        let height = trapezoid.bottom - trapezoid.top;
+        inside_to_the_right[filler.edges[trapezoid.left].outline_edge] += height;
+        inside_to_the_right[filler.edges[trapezoid.right].outline_edge] -= height;
+    }
+
+    let color_outer = Color32::TRANSPARENT;
+    let mut inner_outlines = Vec::with_capacity(outlines.len());
+    let mut first_edge = 0;
+    for outline in &outlines {
+        let n = outline.len();
+        let normals = inside_normals(outline, &inside_to_the_right[first_edge..first_edge + n]);
+        first_edge += n;
+
+        let idx_inner = out.vertices.len() as u32;
+        let idx_outer = idx_inner + 1;
+        let mut inner_outline = Vec::with_capacity(n);
+        for i in 0..n {
+            let dm = 0.5 * feathering * miter_normal(normals[(i + n - 1) % n], normals[i]);
+            out.colored_vertex(outline[i] + dm, color);
+            out.colored_vertex(outline[i] - dm, color_outer);
+            inner_outline.push(outline[i] + dm);
+        }
+        for (i0, normal) in normals.iter().enumerate() {
+            if normal.is_some() {
+                let (i0, i1) = (i0 as u32, ((i0 + 1) % n) as u32);
+                out.add_triangle(idx_inner + 2 * i0, idx_inner + 2 * i1, idx_outer + 2 * i1);
+                out.add_triangle(idx_inner + 2 * i0, idx_outer + 2 * i1, idx_outer + 2 * i0);
+            }
+        }
+        inner_outlines.push(inner_outline);
+    }
+
+    let inner_slices: Vec<&[Pos2]> = inner_outlines.iter().map(Vec::as_slice).collect();
+    PolygonFiller::fill(&inner_slices, fill_mode).add_trapezoids(color, out);
+}
+
+/// The unit normal towards the inside of each edge (from point `i` to `i + 1`) of the `outline`
+/// that is between inside and outside, given how much of each non-horizontal edge
+/// has the inside to its right (positive) or left (negative).
+///
+/// Horizontal edges aren't in any trapezoid, so they have the inside on the same side
+/// as the closest non-horizontal edge before them, or else after them.
+fn inside_normals(outline: &[Pos2], inside_to_the_right: &[f32]) -> Vec<Option<Vec2>> {
+    let n = outline.len();
+    let direction = |i: usize| (outline[(i + 1) % n] - outline[i]).normalized();
+
+    // `1` if the inside is towards `rot90` of the direction, `-1` if it's the other way,
+    // `0` if it's on neither side, and `None` if we don't know because the edge is horizontal:
+    let sides: Vec<Option<f32>> = (0..n)
+        .map(|i| {
+            let inside = inside_to_the_right[i];
+            if outline[i].y == outline[(i + 1) % n].y {
+                None
+            } else if inside == 0.0 {
+                Some(0.0)
+            } else {
+                Some((direction(i).rot90().x * inside).signum())
+            }
+        })
+        .collect();
+
+    let (first, last) = match (
+        sides.iter().find_map(|side| *side),
+        sides.iter().rev().find_map(|side| *side),
+    ) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return vec![None; n],
+    };
+
+    let mut side_before = last;
+    let sides_before: Vec<f32> = sides
+        .iter()
+        .map(|side| {
+            side_before = side.unwrap_or(side_before);
+            side_before
+        })
+        .collect();
+
+    let mut normals = vec![None; n];
+    let mut side_after = first;
+    for i in (0..n).rev() {
+        let side = match sides[i] {
+            Some(side) => {
+                side_after = side;
+                side
+            }
+            None if sides_before[i] != 0.0 => sides_before[i],
+            None => side_after,
+        };
+        if side != 0.0 {
+            normals[i] = Some(side * direction(i).rot90());
+        }
+    }
+    normals
+}
+
+/// How to move a corner between two edges with these unit normals so that both edges
+/// move one unit along their normals, like the miter joins of [`Path::add_line_loop`].
+fn miter_normal(n0: Option<Vec2>, n1: Option<Vec2>) -> Vec2 {
+    match (n0, n1) {
+        (Some(n0), Some(n1)) => {
+            let normal = (n0 + n1) / 2.0;
+            // Don't let the corner shoot off where the outline almost turns back on itself:
+            normal / normal.length_sq().max(0.1)
+        }
+        (Some(normal), None) | (None, Some(normal)) => normal,
+        (None, None) => Vec2::ZERO,
+    }
 }
 
+/// A non-horizontal edge of a polygon.
+#[derive(Clone, Copy)]
+struct PolygonEdge {
+    top: Pos2,
+    bottom: Pos2,
+
+    /// `1` if the outline goes down along this edge, `-1` if it goes up.
+    winding: i32,
+
+    /// Which edge this is of all the outlines, counting the edges of each outline
+    /// from its first point.
+    outline_edge: usize,
+}
+
+impl PolygonEdge {
+    fn new(top: Pos2, bottom: Pos2, winding: i32, outline_edge: usize) -> Self {
+        Self {
+            top,
+            bottom,
+            winding,
+            outline_edge,
+        }
+    }
+
+    fn x_at(&self, y: f32) -> f32 {
+        let t = (y - self.top.y) / (self.bottom.y - self.top.y);
+        lerp(self.top.x..=self.bottom.x, t)
+    }
+
+    fn pos_at(&self, y: f32) -> Pos2 {
+        pos2(self.x_at(y), y)
+    }
+}
+
+/// The inside of a polygon between two of its edges, from `top` to `bottom`.
+struct Trapezoid {
+    left: usize,
+    right: usize,
+    top: f32,
+    bottom: f32,
+}
+
+/// Cuts a polygon into [`Trapezoid`]s, see [`fill_polygon`].
+struct PolygonFiller {
+    edges: Vec<PolygonEdge>,
+    fill_mode: FillMode,
+
+    /// The inside parts of the last slab, left to right, as `(left edge, right edge, where it started)`.
+    spans: Vec<(usize, usize, f32)>,
+
+    trapezoids: Vec<Trapezoid>,
+}
+
+impl PolygonFiller {
+    /// Edges closer than this (in points) don't count as crossing.
+    const EPSILON: f32 = 1e-3;
+
+    fn fill(outlines: &[&[Pos2]], fill_mode: FillMode) -> Self {
+        let mut edges = vec![];
+        let mut outline_edge = 0;
+        for outline in outlines {
+            for (i, &a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
+                if a.y < b.y {
+                    edges.push(PolygonEdge::new(a, b, 1, outline_edge));
+                } else if b.y < a.y {
+                    edges.push(PolygonEdge::new(b, a, -1, outline_edge));
+                }
+                outline_edge += 1;
+            }
+        }
+        edges.retain(|edge| edge.top.is_finite() && edge.bottom.is_finite());
+        edges.sort_by(|a, b| a.top.y.partial_cmp(&b.top.y).unwrap_or(Ordering::Equal));
+
+        let mut ys: Vec<f32> = edges
+            .iter()
+            .flat_map(|edge| [edge.top.y, edge.bottom.y])
+            .collect();
+        ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        ys.dedup();
+
+        let mut filler = Self {
+            edges,
+            fill_mode,
+            spans: vec![],
+            trapezoids: vec![],
+        };
+
+        let mut active: Vec<usize> = vec![];
+        let mut next_edge = 0;
+        for slab in ys.windows(2) {
+            let (top, bottom) = (slab[0], slab[1]);
+
+            active.retain(|&edge| filler.edges[edge].bottom.y > top);
+            while next_edge < filler.edges.len() && filler.edges[next_edge].top.y <= top {
+                active.push(next_edge);
+                next_edge += 1;
+            }
+
+            let mut y = top;
+            while y < bottom {
+                let next_y = filler.sort_until_crossing(&mut active, y, bottom);
+                filler.add_slab(&active, y);
+                y = next_y;
+            }
+        }
+
+        if let Some(&last_y) = ys.last() {
+            filler.add_slab(&[], last_y);
+        }
+        filler
+    }
+
+    /// Sort the `active` edges left to right, and return how far down from `top`
+    /// (at most to `bottom`) they stay in that order.
+    fn sort_until_crossing(&self, active: &mut [usize], top: f32, mut bottom: f32) -> f32 {
+        let edges = &self.edges;
+        loop {
+            let middle = 0.5 * (top + bottom);
+            active.sort_by(|&a, &b| {
+                let (a, b) = (edges[a].x_at(middle), edges[b].x_at(middle));
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            });
+
+            // If edges aren't in the same order at the top or bottom, two neighbors must have crossed:
+            let mut crossing = bottom;
+            for pair in active.windows(2) {
+                let (left, right) = (&edges[pair[0]], &edges[pair[1]]);
+                let distance_at = |y: f32| right.x_at(y) - left.x_at(y);
+                let (d_top, d_middle, d_bottom) =
+                    (distance_at(top), distance_at(middle), distance_at(bottom));
+                let y = if d_top < -Self::EPSILON {
+                    lerp(top..=middle, d_top / (d_top - d_middle))
+                } else if d_bottom < -Self::EPSILON {
+                    lerp(middle..=bottom, d_middle / (d_middle - d_bottom))
+                } else {
+                    continue;
+                };
+                if top + Self::EPSILON < y && y < crossing {
+                    crossing = y;
+                }
+            }
+
+            if crossing < bottom {
+                bottom = crossing;
+            } else {
+                return bottom;
+            }
+        }
+    }
+
+    /// Start the slab at `top`, where the `active` edges are sorted left to right,
+    /// and finish the trapezoids that ended there.
+    fn add_slab(&mut self, active: &[usize], top: f32) {
+        let mut spans = vec![];
+        let mut winding = 0;
+        let mut left_edge = 0;
+        for &edge in active {
+            let was_inside = self.fill_mode.is_inside(winding);
+            winding += self.edges[edge].winding;
+            let is_inside = self.fill_mode.is_inside(winding);
+
+            if !was_inside && is_inside {
+                left_edge = edge;
+            } else if was_inside && !is_inside {
+                spans.push((left_edge, edge));
+            }
+        }
+
+        // Continue the spans that are the same as in the slab above, and finish the others.
+        // Both are sorted left to right, so we go through them side by side:
+        let mut previous = std::mem::take(&mut self.spans).into_iter().peekable();
+        for (left, right) in spans {
+            let mut start = top;
+            while let Some(&(previous_left, previous_right, previous_start)) = previous.peek() {
+                if (previous_left, previous_right) == (left, right) {
+                    start = previous_start;
+                    previous.next();
+                    break;
+                } else if self.edges[left].x_at(top) < self.edges[previous_left].x_at(top) {
+                    break;
+                }
+                self.finish_span(previous_left, previous_right, previous_start, top);
+                previous.next();
+            }
+            self.spans.push((left, right, start));
+        }
+        for (left, right, start) in previous {
+            self.finish_span(left, right, start, top);
+        }
+    }
+
+    fn finish_span(&mut self, left: usize, right: usize, top: f32, bottom: f32) {
+        self.trapezoids.push(Trapezoid {
+            left,
+            right,
+            top,
+            bottom,
+        });
+    }
+
+    fn add_trapezoids(&self, color: Color32, out: &mut Mesh) {
+        out.reserve_triangles(2 * self.trapezoids.len());
+        out.reserve_vertices(4 * self.trapezoids.len());
+        for trapezoid in &self.trapezoids {
+            let (left, right) = (self.edges[trapezoid.left], self.edges[trapezoid.right]);
+            let idx = out.vertices.len() as u32;
+            out.colored_vertex(left.pos_at(trapezoid.top), color);
+            out.colored_vertex(right.pos_at(trapezoid.top), color);
+            out.colored_vertex(right.pos_at(trapezoid.bottom), color);
+            out.colored_vertex(left.pos_at(trapezoid.bottom), color);
+            out.add_triangle(idx, idx + 1, idx + 2);
+            out.add_triangle(idx, idx + 2, idx + 3);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 pub mod path {
     //! Helpers for constructing paths
     use crate::shape::Rounding;
@@ -575,6 +1260,41 @@ pub mod path {
         }
     }
 
+    /// Add points along an arc of an ellipse, including both ends.
+    ///
+    /// The `angles` are parametric: the point at `angle` is `center + radius * Vec2::angled(angle)`.
+    /// Unless the radii are equal, this is not the point in the direction of `angle` from the `center`.
+    ///
+    /// The points are close enough together that the arc is
+    /// never further than `tolerance` from the lines between them.
+    pub fn add_ellipse_arc(
+        path: &mut Vec<Pos2>,
+        center: Pos2,
+        radius: Vec2,
+        angles: std::ops::RangeInclusive<f32>,
+        tolerance: f32,
+    ) {
+        use std::f32::consts::TAU;
+
+        let (start, end) = (*angles.start(), *angles.end());
+
+        // How far apart the points can be (in angle) along a circle of the larger radius,
+        // which is never less curved than the ellipse:
+        let max_radius = radius.max_elem();
+        let max_step = if 0.0 < tolerance && tolerance < max_radius {
+            (2.0 * (1.0 - tolerance / max_radius).acos()).min(TAU / 4.0)
+        } else {
+            TAU / 4.0
+        };
+        let n = ((end - start).abs() / max_step).ceil().clamp(1.0, 1024.0) as usize;
+
+        path.reserve(n + 1);
+        path.extend((0..=n).map(|i| {
+            let angle = lerp(start..=end, i as f32 / n as f32);
+            center + radius * Vec2::angled(angle)
+        }));
+    }
+
     // Ensures the radius of each corner is within a valid range
     fn clamp_radius(rounding: Rounding, rect: Rect) -> Rounding {
         let half_width = rect.width() * 0.5;
@@ -639,6 +1359,9 @@ pub struct TessellationOptions {
     /// The maximum distance between the original curve and the flattened curve.
     pub bezier_tolerance: f32,
 
+    /// The maximum distance between an ellipse or arc and the polygon it is painted as.
+    pub ellipse_tolerance: f32,
+
     /// The default value will be 1.0e-5, it will be used during float compare.
     pub epsilon: f32,
 }
@@ -655,6 +1378,7 @@ impl Default for TessellationOptions {
             debug_paint_clip_rects: false,
             debug_ignore_clip_rects: false,
             bezier_tolerance: 0.1,
+            ellipse_tolerance: 0.1,
             epsilon: 1.0e-5,
         }
     }
@@ -680,6 +1404,18 @@ fn cw_signed_area(path: &[PathPoint]) -> f64 {
 ///
 /// The preferred winding order is clockwise.
 fn fill_closed_path(feathering: f32, path: &mut [PathPoint], color: Color32, out: &mut Mesh) {
+    fill_closed_path_around(feathering, path, color, None, out);
+}
+
+/// Like [`fill_closed_path`], but the fill is a triangle fan around `fan_center`, if given,
+/// which must be inside the path.
+fn fill_closed_path_around(
+    feathering: f32,
+    path: &mut [PathPoint],
+    color: Color32,
+    fan_center: Option<Pos2>,
+    out: &mut Mesh,
+) {
     if color == Color32::TRANSPARENT {
         return;
     }
@@ -701,8 +1437,17 @@ fn fill_closed_path(feathering: f32, path: &mut [PathPoint], color: Color32, out
         let idx_outer = idx_inner + 1;
 
         // The fill:
-        for i in 2..n {
-            out.add_triangle(idx_inner + 2 * (i - 1), idx_inner, idx_inner + 2 * i);
+        if fan_center.is_some() {
+            let idx_center = idx_inner + 2 * n;
+            let mut i0 = n - 1;
+            for i1 in 0..n {
+                out.add_triangle(idx_center, idx_inner + 2 * i0, idx_inner + 2 * i1);
+                i0 = i1;
+            }
+        } else {
+            for i in 2..n {
+                out.add_triangle(idx_inner + 2 * (i - 1), idx_inner, idx_inner + 2 * i);
+            }
         }
 
         // The feathering:
@@ -716,6 +1461,10 @@ fn fill_closed_path(feathering: f32, path: &mut [PathPoint], color: Color32, out
             out.add_triangle(idx_outer + i0 * 2, idx_outer + i1 * 2, idx_inner + 2 * i1);
             i0 = i1;
         }
+
+        if let Some(center) = fan_center {
+            out.colored_vertex(center, color);
+        }
     } else {
         out.reserve_triangles(n as usize);
         let idx = out.vertices.len() as u32;
@@ -724,10 +1473,39 @@ fn fill_closed_path(feathering: f32, path: &mut [PathPoint], color: Color32, out
             uv: WHITE_UV,
             color,
         }));
-        for i in 2..n {
-            out.add_triangle(idx, idx + i - 1, idx + i);
+        if let Some(center) = fan_center {
+            out.colored_vertex(center, color);
+            let mut i0 = n - 1;
+            for i1 in 0..n {
+                out.add_triangle(idx + n, idx + i0, idx + i1);
+                i0 = i1;
+            }
+        } else {
+            for i in 2..n {
+                out.add_triangle(idx, idx + i - 1, idx + i);
+            }
+        }
+    }
+}
+
+/// Is `pos` inside the convex path (or on its edge)?
+fn convex_path_contains(path: &[PathPoint], pos: Pos2) -> bool {
+    let mut sign = 0.0;
+    let mut p0 = match path.last() {
+        Some(last) => last.pos,
+        None => return false,
+    };
+    for point in path {
+        let p1 = point.pos;
+        let cross = (p1 - p0).x * (pos - p0).y - (p1 - p0).y * (pos - p0).x;
+        if cross * sign < 0.0 {
+            return false;
+        } else if cross != 0.0 {
+            sign = cross;
         }
+        p0 = p1;
     }
+    true
 }
 
 /// Tessellate the given path as a stroke with thickness.
@@ -737,6 +1515,35 @@ fn stroke_path(
     path_type: PathType,
     stroke: Stroke,
     out: &mut Mesh,
+) {
+    // The normals of the path already make miter joins, so we can use them as they are,
+    // unless a corner was cut off (which makes two points there) or reaches past the miter limit:
+    let plain_miters = stroke.join == LineJoin::Miter
+        && path.windows(2).all(|pair| pair[0].pos != pair[1].pos)
+        && path
+            .iter()
+            .all(|point| point.normal.length_sq() <= stroke.miter_limit * stroke.miter_limit);
+    if plain_miters && stroke.cap == LineCap::Butt && stroke.dash.is_solid() {
+        stroke_strip(feathering, path, None, path_type, stroke, true, out);
+    } else {
+        stroke_styled_path(feathering, path, path_type, stroke, out);
+    }
+}
+
+/// Give the path thickness along its normals.
+///
+/// With `back_normals`, the other side of the path goes along those instead
+/// (for the inside of round and bevel joins).
+///
+/// With `extrude_ends`, the ends of an open path are anti-aliased by extending them a bit.
+fn stroke_strip(
+    feathering: f32,
+    path: &[PathPoint],
+    back_normals: Option<&[Vec2]>,
+    path_type: PathType,
+    stroke: Stroke,
+    extrude_ends: bool,
+    out: &mut Mesh,
 ) {
     let n = path.len() as u32;
 
@@ -744,6 +1551,8 @@ fn stroke_path(
         return;
     }
 
+    let back_normal =
+        |i: u32| back_normals.map_or(path[i as usize].normal, |normals| normals[i as usize]);
     let idx = out.vertices.len() as u32;
 
     if feathering > 0.0 {
@@ -774,9 +1583,10 @@ fn stroke_path(
                 let p1 = &path[i1 as usize];
                 let p = p1.pos;
                 let n = p1.normal;
+                let back = back_normal(i1);
                 out.colored_vertex(p + n * feathering, color_outer);
                 out.colored_vertex(p, color_inner);
-                out.colored_vertex(p - n * feathering, color_outer);
+                out.colored_vertex(p - back * feathering, color_outer);
 
                 if connect_with_previous {
                     out.add_triangle(idx + 3 * i0 + 0, idx + 3 * i0 + 1, idx + 3 * i1 + 0);
@@ -813,10 +1623,11 @@ fn stroke_path(
                         let p1 = &path[i1 as usize];
                         let p = p1.pos;
                         let n = p1.normal;
+                        let back = back_normal(i1);
                         out.colored_vertex(p + n * outer_rad, color_outer);
                         out.colored_vertex(p + n * inner_rad, color_inner);
-                        out.colored_vertex(p - n * inner_rad, color_inner);
-                        out.colored_vertex(p - n * outer_rad, color_outer);
+                        out.colored_vertex(p - back * inner_rad, color_inner);
+                        out.colored_vertex(p - back * outer_rad, color_outer);
 
                         out.add_triangle(idx + 4 * i0 + 0, idx + 4 * i0 + 1, idx + 4 * i1 + 0);
                         out.add_triangle(idx + 4 * i0 + 1, idx + 4 * i1 + 0, idx + 4 * i1 + 1);
@@ -843,7 +1654,9 @@ fn stroke_path(
                     //   |    |  que  |    |
                     //   |    |       |    |
 
-                    // (in the future it would be great with an option to add a circular end instead)
+                    // (round caps are added separately, so then we don't extrude)
+
+                    let end_extrusion = if extrude_ends { feathering } else { 0.0 };
 
                     out.reserve_triangles(6 * n as usize + 4);
                     out.reserve_vertices(4 * n as usize);
@@ -852,7 +1665,7 @@ fn stroke_path(
                         let end = &path[0];
                         let p = end.pos;
                         let n = end.normal;
-                        let back_extrude = n.rot90() * feathering;
+                        let back_extrude = n.rot90() * end_extrusion;
                         out.colored_vertex(p + n * outer_rad + back_extrude, color_outer);
                         out.colored_vertex(p + n * inner_rad, color_inner);
                         out.colored_vertex(p - n * inner_rad, color_inner);
@@ -867,10 +1680,11 @@ fn stroke_path(
                         let point = &path[i1 as usize];
                         let p = point.pos;
                         let n = point.normal;
+                        let back = back_normal(i1);
                         out.colored_vertex(p + n * outer_rad, color_outer);
                         out.colored_vertex(p + n * inner_rad, color_inner);
-                        out.colored_vertex(p - n * inner_rad, color_inner);
-                        out.colored_vertex(p - n * outer_rad, color_outer);
+                        out.colored_vertex(p - back * inner_rad, color_inner);
+                        out.colored_vertex(p - back * outer_rad, color_outer);
 
                         out.add_triangle(idx + 4 * i0 + 0, idx + 4 * i0 + 1, idx + 4 * i1 + 0);
                         out.add_triangle(idx + 4 * i0 + 1, idx + 4 * i1 + 0, idx + 4 * i1 + 1);
@@ -889,7 +1703,7 @@ fn stroke_path(
                         let end = &path[i1 as usize];
                         let p = end.pos;
                         let n = end.normal;
-                        let back_extrude = -n.rot90() * feathering;
+                        let back_extrude = -n.rot90() * end_extrusion;
                         out.colored_vertex(p + n * outer_rad + back_extrude, color_outer);
                         out.colored_vertex(p + n * inner_rad, color_inner);
                         out.colored_vertex(p - n * inner_rad, color_inner);
@@ -942,44 +1756,333 @@ fn stroke_path(
             if color == Color32::TRANSPARENT {
                 return;
             }
-            for p in path {
+            for (i, p) in path.iter().enumerate() {
                 out.colored_vertex(p.pos + radius * p.normal, color);
-                out.colored_vertex(p.pos - radius * p.normal, color);
+                out.colored_vertex(p.pos - radius * back_normal(i as u32), color);
             }
         } else {
             let radius = stroke.width / 2.0;
-            for p in path {
+            for (i, p) in path.iter().enumerate() {
                 out.colored_vertex(p.pos + radius * p.normal, stroke.color);
-                out.colored_vertex(p.pos - radius * p.normal, stroke.color);
+                out.colored_vertex(p.pos - radius * back_normal(i as u32), stroke.color);
             }
         }
     }
 }
 
-fn mul_color(color: Color32, factor: f32) -> Color32 {
-    crate::epaint_assert!(0.0 <= factor && factor <= 1.0);
-    // As an unfortunate side-effect of using premultiplied alpha
-    // we need a somewhat expensive conversion to linear space and back.
-    color.linear_multiply(factor)
-}
-
-// ----------------------------------------------------------------------------
-
-/// Converts [`Shape`]s into triangles ([`Mesh`]).
-///
-/// For performance reasons it is smart to reuse the same [`Tessellator`].
+/// Stroke with the caps, joins and dash pattern of the [`Stroke`].
 ///
-/// Se also [`tessellate_shapes`], a convenient wrapper around [`Tessellator`].
-pub struct Tessellator {
-    pixels_per_point: f32,
-    options: TessellationOptions,
-    font_tex_size: [usize; 2],
+/// We recreate the normals of the path with the joins we want, and add the caps ourselves.
+fn stroke_styled_path(
+    feathering: f32,
+    path: &[PathPoint],
+    path_type: PathType,
+    stroke: Stroke,
+    out: &mut Mesh,
+) {
+    if stroke.is_empty() {
+        return;
+    }
+
+    let mut points: Vec<Pos2> = path.iter().map(|point| point.pos).collect();
+    points.dedup();
+    if path_type == PathType::Closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 2 {
+        return;
+    }
+
+    let radius = 0.5 * stroke.width;
+    let thin_line = stroke.width <= feathering;
+    let cap = if thin_line && stroke.cap == LineCap::Round {
+        LineCap::Square // too small to see the difference
+    } else {
+        stroke.cap
+    };
+
+    let pieces = if stroke.dash.is_solid() {
+        vec![(points, path_type)]
+    } else {
+        dash_polyline(&points, path_type, &stroke.dash)
+    };
+
+    let mut styled = vec![];
+    let mut back_normals = vec![];
+    for (mut points, path_type) in pieces {
+        let n = points.len();
+        let start_dir = (points[1] - points[0]).normalized();
+        let end_dir = (points[n - 1] - points[n - 2]).normalized();
+        let is_open = path_type == PathType::Open;
+
+        if is_open && cap == LineCap::Square {
+            points[0] -= radius * start_dir;
+            points[n - 1] += radius * end_dir;
+        }
+
+        styled.clear();
+        back_normals.clear();
+        add_joined_points(&points, path_type, &stroke, &mut styled, &mut back_normals);
+        stroke_strip(
+            feathering,
+            &styled,
+            Some(&back_normals),
+            path_type,
+            stroke,
+            cap != LineCap::Round,
+            out,
+        );
+
+        if is_open && cap == LineCap::Round {
+            add_round_cap(feathering, points[0], -start_dir, stroke, out);
+            add_round_cap(feathering, points[n - 1], end_dir, stroke, out);
+        }
+    }
+}
+
+/// Corners that are rounded off (or cut off) by less than this (in points) are left as miters.
+const JOIN_TOLERANCE: f32 = 0.1;
+
+/// Add the `points` with normals that make the joins of the `stroke`,
+/// and the normals of the other side of the stroke to `back_normals`.
+///
+/// Round and bevel joins add several points at a corner, which all have the same inner vertex
+/// (where the inner sides of the stroke meet), so that the join doesn't paint over itself there.
+fn add_joined_points(
+    points: &[Pos2],
+    path_type: PathType,
+    stroke: &Stroke,
+    out: &mut Vec<PathPoint>,
+    back_normals: &mut Vec<Vec2>,
+) {
+    let n = points.len();
+    let radius = 0.5 * stroke.width;
+    for (i, &pos) in points.iter().enumerate() {
+        let open = path_type == PathType::Open;
+        if open && (i == 0 || i == n - 1) {
+            let dir = if i == 0 {
+                points[1] - pos
+            } else {
+                pos - points[n - 2]
+            };
+            let normal = dir.normalized().rot90();
+            out.push(PathPoint { pos, normal });
+            back_normals.push(normal);
+            continue;
+        }
+
+        let (previous, next) = (points[(i + n - 1) % n], points[(i + 1) % n]);
+        let n0 = (pos - previous).normalized().rot90();
+        let n1 = (next - pos).normalized().rot90();
+
+        let normal = (n0 + n1) / 2.0;
+        let length_sq = normal.length_sq();
+
+        // How far out the tip of a miter is, in half widths:
+        let miter_length = 1.0 / length_sq.sqrt();
+
+        let miter = match stroke.join {
+            _ if radius * (miter_length - 1.0) < JOIN_TOLERANCE => true,
+            LineJoin::Miter => miter_length <= stroke.miter_limit,
+            LineJoin::Round | LineJoin::Bevel => false,
+        };
+
+        if miter {
+            out.push(PathPoint {
+                pos,
+                normal: normal / length_sq,
+            });
+            back_normals.push(normal / length_sq);
+            continue;
+        }
+
+        let angle = (n0.x * n1.y - n0.y * n1.x).atan2(n0.dot(n1));
+        let normals: Vec<Vec2> = if stroke.join == LineJoin::Round {
+            let steps = round_steps(radius, angle.abs());
+            (0..=steps)
+                .map(|step| Vec2::angled(n0.angle() + angle * step as f32 / steps as f32))
+                .collect()
+        } else {
+            // bevel:
+            vec![n0, n1]
+        };
+
+        // The inner sides meet at the miter on the inside of the corner, unless that is beyond
+        // the ends of the lines to the corner, when it's too sharp for that:
+        let shortest = pos.distance(previous).min(pos.distance(next));
+        let inner = (radius * miter_length <= shortest).then(|| normal / length_sq);
+
+        // A positive angle turns away from the side of the normals, so that side is outside:
+        for normal in normals {
+            match inner {
+                Some(inner) if angle > 0.0 => {
+                    out.push(PathPoint { pos, normal });
+                    back_normals.push(inner);
+                }
+                Some(inner) => {
+                    out.push(PathPoint { pos, normal: inner });
+                    back_normals.push(normal);
+                }
+                None => {
+                    out.push(PathPoint { pos, normal });
+                    back_normals.push(normal);
+                }
+            }
+        }
+    }
+}
+
+/// How many steps to go around `angle` radians of a circle with the given radius.
+fn round_steps(radius: f32, angle: f32) -> usize {
+    let max_step = 2.0 * (1.0 - JOIN_TOLERANCE / radius).max(0.0).acos();
+    (angle / max_step).ceil().max(1.0) as usize
+}
+
+/// Add half a circle to the end of a line at `center`, going in the direction `dir`.
+fn add_round_cap(feathering: f32, center: Pos2, dir: Vec2, stroke: Stroke, out: &mut Mesh) {
+    let color = stroke.color;
+    let normal = dir.rot90();
+    let steps = round_steps(0.5 * stroke.width, std::f32::consts::PI) as u32;
+
+    let idx = out.vertices.len() as u32;
+    out.colored_vertex(center, color);
+    for step in 0..=steps {
+        let (sin, cos) = (std::f32::consts::PI * step as f32 / steps as f32).sin_cos();
+        let dir = cos * normal + sin * dir;
+        if feathering > 0.0 {
+            let inner_rad = 0.5 * (stroke.width - feathering);
+            let outer_rad = 0.5 * (stroke.width + feathering);
+            out.colored_vertex(center + inner_rad * dir, color);
+            out.colored_vertex(center + outer_rad * dir, Color32::TRANSPARENT);
+            if step > 0 {
+                let (inner0, inner1) = (idx + 2 * step - 1, idx + 2 * step + 1);
+                out.add_triangle(idx, inner0, inner1);
+                out.add_triangle(inner0, inner0 + 1, inner1);
+                out.add_triangle(inner0 + 1, inner1, inner1 + 1);
+            }
+        } else {
+            out.colored_vertex(center + 0.5 * stroke.width * dir, color);
+            if step > 0 {
+                out.add_triangle(idx, idx + step, idx + step + 1);
+            }
+        }
+    }
+}
+
+/// Zero-length dashes are made this long (in points), so that they have a direction for their caps.
+const DOT_LENGTH: f32 = 0.01;
+
+/// Cut the polyline (without duplicated points) into the dashes of the `dash` pattern.
+fn dash_polyline(
+    points: &[Pos2],
+    path_type: PathType,
+    dash: &DashPattern,
+) -> Vec<(Vec<Pos2>, PathType)> {
+    let lengths = dash.lengths();
+    let pattern_len = if lengths.len() % 2 == 0 {
+        lengths.len()
+    } else {
+        2 * lengths.len() // an odd pattern is repeated, so that dashes become gaps the second time
+    };
+
+    // Find where in the pattern we start:
+    let mut index = 0;
+    let mut offset = dash.offset.rem_euclid(dash.period());
+    while offset > 0.0 && offset >= lengths[index % lengths.len()] {
+        offset -= lengths[index % lengths.len()];
+        index += 1;
+    }
+    let mut remaining = lengths[index % lengths.len()] - offset;
+    let mut is_dash = index % 2 == 0;
+
+    let started_with_dash = is_dash;
+    let mut dashes = vec![];
+    let mut current = if is_dash { vec![points[0]] } else { vec![] };
+
+    let finish = |mut dash: Vec<Pos2>, dir: Vec2, dashes: &mut Vec<(Vec<Pos2>, PathType)>| {
+        dash.dedup();
+        if dash.len() == 1 {
+            dash.push(dash[0] + DOT_LENGTH * dir);
+        }
+        dashes.push((dash, PathType::Open));
+    };
+
+    let n = points.len();
+    let segments = match path_type {
+        PathType::Open => n - 1,
+        PathType::Closed => n,
+    };
+    let mut dir = Vec2::ZERO;
+    for i in 0..segments {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let length = a.distance(b);
+        dir = (b - a) / length;
+
+        let mut t = 0.0;
+        while remaining <= length - t {
+            t += remaining;
+            let pos = a + t * dir;
+            if is_dash {
+                current.push(pos);
+                finish(std::mem::take(&mut current), dir, &mut dashes);
+            } else {
+                current.push(pos);
+            }
+            is_dash = !is_dash;
+            index = (index + 1) % pattern_len;
+            remaining = lengths[index % lengths.len()];
+        }
+        remaining -= length - t;
+
+        if is_dash {
+            current.push(b);
+        }
+    }
+
+    if is_dash {
+        if path_type == PathType::Closed && started_with_dash {
+            if dashes.is_empty() {
+                // One dash all the way around:
+                return vec![(points.to_vec(), PathType::Closed)];
+            }
+            // The last dash continues into the first one:
+            let first = dashes.remove(0).0;
+            current.extend_from_slice(&first);
+        }
+        finish(current, dir, &mut dashes);
+    }
+
+    dashes
+}
+
+fn mul_color(color: Color32, factor: f32) -> Color32 {
+    crate::epaint_assert!(0.0 <= factor && factor <= 1.0);
+    // As an unfortunate side-effect of using premultiplied alpha
+    // we need a somewhat expensive conversion to linear space and back.
+    color.linear_multiply(factor)
+}
+
+// ----------------------------------------------------------------------------
+
+/// Converts [`Shape`]s into triangles ([`Mesh`]).
+///
+/// For performance reasons it is smart to reuse the same [`Tessellator`].
+///
+/// Se also [`tessellate_shapes`], a convenient wrapper around [`Tessellator`].
+pub struct Tessellator {
+    pixels_per_point: f32,
+    options: TessellationOptions,
+    font_tex_size: [usize; 2],
     /// See [`TextureAtlas::prepared_discs`].
     prepared_discs: Vec<PreparedDisc>,
     /// size of feathering in points. normally the size of a physical pixel. 0.0 if disabled
     feathering: f32,
     /// Only used for culling
     clip_rect: Rect,
+    /// Of the [`Shape::Group`]:s we are in.
+    opacity: f32,
+    /// Of the innermost [`Shape::Group`] we are in that has one.
+    blend_mode: BlendMode,
     scratchpad_points: Vec<Pos2>,
     scratchpad_path: Path,
 }
@@ -1008,6 +2111,8 @@ impl Tessellator {
             prepared_discs,
             feathering,
             clip_rect: Rect::EVERYTHING,
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
             scratchpad_points: Default::default(),
             scratchpad_path: Default::default(),
         }
@@ -1046,23 +2151,55 @@ impl Tessellator {
             return;
         }
 
+        if let Shape::Group(group) = new_shape {
+            let (opacity, blend_mode) = (self.opacity, self.blend_mode);
+            self.opacity *= group.opacity.clamp(0.0, 1.0);
+            if group.blend_mode != BlendMode::Normal {
+                self.blend_mode = group.blend_mode;
+            }
+            if self.opacity > 0.0 {
+                for shape in group.shapes {
+                    self.tessellate_clipped_shape(
+                        ClippedShape(new_clip_rect, shape),
+                        out_primitives,
+                    );
+                }
+            }
+            self.opacity = opacity;
+            self.blend_mode = blend_mode;
+            return;
+        }
+
         if let Shape::Callback(callback) = new_shape {
             out_primitives.push(ClippedPrimitive {
                 clip_rect: new_clip_rect,
                 primitive: Primitive::Callback(callback),
+                blend_mode: BlendMode::Normal,
             });
             return;
         }
 
+        if let Shape::BackdropBlur(blur) = new_shape {
+            if blur.blur > 0.0 && blur.rect.intersects(new_clip_rect) {
+                out_primitives.push(ClippedPrimitive {
+                    clip_rect: new_clip_rect,
+                    primitive: Primitive::BackdropBlur(blur),
+                    blend_mode: BlendMode::Normal,
+                });
+            }
+            return;
+        }
+
         let start_new_mesh = match out_primitives.last() {
             None => true,
             Some(output_clipped_primitive) => {
                 output_clipped_primitive.clip_rect != new_clip_rect
+                    || output_clipped_primitive.blend_mode != self.blend_mode
                     || match &output_clipped_primitive.primitive {
                         Primitive::Mesh(output_mesh) => {
                             output_mesh.texture_id != new_shape.texture_id()
                         }
-                        Primitive::Callback(_) => true,
+                        Primitive::Callback(_) | Primitive::BackdropBlur(_) => true,
                     }
             }
         };
@@ -1070,7 +2207,8 @@ impl Tessellator {
         if start_new_mesh {
             out_primitives.push(ClippedPrimitive {
                 clip_rect: new_clip_rect,
-                primitive: Primitive::Mesh(Mesh::default()),
+                primitive: Primitive::Mesh(Mesh::with_texture(new_shape.texture_id())),
+                blend_mode: self.blend_mode,
             });
         }
 
@@ -1078,7 +2216,9 @@ impl Tessellator {
 
         if let Primitive::Mesh(out_mesh) = &mut out.primitive {
             self.clip_rect = new_clip_rect;
+            let first_vertex = out_mesh.vertices.len();
             self.tessellate_shape(new_shape, out_mesh);
+            multiply_opacity(&mut out_mesh.vertices[first_vertex..], self.opacity);
         } else {
             unreachable!();
         }
@@ -1087,7 +2227,10 @@ impl Tessellator {
     /// Tessellate a single [`Shape`] into a [`Mesh`].
     ///
     /// This call can panic the given shape is of [`Shape::Vec`] or [`Shape::Callback`].
-    /// For that, use [`Self::tessellate_clipped_shape`] instead.
+    /// A [`Shape::BackdropBlur`] is skipped, since it has no triangles,
+    /// and the [`GroupShape::blend_mode`] of a [`Shape::Group`] is ignored (it is painted with [`BlendMode::Normal`]),
+    /// since a blend mode needs a mesh of its own.
+    /// For those, use [`Self::tessellate_clipped_shape`] instead.
     /// * `shape`: the shape to tessellate.
     /// * `out`: triangles are appended to this.
     pub fn tessellate_shape(&mut self, shape: Shape, out: &mut Mesh) {
@@ -1098,9 +2241,30 @@ impl Tessellator {
                     self.tessellate_shape(shape, out);
                 }
             }
+            Shape::Group(group) => {
+                // The blend mode needs a mesh of its own, so only `tessellate_clipped_shape` can do that.
+                #[cfg(feature = "tracing")]
+                if group.blend_mode != BlendMode::Normal {
+                    tracing::warn!(
+                        "Tessellator::tessellate_shape ignores the blend mode {:?} of a Shape::Group",
+                        group.blend_mode
+                    );
+                }
+                let first_vertex = out.vertices.len();
+                for shape in group.shapes {
+                    self.tessellate_shape(shape, out);
+                }
+                multiply_opacity(&mut out.vertices[first_vertex..], group.opacity);
+            }
             Shape::Circle(circle) => {
                 self.tessellate_circle(circle, out);
             }
+            Shape::Ellipse(ellipse) => {
+                self.tessellate_ellipse(ellipse, out);
+            }
+            Shape::Arc(arc) => {
+                self.tessellate_arc(arc, out);
+            }
             Shape::Mesh(mesh) => {
                 if !mesh.is_valid() {
                     crate::epaint_assert!(false, "Invalid Mesh in Shape::Mesh");
@@ -1138,6 +2302,13 @@ impl Tessellator {
             Shape::Callback(_) => {
                 panic!("Shape::Callback passed to Tessellator");
             }
+            Shape::BackdropBlur(_) => {
+                // Only `tessellate_clipped_shape` can turn this into a `Primitive::BackdropBlur`.
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    "Shape::BackdropBlur passed to Tessellator::tessellate_shape is ignored"
+                );
+            }
         }
     }
 
@@ -1166,7 +2337,7 @@ impl Tessellator {
             return;
         }
 
-        if self.options.prerasterized_discs && fill != Color32::TRANSPARENT {
+        if let (true, Brush::Solid(fill_color)) = (self.options.prerasterized_discs, fill) {
             let radius_px = radius * self.pixels_per_point;
             // strike the right balance between some circles becoming too blurry, and some too sharp.
             let cutoff_radius = radius_px * 2.0_f32.powf(0.25);
@@ -1177,13 +2348,13 @@ impl Tessellator {
                 if cutoff_radius <= disc.r {
                     let side = radius_px * disc.w / (self.pixels_per_point * disc.r);
                     let rect = Rect::from_center_size(center, Vec2::splat(side));
-                    out.add_rect_with_uv(rect, disc.uv, fill);
+                    out.add_rect_with_uv(rect, disc.uv, fill_color);
 
                     if stroke.is_empty() {
                         return; // we are done
                     } else {
                         // we still need to do the stroke
-                        fill = Color32::TRANSPARENT; // don't fill again below
+                        fill = Brush::default(); // don't fill again below
                         break;
                     }
                 }
@@ -1192,11 +2363,123 @@ impl Tessellator {
 
         self.scratchpad_path.clear();
         self.scratchpad_path.add_circle(center, radius);
-        self.scratchpad_path.fill(self.feathering, fill, out);
+        self.scratchpad_path
+            .fill_with_brush(self.feathering, fill, out);
         self.scratchpad_path
             .stroke_closed(self.feathering, stroke, out);
     }
 
+    /// Tessellate a single [`EllipseShape`] into a [`Mesh`].
+    ///
+    /// * `shape`: the ellipse to tessellate.
+    /// * `out`: triangles are appended to this.
+    pub fn tessellate_ellipse(&mut self, shape: EllipseShape, out: &mut Mesh) {
+        let EllipseShape {
+            center,
+            radius,
+            fill,
+            stroke,
+        } = shape;
+
+        if radius.x <= 0.0 || radius.y <= 0.0 {
+            return;
+        }
+
+        if radius.x == radius.y {
+            let radius = radius.x;
+            self.tessellate_circle(
+                CircleShape {
+                    center,
+                    radius,
+                    fill,
+                    stroke,
+                },
+                out,
+            );
+            return;
+        }
+
+        if self.options.coarse_tessellation_culling
+            && !shape.visual_bounding_rect().intersects(self.clip_rect)
+        {
+            return;
+        }
+
+        self.scratchpad_points.clear();
+        path::add_ellipse_arc(
+            &mut self.scratchpad_points,
+            center,
+            radius,
+            0.0..=std::f32::consts::TAU,
+            self.options.ellipse_tolerance,
+        );
+        self.scratchpad_points.pop(); // same as the first point
+
+        let path = &mut self.scratchpad_path;
+        path.clear();
+        path.add_line_loop(&self.scratchpad_points);
+        path.fill_with_brush(self.feathering, fill, out);
+        path.stroke_closed(self.feathering, stroke, out);
+    }
+
+    /// Tessellate a single [`ArcShape`] into a [`Mesh`].
+    ///
+    /// * `shape`: the arc or sector to tessellate.
+    /// * `out`: triangles are appended to this.
+    pub fn tessellate_arc(&mut self, shape: ArcShape, out: &mut Mesh) {
+        let ArcShape {
+            center,
+            radius,
+            sector,
+            fill,
+            stroke,
+            ..
+        } = shape;
+
+        if radius.x <= 0.0 || radius.y <= 0.0 || shape.sweep_angle() == 0.0 {
+            return;
+        }
+
+        if self.options.coarse_tessellation_culling
+            && !shape.visual_bounding_rect().intersects(self.clip_rect)
+        {
+            return;
+        }
+
+        let full_turn = shape.is_full_turn();
+
+        // Going clockwise is the best winding order for filling.
+        // The center goes first, so that the fill is a triangle fan around it,
+        // which works even for slices larger than half the pie.
+        // A whole pie has no edges to the center, so it is just the ellipse.
+        self.scratchpad_points.clear();
+        if sector && !full_turn {
+            self.scratchpad_points.push(center);
+        }
+        path::add_ellipse_arc(
+            &mut self.scratchpad_points,
+            center,
+            radius,
+            shape.clockwise_angles(),
+            self.options.ellipse_tolerance,
+        );
+        if full_turn {
+            self.scratchpad_points.pop(); // same as the first point
+        }
+
+        let path = &mut self.scratchpad_path;
+        path.clear();
+        path.add_line_loop(&self.scratchpad_points);
+        path.fill_with_brush(self.feathering, fill, out);
+        if sector || full_turn {
+            path.stroke_closed(self.feathering, stroke, out);
+        } else {
+            path.clear();
+            path.add_open_points(&self.scratchpad_points);
+            path.stroke_open(self.feathering, stroke, out);
+        }
+    }
+
     /// Tessellate a single [`Mesh`] into a [`Mesh`].
     ///
     /// * `mesh`: the mesh to tessellate.
@@ -1258,6 +2541,8 @@ impl Tessellator {
             points,
             closed,
             fill,
+            fill_mode,
+            holes,
             stroke,
         } = path_shape;
 
@@ -1268,12 +2553,21 @@ impl Tessellator {
             self.scratchpad_path.add_open_points(points);
         }
 
-        if *fill != Color32::TRANSPARENT {
+        if !fill.is_transparent() {
             crate::epaint_assert!(
                 closed,
                 "You asked to fill a path that is not closed. That makes no sense."
             );
-            self.scratchpad_path.fill(self.feathering, *fill, out);
+            if *fill_mode == FillMode::Convex {
+                self.scratchpad_path
+                    .fill_with_brush(self.feathering, *fill, out);
+            } else {
+                let outlines: Vec<&[Pos2]> = std::iter::once(points)
+                    .chain(holes)
+                    .map(|outline| outline.as_slice())
+                    .collect();
+                fill_polygon_with_brush(self.feathering, &outlines, *fill_mode, *fill, out);
+            }
         }
         let typ = if *closed {
             PathType::Closed
@@ -1282,6 +2576,15 @@ impl Tessellator {
         };
         self.scratchpad_path
             .stroke(self.feathering, typ, *stroke, out);
+
+        if *fill_mode != FillMode::Convex {
+            for hole in holes.iter().filter(|hole| hole.len() >= 2) {
+                self.scratchpad_path.clear();
+                self.scratchpad_path.add_line_loop(hole);
+                self.scratchpad_path
+                    .stroke_closed(self.feathering, *stroke, out);
+            }
+        }
     }
 
     /// Tessellate a single [`Rect`] into a [`Mesh`].
@@ -1314,7 +2617,7 @@ impl Tessellator {
         path.clear();
         path::rounded_rectangle(&mut self.scratchpad_points, rect, rounding);
         path.add_line_loop(&self.scratchpad_points);
-        path.fill(self.feathering, fill, out);
+        path.fill_with_brush(self.feathering, fill, out);
         path.stroke_closed(self.feathering, stroke, out);
     }
 
@@ -1476,7 +2779,7 @@ impl Tessellator {
     fn tessellate_bezier_complete(
         &mut self,
         points: &[Pos2],
-        fill: Color32,
+        fill: Brush,
         closed: bool,
         stroke: Stroke,
         out: &mut Mesh,
@@ -1487,12 +2790,13 @@ impl Tessellator {
         } else {
             self.scratchpad_path.add_open_points(points);
         }
-        if fill != Color32::TRANSPARENT {
+        if !fill.is_transparent() {
             crate::epaint_assert!(
                 closed,
                 "You asked to fill a path that is not closed. That makes no sense."
             );
-            self.scratchpad_path.fill(self.feathering, fill, out);
+            self.scratchpad_path
+                .fill_with_brush(self.feathering, fill, out);
         }
         let typ = if closed {
             PathType::Closed
@@ -1549,7 +2853,7 @@ pub fn tessellate_shapes(
         p.clip_rect.is_positive()
             && match &p.primitive {
                 Primitive::Mesh(mesh) => !mesh.is_empty(),
-                Primitive::Callback(_) => true,
+                Primitive::Callback(_) | Primitive::BackdropBlur(_) => true,
             }
     });
 
@@ -1562,6 +2866,15 @@ pub fn tessellate_shapes(
     clipped_primitives
 }
 
+fn multiply_opacity(vertices: &mut [Vertex], opacity: f32) {
+    if opacity < 1.0 {
+        let opacity = opacity.max(0.0);
+        for vertex in vertices {
+            vertex.color = vertex.color.linear_multiply(opacity);
+        }
+    }
+}
+
 fn add_clip_rects(
     tessellator: &mut Tessellator,
     clipped_primitives: Vec<ClippedPrimitive>,
@@ -1583,6 +2896,7 @@ fn add_clip_rects(
                 ClippedPrimitive {
                     clip_rect: Rect::EVERYTHING, // whatever
                     primitive: Primitive::Mesh(clip_rect_mesh),
+                    blend_mode: BlendMode::Normal,
                 },
             ]
         })
@@ -1621,3 +2935,415 @@ fn test_tessellator() {
     );
     assert_eq!(primitives.len(), 2);
 }
+
+#[test]
+fn test_tessellate_groups() {
+    use crate::*;
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+    let shape = Shape::Vec(vec![
+        Shape::rect_filled(rect, 0.0, Color32::WHITE),
+        GroupShape::new(vec![Shape::rect_filled(rect, 0.0, Color32::WHITE)])
+            .with_opacity(0.5)
+            .with_blend_mode(BlendMode::Multiply)
+            .into(),
+        Shape::rect_filled(rect, 0.0, Color32::WHITE),
+    ]);
+
+    let primitives = tessellate_shapes(
+        1.0,
+        Default::default(),
+        [1024, 1024],
+        vec![],
+        vec![ClippedShape(rect, shape)],
+    );
+    let blend_modes: Vec<_> = primitives.iter().map(|p| p.blend_mode).collect();
+    assert_eq!(
+        blend_modes,
+        [BlendMode::Normal, BlendMode::Multiply, BlendMode::Normal]
+    );
+    if let Primitive::Mesh(mesh) = &primitives[1].primitive {
+        let half_white = Color32::WHITE.linear_multiply(0.5);
+        assert!(mesh.vertices.iter().any(|v| v.color == half_white));
+        assert!(mesh.vertices.iter().all(|v| v.color.a() <= half_white.a()));
+    }
+}
+
+#[cfg(test)]
+fn triangle_area(mesh: &Mesh, triangle: &[u32]) -> f32 {
+    let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].pos);
+    0.5 * ((b - a).x * (c - a).y - (b - a).y * (c - a).x).abs()
+}
+
+#[cfg(test)]
+fn mesh_area(mesh: &Mesh) -> f32 {
+    mesh.indices
+        .chunks_exact(3)
+        .map(|triangle| triangle_area(mesh, triangle))
+        .sum()
+}
+
+#[test]
+fn test_fill_polygon_fill_rules() {
+    fn filled_area(fill_mode: FillMode, outlines: &[&[Pos2]]) -> f32 {
+        let mut mesh = Mesh::default();
+        fill_polygon(0.0, outlines, fill_mode, Color32::WHITE, &mut mesh);
+        mesh_area(&mesh)
+    }
+
+    let square = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    let hole = [
+        pos2(2.0, 2.0),
+        pos2(5.0, 2.0),
+        pos2(5.0, 5.0),
+        pos2(2.0, 5.0),
+    ];
+    let reversed_hole = [
+        pos2(2.0, 2.0),
+        pos2(2.0, 5.0),
+        pos2(5.0, 5.0),
+        pos2(5.0, 2.0),
+    ];
+
+    assert_eq!(filled_area(FillMode::EvenOdd, &[&square, &hole]), 91.0);
+    assert_eq!(filled_area(FillMode::NonZero, &[&square, &hole]), 100.0);
+    assert_eq!(
+        filled_area(FillMode::NonZero, &[&square, &reversed_hole]),
+        91.0
+    );
+
+    // A bow tie, which crosses itself in the middle:
+    let bow_tie = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(10.0, 0.0),
+        pos2(0.0, 10.0),
+    ];
+    assert_eq!(filled_area(FillMode::NonZero, &[&bow_tie]), 50.0);
+}
+
+#[test]
+fn test_fill_polygon_feathering() {
+    let square = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    let hole = [
+        pos2(2.0, 2.0),
+        pos2(5.0, 2.0),
+        pos2(5.0, 5.0),
+        pos2(2.0, 5.0),
+    ];
+
+    // Feathered like a convex fill, on every side and around the corners:
+    let mut convex = Mesh::default();
+    let mut path = Path::default();
+    path.add_line_loop(&square);
+    path.fill(1.0, Color32::WHITE, &mut convex);
+    for fill_mode in [FillMode::NonZero, FillMode::EvenOdd] {
+        let mut mesh = Mesh::default();
+        fill_polygon(1.0, &[&square], fill_mode, Color32::WHITE, &mut mesh);
+        for vertex in &convex.vertices {
+            assert!(mesh.vertices.contains(vertex), "{:?}", vertex);
+        }
+        assert_eq!(mesh_area(&mesh), mesh_area(&convex));
+    }
+
+    let mut mesh = Mesh::default();
+    fill_polygon(
+        1.0,
+        &[&square, &hole],
+        FillMode::EvenOdd,
+        Color32::WHITE,
+        &mut mesh,
+    );
+    let opaque_area: f32 = mesh
+        .indices
+        .chunks_exact(3)
+        .filter(|triangle| {
+            let color = |i: usize| mesh.vertices[triangle[i] as usize].color;
+            (0..3).all(|i| color(i) == Color32::WHITE)
+        })
+        .map(|triangle| triangle_area(&mesh, triangle))
+        .sum();
+    // Filled between the square and the hole, both moved in by half the feathering:
+    assert_eq!(opaque_area, 9.0 * 9.0 - 4.0 * 4.0);
+    // Without gaps or overlaps out to half the feathering beyond the square and into the hole:
+    assert_eq!(mesh_area(&mesh), 11.0 * 11.0 - 2.0 * 2.0);
+    assert!(mesh
+        .vertices
+        .iter()
+        .any(|vertex| { vertex.pos == pos2(2.5, 2.5) && vertex.color == Color32::TRANSPARENT }));
+}
+
+#[test]
+fn test_dashed_stroke() {
+    fn stroked_area(stroke: Stroke) -> f32 {
+        let mut path = Path::default();
+        path.add_line_segment([pos2(0.0, 0.0), pos2(10.0, 0.0)]);
+        let mut mesh = Mesh::default();
+        path.stroke_open(0.0, stroke, &mut mesh);
+        mesh_area(&mesh)
+    }
+
+    let stroke = Stroke::new(2.0, Color32::WHITE);
+    assert_eq!(stroked_area(stroke.with_cap(LineCap::Square)), 24.0);
+
+    // Dashes at 0-2, 4-6 and 8-10:
+    let dashed = stroke.with_dash(DashPattern::dashed(2.0, 2.0));
+    assert_eq!(stroked_area(dashed), 12.0);
+
+    // Dashes at 0-1, 3-5 and 7-9:
+    assert_eq!(
+        stroked_area(dashed.with_dash(dashed.dash.with_offset(1.0))),
+        10.0
+    );
+}
+
+#[test]
+fn test_stroke_joins() {
+    fn stroke_mesh(points: &[Pos2], stroke: Stroke) -> Mesh {
+        let mut path = Path::default();
+        path.add_open_points(points);
+        let mut mesh = Mesh::default();
+        path.stroke_open(0.0, stroke, &mut mesh);
+        mesh
+    }
+
+    // The two lines (minus where they overlap) and a bit outside the corner,
+    // without painting over anything twice on the inside of the corner:
+    let corner = [pos2(0.0, 0.0), pos2(10.0, 0.0), pos2(10.0, 10.0)];
+    let stroke = Stroke::new(2.0, Color32::WHITE);
+    let bevel = stroke_mesh(&corner, stroke.with_join(LineJoin::Bevel));
+    assert_eq!(mesh_area(&bevel), 39.5);
+    let round = mesh_area(&stroke_mesh(&corner, stroke.with_join(LineJoin::Round)));
+    assert!(
+        39.5 < round && round <= 39.0 + std::f32::consts::PI / 4.0,
+        "{}",
+        round
+    );
+
+    // A 60° corner has a miter twice the half width, which the default limit allows:
+    let sharp = [pos2(0.0, 0.0), pos2(10.0, 0.0), pos2(5.0, 75f32.sqrt())];
+    let tip = pos2(10.0 + 3f32.sqrt(), -1.0);
+    let reaches_tip = |mesh: &Mesh| mesh.vertices.iter().any(|v| v.pos.distance(tip) < 1e-3);
+    assert!(reaches_tip(&stroke_mesh(&sharp, stroke)));
+    assert!(!reaches_tip(&stroke_mesh(
+        &sharp,
+        stroke.with_miter_limit(1.5)
+    )));
+}
+
+#[cfg(test)]
+fn assert_gradient_mesh(brush: &Brush, outline: &[Pos2], max_t_error: f32) {
+    fn cross(a: Vec2, b: Vec2) -> f32 {
+        a.x * b.y - a.y * b.x
+    }
+
+    let mut mesh = Mesh::default();
+    fill_polygon_with_brush(0.0, &[outline], FillMode::Convex, *brush, &mut mesh);
+    let rect = Rect::from_points(outline);
+
+    for vertex in &mesh.vertices {
+        assert_eq!(vertex.color, brush.color_at(vertex.pos, rect));
+    }
+
+    let stops: Vec<f32> = match brush {
+        Brush::Solid(_) => vec![],
+        Brush::LinearGradient { stops, .. } | Brush::RadialGradient { stops, .. } => {
+            stops.as_slice().iter().map(|(offset, _)| *offset).collect()
+        }
+    };
+    let mut area = 0.0;
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].pos);
+        area += 0.5 * cross(b - a, c - a).abs();
+        let ts = [a, b, c].map(|pos| brush.gradient_t(pos, rect));
+
+        // The colors are interpolated between the vertices, so no stop may be inside a triangle:
+        for &stop in &stops {
+            let below = ts.iter().any(|&t| t < stop - 1e-4);
+            let above = ts.iter().any(|&t| t > stop + 1e-4);
+            assert!(!(below && above), "Stop {} is inside {:?}", stop, ts);
+        }
+
+        // …and the gradient must be close to linear within the triangle:
+        let centroid = pos2((a.x + b.x + c.x) / 3.0, (a.y + b.y + c.y) / 3.0);
+        let t_error = brush.gradient_t(centroid, rect) - (ts[0] + ts[1] + ts[2]) / 3.0;
+        assert!(t_error.abs() <= max_t_error, "t is off by {}", t_error);
+
+        // No cracks: a vertex on the edge of a triangle must be one of its corners.
+        for (p, q) in [(a, b), (b, c), (c, a)] {
+            if p.distance(q) < 1e-3 {
+                continue; // A sliver between vertices that are in the same place.
+            }
+            for vertex in &mesh.vertices {
+                let f = (vertex.pos - p).dot(q - p) / (q - p).length_sq();
+                let on_edge =
+                    1e-3 < f && f < 1.0 - 1e-3 && (p + f * (q - p)).distance(vertex.pos) < 1e-3;
+                assert!(!on_edge, "T-junction at {:?}", vertex.pos);
+            }
+        }
+    }
+
+    let polygon_area: f32 = (0..outline.len())
+        .map(|i| {
+            0.5 * cross(
+                outline[i].to_vec2(),
+                outline[(i + 1) % outline.len()].to_vec2(),
+            )
+        })
+        .sum();
+    assert!((area - polygon_area.abs()).abs() < 1e-2 * polygon_area.abs());
+}
+
+#[test]
+fn test_multi_stop_linear_gradient() {
+    let brush = Brush::LinearGradient {
+        start: pos2(0.0, 0.0),
+        end: pos2(1.0, 1.0),
+        stops: crate::ColorStops::new(&[
+            (0.0, Color32::RED),
+            (0.25, Color32::YELLOW),
+            (0.5, Color32::GREEN),
+            (1.0, Color32::BLUE),
+        ]),
+    };
+    let square = [
+        pos2(0.0, 0.0),
+        pos2(100.0, 0.0),
+        pos2(100.0, 100.0),
+        pos2(0.0, 100.0),
+    ];
+    assert_gradient_mesh(&brush, &square, 1e-4);
+}
+
+#[test]
+fn test_multi_stop_radial_gradient() {
+    let brush = Brush::RadialGradient {
+        center: pos2(0.5, 0.5),
+        radius: vec2(0.5, 0.5),
+        stops: crate::ColorStops::new(&[
+            (0.0, Color32::WHITE),
+            (0.5, Color32::RED),
+            (1.0, Color32::TRANSPARENT),
+        ]),
+    };
+    let circle: Vec<Pos2> = (0..32)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::TAU / 32.0;
+            pos2(50.0, 50.0) + 50.0 * Vec2::angled(angle)
+        })
+        .collect();
+    assert_gradient_mesh(&brush, &circle, 1.0 / 32.0);
+}
+
+#[test]
+fn test_tessellate_shape_skips_backdrop_blur() {
+    let rect = Rect::from_min_size(pos2(0.0, 0.0), vec2(10.0, 10.0));
+    let mut tessellator = Tessellator::new(1.0, Default::default(), [1, 1], vec![]);
+
+    let mut rect_mesh = Mesh::default();
+    tessellator.tessellate_shape(Shape::rect_filled(rect, 0.0, Color32::RED), &mut rect_mesh);
+
+    let mut mesh = Mesh::default();
+    let shape = Shape::Vec(vec![
+        BackdropBlur::new(rect, 2.0, 8.0).into(),
+        Shape::rect_filled(rect, 0.0, Color32::RED),
+    ]);
+    tessellator.tessellate_shape(shape, &mut mesh);
+    assert_eq!(mesh, rect_mesh, "only the rect is tessellated");
+}
+
+#[test]
+fn test_ellipse_arc_tolerance() {
+    use std::f32::consts::PI;
+
+    let center = pos2(0.0, 0.0);
+    let radius = vec2(100.0, 50.0);
+    let half_ellipse = |tolerance: f32| {
+        let mut points = vec![];
+        path::add_ellipse_arc(&mut points, center, radius, 0.0..=PI, tolerance);
+        points
+    };
+    let distance_to_segment = |pos: Pos2, [a, b]: [Pos2; 2]| {
+        let t = ((pos - a).dot(b - a) / (b - a).length_sq()).clamp(0.0, 1.0);
+        pos.distance(a + t * (b - a))
+    };
+
+    for tolerance in [1.0, 0.1, 0.01] {
+        let points = half_ellipse(tolerance);
+
+        // As many points as a circle with the larger radius needs:
+        let max_step = 2.0 * (1.0 - tolerance / radius.x).acos();
+        let n = (PI / max_step).ceil() as usize;
+        assert_eq!(points.len(), n + 1, "tolerance {}", tolerance);
+        assert!(points[0].distance(pos2(100.0, 0.0)) < 1e-3);
+        assert!(points[n].distance(pos2(-100.0, 0.0)) < 1e-3);
+
+        // The ellipse is never further from the lines than the tolerance:
+        for (i, segment) in points.windows(2).enumerate() {
+            for j in 1..10 {
+                let angle = PI * (i as f32 + j as f32 / 10.0) / n as f32;
+                let on_ellipse = center + radius * Vec2::angled(angle);
+                let distance = distance_to_segment(on_ellipse, [segment[0], segment[1]]);
+                assert!(
+                    distance <= 1.01 * tolerance,
+                    "{} from the ellipse with tolerance {}",
+                    distance,
+                    tolerance
+                );
+            }
+        }
+    }
+
+    // At least one point every quarter turn:
+    assert_eq!(half_ellipse(1000.0).len(), 3);
+    assert_eq!(half_ellipse(0.0).len(), 3);
+}
+
+#[test]
+fn test_full_turn_sector() {
+    use std::f32::consts::TAU;
+
+    let center = pos2(50.0, 50.0);
+    let radius = vec2(20.0, 10.0);
+    let stroke = Stroke::new(2.0, Color32::RED);
+    let mut tessellator = Tessellator::new(1.0, Default::default(), [1, 1], vec![]);
+
+    let mut sector = Mesh::default();
+    let shape = ArcShape {
+        radius,
+        ..ArcShape::sector(center, 0.0, 0.0..=TAU, Color32::TRANSPARENT, stroke)
+    };
+    tessellator.tessellate_arc(shape, &mut sector);
+    assert!(!sector.is_empty());
+    for vertex in &sector.vertices {
+        let relative = (vertex.pos - center) / radius;
+        assert!(
+            relative.length() > 0.8,
+            "{:?} is on a line to the center",
+            vertex.pos
+        );
+    }
+
+    // The same as the whole ellipse:
+    let mut ellipse = Mesh::default();
+    tessellator.tessellate_ellipse(
+        EllipseShape {
+            center,
+            radius,
+            fill: Color32::TRANSPARENT.into(),
+            stroke,
+        },
+        &mut ellipse,
+    );
+    assert_eq!(sector, ellipse);
+}
@@ -0,0 +1,620 @@
+//! Picking the face of a font that best matches a [`FontWeight`], [`FontStretch`] and [`FontStyle`],
+//! and using the axes of variable fonts to match them exactly.
+
+use std::sync::Arc;
+
+use ab_glyph::{GlyphId, Outline, OutlineCurve, Point, Rect};
+
+use super::{FontData, FontStretch, FontStyle, FontWeight};
+
+/// How much to slant upright faces when asked for italics (horizontal shift per height).
+const SYNTHETIC_OBLIQUE_SKEW: f32 = 0.25;
+
+/// The slant (in degrees, counter-clockwise) we ask of fonts with a `slnt` axis.
+const OBLIQUE_ANGLE: f32 = -12.0;
+
+/// What the font file says about a face.
+#[derive(Clone, Debug)]
+pub(crate) struct FaceInfo {
+    weight: FontWeight,
+    stretch: FontStretch,
+    style: FontStyle,
+    axes: Vec<ttf_parser::VariationAxis>,
+}
+
+impl FaceInfo {
+    pub fn new(font_data: &FontData) -> Self {
+        match ttf_parser::Face::from_slice(&font_data.font, font_data.index) {
+            Ok(face) => Self {
+                weight: FontWeight(face.weight().to_number()),
+                stretch: stretch_from_width_class(face.width().to_number()),
+                style: match face.style() {
+                    ttf_parser::Style::Normal => FontStyle::Normal,
+                    ttf_parser::Style::Italic => FontStyle::Italic,
+                    ttf_parser::Style::Oblique => FontStyle::Oblique,
+                },
+                axes: face.variation_axes().into_iter().collect(),
+            },
+            Err(_) => Self {
+                weight: FontWeight::NORMAL,
+                stretch: FontStretch::NORMAL,
+                style: FontStyle::Normal,
+                axes: vec![],
+            },
+        }
+    }
+
+    fn axis(&self, tag: &[u8; 4]) -> Option<&ttf_parser::VariationAxis> {
+        let tag = ttf_parser::Tag::from_bytes(tag);
+        self.axes.iter().find(|axis| axis.tag == tag)
+    }
+
+    fn can_slant(&self) -> bool {
+        self.axis(b"ital").is_some() || self.axis(b"slnt").is_some()
+    }
+
+    /// How far off this face is from the request. Lower is better.
+    fn distance(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> (u8, f32, f32) {
+        let style_distance =
+            if self.style == style || (style != FontStyle::Normal && self.can_slant()) {
+                0
+            } else if self.style != FontStyle::Normal && style != FontStyle::Normal {
+                1 // italic for oblique, or the other way around
+            } else {
+                2
+            };
+
+        let axis_distance = |tag: &[u8; 4], value: f32, static_value: f32| match self.axis(tag) {
+            Some(axis) => (value.clamp(axis.min_value, axis.max_value) - value).abs(),
+            None => (static_value - value).abs(),
+        };
+        let stretch_distance = axis_distance(b"wdth", stretch.0 as f32, self.stretch.0 as f32);
+        let weight_distance = axis_distance(b"wght", weight.0 as f32, self.weight.0 as f32);
+
+        (style_distance, stretch_distance, weight_distance)
+    }
+}
+
+/// The OS/2 width class (1-9) as a percentage.
+fn stretch_from_width_class(width_class: u16) -> FontStretch {
+    match width_class {
+        1 => FontStretch::ULTRA_CONDENSED,
+        2 => FontStretch::EXTRA_CONDENSED,
+        3 => FontStretch::CONDENSED,
+        4 => FontStretch::SEMI_CONDENSED,
+        6 => FontStretch::SEMI_EXPANDED,
+        7 => FontStretch::EXPANDED,
+        8 => FontStretch::EXTRA_EXPANDED,
+        9 => FontStretch::ULTRA_EXPANDED,
+        _ => FontStretch::NORMAL,
+    }
+}
+
+/// A face of a font, set up to match a requested weight, stretch and style.
+#[derive(Clone, Debug)]
+pub(crate) struct FaceInstance {
+    /// Key into [`super::FontDefinitions::font_data`].
+    pub font_name: String,
+
+    /// Values for the variation axes of a variable font.
+    pub variations: Vec<([u8; 4], f32)>,
+
+    /// Slant an upright face, because there is no italic one.
+    pub synthetic_oblique: bool,
+}
+
+impl PartialEq for FaceInstance {
+    /// The variations are compared bit for bit, so that this is an [`Eq`] that agrees with the hash.
+    fn eq(&self, other: &Self) -> bool {
+        self.font_name == other.font_name
+            && self.synthetic_oblique == other.synthetic_oblique
+            && self.variations.len() == other.variations.len()
+            && self.variations.iter().zip(&other.variations).all(
+                |((tag, value), (other_tag, other_value))| {
+                    tag == other_tag && value.to_bits() == other_value.to_bits()
+                },
+            )
+    }
+}
+
+impl Eq for FaceInstance {}
+
+impl std::hash::Hash for FaceInstance {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let Self {
+            font_name,
+            variations,
+            synthetic_oblique,
+        } = self;
+        font_name.hash(state);
+        for (tag, value) in variations {
+            tag.hash(state);
+            crate::f32_hash(state, *value);
+        }
+        synthetic_oblique.hash(state);
+    }
+}
+
+impl FaceInstance {
+    /// Of these faces, pick the one closest to the request.
+    ///
+    /// `faces` is non-empty, and in order of preference for equally good matches.
+    pub fn select<'a>(
+        faces: impl Iterator<Item = (&'a str, &'a FaceInfo)>,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Self {
+        let mut best: Option<(&str, &FaceInfo, (u8, f32, f32))> = None;
+        for (name, info) in faces {
+            let distance = info.distance(weight, stretch, style);
+            let is_better = best.map_or(true, |(_, _, best_distance)| {
+                distance.partial_cmp(&best_distance) == Some(std::cmp::Ordering::Less)
+            });
+            if is_better {
+                best = Some((name, info, distance));
+            }
+        }
+        let (font_name, info, _) = best.expect("No faces to pick from");
+
+        let mut variations = vec![];
+        let mut vary = |tag: &[u8; 4], value: f32| {
+            if let Some(axis) = info.axis(tag) {
+                let value = value.clamp(axis.min_value, axis.max_value);
+                if value != axis.def_value {
+                    variations.push((*tag, value));
+                }
+            }
+        };
+        vary(b"wght", weight.0 as f32);
+        vary(b"wdth", stretch.0 as f32);
+
+        let mut synthetic_oblique = false;
+        if style != FontStyle::Normal && info.style == FontStyle::Normal {
+            let has_ital = info.axis(b"ital").is_some();
+            let has_slnt = info.axis(b"slnt").is_some();
+            if has_ital && (style == FontStyle::Italic || !has_slnt) {
+                vary(b"ital", 1.0);
+            } else if has_slnt {
+                vary(b"slnt", OBLIQUE_ANGLE);
+            } else {
+                synthetic_oblique = true;
+            }
+        }
+
+        Self {
+            font_name: font_name.to_owned(),
+            variations,
+            synthetic_oblique,
+        }
+    }
+
+    /// Is this just the face as it is in the font file?
+    pub fn is_default(&self) -> bool {
+        self.variations.is_empty() && !self.synthetic_oblique
+    }
+
+    #[cfg(feature = "shaping")]
+    pub fn rustybuzz_variations(&self) -> Vec<rustybuzz::Variation> {
+        self.variations
+            .iter()
+            .map(|(tag, value)| rustybuzz::Variation {
+                tag: ttf_parser::Tag::from_bytes(tag),
+                value: *value,
+            })
+            .collect()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A font with its variation axes set, and/or slanted.
+///
+/// Everything that doesn't depend on the variations is forwarded to the plain font.
+/// The rest is read from the font file with the variations set.
+///
+/// A [`ttf_parser::Face`] borrows the font file, so it is parsed when needed.
+/// That is only once per glyph and size, since [`super::font::FontImpl`] caches the glyphs.
+pub(crate) struct VariedFont {
+    font: ab_glyph::FontArc,
+    font_data: Arc<FontData>,
+    variations: Vec<([u8; 4], f32)>,
+    synthetic_oblique: bool,
+}
+
+impl VariedFont {
+    pub fn new(font: ab_glyph::FontArc, font_data: Arc<FontData>, instance: &FaceInstance) -> Self {
+        Self {
+            font,
+            font_data,
+            variations: instance.variations.clone(),
+            synthetic_oblique: instance.synthetic_oblique,
+        }
+    }
+
+    /// The font file, with the variations set.
+    fn face(&self) -> Option<ttf_parser::Face<'_>> {
+        let mut face =
+            ttf_parser::Face::from_slice(&self.font_data.font, self.font_data.index).ok()?;
+        for (tag, value) in &self.variations {
+            face.set_variation(ttf_parser::Tag::from_bytes(tag), *value);
+        }
+        Some(face)
+    }
+
+    fn skew(&self) -> f32 {
+        if self.synthetic_oblique {
+            SYNTHETIC_OBLIQUE_SKEW
+        } else {
+            0.0
+        }
+    }
+}
+
+impl ab_glyph::Font for VariedFont {
+    fn units_per_em(&self) -> Option<f32> {
+        self.font.units_per_em()
+    }
+
+    fn ascent_unscaled(&self) -> f32 {
+        self.font.ascent_unscaled()
+    }
+
+    fn descent_unscaled(&self) -> f32 {
+        self.font.descent_unscaled()
+    }
+
+    fn line_gap_unscaled(&self) -> f32 {
+        self.font.line_gap_unscaled()
+    }
+
+    fn glyph_id(&self, c: char) -> GlyphId {
+        self.font.glyph_id(c)
+    }
+
+    fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.face()
+            .and_then(|face| face.glyph_hor_advance(ttf_parser::GlyphId(id.0)))
+            .map_or_else(|| self.font.h_advance_unscaled(id), f32::from)
+    }
+
+    fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.face()
+            .and_then(|face| face.glyph_hor_side_bearing(ttf_parser::GlyphId(id.0)))
+            .map_or_else(|| self.font.h_side_bearing_unscaled(id), f32::from)
+    }
+
+    fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.v_advance_unscaled(id)
+    }
+
+    fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.v_side_bearing_unscaled(id)
+    }
+
+    fn kern_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
+        self.font.kern_unscaled(first, second)
+    }
+
+    fn outline(&self, id: GlyphId) -> Option<Outline> {
+        let face = self.face()?;
+        let mut builder = OutlineBuilder {
+            skew: self.skew(),
+            ..Default::default()
+        };
+        face.outline_glyph(ttf_parser::GlyphId(id.0), &mut builder)?;
+        let OutlineBuilder {
+            curves, min, max, ..
+        } = builder;
+        if curves.is_empty() || min.x >= max.x || min.y >= max.y {
+            return None;
+        }
+        Some(Outline {
+            // Unscaled bounds have y pointing up:
+            bounds: Rect {
+                min: Point { x: min.x, y: max.y },
+                max: Point { x: max.x, y: min.y },
+            },
+            curves,
+        })
+    }
+
+    fn glyph_count(&self) -> usize {
+        self.font.glyph_count()
+    }
+
+    fn codepoint_ids(&self) -> ab_glyph::CodepointIdIter<'_> {
+        self.font.codepoint_ids()
+    }
+
+    fn glyph_raster_image(&self, id: GlyphId, pixel_size: u16) -> Option<ab_glyph::GlyphImage<'_>> {
+        self.font.glyph_raster_image(id, pixel_size)
+    }
+}
+
+/// Collects the outline of a glyph as [`OutlineCurve`]s, optionally skewed.
+struct OutlineBuilder {
+    skew: f32,
+    curves: Vec<OutlineCurve>,
+    start: Point,
+    last: Point,
+    min: Point,
+    max: Point,
+}
+
+impl Default for OutlineBuilder {
+    fn default() -> Self {
+        let zero = Point { x: 0.0, y: 0.0 };
+        Self {
+            skew: 0.0,
+            curves: vec![],
+            start: zero,
+            last: zero,
+            min: Point {
+                x: f32::INFINITY,
+                y: f32::INFINITY,
+            },
+            max: Point {
+                x: f32::NEG_INFINITY,
+                y: f32::NEG_INFINITY,
+            },
+        }
+    }
+}
+
+impl OutlineBuilder {
+    /// Skew and include in the bounds.
+    fn point(&mut self, x: f32, y: f32) -> Point {
+        let point = Point {
+            x: x + self.skew * y,
+            y,
+        };
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+        point
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.curves.push(OutlineCurve::Line(self.last, point));
+        self.last = point;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let control = self.point(x1, y1);
+        let point = self.point(x, y);
+        self.curves
+            .push(OutlineCurve::Quad(self.last, control, point));
+        self.last = point;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let control1 = self.point(x1, y1);
+        let control2 = self.point(x2, y2);
+        let point = self.point(x, y);
+        self.curves
+            .push(OutlineCurve::Cubic(self.last, control1, control2, point));
+        self.last = point;
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.curves.push(OutlineCurve::Line(self.last, self.start));
+            self.last = self.start;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn face(weight: u16, stretch: u16, style: FontStyle) -> FaceInfo {
+        FaceInfo {
+            weight: FontWeight(weight),
+            stretch: FontStretch(stretch),
+            style,
+            axes: vec![],
+        }
+    }
+
+    fn axis(
+        tag: &[u8; 4],
+        min_value: f32,
+        def_value: f32,
+        max_value: f32,
+    ) -> ttf_parser::VariationAxis {
+        ttf_parser::VariationAxis {
+            tag: ttf_parser::Tag::from_bytes(tag),
+            min_value,
+            def_value,
+            max_value,
+            name_id: 0,
+            hidden: false,
+        }
+    }
+
+    fn select(
+        faces: &[(&str, FaceInfo)],
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> FaceInstance {
+        let faces = faces.iter().map(|(name, info)| (*name, info));
+        FaceInstance::select(faces, weight, stretch, style)
+    }
+
+    #[test]
+    fn select_static_faces() {
+        let faces = [
+            ("Regular", face(400, 100, FontStyle::Normal)),
+            ("Bold", face(700, 100, FontStyle::Normal)),
+            ("Condensed", face(400, 75, FontStyle::Normal)),
+            ("Italic", face(400, 100, FontStyle::Italic)),
+        ];
+        let name = |weight, stretch, style| select(&faces, weight, stretch, style).font_name;
+
+        use FontStyle::{Italic, Normal, Oblique};
+        assert_eq!(
+            name(FontWeight::NORMAL, FontStretch::NORMAL, Normal),
+            "Regular"
+        );
+        assert_eq!(
+            name(FontWeight::SEMI_BOLD, FontStretch::NORMAL, Normal),
+            "Bold"
+        );
+        assert_eq!(
+            name(FontWeight::MEDIUM, FontStretch::NORMAL, Normal),
+            "Regular"
+        );
+        assert_eq!(
+            name(FontWeight::NORMAL, FontStretch::CONDENSED, Normal),
+            "Condensed"
+        );
+        assert_eq!(
+            name(FontWeight::NORMAL, FontStretch::NORMAL, Italic),
+            "Italic"
+        );
+
+        // The style matters more than the weight, and italic is closer to oblique than upright:
+        assert_eq!(
+            name(FontWeight::BOLD, FontStretch::NORMAL, Italic),
+            "Italic"
+        );
+        assert_eq!(
+            name(FontWeight::NORMAL, FontStretch::NORMAL, Oblique),
+            "Italic"
+        );
+
+        // Ties go to the first face:
+        assert_eq!(
+            name(FontWeight(550), FontStretch::NORMAL, Normal),
+            "Regular"
+        );
+
+        // No variations for static faces:
+        let instance = select(&faces, FontWeight(550), FontStretch::NORMAL, Normal);
+        assert!(instance.is_default());
+    }
+
+    #[test]
+    fn synthetic_oblique() {
+        let faces = [
+            ("Regular", face(400, 100, FontStyle::Normal)),
+            ("Bold", face(700, 100, FontStyle::Normal)),
+        ];
+        let instance = select(
+            &faces,
+            FontWeight::BOLD,
+            FontStretch::NORMAL,
+            FontStyle::Italic,
+        );
+        assert_eq!(instance.font_name, "Bold");
+        assert!(instance.synthetic_oblique);
+        assert!(!instance.is_default());
+    }
+
+    #[test]
+    fn select_variable_face() {
+        let mut variable = face(400, 100, FontStyle::Normal);
+        variable.axes = vec![
+            axis(b"wght", 100.0, 400.0, 900.0),
+            axis(b"wdth", 75.0, 100.0, 100.0),
+            axis(b"slnt", -15.0, 0.0, 0.0),
+        ];
+        let faces = [
+            ("Bold", face(700, 100, FontStyle::Normal)),
+            ("Variable", variable),
+        ];
+
+        // The axis can reach any weight, so it beats the static face:
+        let instance = select(
+            &faces,
+            FontWeight(650),
+            FontStretch::CONDENSED,
+            FontStyle::Italic,
+        );
+        assert_eq!(instance.font_name, "Variable");
+        assert_eq!(
+            instance.variations,
+            [
+                (*b"wght", 650.0),
+                (*b"wdth", 75.0),
+                (*b"slnt", OBLIQUE_ANGLE)
+            ]
+        );
+        assert!(!instance.synthetic_oblique);
+
+        // Values are clamped to the axis range, and default values are left out:
+        let instance = select(
+            &faces,
+            FontWeight::NORMAL,
+            FontStretch::EXPANDED,
+            FontStyle::Normal,
+        );
+        assert_eq!(instance.font_name, "Variable");
+        assert!(instance.is_default());
+    }
+
+    #[test]
+    fn font_definitions_faces() {
+        use crate::text::{FontDefinitions, FontFamily, FontId, Fonts};
+
+        // Ubuntu-Light is light, and Hack (monospace) is regular:
+        let mut definitions = FontDefinitions::default();
+        definitions
+            .families
+            .insert(FontFamily::Proportional, vec!["Ubuntu-Light".to_owned()]);
+        definitions
+            .faces
+            .insert("Ubuntu-Light".to_owned(), vec!["Hack".to_owned()]);
+        let fonts = Fonts::new(1.0, 1024, definitions);
+
+        let is_monospace =
+            |font_id: FontId| fonts.glyph_width(&font_id, 'i') == fonts.glyph_width(&font_id, 'W');
+        assert!(is_monospace(FontId::proportional(14.0)));
+        assert!(is_monospace(
+            FontId::proportional(14.0).weight(FontWeight::BOLD)
+        ));
+        assert!(!is_monospace(
+            FontId::proportional(14.0).weight(FontWeight::LIGHT)
+        ));
+    }
+
+    #[test]
+    fn varied_font_outline() {
+        use ab_glyph::Font as _;
+
+        let hack: &[u8] = include_bytes!("../../fonts/Hack-Regular.ttf");
+        let font = ab_glyph::FontArc::try_from_slice(hack).unwrap();
+        let font_data = Arc::new(FontData::from_static(hack));
+        let instance = FaceInstance {
+            font_name: "Hack".to_owned(),
+            variations: vec![],
+            synthetic_oblique: true,
+        };
+        let slanted = VariedFont::new(font.clone(), font_data, &instance);
+
+        let id = font.glyph_id('l');
+        assert_eq!(slanted.h_advance_unscaled(id), font.h_advance_unscaled(id));
+        let upright = font.outline(id).unwrap().bounds;
+        let slanted = slanted.outline(id).unwrap().bounds;
+        assert_eq!(upright.min.y, slanted.min.y);
+        assert!(slanted.max.x > upright.max.x, "the top leans to the right");
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     mutex::{Mutex, RwLock},
+    text::sdf::SdfGlyphs,
     TextureAtlas,
 };
 use ahash::AHashMap;
@@ -24,6 +25,11 @@ pub struct UvRect {
 
     /// Bottom right corner (exclusive).
     pub max: [u16; 2],
+
+    /// Is this a color glyph (e.g. an emoji)?
+    ///
+    /// Color glyphs are painted with their own colors instead of the text color.
+    pub is_color: bool,
 }
 
 impl UvRect {
@@ -55,6 +61,10 @@ impl Default for GlyphInfo {
 
 // ----------------------------------------------------------------------------
 
+/// The glyphs of a cluster and their offsets in whole pixels.
+#[cfg(feature = "shaping")]
+type ClusterKey = Vec<(ab_glyph::GlyphId, [i32; 2])>;
+
 /// A specific font with a size.
 /// The interface uses points as the unit for everything.
 pub struct FontImpl {
@@ -68,6 +78,27 @@ pub struct FontImpl {
     pixels_per_point: f32,
     glyph_info_cache: RwLock<AHashMap<char, GlyphInfo>>, // TODO(emilk): standard Mutex
     atlas: Arc<Mutex<TextureAtlas>>,
+
+    /// The font file, used for shaping.
+    ///
+    /// A [`rustybuzz::Face`] borrows the font file, so it is parsed for each shaped run.
+    #[cfg(feature = "shaping")]
+    font_data: Arc<crate::text::FontData>,
+
+    /// Variation axes to set when shaping.
+    #[cfg(feature = "shaping")]
+    variations: Vec<rustybuzz::Variation>,
+
+    /// Glyphs produced by the shaper, by glyph id.
+    #[cfg(feature = "shaping")]
+    glyph_id_cache: RwLock<AHashMap<ab_glyph::GlyphId, GlyphInfo>>,
+
+    /// Multi-glyph clusters (e.g. a base and its marks), rasterized together.
+    #[cfg(feature = "shaping")]
+    cluster_cache: RwLock<AHashMap<ClusterKey, UvRect>>,
+
+    /// With [`crate::text::GlyphRendering::Sdf`]: the glyphs shared with the other sizes of this face.
+    sdf_glyphs: Option<Arc<SdfGlyphs>>,
 }
 
 impl FontImpl {
@@ -76,6 +107,7 @@ impl FontImpl {
         pixels_per_point: f32,
         name: String,
         ab_glyph_font: ab_glyph::FontArc,
+        #[cfg(feature = "shaping")] font_data: Arc<crate::text::FontData>,
         scale_in_pixels: u32,
         y_offset_points: f32,
     ) -> FontImpl {
@@ -103,6 +135,60 @@ impl FontImpl {
             pixels_per_point,
             glyph_info_cache: Default::default(),
             atlas,
+            #[cfg(feature = "shaping")]
+            font_data,
+            #[cfg(feature = "shaping")]
+            variations: vec![],
+            #[cfg(feature = "shaping")]
+            glyph_id_cache: Default::default(),
+            #[cfg(feature = "shaping")]
+            cluster_cache: Default::default(),
+            sdf_glyphs: None,
+        }
+    }
+
+    /// Set the axes of a variable font, for shaping.
+    #[cfg(feature = "shaping")]
+    pub(crate) fn set_variations(&mut self, variations: Vec<rustybuzz::Variation>) {
+        self.variations = variations;
+    }
+
+    /// Use glyphs rasterized as signed distance fields, shared with the other sizes of this face.
+    pub(crate) fn set_sdf_glyphs(&mut self, sdf_glyphs: Arc<SdfGlyphs>) {
+        self.sdf_glyphs = Some(sdf_glyphs);
+    }
+
+    fn allocate_glyph(&self, glyph_id: ab_glyph::GlyphId) -> GlyphInfo {
+        if let Some(sdf_glyphs) = &self.sdf_glyphs {
+            use ab_glyph::{Font as _, ScaleFont};
+            let scale_in_pixels = self.scale_in_pixels as f32;
+            let uv_rect = sdf_glyphs.uv_rect(
+                &mut self.atlas.lock(),
+                &self.ab_glyph_font,
+                &[(glyph_id, Vec2::ZERO)],
+                scale_in_pixels,
+                self.y_offset,
+                self.pixels_per_point,
+            );
+            let advance_width = self
+                .ab_glyph_font
+                .as_scaled(scale_in_pixels)
+                .h_advance(glyph_id)
+                / self.pixels_per_point;
+            GlyphInfo {
+                id: glyph_id,
+                advance_width,
+                uv_rect,
+            }
+        } else {
+            allocate_glyph(
+                &mut self.atlas.lock(),
+                &self.ab_glyph_font,
+                glyph_id,
+                self.scale_in_pixels as f32,
+                self.y_offset,
+                self.pixels_per_point,
+            )
         }
     }
 
@@ -171,15 +257,7 @@ impl FontImpl {
                 None // unsupported character
             }
         } else {
-            let glyph_info = allocate_glyph(
-                &mut self.atlas.lock(),
-                &self.ab_glyph_font,
-                glyph_id,
-                self.scale_in_pixels as f32,
-                self.y_offset,
-                self.pixels_per_point,
-            );
-
+            let glyph_info = self.allocate_glyph(glyph_id);
             self.glyph_info_cache.write().insert(c, glyph_info);
             Some(glyph_info)
         }
@@ -198,6 +276,112 @@ impl FontImpl {
             / self.pixels_per_point
     }
 
+    /// Does this font have a glyph for this character?
+    #[cfg(feature = "shaping")]
+    pub(crate) fn has_glyph(&self, c: char) -> bool {
+        use ab_glyph::Font as _;
+        !self.ignore_character(c) && self.ab_glyph_font.glyph_id(c).0 != 0
+    }
+
+    /// Shape a run of text that is all set in this font, and all in the same direction.
+    ///
+    /// The glyphs are returned in visual order (left to right).
+    #[cfg(feature = "shaping")]
+    pub(crate) fn shape(&self, text: &str, rtl: bool) -> Vec<ShapedGlyph> {
+        use ab_glyph::Font as _;
+
+        let mut face = match rustybuzz::Face::from_slice(&self.font_data.font, self.font_data.index)
+        {
+            Some(face) => face,
+            None => return vec![],
+        };
+        face.set_variations(&self.variations);
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        buffer.set_direction(if rtl {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+        let glyph_buffer = rustybuzz::shape(&face, &[], buffer);
+
+        // Same scale as `ab_glyph::Font::as_scaled` uses:
+        let points_per_unit = self.scale_in_pixels as f32
+            / self.ab_glyph_font.height_unscaled()
+            / self.pixels_per_point;
+
+        glyph_buffer
+            .glyph_infos()
+            .iter()
+            .zip(glyph_buffer.glyph_positions())
+            .map(|(info, pos)| ShapedGlyph {
+                id: ab_glyph::GlyphId(info.glyph_id as u16),
+                cluster: info.cluster as usize,
+                advance: pos.x_advance as f32 * points_per_unit,
+                offset: vec2(pos.x_offset as f32, -pos.y_offset as f32) * points_per_unit,
+            })
+            .collect()
+    }
+
+    /// Where in the atlas is this glyph (as picked by the shaper)?
+    #[cfg(feature = "shaping")]
+    pub(crate) fn glyph_uv_rect(&self, glyph_id: ab_glyph::GlyphId) -> UvRect {
+        if glyph_id.0 == 0 {
+            return UvRect::default();
+        }
+
+        if let Some(glyph_info) = self.glyph_id_cache.read().get(&glyph_id) {
+            return glyph_info.uv_rect;
+        }
+
+        let glyph_info = self.allocate_glyph(glyph_id);
+        self.glyph_id_cache.write().insert(glyph_id, glyph_info);
+        glyph_info.uv_rect
+    }
+
+    /// Rasterize several glyphs (with offsets in points) into one atlas entry.
+    ///
+    /// Used for clusters that consist of more than one glyph.
+    #[cfg(feature = "shaping")]
+    pub(crate) fn cluster_uv_rect(&self, glyphs: &[(ab_glyph::GlyphId, Vec2)]) -> UvRect {
+        if let Some(sdf_glyphs) = &self.sdf_glyphs {
+            return sdf_glyphs.uv_rect(
+                &mut self.atlas.lock(),
+                &self.ab_glyph_font,
+                glyphs,
+                self.scale_in_pixels as f32,
+                self.y_offset,
+                self.pixels_per_point,
+            );
+        }
+
+        let key: ClusterKey = glyphs
+            .iter()
+            .filter(|(id, _)| id.0 != 0)
+            .map(|&(id, offset)| {
+                let offset = offset * self.pixels_per_point;
+                (id, [offset.x.round() as i32, offset.y.round() as i32])
+            })
+            .collect();
+
+        if let Some(uv_rect) = self.cluster_cache.read().get(&key) {
+            return *uv_rect;
+        }
+
+        let uv_rect = allocate_cluster(
+            &mut self.atlas.lock(),
+            &self.ab_glyph_font,
+            &key,
+            self.scale_in_pixels as f32,
+            self.y_offset,
+            self.pixels_per_point,
+        );
+        self.cluster_cache.write().insert(key, uv_rect);
+        uv_rect
+    }
+
     /// Height of one row of text. In points
     #[inline(always)]
     pub fn row_height(&self) -> f32 {
@@ -210,7 +394,23 @@ impl FontImpl {
     }
 }
 
-type FontIndex = usize;
+/// A glyph positioned by the shaper.
+#[cfg(feature = "shaping")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ShapedGlyph {
+    pub id: ab_glyph::GlyphId,
+
+    /// Byte offset into the shaped text of the first character of the cluster this glyph belongs to.
+    pub cluster: usize,
+
+    /// How much to move the pen after this glyph. Unit: points.
+    pub advance: f32,
+
+    /// Where to draw the glyph relative to the pen. Positive y is down. Unit: points.
+    pub offset: Vec2,
+}
+
+pub(crate) type FontIndex = usize;
 
 // TODO(emilk): rename?
 /// Wrapper over multiple [`FontImpl`] (e.g. a primary + fallbacks for emojis)
@@ -222,6 +422,10 @@ pub struct Font {
     pixels_per_point: f32,
     row_height: f32,
     glyph_info_cache: AHashMap<char, (FontIndex, GlyphInfo)>,
+
+    /// Where to look for characters none of [`Self::fonts`] have.
+    #[cfg(feature = "system_fonts")]
+    system_fallback: Option<crate::text::fonts::SystemFallback>,
 }
 
 impl Font {
@@ -234,6 +438,8 @@ impl Font {
                 pixels_per_point: 1.0,
                 row_height: 0.0,
                 glyph_info_cache: Default::default(),
+                #[cfg(feature = "system_fonts")]
+                system_fallback: None,
             };
         }
 
@@ -247,6 +453,8 @@ impl Font {
             pixels_per_point,
             row_height,
             glyph_info_cache: Default::default(),
+            #[cfg(feature = "system_fonts")]
+            system_fallback: None,
         };
 
         const PRIMARY_REPLACEMENT_CHAR: char = '◻'; // white medium square
@@ -266,6 +474,16 @@ impl Font {
         slf
     }
 
+    /// Look for missing characters among the fonts installed on the system,
+    /// and add the fonts that have them as fallbacks.
+    #[cfg(feature = "system_fonts")]
+    pub(crate) fn set_system_fallback(
+        &mut self,
+        system_fallback: crate::text::fonts::SystemFallback,
+    ) {
+        self.system_fallback = Some(system_fallback);
+    }
+
     pub fn preload_common_characters(&mut self) {
         // Preload the printable ASCII characters [32, 126] (which excludes control codes):
         const FIRST_ASCII: usize = 32; // 32 == space
@@ -318,6 +536,9 @@ impl Font {
         }
 
         let font_index_glyph_info = self.glyph_info_no_cache_or_fallback(c);
+        #[cfg(feature = "system_fonts")]
+        let font_index_glyph_info =
+            font_index_glyph_info.or_else(|| self.glyph_info_from_system_fonts(c));
         let font_index_glyph_info = font_index_glyph_info.unwrap_or(self.replacement_glyph);
         self.glyph_info_cache.insert(c, font_index_glyph_info);
         font_index_glyph_info
@@ -333,6 +554,40 @@ impl Font {
         (Some(font_impl), glyph_info)
     }
 
+    /// Which font (of the primary and its fallbacks) has a glyph for this character?
+    ///
+    /// `None` means none of them do, and the replacement glyph will be used.
+    #[cfg(feature = "shaping")]
+    pub(crate) fn font_index_for_char(&self, c: char) -> Option<FontIndex> {
+        self.fonts
+            .iter()
+            .position(|font_impl| font_impl.has_glyph(c))
+    }
+
+    #[cfg(feature = "shaping")]
+    pub(crate) fn font_impl(&self, font_index: FontIndex) -> &FontImpl {
+        &self.fonts[font_index]
+    }
+
+    /// Append a system font with a glyph for this character to the fallbacks.
+    #[cfg(feature = "system_fonts")]
+    fn glyph_info_from_system_fonts(&mut self, c: char) -> Option<(FontIndex, GlyphInfo)> {
+        if c == '\n' || c == '\t' || self.fonts.is_empty() {
+            return None;
+        }
+        let font_impl = self.system_fallback.as_ref()?.font_impl_for(c)?;
+        if self.fonts.iter().any(|font| Arc::ptr_eq(font, &font_impl)) {
+            return None; // already a fallback
+        }
+        let glyph_info = font_impl.glyph_info(c)?;
+        self.fonts.push(font_impl);
+        self.characters = None;
+
+        let font_index = self.fonts.len() - 1;
+        self.glyph_info_cache.insert(c, (font_index, glyph_info));
+        Some((font_index, glyph_info))
+    }
+
     fn glyph_info_no_cache_or_fallback(&mut self, c: char) -> Option<(FontIndex, GlyphInfo)> {
         for (font_index, font_impl) in self.fonts.iter().enumerate() {
             if let Some(glyph_info) = font_impl.glyph_info(c) {
@@ -366,34 +621,49 @@ fn allocate_glyph(
     let glyph =
         glyph_id.with_scale_and_position(scale_in_pixels, ab_glyph::Point { x: 0.0, y: 0.0 });
 
-    let uv_rect = font.outline_glyph(glyph).map(|glyph| {
-        let bb = glyph.px_bounds();
-        let glyph_width = bb.width() as usize;
-        let glyph_height = bb.height() as usize;
-        if glyph_width == 0 || glyph_height == 0 {
-            UvRect::default()
-        } else {
-            let (glyph_pos, image) = atlas.allocate((glyph_width, glyph_height));
-            glyph.draw(|x, y, v| {
-                if v > 0.0 {
-                    let px = glyph_pos.0 + x as usize;
-                    let py = glyph_pos.1 + y as usize;
-                    image[(px, py)] = v;
+    #[cfg(feature = "color_emoji")]
+    let color_uv_rect = allocate_color_glyph(
+        atlas,
+        font,
+        glyph_id,
+        scale_in_pixels,
+        y_offset,
+        pixels_per_point,
+    );
+    #[cfg(not(feature = "color_emoji"))]
+    let color_uv_rect = None;
+
+    let uv_rect = color_uv_rect.or_else(|| {
+        font.outline_glyph(glyph).map(|glyph| {
+            let bb = glyph.px_bounds();
+            let glyph_width = bb.width() as usize;
+            let glyph_height = bb.height() as usize;
+            if glyph_width == 0 || glyph_height == 0 {
+                UvRect::default()
+            } else {
+                let (glyph_pos, image) = atlas.allocate((glyph_width, glyph_height));
+                glyph.draw(|x, y, v| {
+                    if v > 0.0 {
+                        let px = glyph_pos.0 + x as usize;
+                        let py = glyph_pos.1 + y as usize;
+                        image[(px, py)] = v;
+                    }
+                });
+
+                let offset_in_pixels = vec2(bb.min.x, scale_in_pixels + bb.min.y);
+                let offset = offset_in_pixels / pixels_per_point + y_offset * Vec2::Y;
+                UvRect {
+                    offset,
+                    size: vec2(glyph_width as f32, glyph_height as f32) / pixels_per_point,
+                    min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+                    max: [
+                        (glyph_pos.0 + glyph_width) as u16,
+                        (glyph_pos.1 + glyph_height) as u16,
+                    ],
+                    is_color: false,
                 }
-            });
-
-            let offset_in_pixels = vec2(bb.min.x, scale_in_pixels + bb.min.y);
-            let offset = offset_in_pixels / pixels_per_point + y_offset * Vec2::Y;
-            UvRect {
-                offset,
-                size: vec2(glyph_width as f32, glyph_height as f32) / pixels_per_point,
-                min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
-                max: [
-                    (glyph_pos.0 + glyph_width) as u16,
-                    (glyph_pos.1 + glyph_height) as u16,
-                ],
             }
-        }
+        })
     });
     let uv_rect = uv_rect.unwrap_or_default();
 
@@ -406,3 +676,183 @@ fn allocate_glyph(
         uv_rect,
     }
 }
+
+/// Rasterize glyphs placed at the given pixel offsets into a single atlas region.
+#[cfg(feature = "shaping")]
+fn allocate_cluster(
+    atlas: &mut TextureAtlas,
+    font: &ab_glyph::FontArc,
+    glyphs: &[(ab_glyph::GlyphId, [i32; 2])],
+    scale_in_pixels: f32,
+    y_offset: f32,
+    pixels_per_point: f32,
+) -> UvRect {
+    use ab_glyph::Font as _;
+
+    let outlined: Vec<ab_glyph::OutlinedGlyph> = glyphs
+        .iter()
+        .filter_map(|&(glyph_id, [x, y])| {
+            let position = ab_glyph::Point {
+                x: x as f32,
+                y: y as f32,
+            };
+            font.outline_glyph(glyph_id.with_scale_and_position(scale_in_pixels, position))
+        })
+        .collect();
+
+    let bb = outlined
+        .iter()
+        .map(|glyph| glyph.px_bounds())
+        .reduce(|a, b| ab_glyph::Rect {
+            min: ab_glyph::Point {
+                x: a.min.x.min(b.min.x),
+                y: a.min.y.min(b.min.y),
+            },
+            max: ab_glyph::Point {
+                x: a.max.x.max(b.max.x),
+                y: a.max.y.max(b.max.y),
+            },
+        });
+    let bb = match bb {
+        Some(bb) => bb,
+        None => return UvRect::default(),
+    };
+
+    let cluster_width = bb.width() as usize;
+    let cluster_height = bb.height() as usize;
+    if cluster_width == 0 || cluster_height == 0 {
+        return UvRect::default();
+    }
+
+    let (cluster_pos, image) = atlas.allocate((cluster_width, cluster_height));
+    for glyph in &outlined {
+        let glyph_bb = glyph.px_bounds();
+        let dx = (glyph_bb.min.x - bb.min.x) as usize;
+        let dy = (glyph_bb.min.y - bb.min.y) as usize;
+        glyph.draw(|x, y, v| {
+            if v > 0.0 {
+                let px = cluster_pos.0 + dx + x as usize;
+                let py = cluster_pos.1 + dy + y as usize;
+                if px < cluster_pos.0 + cluster_width && py < cluster_pos.1 + cluster_height {
+                    let coverage = &mut image[(px, py)];
+                    *coverage = coverage.max(v);
+                }
+            }
+        });
+    }
+
+    let offset_in_pixels = vec2(bb.min.x, scale_in_pixels + bb.min.y);
+    let offset = offset_in_pixels / pixels_per_point + y_offset * Vec2::Y;
+    UvRect {
+        offset,
+        size: vec2(cluster_width as f32, cluster_height as f32) / pixels_per_point,
+        min: [cluster_pos.0 as u16, cluster_pos.1 as u16],
+        max: [
+            (cluster_pos.0 + cluster_width) as u16,
+            (cluster_pos.1 + cluster_height) as u16,
+        ],
+        is_color: false,
+    }
+}
+
+/// Copy the embedded bitmap of a color glyph (e.g. an emoji) into the atlas, if it has one.
+#[cfg(feature = "color_emoji")]
+pub(crate) fn allocate_color_glyph(
+    atlas: &mut TextureAtlas,
+    font: &ab_glyph::FontArc,
+    glyph_id: ab_glyph::GlyphId,
+    scale_in_pixels: f32,
+    y_offset: f32,
+    pixels_per_point: f32,
+) -> Option<UvRect> {
+    use ab_glyph::Font as _;
+
+    // Same scale as `ab_glyph::Font::as_scaled` uses:
+    let pixels_per_em = scale_in_pixels * font.units_per_em()? / font.height_unscaled();
+
+    let glyph_image = font.glyph_raster_image(glyph_id, pixels_per_em.ceil() as u16)?;
+    let bitmap = match glyph_image.format {
+        ab_glyph::GlyphImageFormat::Png => decode_png(glyph_image.data)?,
+        _ => return None,
+    };
+
+    // The bitmap comes from the strike closest to our size, and must be scaled to fit:
+    let scale = pixels_per_em / glyph_image.scale;
+    let glyph_width = (bitmap.width() as f32 * scale).round() as usize;
+    let glyph_height = (bitmap.height() as f32 * scale).round() as usize;
+    if glyph_width == 0 || glyph_height == 0 {
+        return None;
+    }
+
+    let (glyph_pos, image) = atlas.allocate((glyph_width, glyph_height));
+    let [src_w, src_h] = bitmap.size;
+    for y in 0..glyph_height {
+        // Box filter:
+        let src_y = (y * src_h / glyph_height)..div_ceil((y + 1) * src_h, glyph_height);
+        for x in 0..glyph_width {
+            let src_x = (x * src_w / glyph_width)..div_ceil((x + 1) * src_w, glyph_width);
+            let mut sum = crate::Rgba::TRANSPARENT;
+            for sy in src_y.clone() {
+                for sx in src_x.clone() {
+                    sum = sum + crate::Rgba::from(bitmap[(sx, sy)]);
+                }
+            }
+            let num_texels = (src_x.len() * src_y.len()) as f32;
+            image.set_color(
+                (glyph_pos.0 + x, glyph_pos.1 + y),
+                (sum * (1.0 / num_texels)).into(),
+            );
+        }
+    }
+
+    // The image origin is its bottom left corner, relative to the baseline (y up):
+    let offset_in_pixels = vec2(
+        glyph_image.origin.x * scale,
+        scale_in_pixels - glyph_image.origin.y * scale - glyph_height as f32,
+    )
+    .round();
+    let offset = offset_in_pixels / pixels_per_point + y_offset * Vec2::Y;
+    Some(UvRect {
+        offset,
+        size: vec2(glyph_width as f32, glyph_height as f32) / pixels_per_point,
+        min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+        max: [
+            (glyph_pos.0 + glyph_width) as u16,
+            (glyph_pos.1 + glyph_height) as u16,
+        ],
+        is_color: true,
+    })
+}
+
+#[cfg(feature = "color_emoji")]
+fn div_ceil(numerator: usize, denominator: usize) -> usize {
+    (numerator + denominator - 1) / denominator
+}
+
+/// Decode a PNG glyph bitmap into premultiplied colors.
+#[cfg(feature = "color_emoji")]
+fn decode_png(data: &[u8]) -> Option<crate::ColorImage> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    let buf = &buf[..info.buffer_size()];
+
+    let rgba: Vec<u8> = match info.color_type {
+        png::ColorType::Rgba => buf.to_vec(),
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        png::ColorType::Indexed => return None, // expanded by `normalize_to_color8`
+    };
+
+    let size = [info.width as usize, info.height as usize];
+    Some(crate::ColorImage::from_rgba_unmultiplied(size, &rgba))
+}
@@ -4,7 +4,9 @@ use std::sync::Arc;
 use crate::{
     mutex::{Mutex, MutexGuard},
     text::{
+        face::{FaceInfo, FaceInstance, VariedFont},
         font::{Font, FontImpl},
+        sdf::SdfGlyphs,
         Galley, LayoutJob,
     },
     TextureAtlas,
@@ -22,23 +24,46 @@ pub struct FontId {
 
     /// What font family to use.
     pub family: FontFamily,
-    // TODO(emilk): weight (bold), italics, …
+
+    /// How bold the text should be.
+    ///
+    /// Picks the closest face in [`FontDefinitions::faces`],
+    /// or sets the `wght` axis of a variable font.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weight: FontWeight,
+
+    /// How wide the text should be.
+    ///
+    /// Picks the closest face in [`FontDefinitions::faces`],
+    /// or sets the `wdth` axis of a variable font.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stretch: FontStretch,
+
+    /// Upright or italic.
+    ///
+    /// Picks an italic face in [`FontDefinitions::faces`], or sets the `ital` or `slnt` axis of a variable font.
+    /// If there is no italic face, the upright one is slanted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub style: FontStyle,
 }
 
 impl Default for FontId {
     #[inline]
     fn default() -> Self {
-        Self {
-            size: 14.0,
-            family: FontFamily::Proportional,
-        }
+        Self::new(14.0, FontFamily::Proportional)
     }
 }
 
 impl FontId {
     #[inline]
     pub const fn new(size: f32, family: FontFamily) -> Self {
-        Self { size, family }
+        Self {
+            size,
+            family,
+            weight: FontWeight::NORMAL,
+            stretch: FontStretch::NORMAL,
+            style: FontStyle::Normal,
+        }
     }
 
     #[inline]
@@ -50,15 +75,117 @@ impl FontId {
     pub const fn monospace(size: f32) -> Self {
         Self::new(size, FontFamily::Monospace)
     }
+
+    #[inline]
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    #[inline]
+    pub fn stretch(mut self, stretch: FontStretch) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    #[inline]
+    pub fn style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+        self
+    }
 }
 
 #[allow(clippy::derive_hash_xor_eq)]
 impl std::hash::Hash for FontId {
     #[inline(always)]
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        let Self { size, family } = self;
+        let Self {
+            size,
+            family,
+            weight,
+            stretch,
+            style,
+        } = self;
         crate::f32_hash(state, *size);
         family.hash(state);
+        weight.hash(state);
+        stretch.hash(state);
+        style.hash(state);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// How thick the strokes of a font are.
+///
+/// Same scale as the CSS `font-weight` and the OpenType `wght` axis:
+/// from 100 (thin) over 400 (normal) and 700 (bold) to 900 (black).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const THIN: Self = Self(100);
+    pub const EXTRA_LIGHT: Self = Self(200);
+    pub const LIGHT: Self = Self(300);
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMI_BOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+    pub const EXTRA_BOLD: Self = Self(800);
+    pub const BLACK: Self = Self(900);
+}
+
+impl Default for FontWeight {
+    #[inline]
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// How wide a font is, in percent of the normal width.
+///
+/// Same scale as the CSS `font-stretch` and the OpenType `wdth` axis.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FontStretch(pub u16);
+
+impl FontStretch {
+    pub const ULTRA_CONDENSED: Self = Self(50);
+    pub const EXTRA_CONDENSED: Self = Self(62);
+    pub const CONDENSED: Self = Self(75);
+    pub const SEMI_CONDENSED: Self = Self(87);
+    pub const NORMAL: Self = Self(100);
+    pub const SEMI_EXPANDED: Self = Self(112);
+    pub const EXPANDED: Self = Self(125);
+    pub const EXTRA_EXPANDED: Self = Self(150);
+    pub const ULTRA_EXPANDED: Self = Self(200);
+}
+
+impl Default for FontStretch {
+    #[inline]
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Upright or slanted text.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FontStyle {
+    Normal,
+
+    /// A cursive, slanted design.
+    Italic,
+
+    /// The upright design, slanted.
+    Oblique,
+}
+
+impl Default for FontStyle {
+    #[inline]
+    fn default() -> Self {
+        Self::Normal
     }
 }
 
@@ -199,6 +326,32 @@ fn ab_glyph_font_from_font_data(name: &str, data: &FontData) -> ab_glyph::FontAr
     .unwrap_or_else(|err| panic!("Error parsing {:?} TTF/OTF font file: {}", name, err))
 }
 
+/// How glyphs are put into the font atlas.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GlyphRendering {
+    /// Rasterize each glyph for every font size and `pixels_per_point` it is used at.
+    ///
+    /// This gives the sharpest small text, and works with every painter.
+    Bitmap,
+
+    /// Rasterize each glyph once, as a signed distance field, and use that for all sizes.
+    ///
+    /// Text stays sharp when scaled or zoomed, and animating the font size doesn't fill up the atlas.
+    /// Small text is a bit softer than with [`Self::Bitmap`].
+    ///
+    /// The font texture is then a signed distance field (see [`crate::FontImage::sdf`]),
+    /// which the painter must support (`egui_glow` and `egui-wgpu` do).
+    Sdf,
+}
+
+impl Default for GlyphRendering {
+    #[inline]
+    fn default() -> Self {
+        Self::Bitmap
+    }
+}
+
 /// Describes the font data and the sizes to use.
 ///
 /// Often you would start with [`FontDefinitions::default()`] and then add/change the contents.
@@ -241,6 +394,33 @@ pub struct FontDefinitions {
     /// the first font and then move to the second, and so on.
     /// So the first font is the primary, and then comes a list of fallbacks in order of priority.
     pub families: BTreeMap<FontFamily, Vec<String>>,
+
+    /// Other faces (bold, italic, condensed, …) of the fonts in [`Self::families`].
+    ///
+    /// Both the keys and the values are keys into [`Self::font_data`].
+    /// The weight, stretch and style of each face is read from its font file.
+    /// When a [`FontId`] asks for e.g. [`FontWeight::BOLD`], the closest face is used.
+    ///
+    /// A variable font doesn't need any other faces:
+    /// its `wght`, `wdth`, `ital` and `slnt` axes are set to match the [`FontId`].
+    ///
+    /// ```ignore
+    /// fonts.faces.insert("Inter".to_owned(), vec!["Inter-Bold".to_owned(), "Inter-Italic".to_owned()]);
+    /// ```
+    pub faces: BTreeMap<String, Vec<String>>,
+
+    /// Fonts installed on the system, used as a last fallback for characters
+    /// that none of the fonts in [`Self::families`] have.
+    ///
+    /// ```ignore
+    /// fonts.system_fonts = Some(epaint::text::SystemFonts::load());
+    /// ```
+    #[cfg(feature = "system_fonts")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub system_fonts: Option<super::SystemFonts>,
+
+    /// Bitmaps per font size, or one signed distance field per glyph.
+    pub glyph_rendering: GlyphRendering,
 }
 
 impl Default for FontDefinitions {
@@ -307,6 +487,10 @@ impl Default for FontDefinitions {
         Self {
             font_data,
             families,
+            faces: Default::default(),
+            #[cfg(feature = "system_fonts")]
+            system_fonts: None,
+            glyph_rendering: Default::default(),
         }
     }
 }
@@ -321,6 +505,26 @@ impl FontDefinitions {
         Self {
             font_data: Default::default(),
             families,
+            faces: Default::default(),
+            #[cfg(feature = "system_fonts")]
+            system_fonts: None,
+            glyph_rendering: Default::default(),
+        }
+    }
+
+    /// Add the installed system font family with this name (e.g. `"Noto Sans CJK JP"`),
+    /// as a [`FontFamily::Name`] of the same name.
+    ///
+    /// Returns `false` if there is no such font installed.
+    #[cfg(feature = "system_fonts")]
+    pub fn add_system_font(&mut self, system_fonts: &super::SystemFonts, family: &str) -> bool {
+        if let Some(font_data) = system_fonts.font_data(family) {
+            self.font_data.insert(family.to_owned(), font_data);
+            self.families
+                .insert(FontFamily::Name(family.into()), vec![family.to_owned()]);
+            true
+        } else {
+            false
         }
     }
 }
@@ -332,10 +536,11 @@ impl FontDefinitions {
 /// Required in order to paint text. Create one and reuse. Cheap to clone.
 ///
 /// Each [`Fonts`] comes with a font atlas textures that needs to be used when painting.
+/// When it gets full, the atlas uses more textures (see [`TextureAtlas::texture_id`]).
 ///
 /// If you are using `egui`, use `egui::Context::set_fonts` and `egui::Context::fonts`.
 ///
-/// You need to call [`Self::begin_frame`] and [`Self::font_image_delta`] once every frame.
+/// You need to call [`Self::begin_frame`] and [`Self::font_image_deltas`] once every frame.
 pub struct Fonts(Arc<Mutex<FontsAndCache>>);
 
 impl Fonts {
@@ -363,15 +568,23 @@ impl Fonts {
     ///
     /// This function will react to changes in `pixels_per_point` and `max_texture_side`,
     /// as well as notice when the font atlas is getting full, and handle that.
+    ///
+    /// When the atlas is getting full, new glyphs are put on another atlas page,
+    /// and the least recently used page that no [`Galley`] is on may be cleared.
+    /// If every page has a live [`Galley`] on it, the atlas is recreated,
+    /// so drop the galleys you no longer need.
     pub fn begin_frame(&self, pixels_per_point: f32, max_texture_side: usize) {
         let mut fonts_and_cache = self.0.lock();
 
         let pixels_per_point_changed =
             (fonts_and_cache.fonts.pixels_per_point - pixels_per_point).abs() > 1e-3;
         let max_texture_side_changed = fonts_and_cache.fonts.max_texture_side != max_texture_side;
-        let font_atlas_almost_full = fonts_and_cache.fonts.atlas.lock().fill_ratio() > 0.8;
-        let needs_recreate =
-            pixels_per_point_changed || max_texture_side_changed || font_atlas_almost_full;
+        let mut needs_recreate = pixels_per_point_changed || max_texture_side_changed;
+
+        if !needs_recreate {
+            fonts_and_cache.galley_cache.flush_cache();
+            needs_recreate = !fonts_and_cache.make_room_in_atlas();
+        }
 
         if needs_recreate {
             let definitions = fonts_and_cache.fonts.definitions.clone();
@@ -381,13 +594,31 @@ impl Fonts {
                 galley_cache: Default::default(),
             };
         }
+    }
 
-        fonts_and_cache.galley_cache.flush_cache();
+    /// Call at the end of each frame (before painting) to get the changes to the font textures since last call.
+    ///
+    /// `texture_id_of_page` is called when a new atlas page is used for the first time,
+    /// to pick the texture that shows it (see [`TextureAtlas::set_texture_id`]).
+    /// The first page is always [`crate::TextureId::default`].
+    pub fn font_image_deltas(
+        &self,
+        texture_id_of_page: impl FnMut(usize) -> crate::TextureId,
+    ) -> Vec<(crate::TextureId, crate::ImageDelta)> {
+        self.lock()
+            .fonts
+            .atlas
+            .lock()
+            .take_deltas(texture_id_of_page)
     }
 
     /// Call at the end of each frame (before painting) to get the change to the font texture since last call.
+    ///
+    /// This only includes the first page of the font atlas,
+    /// so text that doesn't fit on it will not be painted correctly.
+    #[deprecated = "Use font_image_deltas instead, which includes all pages of the font atlas"]
     pub fn font_image_delta(&self) -> Option<crate::ImageDelta> {
-        self.lock().fonts.atlas.lock().take_delta()
+        self.lock().fonts.atlas.lock().take_page_delta(0)
     }
 
     /// Access the underlying [`FontsAndCache`].
@@ -413,7 +644,7 @@ impl Fonts {
         self.lock().fonts.atlas.clone()
     }
 
-    /// Current size of the font image.
+    /// Current size of the font image (of each page).
     /// Pass this to [`crate::Tessellator`].
     pub fn font_image_size(&self) -> [usize; 2] {
         self.lock().fonts.atlas.lock().size()
@@ -466,6 +697,11 @@ impl Fonts {
         self.lock().fonts.atlas.lock().fill_ratio()
     }
 
+    /// How many pages the font atlas uses, how full it is, etc.
+    pub fn font_atlas_stats(&self) -> crate::stats::AtlasStats {
+        self.lock().fonts.atlas.lock().stats()
+    }
+
     /// Will wrap text at the given width and line break at `\n`.
     ///
     /// The implementation uses memoization so repeated calls are cheap.
@@ -522,6 +758,24 @@ impl FontsAndCache {
     fn layout_job(&mut self, job: LayoutJob) -> Arc<Galley> {
         self.galley_cache.layout(&mut self.fonts, job)
     }
+
+    /// If the font atlas is getting full, switch to another page.
+    ///
+    /// Returns `false` if there is no room left, and the atlas needs to be recreated.
+    fn make_room_in_atlas(&mut self) -> bool {
+        let mut atlas = self.fonts.atlas.lock();
+        atlas.begin_frame();
+        if atlas.overflowed() {
+            false
+        } else if atlas.fill_ratio() > 0.8 {
+            let switched = atlas.switch_page();
+            drop(atlas);
+            self.fonts.forget_glyphs_on_page_switch();
+            switched
+        } else {
+            true
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -534,8 +788,8 @@ pub struct FontsImpl {
     max_texture_side: usize,
     definitions: FontDefinitions,
     atlas: Arc<Mutex<TextureAtlas>>,
-    font_impl_cache: FontImplCache,
-    sized_family: ahash::AHashMap<(u32, FontFamily), Font>,
+    font_impl_cache: Arc<Mutex<FontImplCache>>,
+    sized_family: ahash::AHashMap<(u32, FontFamily, FontWeight, FontStretch, FontStyle), Font>,
 }
 
 impl FontsImpl {
@@ -554,12 +808,18 @@ impl FontsImpl {
 
         let texture_width = max_texture_side.at_most(8 * 1024);
         let initial_height = 64;
-        let atlas = TextureAtlas::new([texture_width, initial_height]);
+        let atlas = match definitions.glyph_rendering {
+            GlyphRendering::Bitmap => TextureAtlas::new([texture_width, initial_height]),
+            GlyphRendering::Sdf => TextureAtlas::new_sdf([texture_width, initial_height]),
+        };
 
         let atlas = Arc::new(Mutex::new(atlas));
 
-        let font_impl_cache =
-            FontImplCache::new(atlas.clone(), pixels_per_point, &definitions.font_data);
+        let font_impl_cache = Arc::new(Mutex::new(FontImplCache::new(
+            atlas.clone(),
+            pixels_per_point,
+            &definitions,
+        )));
 
         Self {
             pixels_per_point,
@@ -581,25 +841,64 @@ impl FontsImpl {
         &self.definitions
     }
 
+    /// The font atlas page new glyphs are put on.
+    pub(crate) fn current_atlas_page(&self) -> crate::AtlasPageRef {
+        self.atlas.lock().current_page_ref()
+    }
+
+    /// If the font atlas switched page, forget where all glyphs are,
+    /// so that they are put on the new page when next used.
+    ///
+    /// Returns `true` if it did.
+    pub(crate) fn forget_glyphs_on_page_switch(&mut self) -> bool {
+        if self.atlas.lock().take_page_switch() {
+            self.font_impl_cache.lock().clear_glyphs();
+            self.sized_family.clear();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Get the right font implementation from size and [`FontFamily`].
     pub fn font(&mut self, font_id: &FontId) -> &mut Font {
-        let FontId { size, family } = font_id;
-        let scale_in_pixels = self.font_impl_cache.scale_as_pixels(*size);
+        let FontId {
+            size,
+            family,
+            weight,
+            stretch,
+            style,
+        } = font_id;
+        let scale_in_pixels = self.font_impl_cache.lock().scale_as_pixels(*size);
 
         self.sized_family
-            .entry((scale_in_pixels, family.clone()))
+            .entry((scale_in_pixels, family.clone(), *weight, *stretch, *style))
             .or_insert_with(|| {
                 let fonts = &self.definitions.families.get(family);
                 let fonts = fonts.unwrap_or_else(|| {
                     panic!("FontFamily::{:?} is not bound to any fonts", family)
                 });
 
+                let mut font_impl_cache = self.font_impl_cache.lock();
                 let fonts: Vec<Arc<FontImpl>> = fonts
                     .iter()
-                    .map(|font_name| self.font_impl_cache.font_impl(scale_in_pixels, font_name))
+                    .map(|font_name| font_impl_cache.font_impl(scale_in_pixels, font_name, font_id))
                     .collect();
 
-                Font::new(fonts)
+                #[allow(unused_mut)]
+                let mut font = Font::new(fonts);
+
+                #[cfg(feature = "system_fonts")]
+                if let Some(system_fonts) = &self.definitions.system_fonts {
+                    font.set_system_fallback(SystemFallback {
+                        system_fonts: system_fonts.clone(),
+                        font_impl_cache: self.font_impl_cache.clone(),
+                        scale_in_pixels,
+                        font_id: font_id.clone(),
+                    });
+                }
+
+                font
             })
     }
 
@@ -640,8 +939,7 @@ impl GalleyCache {
                 cached.galley.clone()
             }
             std::collections::hash_map::Entry::Vacant(entry) => {
-                let galley = super::layout(fonts, job.into());
-                let galley = Arc::new(galley);
+                let galley = Arc::new(super::layout(fonts, job.into()));
                 entry.insert(CachedGalley {
                     last_used: self.generation,
                     galley: galley.clone(),
@@ -667,38 +965,95 @@ impl GalleyCache {
 
 // ----------------------------------------------------------------------------
 
-struct FontImplCache {
+/// What a [`Font`] needs to add fallback fonts from the system as it goes.
+#[cfg(feature = "system_fonts")]
+pub(crate) struct SystemFallback {
+    pub system_fonts: super::SystemFonts,
+    pub font_impl_cache: Arc<Mutex<FontImplCache>>,
+    pub scale_in_pixels: u32,
+    pub font_id: FontId,
+}
+
+#[cfg(feature = "system_fonts")]
+impl SystemFallback {
+    /// A system font with a glyph for this character, at the size of the [`Font`].
+    pub fn font_impl_for(&self, c: char) -> Option<Arc<FontImpl>> {
+        let id = self.system_fonts.fallback_for(c)?;
+        let font_name = self.system_fonts.face_name(id)?;
+        let mut font_impl_cache = self.font_impl_cache.lock();
+        if !font_impl_cache.ab_glyph_fonts.contains_key(&font_name) {
+            let font_data = self.system_fonts.face_data(id)?;
+            font_impl_cache.add_font_data(&font_name, font_data);
+        }
+        Some(font_impl_cache.font_impl(self.scale_in_pixels, &font_name, &self.font_id))
+    }
+}
+
+pub(crate) struct FontImplCache {
     atlas: Arc<Mutex<TextureAtlas>>,
     pixels_per_point: f32,
     ab_glyph_fonts: BTreeMap<String, (FontTweak, ab_glyph::FontArc)>,
 
-    /// Map font pixel sizes and names to the cached [`FontImpl`].
-    cache: ahash::AHashMap<(u32, String), Arc<FontImpl>>,
+    /// The font files, for the shaper and variable fonts.
+    font_data: BTreeMap<String, Arc<FontData>>,
+
+    /// See [`FontDefinitions::faces`].
+    faces: BTreeMap<String, Vec<String>>,
+
+    /// Weight, stretch, style and variation axes of each font.
+    face_info: BTreeMap<String, FaceInfo>,
+
+    /// Map font pixel sizes and faces to the cached [`FontImpl`].
+    cache: ahash::AHashMap<(u32, FaceInstance), Arc<FontImpl>>,
+
+    /// With [`GlyphRendering::Sdf`]: the glyphs of each face, shared by all sizes.
+    sdf_glyphs: Option<ahash::AHashMap<FaceInstance, Arc<SdfGlyphs>>>,
 }
 
 impl FontImplCache {
     pub fn new(
         atlas: Arc<Mutex<TextureAtlas>>,
         pixels_per_point: f32,
-        font_data: &BTreeMap<String, FontData>,
+        definitions: &FontDefinitions,
     ) -> Self {
-        let ab_glyph_fonts = font_data
-            .iter()
-            .map(|(name, font_data)| {
-                let tweak = font_data.tweak;
-                let ab_glyph = ab_glyph_font_from_font_data(name, font_data);
-                (name.clone(), (tweak, ab_glyph))
-            })
-            .collect();
-
-        Self {
+        let mut slf = Self {
             atlas,
             pixels_per_point,
-            ab_glyph_fonts,
+            ab_glyph_fonts: Default::default(),
+            font_data: Default::default(),
+            faces: definitions.faces.clone(),
+            face_info: Default::default(),
             cache: Default::default(),
+            sdf_glyphs: match definitions.glyph_rendering {
+                GlyphRendering::Bitmap => None,
+                GlyphRendering::Sdf => Some(Default::default()),
+            },
+        };
+        for (name, font_data) in &definitions.font_data {
+            slf.add_font_data(name, font_data.clone());
+        }
+        slf
+    }
+
+    /// Forget all glyphs, e.g. because the atlas switched page.
+    pub fn clear_glyphs(&mut self) {
+        self.cache.clear();
+        if let Some(sdf_glyphs) = &mut self.sdf_glyphs {
+            sdf_glyphs.clear();
         }
     }
 
+    /// Add a font, e.g. a fallback from the system.
+    fn add_font_data(&mut self, font_name: &str, font_data: FontData) {
+        let ab_glyph = ab_glyph_font_from_font_data(font_name, &font_data);
+        self.ab_glyph_fonts
+            .insert(font_name.to_owned(), (font_data.tweak, ab_glyph));
+        self.face_info
+            .insert(font_name.to_owned(), FaceInfo::new(&font_data));
+        self.font_data
+            .insert(font_name.to_owned(), Arc::new(font_data));
+    }
+
     #[inline]
     pub fn scale_as_pixels(&self, scale_in_points: f32) -> u32 {
         let scale_in_pixels = self.pixels_per_point * scale_in_points;
@@ -708,12 +1063,33 @@ impl FontImplCache {
         scale_in_pixels.round() as u32
     }
 
-    pub fn font_impl(&mut self, scale_in_pixels: u32, font_name: &str) -> Arc<FontImpl> {
-        let (tweak, ab_glyph_font) = self
-            .ab_glyph_fonts
-            .get(font_name)
-            .unwrap_or_else(|| panic!("No font data found for {:?}", font_name))
-            .clone();
+    /// The face of the font with this name that best matches the weight, stretch and style of `font_id`.
+    pub fn font_impl(
+        &mut self,
+        scale_in_pixels: u32,
+        font_name: &str,
+        font_id: &FontId,
+    ) -> Arc<FontImpl> {
+        let face_info = &self.face_info;
+        let faces = std::iter::once(font_name)
+            .chain(
+                self.faces
+                    .get(font_name)
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str),
+            )
+            .map(|name| {
+                let info = face_info
+                    .get(name)
+                    .unwrap_or_else(|| panic!("No font data found for {:?}", name));
+                (name, info)
+            });
+        let instance = FaceInstance::select(faces, font_id.weight, font_id.stretch, font_id.style);
+        let font_name = instance.font_name.as_str();
+
+        let (tweak, ab_glyph_font) = self.ab_glyph_fonts[font_name].clone();
+        let font_data = self.font_data[font_name].clone();
 
         let scale_in_pixels = (scale_in_pixels as f32 * tweak.scale).round() as u32;
 
@@ -722,17 +1098,42 @@ impl FontImplCache {
             scale_in_points * tweak.y_offset_factor
         } + tweak.y_offset;
 
+        let sdf_glyphs = self
+            .sdf_glyphs
+            .as_mut()
+            .map(|sdf_glyphs| sdf_glyphs.entry(instance.clone()).or_default().clone());
+
+        let atlas = &self.atlas;
+        let pixels_per_point = self.pixels_per_point;
         self.cache
-            .entry((scale_in_pixels, font_name.to_owned()))
+            .entry((scale_in_pixels, instance.clone()))
             .or_insert_with(|| {
-                Arc::new(FontImpl::new(
-                    self.atlas.clone(),
-                    self.pixels_per_point,
-                    font_name.to_owned(),
+                let ab_glyph_font = if instance.is_default() {
+                    ab_glyph_font
+                } else {
+                    ab_glyph::FontArc::new(VariedFont::new(
+                        ab_glyph_font,
+                        font_data.clone(),
+                        &instance,
+                    ))
+                };
+
+                let mut font_impl = FontImpl::new(
+                    atlas.clone(),
+                    pixels_per_point,
+                    instance.font_name.clone(),
                     ab_glyph_font,
+                    #[cfg(feature = "shaping")]
+                    font_data,
                     scale_in_pixels,
                     y_offset_points,
-                ))
+                );
+                #[cfg(feature = "shaping")]
+                font_impl.set_variations(instance.rustybuzz_variations());
+                if let Some(sdf_glyphs) = sdf_glyphs {
+                    font_impl.set_sdf_glyphs(sdf_glyphs);
+                }
+                Arc::new(font_impl)
             })
             .clone()
     }
@@ -0,0 +1,221 @@
+use ahash::AHashMap;
+
+/// Finds where words can be hyphenated, using Liang's algorithm (the one used by TeX).
+///
+/// Hyphenation depends on the language, so you need to give it the patterns for the language of your text,
+/// e.g. from <https://github.com/hyphenation/tex-hyphen>.
+/// Set it with [`super::TextWrapping::hyphenator`].
+///
+/// ```
+/// # use epaint::text::Hyphenator;
+/// let hyphenator = Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n");
+/// assert_eq!(hyphenator.hyphenation_points("hyphenation"), vec![2, 6]);
+/// ```
+#[derive(Clone)]
+pub struct Hyphenator {
+    /// The letters of a pattern, and the values between them.
+    patterns: AHashMap<String, Vec<u8>>,
+
+    /// Number of letters in the longest pattern.
+    max_pattern_len: usize,
+
+    /// Words that are hyphenated differently than the patterns say.
+    exceptions: AHashMap<String, Vec<usize>>,
+
+    left_min: usize,
+    right_min: usize,
+
+    /// Hash of all of the above, so we don't need to hash the patterns for every [`super::LayoutJob`].
+    hash: u64,
+}
+
+impl Hyphenator {
+    /// Patterns are separated by whitespace, in the format used by TeX, e.g. `"1ba .ach4 n2at"`.
+    ///
+    /// A `.` marks the start or end of a word,
+    /// and an odd number between two letters means that the word can be hyphenated there.
+    pub fn new(patterns: &str) -> Self {
+        let mut hyphenator = Self {
+            patterns: Default::default(),
+            max_pattern_len: 0,
+            exceptions: Default::default(),
+            left_min: 2,
+            right_min: 3,
+            hash: 0,
+        };
+        for pattern in patterns.split_whitespace() {
+            let mut letters = String::new();
+            let mut values = vec![0];
+            for chr in pattern.chars() {
+                if let Some(value) = chr.to_digit(10) {
+                    *values.last_mut().unwrap() = value as u8;
+                } else {
+                    letters.push(lowercase(chr));
+                    values.push(0);
+                }
+            }
+            hyphenator.max_pattern_len = hyphenator.max_pattern_len.max(values.len() - 1);
+            hyphenator.patterns.insert(letters, values);
+        }
+        hyphenator.hash = crate::util::hash(patterns);
+        hyphenator
+    }
+
+    /// Words that the patterns get wrong, separated by whitespace, with hyphens where they can be broken,
+    /// e.g. `"as-so-ciate project"`.
+    pub fn with_exceptions(mut self, exceptions: &str) -> Self {
+        for exception in exceptions.split_whitespace() {
+            let mut word = String::new();
+            let mut points = vec![];
+            let mut num_chars = 0;
+            for chr in exception.chars() {
+                if chr == '-' {
+                    points.push(num_chars);
+                } else {
+                    word.push(lowercase(chr));
+                    num_chars += 1;
+                }
+            }
+            self.exceptions.insert(word, points);
+        }
+        self.hash = crate::util::hash((self.hash, exceptions));
+        self
+    }
+
+    /// The minimum number of letters to keep before and after a hyphen.
+    ///
+    /// Default: 2 and 3 (good for English).
+    pub fn with_min_lengths(mut self, left_min: usize, right_min: usize) -> Self {
+        self.left_min = left_min.max(1);
+        self.right_min = right_min.max(1);
+        self
+    }
+
+    /// Where can this word be hyphenated?
+    ///
+    /// Returns the indices of the characters a hyphenated row can end before.
+    pub fn hyphenation_points(&self, word: &str) -> Vec<usize> {
+        let word: Vec<char> = word.chars().map(lowercase).collect();
+        let num_chars = word.len();
+        if num_chars < self.left_min + self.right_min {
+            return vec![];
+        }
+
+        let keep = |&point: &usize| self.left_min <= point && point <= num_chars - self.right_min;
+
+        if !self.exceptions.is_empty() {
+            let word: String = word.iter().collect();
+            if let Some(points) = self.exceptions.get(&word) {
+                return points.iter().copied().filter(keep).collect();
+            }
+        }
+
+        let dotted: Vec<char> = std::iter::once('.')
+            .chain(word.iter().copied())
+            .chain(std::iter::once('.'))
+            .collect();
+
+        // `values[i]` is between `dotted[i - 1]` and `dotted[i]`:
+        let mut values = vec![0_u8; dotted.len() + 1];
+        let mut part = String::new();
+        for start in 0..dotted.len() {
+            part.clear();
+            for &chr in dotted[start..].iter().take(self.max_pattern_len) {
+                part.push(chr);
+                if let Some(pattern) = self.patterns.get(&part) {
+                    for (value, &pattern_value) in values[start..].iter_mut().zip(pattern) {
+                        *value = (*value).max(pattern_value);
+                    }
+                }
+            }
+        }
+
+        // The break before `word[i]` is before `dotted[i + 1]`:
+        (1..num_chars)
+            .filter(|i| values[i + 1] % 2 == 1)
+            .filter(keep)
+            .collect()
+    }
+}
+
+/// Lowercase one character to one character, so that the indices of the characters stay the same.
+///
+/// Characters with a longer lowercase form (like 'İ', which becomes "i̇") are lowercased to its first character.
+fn lowercase(chr: char) -> char {
+    chr.to_lowercase().next().unwrap_or(chr)
+}
+
+impl PartialEq for Hyphenator {
+    /// The same as [`std::hash::Hash`]: the patterns and exceptions are compared by their hash.
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.left_min == other.left_min
+            && self.right_min == other.right_min
+    }
+}
+
+impl std::fmt::Debug for Hyphenator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hyphenator")
+            .field("num_patterns", &self.patterns.len())
+            .field("num_exceptions", &self.exceptions.len())
+            .field("left_min", &self.left_min)
+            .field("right_min", &self.right_min)
+            .finish()
+    }
+}
+
+impl std::hash::Hash for Hyphenator {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+        self.left_min.hash(state);
+        self.right_min.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: &str = "hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n";
+
+    #[test]
+    fn hyphenation_points() {
+        let hyphenator = Hyphenator::new(PATTERNS);
+        assert_eq!(hyphenator.hyphenation_points("hyphenation"), vec![2, 6]);
+        assert_eq!(hyphenator.hyphenation_points("Hyphenation"), vec![2, 6]);
+        assert_eq!(hyphenator.hyphenation_points("hyphen"), vec![2]);
+        assert_eq!(hyphenator.hyphenation_points("word"), vec![]);
+        assert_eq!(hyphenator.hyphenation_points(""), vec![]);
+    }
+
+    #[test]
+    fn min_lengths() {
+        let hyphenator = Hyphenator::new(PATTERNS).with_min_lengths(3, 3);
+        assert_eq!(hyphenator.hyphenation_points("hyphenation"), vec![6]);
+        let hyphenator = Hyphenator::new(PATTERNS).with_min_lengths(2, 6);
+        assert_eq!(hyphenator.hyphenation_points("hyphenation"), vec![2]);
+    }
+
+    #[test]
+    fn multi_char_lowercase() {
+        // 'İ' lowercases to two characters, but must still count as one:
+        let hyphenator = Hyphenator::new(PATTERNS);
+        assert_eq!(hyphenator.hyphenation_points("İhyphenation"), vec![3, 7]);
+        assert_eq!(hyphenator.hyphenation_points("hyphenatİon"), vec![2, 6]);
+
+        let hyphenator = Hyphenator::new(PATTERNS).with_exceptions("İs-tan-bul");
+        assert_eq!(hyphenator.hyphenation_points("İstanbul"), vec![2, 5]);
+        assert_eq!(hyphenator.hyphenation_points("istanbul"), vec![2, 5]);
+    }
+
+    #[test]
+    fn exceptions() {
+        let hyphenator = Hyphenator::new(PATTERNS).with_exceptions("hy-phe-na-tion");
+        assert_eq!(hyphenator.hyphenation_points("hyphenation"), vec![2, 5, 7]);
+        assert_ne!(hyphenator, Hyphenator::new(PATTERNS));
+        let other = Hyphenator::new(PATTERNS);
+        assert_ne!(crate::util::hash(&hyphenator), crate::util::hash(&other));
+    }
+}
@@ -1,8 +1,13 @@
 //! Everything related to text, fonts, text layout, cursors etc.
 
 pub mod cursor;
+mod face;
 mod font;
 mod fonts;
+mod hyphenation;
+mod sdf;
+#[cfg(feature = "system_fonts")]
+mod system_fonts;
 mod text_layout;
 mod text_layout_types;
 
@@ -10,10 +15,17 @@ mod text_layout_types;
 pub const TAB_SIZE: usize = 4;
 
 pub use {
-    fonts::{FontData, FontDefinitions, FontFamily, FontId, FontTweak, Fonts, FontsImpl},
+    fonts::{
+        FontData, FontDefinitions, FontFamily, FontId, FontStretch, FontStyle, FontTweak,
+        FontWeight, Fonts, FontsImpl, GlyphRendering,
+    },
+    hyphenation::Hyphenator,
     text_layout::layout,
     text_layout_types::*,
 };
 
+#[cfg(feature = "system_fonts")]
+pub use system_fonts::SystemFonts;
+
 /// Suggested character to use to replace those in password text fields.
 pub const PASSWORD_REPLACEMENT_CHAR: char = '•';
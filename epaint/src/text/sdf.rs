@@ -0,0 +1,419 @@
+//! Glyphs as signed distance fields, for [`super::GlyphRendering::Sdf`].
+
+use ab_glyph::{Font as _, GlyphId, OutlineCurve, ScaleFont as _};
+use emath::{pos2, vec2, Pos2, Rect, Vec2};
+
+use crate::{mutex::Mutex, TextureAtlas};
+
+use super::font::UvRect;
+
+/// Glyphs are rasterized at this size (same unit as the `scale_in_pixels` of a font),
+/// whatever size they are later painted at.
+const SDF_SCALE_IN_PIXELS: f32 = 48.0;
+
+/// How far from the outline (in texels) the distance field reaches.
+///
+/// Further away than this, the distance is clamped.
+const SDF_SPREAD: f32 = 6.0;
+
+/// A glyph (or a cluster of glyphs) in the atlas. Unit: texels.
+#[derive(Clone, Copy, Debug, Default)]
+struct SdfEntry {
+    /// Top left corner, relative to the glyph origin on the baseline. Positive y is down.
+    offset: Vec2,
+
+    /// Top left corner in the atlas.
+    min: [u16; 2],
+
+    /// Bottom right corner in the atlas (exclusive).
+    max: [u16; 2],
+
+    is_color: bool,
+}
+
+impl SdfEntry {
+    /// Scale to a font of the given size.
+    fn uv_rect(&self, scale_in_pixels: f32, y_offset: f32, pixels_per_point: f32) -> UvRect {
+        if self.min == self.max {
+            return UvRect::default();
+        }
+
+        let points_per_texel = scale_in_pixels / SDF_SCALE_IN_PIXELS / pixels_per_point;
+        let size = vec2(
+            (self.max[0] - self.min[0]) as f32,
+            (self.max[1] - self.min[1]) as f32,
+        );
+        UvRect {
+            offset: self.offset * points_per_texel
+                + vec2(0.0, scale_in_pixels / pixels_per_point + y_offset),
+            size: size * points_per_texel,
+            min: self.min,
+            max: self.max,
+            is_color: self.is_color,
+        }
+    }
+}
+
+/// Glyphs and their offsets in texels.
+type SdfKey = Vec<(GlyphId, [i32; 2])>;
+
+/// The glyphs of one font face, rasterized once and shared by all sizes of it.
+#[derive(Default)]
+pub(crate) struct SdfGlyphs {
+    entries: Mutex<ahash::AHashMap<SdfKey, SdfEntry>>,
+}
+
+impl SdfGlyphs {
+    /// Where in the atlas are these glyphs, placed at these offsets (in points), and painted at this size?
+    ///
+    /// Rasterizes them if they are not in the atlas yet.
+    pub fn uv_rect(
+        &self,
+        atlas: &mut TextureAtlas,
+        font: &ab_glyph::FontArc,
+        glyphs: &[(GlyphId, Vec2)],
+        scale_in_pixels: f32,
+        y_offset: f32,
+        pixels_per_point: f32,
+    ) -> UvRect {
+        let texels_per_point = SDF_SCALE_IN_PIXELS * pixels_per_point / scale_in_pixels;
+        let key: SdfKey = glyphs
+            .iter()
+            .filter(|(id, _)| id.0 != 0)
+            .map(|&(id, offset)| {
+                let offset = offset * texels_per_point;
+                (id, [offset.x.round() as i32, offset.y.round() as i32])
+            })
+            .collect();
+
+        let mut entries = self.entries.lock();
+        let entry = *entries
+            .entry(key)
+            .or_insert_with_key(|key| allocate_sdf(atlas, font, key));
+        entry.uv_rect(scale_in_pixels, y_offset, pixels_per_point)
+    }
+}
+
+fn allocate_sdf(
+    atlas: &mut TextureAtlas,
+    font: &ab_glyph::FontArc,
+    glyphs: &[(GlyphId, [i32; 2])],
+) -> SdfEntry {
+    #[cfg(feature = "color_emoji")]
+    if let [(glyph_id, [0, 0])] = glyphs {
+        // Stored as colors, which the backend paints as they are (see `FontImage::srgba_pixels`):
+        let color_uv_rect = super::font::allocate_color_glyph(
+            atlas,
+            font,
+            *glyph_id,
+            SDF_SCALE_IN_PIXELS,
+            0.0,
+            1.0,
+        );
+        if let Some(uv_rect) = color_uv_rect {
+            return SdfEntry {
+                offset: uv_rect.offset - vec2(0.0, SDF_SCALE_IN_PIXELS),
+                min: uv_rect.min,
+                max: uv_rect.max,
+                is_color: true,
+            };
+        }
+    }
+
+    let segments = outline_segments(font, glyphs);
+    if segments.is_empty() {
+        return SdfEntry::default();
+    }
+
+    let mut bounds = Rect::NOTHING;
+    for &[a, b] in &segments {
+        bounds.extend_with(a);
+        bounds.extend_with(b);
+    }
+    let min = (bounds.min - Vec2::splat(SDF_SPREAD)).floor();
+    let max = (bounds.max + Vec2::splat(SDF_SPREAD)).ceil();
+    let (w, h) = ((max.x - min.x) as usize, (max.y - min.y) as usize);
+
+    let distances = signed_distances(&segments, min, w, h);
+
+    let (pos, image) = atlas.allocate((w, h));
+    for y in 0..h {
+        for x in 0..w {
+            let distance = distances[y * w + x];
+            image[(pos.0 + x, pos.1 + y)] = (0.5 + 0.5 * distance / SDF_SPREAD).clamp(0.0, 1.0);
+        }
+    }
+
+    SdfEntry {
+        offset: min.to_vec2(),
+        min: [pos.0 as u16, pos.1 as u16],
+        max: [(pos.0 + w) as u16, (pos.1 + h) as u16],
+        is_color: false,
+    }
+}
+
+/// The outlines of the glyphs as line segments, in texels with y down.
+fn outline_segments(font: &ab_glyph::FontArc, glyphs: &[(GlyphId, [i32; 2])]) -> Vec<[Pos2; 2]> {
+    let scale = font.as_scaled(SDF_SCALE_IN_PIXELS);
+    let (h_scale, v_scale) = (scale.h_scale_factor(), scale.v_scale_factor());
+
+    let mut segments = vec![];
+    for &(glyph_id, [dx, dy]) in glyphs {
+        let outline = match font.outline(glyph_id) {
+            Some(outline) => outline,
+            None => continue,
+        };
+        let to_texels =
+            |p: ab_glyph::Point| pos2(p.x * h_scale + dx as f32, -p.y * v_scale + dy as f32);
+
+        for curve in &outline.curves {
+            let mut from = None;
+            let mut line_to = |p: Pos2| {
+                if let Some(from) = from.replace(p) {
+                    segments.push([from, p]);
+                }
+            };
+            match *curve {
+                OutlineCurve::Line(p0, p1) => {
+                    line_to(to_texels(p0));
+                    line_to(to_texels(p1));
+                }
+                OutlineCurve::Quad(p0, p1, p2) => {
+                    let [p0, p1, p2] = [p0, p1, p2].map(to_texels);
+                    for i in 0..=CURVE_STEPS {
+                        let t = i as f32 / CURVE_STEPS as f32;
+                        let s = 1.0 - t;
+                        line_to(
+                            (s * s * p0.to_vec2()
+                                + 2.0 * s * t * p1.to_vec2()
+                                + t * t * p2.to_vec2())
+                            .to_pos2(),
+                        );
+                    }
+                }
+                OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                    let [p0, p1, p2, p3] = [p0, p1, p2, p3].map(to_texels);
+                    for i in 0..=CURVE_STEPS {
+                        let t = i as f32 / CURVE_STEPS as f32;
+                        let s = 1.0 - t;
+                        line_to(
+                            (s * s * s * p0.to_vec2()
+                                + 3.0 * s * s * t * p1.to_vec2()
+                                + 3.0 * s * t * t * p2.to_vec2()
+                                + t * t * t * p3.to_vec2())
+                            .to_pos2(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    segments
+}
+
+/// Number of line segments per curve of an outline.
+const CURVE_STEPS: usize = 8;
+
+/// The signed distance (positive inside) from the center of each texel to the closest segment,
+/// clamped to [`SDF_SPREAD`].
+///
+/// `min` is the position of the top left texel.
+fn signed_distances(segments: &[[Pos2; 2]], min: Pos2, w: usize, h: usize) -> Vec<f32> {
+    // Only texels within the spread of a segment matter, so only visit those:
+    let mut distances_sq = vec![SDF_SPREAD * SDF_SPREAD; w * h];
+    for &[a, b] in segments {
+        let x_range = texel_range(a.x.min(b.x) - min.x, a.x.max(b.x) - min.x, w);
+        let y_range = texel_range(a.y.min(b.y) - min.y, a.y.max(b.y) - min.y, h);
+        for y in y_range {
+            for x in x_range.clone() {
+                let p = min + vec2(x as f32 + 0.5, y as f32 + 0.5);
+                let distance_sq = &mut distances_sq[y * w + x];
+                *distance_sq = distance_sq.min(distance_sq_to_segment(p, a, b));
+            }
+        }
+    }
+
+    // The sign comes from the non-zero winding rule (like TrueType), one row at a time:
+    let mut distances = vec![0.0; w * h];
+    let mut crossings = vec![];
+    for y in 0..h {
+        let py = min.y + y as f32 + 0.5;
+        crossings.clear();
+        for &[a, b] in segments {
+            if (a.y <= py) != (b.y <= py) {
+                let t = (py - a.y) / (b.y - a.y);
+                let winding = if b.y > a.y { 1 } else { -1 };
+                crossings.push((a.x + t * (b.x - a.x), winding));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut winding = 0;
+        let mut crossings = crossings.iter().peekable();
+        for x in 0..w {
+            let px = min.x + x as f32 + 0.5;
+            while let Some((_, crossing)) = crossings.next_if(|(cx, _)| *cx < px) {
+                winding += crossing;
+            }
+            let distance = distances_sq[y * w + x].sqrt();
+            distances[y * w + x] = if winding == 0 { -distance } else { distance };
+        }
+    }
+    distances
+}
+
+/// The texels whose centers are within the spread of `[min, max]`.
+fn texel_range(min: f32, max: f32, len: usize) -> std::ops::Range<usize> {
+    let start = (min - SDF_SPREAD).floor().max(0.0) as usize;
+    let end = ((max + SDF_SPREAD).ceil().max(0.0) as usize).min(len);
+    start..end
+}
+
+fn distance_sq_to_segment(p: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    let t = if length_sq > 0.0 {
+        ((p - a).dot(ab) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    p.distance_sq(a + t * ab)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A closed polygon as segments.
+    fn polygon(points: &[Pos2]) -> Vec<[Pos2; 2]> {
+        (0..points.len())
+            .map(|i| [points[i], points[(i + 1) % points.len()]])
+            .collect()
+    }
+
+    fn square(min: f32, max: f32) -> Vec<Pos2> {
+        vec![
+            pos2(min, min),
+            pos2(max, min),
+            pos2(max, max),
+            pos2(min, max),
+        ]
+    }
+
+    #[test]
+    fn distance_to_segment() {
+        let (a, b) = (pos2(0.0, 0.0), pos2(10.0, 0.0));
+        assert_eq!(distance_sq_to_segment(pos2(5.0, 3.0), a, b), 9.0);
+        assert_eq!(distance_sq_to_segment(pos2(-3.0, 4.0), a, b), 25.0);
+        assert_eq!(distance_sq_to_segment(pos2(13.0, 0.0), a, b), 9.0);
+        assert_eq!(distance_sq_to_segment(pos2(3.0, 4.0), a, a), 25.0);
+    }
+
+    #[test]
+    fn texel_ranges_are_clamped() {
+        assert_eq!(texel_range(10.0, 12.0, 100), 4..18);
+        assert_eq!(texel_range(2.0, 3.0, 100), 0..9);
+        assert_eq!(texel_range(90.0, 99.0, 100), 84..100);
+    }
+
+    #[test]
+    fn square_distances() {
+        let (w, h) = (32, 32);
+        let segments = polygon(&square(8.0, 24.0));
+        let distances = signed_distances(&segments, Pos2::ZERO, w, h);
+        let at = |x: usize, y: usize| distances[y * w + x];
+
+        // Texel centers are at half texels:
+        assert_eq!(at(8, 16), 0.5, "just inside");
+        assert_eq!(at(7, 16), -0.5, "just outside");
+        assert_eq!(at(10, 16), 2.5);
+        assert_eq!(at(4, 16), -3.5);
+
+        // Clamped to the spread:
+        assert_eq!(at(16, 16), SDF_SPREAD);
+        assert_eq!(at(0, 0), -SDF_SPREAD);
+
+        // The winding direction doesn't matter:
+        let mut reversed = square(8.0, 24.0);
+        reversed.reverse();
+        assert_eq!(
+            signed_distances(&polygon(&reversed), Pos2::ZERO, w, h),
+            distances
+        );
+    }
+
+    #[test]
+    fn square_with_hole() {
+        let (w, h) = (32, 32);
+        let mut segments = polygon(&square(4.0, 28.0));
+        let mut hole = square(12.0, 20.0);
+        hole.reverse(); // Wound the other way, so the winding number is zero inside.
+        segments.extend(polygon(&hole));
+        let distances = signed_distances(&segments, Pos2::ZERO, w, h);
+        let at = |x: usize, y: usize| distances[y * w + x];
+
+        assert_eq!(at(8, 16), 3.5, "between the outer and inner square");
+        assert_eq!(at(16, 16), -3.5, "in the hole");
+        assert_eq!(at(1, 16), -2.5, "outside");
+    }
+
+    #[test]
+    fn entry_scales_with_font_size() {
+        let entry = SdfEntry {
+            offset: vec2(-4.0, -40.0),
+            min: [10, 20],
+            max: [34, 68],
+            is_color: false,
+        };
+
+        let full_size = entry.uv_rect(SDF_SCALE_IN_PIXELS, 0.0, 1.0);
+        assert_eq!(full_size.size, vec2(24.0, 48.0));
+        assert_eq!(full_size.offset, vec2(-4.0, -40.0 + SDF_SCALE_IN_PIXELS));
+        assert_eq!((full_size.min, full_size.max), (entry.min, entry.max));
+
+        // Half the size, or twice the pixels per point:
+        let half_size = entry.uv_rect(SDF_SCALE_IN_PIXELS / 2.0, 0.0, 1.0);
+        assert_eq!(half_size.size, vec2(12.0, 24.0));
+        assert_eq!(
+            entry.uv_rect(SDF_SCALE_IN_PIXELS, 0.0, 2.0).size,
+            half_size.size
+        );
+        assert_eq!(
+            (half_size.min, half_size.max),
+            (entry.min, entry.max),
+            "same texels for all sizes"
+        );
+
+        assert!(SdfEntry::default().uv_rect(24.0, 0.0, 1.0).is_nothing());
+    }
+
+    #[test]
+    fn glyphs_are_shared_by_all_sizes() {
+        let font =
+            ab_glyph::FontArc::try_from_slice(include_bytes!("../../fonts/Hack-Regular.ttf"))
+                .unwrap();
+        let mut atlas = TextureAtlas::new_sdf([1024, 256]);
+        let glyphs = SdfGlyphs::default();
+        let a = [(font.glyph_id('a'), Vec2::ZERO)];
+
+        let small = glyphs.uv_rect(&mut atlas, &font, &a, 12.0, 0.0, 1.0);
+        let large = glyphs.uv_rect(&mut atlas, &font, &a, 96.0, 0.0, 1.0);
+        assert_eq!((small.min, small.max), (large.min, large.max));
+        assert_eq!(large.size, 8.0 * small.size);
+        assert!(!small.is_color);
+
+        // The distances are mapped so that 0.5 is on the outline, and below that is outside:
+        let image = atlas.image();
+        let [x, y] = small.min;
+        assert!(
+            image[(x as usize, y as usize)] < 0.5,
+            "the corner is outside"
+        );
+        assert!(image.sdf);
+
+        let space = [(font.glyph_id(' '), Vec2::ZERO)];
+        assert!(glyphs
+            .uv_rect(&mut atlas, &font, &space, 12.0, 0.0, 1.0)
+            .is_nothing());
+    }
+}
@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use crate::text::FontData;
+
+/// The fonts installed on the system, found with [`fontdb`] (using fontconfig on Linux).
+///
+/// Put this in [`crate::text::FontDefinitions::system_fonts`] to use them as fallbacks
+/// for characters that none of the fonts of a [`crate::FontFamily`] have.
+///
+/// Scanning the system is slow, so only call [`Self::load`] once.
+/// Cheap to clone.
+#[derive(Clone)]
+pub struct SystemFonts(Arc<SystemFontsImpl>);
+
+struct SystemFontsImpl {
+    db: fontdb::Database,
+
+    /// Which face to use as a fallback for each character that any of the faces have.
+    fallbacks: ahash::AHashMap<char, fontdb::ID>,
+}
+
+impl SystemFonts {
+    /// Find all fonts installed on the system,
+    /// and which characters each of them has.
+    pub fn load() -> Self {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        Self::from_database(db)
+    }
+
+    pub(crate) fn from_database(db: fontdb::Database) -> Self {
+        let fallbacks = fallback_index(&db);
+        Self(Arc::new(SystemFontsImpl { db, fallbacks }))
+    }
+
+    /// The names of all installed font families, sorted.
+    pub fn families(&self) -> Vec<String> {
+        let mut families: Vec<String> = self
+            .0
+            .db
+            .faces()
+            .iter()
+            .map(|face| face.family.clone())
+            .collect();
+        families.sort();
+        families.dedup();
+        families
+    }
+
+    /// The regular face of an installed font family, e.g. `"Noto Sans CJK JP"`.
+    pub fn font_data(&self, family: &str) -> Option<FontData> {
+        let id = self.0.db.query(&fontdb::Query {
+            families: &[fontdb::Family::Name(family)],
+            ..Default::default()
+        })?;
+        self.face_data(id)
+    }
+
+    /// An installed font face that has a glyph for this character, if there is one.
+    ///
+    /// Regular faces are preferred over bold, italic etc.
+    pub(crate) fn fallback_for(&self, c: char) -> Option<fontdb::ID> {
+        self.0.fallbacks.get(&c).copied()
+    }
+
+    /// The unique name we use for this face in [`crate::text::FontDefinitions::font_data`].
+    pub(crate) fn face_name(&self, id: fontdb::ID) -> Option<String> {
+        let face = self.0.db.face(id)?;
+        Some(format!("{} (system)", face.post_script_name))
+    }
+
+    pub(crate) fn face_data(&self, id: fontdb::ID) -> Option<FontData> {
+        self.0.db.with_face_data(id, |data, index| FontData {
+            index,
+            ..FontData::from_owned(data.to_vec())
+        })
+    }
+}
+
+/// Which face to use for each character, found by parsing every face once.
+///
+/// Regular faces are preferred over bold, italic etc.
+fn fallback_index(db: &fontdb::Database) -> ahash::AHashMap<char, fontdb::ID> {
+    use ab_glyph::Font as _;
+
+    let is_regular = |face: &&fontdb::FaceInfo| {
+        face.style == fontdb::Style::Normal
+            && face.weight == fontdb::Weight::NORMAL
+            && face.stretch == fontdb::Stretch::Normal
+    };
+    let faces = db.faces();
+    let mut fallbacks = ahash::AHashMap::default();
+    for face in faces
+        .iter()
+        .filter(is_regular)
+        .chain(faces.iter().filter(|face| !is_regular(face)))
+    {
+        db.with_face_data(face.id, |data, index| {
+            if let Ok(font) = ab_glyph::FontRef::try_from_slice_and_index(data, index) {
+                for (glyph_id, c) in font.codepoint_ids() {
+                    if glyph_id.0 != 0 {
+                        fallbacks.entry(c).or_insert(face.id);
+                    }
+                }
+            }
+        });
+    }
+    fallbacks
+}
+
+impl std::fmt::Debug for SystemFonts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemFonts")
+            .field("num_faces", &self.0.db.len())
+            .finish()
+    }
+}
+
+impl PartialEq for SystemFonts {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled_fonts() -> SystemFonts {
+        let mut db = fontdb::Database::new();
+        db.load_font_data(include_bytes!("../../fonts/Ubuntu-Light.ttf").to_vec());
+        db.load_font_data(include_bytes!("../../fonts/Hack-Regular.ttf").to_vec());
+        SystemFonts::from_database(db)
+    }
+
+    #[test]
+    fn fallback_faces() {
+        let fonts = bundled_fonts();
+        let face_name = |c| fonts.fallback_for(c).and_then(|id| fonts.face_name(id));
+
+        // Hack is regular, Ubuntu-Light is not, so Hack is preferred:
+        assert_eq!(face_name('a').as_deref(), Some("Hack-Regular (system)"));
+
+        // Only Hack has a combining acute accent:
+        assert_eq!(
+            face_name('\u{301}').as_deref(),
+            Some("Hack-Regular (system)")
+        );
+
+        // Neither of them has Hiragana:
+        assert_eq!(face_name('\u{3042}'), None);
+    }
+
+    #[test]
+    fn system_font_families() {
+        let fonts = bundled_fonts();
+        assert_eq!(fonts.families(), ["Hack", "Ubuntu Light"]);
+        let hack = fonts.font_data("Hack").unwrap();
+        assert_eq!(
+            &*hack.font,
+            include_bytes!("../../fonts/Hack-Regular.ttf").as_slice()
+        );
+        assert!(fonts.font_data("Comic Sans").is_none());
+    }
+}
@@ -1,8 +1,12 @@
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
-use super::{FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals};
-use crate::{Color32, Mesh, Stroke, Vertex};
+#[cfg(feature = "shaping")]
+use super::font::UvRect;
+use super::{
+    FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals, TextFormat, UnderlineStyle,
+};
+use crate::{Color32, Mesh, Rounding, Stroke, Vertex};
 use emath::*;
 
 // ----------------------------------------------------------------------------
@@ -47,6 +51,73 @@ struct Paragraph {
 
     /// In case of an empty paragraph ("\n"), use this as height.
     pub empty_paragraph_height: f32,
+
+    /// Is this a right-to-left paragraph?
+    pub rtl: bool,
+}
+
+/// The result of running the Unicode Bidirectional Algorithm (UAX #9) on the text of a [`LayoutJob`].
+#[cfg(feature = "bidi")]
+struct BidiLevels {
+    /// One per byte of the text.
+    levels: Vec<unicode_bidi::Level>,
+
+    paragraphs: Vec<unicode_bidi::ParagraphInfo>,
+}
+
+#[cfg(feature = "bidi")]
+impl BidiLevels {
+    /// Returns `None` if there is no right-to-left text, which is the common case.
+    fn new(text: &str) -> Option<Self> {
+        use unicode_bidi::BidiClass::{AL, R, RLE, RLI, RLO};
+        let any_rtl = text
+            .chars()
+            .any(|c| matches!(unicode_bidi::bidi_class(c), R | AL | RLE | RLI | RLO));
+        if !any_rtl {
+            return None;
+        }
+
+        let bidi_info = unicode_bidi::BidiInfo::new(text, None);
+        Some(Self {
+            levels: bidi_info.levels,
+            paragraphs: bidi_info.paragraphs,
+        })
+    }
+
+    fn level(&self, byte_index: usize) -> u8 {
+        self.levels
+            .get(byte_index)
+            .map_or(0, |level| level.number())
+    }
+
+    /// Is the paragraph containing this byte right-to-left?
+    fn is_rtl_paragraph(&self, byte_index: usize) -> bool {
+        self.paragraphs
+            .iter()
+            .find(|paragraph| byte_index < paragraph.range.end)
+            .or_else(|| self.paragraphs.last())
+            .map_or(false, |paragraph| paragraph.level.is_rtl())
+    }
+}
+
+/// Without the `bidi` feature all text is laid out left-to-right.
+#[cfg(not(feature = "bidi"))]
+enum BidiLevels {}
+
+#[cfg(not(feature = "bidi"))]
+impl BidiLevels {
+    #[allow(clippy::unnecessary_wraps)]
+    fn new(_text: &str) -> Option<Self> {
+        None
+    }
+
+    fn level(&self, _byte_index: usize) -> u8 {
+        match *self {}
+    }
+
+    fn is_rtl_paragraph(&self, _byte_index: usize) -> bool {
+        match *self {}
+    }
 }
 
 /// Layout text into a [`Galley`].
@@ -54,9 +125,33 @@ struct Paragraph {
 /// In most cases you should use [`crate::Fonts::layout_job`] instead
 /// since that memoizes the input, making subsequent layouting of the same text much faster.
 pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
-    let mut paragraphs = vec![Paragraph::default()];
+    let galley = layout_on_current_page(fonts, job.clone());
+    if fonts.forget_glyphs_on_page_switch() {
+        // Some glyphs went on the previous atlas page - a galley must only use one.
+        // All glyphs are put on the new page when laid out again:
+        layout_on_current_page(fonts, job)
+    } else {
+        galley
+    }
+}
+
+fn layout_on_current_page(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
+    let bidi = BidiLevels::new(&job.text);
+
+    let mut paragraphs = vec![Paragraph {
+        cursor_x: job.paragraph_indent,
+        rtl: bidi.as_ref().map_or(false, |bidi| bidi.is_rtl_paragraph(0)),
+        ..Default::default()
+    }];
     for (section_index, section) in job.sections.iter().enumerate() {
-        layout_section(fonts, &job, section_index as u32, section, &mut paragraphs);
+        layout_section(
+            fonts,
+            &job,
+            bidi.as_ref(),
+            section_index as u32,
+            section,
+            &mut paragraphs,
+        );
     }
 
     let point_scale = PointScale::new(fonts.pixels_per_point());
@@ -67,25 +162,45 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
 
     if justify || job.halign != Align::LEFT {
         let num_rows = rows.len();
+        let mut is_first_row_of_paragraph = true;
         for (i, row) in rows.iter_mut().enumerate() {
             let is_last_row = i + 1 == num_rows;
             let justify_row = justify && !row.ends_with_newline && !is_last_row;
+            let indent = if is_first_row_of_paragraph {
+                job.paragraph_indent
+            } else {
+                0.0
+            };
+            is_first_row_of_paragraph = row.ends_with_newline;
             halign_and_jusitfy_row(
                 point_scale,
                 row,
                 job.halign,
                 job.wrap.max_width,
                 justify_row,
+                indent,
             );
         }
     }
 
-    galley_from_rows(point_scale, job, rows)
+    if bidi.is_some() {
+        for row in &mut rows {
+            reorder_row(row);
+        }
+        if job.halign == Align::LEFT {
+            align_rtl_rows_right(&mut rows);
+        }
+    }
+
+    let mut galley = galley_from_rows(point_scale, job, rows);
+    galley.atlas_page = fonts.current_atlas_page();
+    galley
 }
 
 fn layout_section(
     fonts: &mut FontsImpl,
     job: &LayoutJob,
+    bidi: Option<&BidiLevels>,
     section_index: u32,
     section: &LayoutSection,
     out_paragraphs: &mut Vec<Paragraph>,
@@ -96,42 +211,262 @@ fn layout_section(
         format,
     } = section;
     let font = fonts.font(&format.font_id);
-    let font_height = font.row_height();
+    let line_height = font.row_height() * format.line_height_factor;
 
-    let mut paragraph = out_paragraphs.last_mut().unwrap();
+    let paragraph = out_paragraphs.last_mut().unwrap();
     if paragraph.glyphs.is_empty() {
-        paragraph.empty_paragraph_height = font_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+        paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
     }
 
     paragraph.cursor_x += leading_space;
 
     let mut last_glyph_id = None;
 
-    for chr in job.text[byte_range.clone()].chars() {
-        if job.break_on_newline && chr == '\n' {
-            out_paragraphs.push(Paragraph::default());
-            paragraph = out_paragraphs.last_mut().unwrap();
-            paragraph.empty_paragraph_height = font_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
-        } else {
-            let (font_impl, glyph_info) = font.glyph_info_and_font_impl(chr);
-            if let Some(font_impl) = font_impl {
-                if let Some(last_glyph_id) = last_glyph_id {
-                    paragraph.cursor_x += font_impl.pair_kerning(last_glyph_id, glyph_info.id);
+    #[cfg(not(feature = "shaping"))]
+    for (byte_index, chr) in job.text[byte_range.clone()].char_indices() {
+        let byte_index = byte_range.start + byte_index;
+        layout_char(
+            font,
+            job,
+            bidi,
+            section_index,
+            (byte_index, chr),
+            out_paragraphs,
+            &mut last_glyph_id,
+        );
+    }
+
+    #[cfg(feature = "shaping")]
+    {
+        // Runs of characters that share the same font and direction are shaped together:
+        let mut run: Option<(usize, super::font::FontIndex, u8)> = None;
+
+        for (byte_index, chr) in job.text[byte_range.clone()].char_indices() {
+            let byte_index = byte_range.start + byte_index;
+            let bidi_level = bidi.map_or(0, |bidi| bidi.level(byte_index));
+            let font_index = if chr == '\n' || chr == '\t' {
+                None
+            } else {
+                font.font_index_for_char(chr)
+            };
+
+            if let Some((run_start, run_font_index, run_level)) = run {
+                if font_index != Some(run_font_index) || bidi_level != run_level {
+                    let paragraph = out_paragraphs.last_mut().unwrap();
+                    layout_shaped_run(
+                        font,
+                        job,
+                        run_start..byte_index,
+                        (run_font_index, run_level),
+                        section_index,
+                        paragraph,
+                    );
+                    run = None;
+                    last_glyph_id = None;
+                }
+            }
+
+            match font_index {
+                Some(font_index) => {
+                    if run.is_none() {
+                        run = Some((byte_index, font_index, bidi_level));
+                    }
+                }
+                None => {
+                    layout_char(
+                        font,
+                        job,
+                        bidi,
+                        section_index,
+                        (byte_index, chr),
+                        out_paragraphs,
+                        &mut last_glyph_id,
+                    );
                 }
             }
+        }
+
+        if let Some((run_start, run_font_index, run_level)) = run {
+            let paragraph = out_paragraphs.last_mut().unwrap();
+            layout_shaped_run(
+                font,
+                job,
+                run_start..byte_range.end,
+                (run_font_index, run_level),
+                section_index,
+                paragraph,
+            );
+        }
+    }
+}
+
+/// Add a single character, one glyph at a time (no shaping).
+fn layout_char(
+    font: &mut super::font::Font,
+    job: &LayoutJob,
+    bidi: Option<&BidiLevels>,
+    section_index: u32,
+    (byte_index, chr): (usize, char),
+    out_paragraphs: &mut Vec<Paragraph>,
+    last_glyph_id: &mut Option<ab_glyph::GlyphId>,
+) {
+    let font_height = font.row_height();
+    let format = &job.sections[section_index as usize].format;
+
+    if job.break_on_newline && chr == '\n' {
+        out_paragraphs.push(Paragraph {
+            cursor_x: job.paragraph_indent,
+            empty_paragraph_height: font_height * format.line_height_factor, // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+            rtl: bidi.map_or(false, |bidi| bidi.is_rtl_paragraph(byte_index + 1)),
+            ..Default::default()
+        });
+    } else {
+        let paragraph = out_paragraphs.last_mut().unwrap();
+        let bidi_level = bidi.map_or(0, |bidi| bidi.level(byte_index));
+        let glyph_chr = if bidi_level % 2 == 1 {
+            mirrored_char(chr)
+        } else {
+            chr
+        };
+        let (font_impl, glyph_info) = if chr == SOFT_HYPHEN {
+            // Only visible where a row is broken, as a hyphen at the end of it.
+            (None, Default::default())
+        } else {
+            font.glyph_info_and_font_impl(glyph_chr)
+        };
+        if let Some(font_impl) = font_impl {
+            if let Some(last_glyph_id) = *last_glyph_id {
+                paragraph.cursor_x += font_impl.pair_kerning(last_glyph_id, glyph_info.id);
+            }
+        }
+
+        paragraph.glyphs.push(Glyph {
+            chr,
+            pos: pos2(paragraph.cursor_x, f32::NAN),
+            size: vec2(glyph_info.advance_width, font_height),
+            uv_rect: glyph_info.uv_rect,
+            section_index,
+            bidi_level,
+        });
+
+        paragraph.cursor_x += glyph_info.advance_width + format.extra_letter_spacing;
+        paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
+        *last_glyph_id = Some(glyph_info.id);
+    }
+}
+
+/// Brackets etc are drawn mirrored in right-to-left text (rule L4 of UAX #9).
+///
+/// This only covers the most common pairs. With the `shaping` feature the shaper takes care of this.
+fn mirrored_char(chr: char) -> char {
+    match chr {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        _ => chr,
+    }
+}
+
+/// Shape a run of text set in a single font, and add one [`Glyph`] per character.
+///
+/// A cluster (e.g. a ligature, or a base character with its combining marks)
+/// is drawn by one of its characters: the first one for left-to-right text,
+/// and the last one for right-to-left text (since that ends up leftmost once the row is reordered).
+/// The advance of the cluster is divided evenly between its characters,
+/// so that the cursor can still be placed within it.
+#[cfg(feature = "shaping")]
+fn layout_shaped_run(
+    font: &super::font::Font,
+    job: &LayoutJob,
+    byte_range: std::ops::Range<usize>,
+    (font_index, bidi_level): (super::font::FontIndex, u8),
+    section_index: u32,
+    paragraph: &mut Paragraph,
+) {
+    let font_impl = font.font_impl(font_index);
+    let font_height = font.row_height();
+    let extra_letter_spacing = job.sections[section_index as usize]
+        .format
+        .extra_letter_spacing;
+    let text = &job.text[byte_range];
+    let rtl = bidi_level % 2 == 1;
+    let shaped = font_impl.shape(text, rtl);
+
+    // Byte offsets where clusters start, so we know where each one ends:
+    let mut cluster_starts: Vec<usize> = shaped.iter().map(|glyph| glyph.cluster).collect();
+    cluster_starts.sort_unstable();
+    cluster_starts.dedup();
+
+    // The shaper outputs glyphs left-to-right, so right-to-left clusters come in reverse order:
+    let mut clusters = vec![];
+    let mut glyph_index = 0;
+    while glyph_index < shaped.len() {
+        let cluster = shaped[glyph_index].cluster;
+        let num_glyphs = shaped[glyph_index..]
+            .iter()
+            .take_while(|glyph| glyph.cluster == cluster)
+            .count();
+        clusters.push(&shaped[glyph_index..glyph_index + num_glyphs]);
+        glyph_index += num_glyphs;
+    }
+    if rtl {
+        clusters.reverse();
+    }
+
+    for cluster_glyphs in clusters {
+        let cluster = cluster_glyphs[0].cluster;
+        let cluster_end = cluster_starts
+            .iter()
+            .copied()
+            .find(|&start| start > cluster)
+            .unwrap_or(text.len());
+
+        let mut pen_x = 0.0;
+        let mut placed = Vec::with_capacity(cluster_glyphs.len());
+        for glyph in cluster_glyphs {
+            placed.push((glyph.id, vec2(pen_x, 0.0) + glyph.offset));
+            pen_x += glyph.advance;
+        }
+        let cluster_advance = pen_x;
+
+        let uv_rect = match placed.as_slice() {
+            [(id, offset)] => {
+                let mut uv_rect = font_impl.glyph_uv_rect(*id);
+                uv_rect.offset += *offset;
+                uv_rect
+            }
+            _ => font_impl.cluster_uv_rect(&placed),
+        };
 
+        let num_chars = text[cluster..cluster_end].chars().count().max(1);
+        let char_advance = cluster_advance / num_chars as f32;
+        let drawing_char = if rtl { num_chars - 1 } else { 0 };
+        for (i, chr) in text[cluster..cluster_end].chars().enumerate() {
             paragraph.glyphs.push(Glyph {
                 chr,
-                pos: pos2(paragraph.cursor_x, f32::NAN),
-                size: vec2(glyph_info.advance_width, font_height),
-                uv_rect: glyph_info.uv_rect,
+                pos: pos2(paragraph.cursor_x + i as f32 * char_advance, f32::NAN),
+                size: vec2(char_advance, font_height),
+                uv_rect: if i == drawing_char {
+                    uv_rect
+                } else {
+                    UvRect::default()
+                },
                 section_index,
+                bidi_level,
             });
-
-            paragraph.cursor_x += glyph_info.advance_width;
-            paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
-            last_glyph_id = Some(glyph_info.id);
         }
+
+        paragraph.cursor_x += cluster_advance + extra_letter_spacing;
+        paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
     }
 }
 
@@ -161,17 +496,21 @@ fn rows_from_paragraphs(
                     vec2(0.0, paragraph.empty_paragraph_height),
                 ),
                 ends_with_newline: !is_last_paragraph,
+                rtl: paragraph.rtl,
+                hyphen: None,
             });
         } else {
             let paragraph_max_x = paragraph.glyphs.last().unwrap().max_x();
             if paragraph_max_x <= job.wrap.max_width {
                 // early-out optimization
-                let paragraph_min_x = paragraph.glyphs[0].pos.x;
+                let paragraph_min_x = paragraph.glyphs[0].pos.x - job.paragraph_indent;
                 rows.push(Row {
                     glyphs: paragraph.glyphs,
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: !is_last_paragraph,
+                    rtl: paragraph.rtl,
+                    hyphen: None,
                 });
             } else {
                 line_break(fonts, &paragraph, job, &mut rows);
@@ -189,15 +528,21 @@ fn line_break(
     job: &LayoutJob,
     out_rows: &mut Vec<Row>,
 ) {
+    let break_opportunities = break_opportunities(job, &paragraph.glyphs);
+
     // Keeps track of good places to insert row break if we exceed `wrap_width`.
     let mut row_break_candidates = RowBreakCandidates::default();
 
     let mut first_row_indentation = paragraph.glyphs[0].pos.x;
+    // `paragraph_indent` is part of the first row, so it is no reason to leave that row empty:
+    let mut may_leave_first_row_empty = first_row_indentation > job.paragraph_indent;
+    // The row rect of the first row includes the `paragraph_indent`:
+    let mut row_indent = job.paragraph_indent;
     let mut row_start_x = 0.0;
     let mut row_start_idx = 0;
     let mut non_empty_rows = 0;
 
-    for i in 0..paragraph.glyphs.len() {
+    for (i, &break_after) in break_opportunities.iter().enumerate() {
         let potential_row_width = paragraph.glyphs[i].max_x() - row_start_x;
 
         if job.wrap.max_rows > 0 && non_empty_rows >= job.wrap.max_rows {
@@ -205,7 +550,7 @@ fn line_break(
         }
 
         if potential_row_width > job.wrap.max_width {
-            if first_row_indentation > 0.0
+            if may_leave_first_row_empty
                 && !row_break_candidates.has_good_candidate(job.wrap.break_anywhere)
             {
                 // Allow the first row to be completely empty, because we know there will be more space on the next row:
@@ -215,10 +560,15 @@ fn line_break(
                     visuals: Default::default(),
                     rect: rect_from_x_range(first_row_indentation..=first_row_indentation),
                     ends_with_newline: false,
+                    rtl: paragraph.rtl,
+                    hyphen: None,
                 });
                 row_start_x += first_row_indentation;
                 first_row_indentation = 0.0;
-            } else if let Some(last_kept_index) = row_break_candidates.get(job.wrap.break_anywhere)
+                may_leave_first_row_empty = false;
+                row_indent = 0.0;
+            } else if let Some((last_kept_index, hyphenate)) =
+                row_break_candidates.get(job.wrap.break_anywhere)
             {
                 let glyphs: Vec<Glyph> = paragraph.glyphs[row_start_idx..=last_kept_index]
                     .iter()
@@ -229,26 +579,50 @@ fn line_break(
                     })
                     .collect();
 
-                let paragraph_min_x = glyphs[0].pos.x;
-                let paragraph_max_x = glyphs.last().unwrap().max_x();
+                let hyphen = if hyphenate {
+                    Some(hyphen_glyph(fonts, job, glyphs.last().unwrap()))
+                } else {
+                    None
+                };
+
+                let paragraph_min_x = glyphs[0].pos.x - row_indent;
+                let paragraph_max_x =
+                    glyphs.last().unwrap().max_x() + hyphen.map_or(0.0, |hyphen| hyphen.size.x);
 
                 out_rows.push(Row {
                     glyphs,
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: false,
+                    rtl: paragraph.rtl,
+                    hyphen,
                 });
 
+                may_leave_first_row_empty = false;
+                row_indent = 0.0;
                 row_start_idx = last_kept_index + 1;
                 row_start_x = paragraph.glyphs[row_start_idx].pos.x;
                 row_break_candidates = Default::default();
                 non_empty_rows += 1;
+
+                // The glyphs moved to the new row may still be good places to break it:
+                for (j, (glyph, &break_after)) in paragraph.glyphs[row_start_idx..i]
+                    .iter()
+                    .zip(&break_opportunities[row_start_idx..i])
+                    .enumerate()
+                {
+                    let j = row_start_idx + j;
+                    let hyphen_fits = hyphen_fits(fonts, job, glyph, break_after, row_start_x);
+                    row_break_candidates.add(j, glyph.chr, break_after, hyphen_fits);
+                }
             } else {
                 // Found no place to break, so we have to overrun wrap_width.
             }
         }
 
-        row_break_candidates.add(i, &paragraph.glyphs[i..]);
+        let glyph = &paragraph.glyphs[i];
+        let hyphen_fits = hyphen_fits(fonts, job, glyph, break_after, row_start_x);
+        row_break_candidates.add(i, glyph.chr, break_after, hyphen_fits);
     }
 
     if row_start_idx < paragraph.glyphs.len() {
@@ -266,7 +640,7 @@ fn line_break(
                 })
                 .collect();
 
-            let paragraph_min_x = glyphs[0].pos.x;
+            let paragraph_min_x = glyphs[0].pos.x - row_indent;
             let paragraph_max_x = glyphs.last().unwrap().max_x();
 
             out_rows.push(Row {
@@ -274,11 +648,155 @@ fn line_break(
                 visuals: Default::default(),
                 rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                 ends_with_newline: false,
+                rtl: paragraph.rtl,
+                hyphen: None,
             });
         }
     }
 }
 
+/// Where a row may be broken after a glyph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BreakAfter {
+    Never,
+
+    /// E.g. after a space or between CJK ideographs, see [`line_breaks`].
+    WordBoundary,
+
+    /// Within a word, with a hyphen added to the end of the row.
+    Hyphen,
+}
+
+const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Where can the row be broken after each glyph?
+fn break_opportunities(job: &LayoutJob, glyphs: &[Glyph]) -> Vec<BreakAfter> {
+    let chars: Vec<char> = glyphs.iter().map(|glyph| glyph.chr).collect();
+    let mut break_after = line_breaks(&chars);
+
+    if let Some(hyphenator) = &job.wrap.hyphenator {
+        let mut word_start = 0;
+        while word_start < chars.len() {
+            let word_len = chars[word_start..]
+                .iter()
+                .take_while(|chr| chr.is_alphabetic())
+                .count();
+            if word_len > 0 {
+                let word: String = chars[word_start..word_start + word_len].iter().collect();
+                for point in hyphenator.hyphenation_points(&word) {
+                    break_after[word_start + point - 1] = BreakAfter::Hyphen;
+                }
+            }
+            word_start += word_len + 1;
+        }
+    }
+
+    break_after
+}
+
+/// Where the Unicode line breaking algorithm (UAX #14) allows breaking after each character.
+#[cfg(feature = "linebreak")]
+fn line_breaks(chars: &[char]) -> Vec<BreakAfter> {
+    let text: String = chars.iter().collect();
+    let char_starts: Vec<usize> = text
+        .char_indices()
+        .map(|(byte_index, _)| byte_index)
+        .collect();
+
+    let mut break_after = vec![BreakAfter::Never; chars.len()];
+    for (byte_index, _) in unicode_linebreak::linebreaks(&text) {
+        // A break before the first character is never useful, and one after the last is implied.
+        match char_starts.binary_search(&byte_index) {
+            Ok(char_index) if char_index > 0 => {
+                break_after[char_index - 1] = if chars[char_index - 1] == SOFT_HYPHEN {
+                    BreakAfter::Hyphen
+                } else {
+                    BreakAfter::WordBoundary
+                };
+            }
+            _ => {}
+        }
+    }
+    break_after
+}
+
+/// Without the `linebreak` feature rows are broken after whitespace, dashes, CJK ideographs,
+/// and kana that aren't followed by a character that may not start a row in Japanese.
+#[cfg(not(feature = "linebreak"))]
+fn line_breaks(chars: &[char]) -> Vec<BreakAfter> {
+    const NON_BREAKING_SPACE: char = '\u{A0}';
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &chr)| {
+            let next = chars.get(i + 1).copied();
+            if chr == SOFT_HYPHEN {
+                BreakAfter::Hyphen
+            } else if (chr.is_whitespace() && chr != NON_BREAKING_SPACE)
+                || chr == '-'
+                || is_cjk_ideograph(chr)
+                || (is_kana(chr) && !next.map_or(false, is_gyoto_kinsoku))
+            {
+                BreakAfter::WordBoundary
+            } else {
+                BreakAfter::Never
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "linebreak"))]
+#[inline]
+fn is_cjk_ideograph(c: char) -> bool {
+    ('\u{4E00}' <= c && c <= '\u{9FFF}')
+        || ('\u{3400}' <= c && c <= '\u{4DBF}')
+        || ('\u{2B740}' <= c && c <= '\u{2B81F}')
+}
+
+#[cfg(not(feature = "linebreak"))]
+#[inline]
+fn is_kana(c: char) -> bool {
+    ('\u{3040}' <= c && c <= '\u{309F}') // Hiragana block
+        || ('\u{30A0}' <= c && c <= '\u{30FF}') // Katakana block
+}
+
+#[cfg(not(feature = "linebreak"))]
+#[inline]
+fn is_gyoto_kinsoku(c: char) -> bool {
+    // Gyōtō (meaning "beginning of line") kinsoku characters in Japanese typesetting are characters that may not appear at the start of a line, according to kinsoku shori rules.
+    // The list of gyōtō kinsoku characters can be found at https://en.wikipedia.org/wiki/Line_breaking_rules_in_East_Asian_languages#Characters_not_permitted_on_the_start_of_a_line.
+    ")]｝〕〉》」』】〙〗〟'\"｠»ヽヾーァィゥェォッャュョヮヵヶぁぃぅぇぉっゃゅょゎゕゖㇰㇱㇲㇳㇴㇵㇶㇷㇸㇹㇺㇻㇼㇽㇾㇿ々〻‐゠–〜?!‼⁇⁈⁉・、:;,。.".contains(c)
+}
+
+/// Can the row be hyphenated after this glyph, and still fit the hyphen?
+fn hyphen_fits(
+    fonts: &mut FontsImpl,
+    job: &LayoutJob,
+    glyph: &Glyph,
+    break_after: BreakAfter,
+    row_start_x: f32,
+) -> bool {
+    break_after == BreakAfter::Hyphen && {
+        let hyphen = hyphen_glyph(fonts, job, glyph);
+        glyph.max_x() + hyphen.size.x - row_start_x <= job.wrap.max_width
+    }
+}
+
+/// The hyphen to add after a glyph when breaking a row within a word.
+fn hyphen_glyph(fonts: &mut FontsImpl, job: &LayoutJob, after: &Glyph) -> Glyph {
+    let section = &job.sections[after.section_index as usize];
+    let font = fonts.font(&section.format.font_id);
+    let (_, glyph_info) = font.glyph_info_and_font_impl('-');
+    Glyph {
+        chr: '-',
+        pos: pos2(f32::NAN, f32::NAN), // Set once the row is aligned.
+        size: vec2(glyph_info.advance_width, font.row_height()),
+        uv_rect: glyph_info.uv_rect,
+        section_index: after.section_index,
+        bidi_level: after.bidi_level,
+    }
+}
+
 fn replace_last_glyph_with_overflow_character(
     fonts: &mut FontsImpl,
     job: &LayoutJob,
@@ -289,6 +807,8 @@ fn replace_last_glyph_with_overflow_character(
         None => return,
     };
 
+    row.hyphen = None;
+
     loop {
         let (prev_glyph, last_glyph) = match row.glyphs.as_mut_slice() {
             [.., prev, last] => (Some(prev), last),
@@ -336,12 +856,14 @@ fn replace_last_glyph_with_overflow_character(
     }
 }
 
+/// `indent` is the [`LayoutJob::paragraph_indent`] if this is the first row of a paragraph.
 fn halign_and_jusitfy_row(
     point_scale: PointScale,
     row: &mut Row,
     halign: Align,
     wrap_width: f32,
     justify: bool,
+    indent: f32,
 ) {
     if row.glyphs.is_empty() {
         return;
@@ -370,20 +892,24 @@ fn halign_and_jusitfy_row(
     assert!(num_glyphs_in_range > 0);
 
     let original_min_x = row.glyphs[glyph_range.0].logical_rect().min.x;
-    let original_max_x = row.glyphs[glyph_range.1 - 1].logical_rect().max.x;
+    let original_max_x = row.glyphs[glyph_range.1 - 1].logical_rect().max.x
+        + row.hyphen.map_or(0.0, |hyphen| hyphen.size.x);
     let original_width = original_max_x - original_min_x;
 
     let target_width = if justify && num_glyphs_in_range > 1 {
-        wrap_width
+        wrap_width - indent
     } else {
         original_width
     };
 
-    let (target_min_x, target_max_x) = match halign {
-        Align::LEFT => (0.0, target_width),
-        Align::Center => (-target_width / 2.0, target_width / 2.0),
-        Align::RIGHT => (-target_width, 0.0),
+    // The indentation is aligned together with the text:
+    let indented_width = indent + target_width;
+    let (indented_min_x, target_max_x) = match halign {
+        Align::LEFT => (0.0, indented_width),
+        Align::Center => (-indented_width / 2.0, indented_width / 2.0),
+        Align::RIGHT => (-indented_width, 0.0),
     };
+    let target_min_x = indented_min_x + indent;
 
     let num_spaces_in_range = row.glyphs[glyph_range.0..glyph_range.1]
         .iter()
@@ -422,10 +948,96 @@ fn halign_and_jusitfy_row(
     }
 
     // Note we ignore the leading/trailing whitespace here!
-    row.rect.min.x = target_min_x;
+    row.rect.min.x = indented_min_x;
     row.rect.max.x = target_max_x;
 }
 
+/// Move the glyphs of a row into visual order, as given by their bidi levels.
+///
+/// The glyphs stay in logical order in [`Row::glyphs`]; only their x positions change.
+fn reorder_row(row: &mut Row) {
+    let num_glyphs = row.glyphs.len();
+    if num_glyphs == 0 {
+        return;
+    }
+
+    // Rule L1 of UAX #9: whitespace at the end of a row gets the paragraph direction.
+    let paragraph_level = row.rtl as u8;
+    for glyph in row.glyphs.iter_mut().rev() {
+        if !glyph.chr.is_whitespace() {
+            break;
+        }
+        glyph.bidi_level = paragraph_level;
+    }
+
+    if row.glyphs.iter().all(|glyph| glyph.bidi_level == 0) {
+        return;
+    }
+
+    // Each glyph takes up the space up to the next glyph, which includes kerning, justification etc:
+    let widths: Vec<f32> = (0..num_glyphs)
+        .map(|i| match row.glyphs.get(i + 1) {
+            Some(next) => next.pos.x - row.glyphs[i].pos.x,
+            None => row.glyphs[i].size.x,
+        })
+        .collect();
+
+    // Rule L2: from the highest level down to the lowest odd level,
+    // reverse any sequence of glyphs at that level or higher.
+    let mut order: Vec<usize> = (0..num_glyphs).collect();
+    let max_level = row.glyphs.iter().map(|g| g.bidi_level).max().unwrap_or(0);
+    let min_level = row.glyphs.iter().map(|g| g.bidi_level).min().unwrap_or(0);
+    let lowest_odd_level = min_level | 1;
+    for level in (lowest_odd_level..=max_level).rev() {
+        let mut i = 0;
+        while i < num_glyphs {
+            if row.glyphs[order[i]].bidi_level >= level {
+                let start = i;
+                while i < num_glyphs && row.glyphs[order[i]].bidi_level >= level {
+                    i += 1;
+                }
+                order[start..i].reverse();
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    let logical_min_x = row.glyphs[0].pos.x;
+    let logical_max_x = row.glyphs[num_glyphs - 1].pos.x + widths[num_glyphs - 1];
+
+    // A right-to-left row is mirrored within its rect,
+    // so that leading and trailing whitespace ends up on the correct side.
+    let mut x = if row.rtl {
+        row.rect.min.x + row.rect.max.x - logical_max_x
+    } else {
+        logical_min_x
+    };
+    for i in order {
+        row.glyphs[i].pos.x = x;
+        x += widths[i];
+    }
+}
+
+/// Right-to-left paragraphs start on the right side,
+/// so with [`Align::LEFT`] we align their rows to the right edge of the widest row.
+fn align_rtl_rows_right(rows: &mut [Row]) {
+    let max_x = rows
+        .iter()
+        .map(|row| row.rect.max.x)
+        .fold(0.0_f32, |a, b| a.max(b));
+
+    for row in rows.iter_mut().filter(|row| row.rtl) {
+        let shift = max_x - row.rect.max.x;
+        if shift > 0.0 {
+            for glyph in &mut row.glyphs {
+                glyph.pos.x += shift;
+            }
+            row.rect = row.rect.translate(vec2(shift, 0.0));
+        }
+    }
+}
+
 /// Calculate the Y positions and tessellate the text.
 fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<Row>) -> Galley {
     let mut first_row_min_height = job.first_row_min_height;
@@ -435,15 +1047,28 @@ fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<
     for row in &mut rows {
         let mut row_height = first_row_min_height.max(row.rect.height());
         first_row_min_height = 0.0;
+
+        if let (Some(hyphen), Some(last)) = (&mut row.hyphen, row.glyphs.last()) {
+            hyphen.pos.x = if last.is_rtl() {
+                last.pos.x - hyphen.size.x
+            } else {
+                last.max_x()
+            };
+        }
+
         for glyph in &row.glyphs {
-            row_height = row_height.max(glyph.size.y);
+            let format = &job.sections[glyph.section_index as usize].format;
+            row_height = row_height.max(glyph.size.y * format.line_height_factor);
         }
         row_height = point_scale.round_to_pixel(row_height);
 
         // Now positions each glyph:
-        for glyph in &mut row.glyphs {
+        for glyph in row.glyphs.iter_mut().chain(&mut row.hyphen) {
             let format = &job.sections[glyph.section_index as usize].format;
-            glyph.pos.y = cursor_y + format.valign.to_factor() * (row_height - glyph.size.y);
+            let line_height = glyph.size.y * format.line_height_factor;
+            glyph.pos.y = cursor_y
+                + format.valign.to_factor() * (row_height - line_height)
+                + 0.5 * (line_height - glyph.size.y);
             glyph.pos.y = point_scale.round_to_pixel(glyph.pos.y);
         }
 
@@ -453,6 +1078,9 @@ fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<
         min_x = min_x.min(row.rect.min.x);
         max_x = max_x.max(row.rect.max.x);
         cursor_y += row_height;
+        if row.ends_with_newline {
+            cursor_y += job.paragraph_spacing;
+        }
         cursor_y = point_scale.round_to_pixel(cursor_y);
     }
 
@@ -462,8 +1090,18 @@ fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<
     let mut num_vertices = 0;
     let mut num_indices = 0;
 
-    for row in &mut rows {
-        row.visuals = tessellate_row(point_scale, &job, &format_summary, row);
+    // Does a highlight continue from a row onto the next one?
+    let highlight_continues: Vec<bool> = rows
+        .windows(2)
+        .map(|pair| format_summary.any_highlight && highlight_continues(&job, &pair[0], &pair[1]))
+        .collect();
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        let highlight_open_ends = [
+            i > 0 && highlight_continues[i - 1],
+            highlight_continues.get(i).copied().unwrap_or(false),
+        ];
+        row.visuals = tessellate_row(point_scale, &job, &format_summary, highlight_open_ends, row);
         mesh_bounds = mesh_bounds.union(row.visuals.mesh_bounds);
         num_vertices += row.visuals.mesh.vertices.len();
         num_indices += row.visuals.mesh.indices.len();
@@ -478,13 +1116,16 @@ fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<
         mesh_bounds,
         num_vertices,
         num_indices,
+        atlas_page: Default::default(), // Set by the caller
     }
 }
 
 #[derive(Default)]
 struct FormatSummary {
     any_background: bool,
+    any_highlight: bool,
     any_underline: bool,
+    any_overline: bool,
     any_strikethrough: bool,
 }
 
@@ -492,16 +1133,38 @@ fn format_summary(job: &LayoutJob) -> FormatSummary {
     let mut format_summary = FormatSummary::default();
     for section in &job.sections {
         format_summary.any_background |= section.format.background != Color32::TRANSPARENT;
+        format_summary.any_highlight |= section.format.highlight != Color32::TRANSPARENT;
         format_summary.any_underline |= section.format.underline != Stroke::none();
+        format_summary.any_overline |= section.format.overline != Stroke::none();
         format_summary.any_strikethrough |= section.format.strikethrough != Stroke::none();
     }
     format_summary
 }
 
+/// Does the highlight at the end of this row continue at the start of the next one?
+fn highlight_continues(job: &LayoutJob, row: &Row, next_row: &Row) -> bool {
+    if row.ends_with_newline {
+        return false;
+    }
+    match (row.glyphs.last(), next_row.glyphs.first()) {
+        (Some(last), Some(first)) => {
+            let last = &job.sections[last.section_index as usize].format;
+            let first = &job.sections[first.section_index as usize].format;
+            last.highlight != Color32::TRANSPARENT
+                && last.highlight == first.highlight
+                && last.highlight_rounding == first.highlight_rounding
+        }
+        _ => false,
+    }
+}
+
+/// `highlight_open_ends`: does the highlight at the start and end of the row
+/// continue from the previous row and to the next row?
 fn tessellate_row(
     point_scale: PointScale,
     job: &LayoutJob,
     format_summary: &FormatSummary,
+    highlight_open_ends: [bool; 2],
     row: &mut Row,
 ) -> RowVisuals {
     if row.glyphs.is_empty() {
@@ -513,29 +1176,61 @@ fn tessellate_row(
     mesh.reserve_triangles(row.glyphs.len() * 2);
     mesh.reserve_vertices(row.glyphs.len() * 4);
 
+    // Backgrounds and lines are merged between neighboring glyphs, so they need the visual order:
+    let visual_glyphs = if row.hyphen.is_some() || row.glyphs.iter().any(Glyph::is_rtl) {
+        let mut glyphs = row.glyphs.clone();
+        glyphs.extend(row.hyphen);
+        glyphs.sort_by(|a, b| {
+            a.pos
+                .x
+                .partial_cmp(&b.pos.x)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        std::borrow::Cow::Owned(glyphs)
+    } else {
+        std::borrow::Cow::Borrowed(row.glyphs.as_slice())
+    };
+
     if format_summary.any_background {
-        add_row_backgrounds(job, row, &mut mesh);
+        add_row_backgrounds(job, &visual_glyphs, &mut mesh);
+    }
+
+    if format_summary.any_highlight {
+        // The start of a right-to-left row is to the right:
+        let [start, end] = highlight_open_ends;
+        let open_sides = if row.rtl { [end, start] } else { [start, end] };
+        add_row_highlights(point_scale, job, &visual_glyphs, open_sides, &mut mesh);
     }
 
     let glyph_vertex_start = mesh.vertices.len();
-    tessellate_glyphs(point_scale, job, row, &mut mesh);
+    tessellate_glyphs(point_scale, job, row, false, &mut mesh);
     let glyph_vertex_end = mesh.vertices.len();
+    tessellate_glyphs(point_scale, job, row, true, &mut mesh);
 
     if format_summary.any_underline {
-        add_row_hline(point_scale, row, &mut mesh, |glyph| {
+        add_row_hline(point_scale, &visual_glyphs, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
             let stroke = format.underline;
             let y = glyph.logical_rect().bottom();
-            (stroke, y)
+            (stroke, format.underline_style, y)
+        });
+    }
+
+    if format_summary.any_overline {
+        add_row_hline(point_scale, &visual_glyphs, &mut mesh, |glyph| {
+            let format = &job.sections[glyph.section_index as usize].format;
+            let stroke = format.overline;
+            let y = glyph.logical_rect().top();
+            (stroke, UnderlineStyle::Solid, y)
         });
     }
 
     if format_summary.any_strikethrough {
-        add_row_hline(point_scale, row, &mut mesh, |glyph| {
+        add_row_hline(point_scale, &visual_glyphs, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
             let stroke = format.strikethrough;
             let y = glyph.logical_rect().center().y;
-            (stroke, y)
+            (stroke, UnderlineStyle::Solid, y)
         });
     }
 
@@ -548,10 +1243,10 @@ fn tessellate_row(
     }
 }
 
-/// Create background for glyphs that have them.
+/// Create background for glyphs (in visual order) that have them.
 /// Creates as few rectangular regions as possible.
-fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
-    if row.glyphs.is_empty() {
+fn add_row_backgrounds(job: &LayoutJob, glyphs: &[Glyph], mesh: &mut Mesh) {
+    if glyphs.is_empty() {
         return;
     }
 
@@ -566,7 +1261,7 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     let mut run_start = None;
     let mut last_rect = Rect::NAN;
 
-    for glyph in &row.glyphs {
+    for glyph in glyphs {
         let format = &job.sections[glyph.section_index as usize].format;
         let color = format.background;
         let rect = glyph.logical_rect();
@@ -593,10 +1288,91 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     end_run(run_start.take(), last_rect.right());
 }
 
-fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
-    for glyph in &row.glyphs {
+/// Create rounded highlights for glyphs (in visual order) that have them.
+///
+/// `open_sides`: should the left and right end of the row have square corners,
+/// because the highlight continues on another row?
+fn add_row_highlights(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    glyphs: &[Glyph],
+    [open_left, open_right]: [bool; 2],
+    mesh: &mut Mesh,
+) {
+    let format =
+        |glyph: &Glyph| -> &TextFormat { &job.sections[glyph.section_index as usize].format };
+    let same_highlight = |a: &Glyph, b: &Glyph| {
+        let (a_format, b_format) = (format(a), format(b));
+        a_format.highlight == b_format.highlight
+            && a_format.highlight_rounding == b_format.highlight_rounding
+            && a.logical_rect().y_range() == b.logical_rect().y_range()
+    };
+
+    let feathering = 1.0 / point_scale.pixels_per_point();
+    let mut points = vec![];
+    let mut path = crate::tessellator::Path::default();
+
+    let mut add_highlight = |start: usize, end: usize| {
+        let format = format(&glyphs[start]);
+        let rect = Rect::from_min_max(
+            glyphs[start].logical_rect().left_top(),
+            pos2(
+                glyphs[end - 1].max_x(),
+                glyphs[start].logical_rect().bottom(),
+            ),
+        );
+        let rect = rect.expand(1.0); // looks better
+        let r = format.highlight_rounding;
+        let left = if open_left && start == 0 { 0.0 } else { r };
+        let right = if open_right && end == glyphs.len() {
+            0.0
+        } else {
+            r
+        };
+        let rounding = Rounding {
+            nw: left,
+            ne: right,
+            sw: left,
+            se: right,
+        };
+
+        points.clear();
+        crate::tessellator::path::rounded_rectangle(&mut points, rect, rounding);
+        path.clear();
+        path.add_line_loop(&points);
+        path.fill(feathering, format.highlight, mesh);
+    };
+
+    let mut run_start: Option<usize> = None;
+
+    for (i, glyph) in glyphs.iter().enumerate() {
+        let continues_run = run_start.map_or(false, |start| same_highlight(&glyphs[start], glyph));
+        if !continues_run {
+            if let Some(start) = run_start.take() {
+                add_highlight(start, i);
+            }
+            if format(glyph).highlight != Color32::TRANSPARENT {
+                run_start = Some(i);
+            }
+        }
+    }
+
+    if let Some(start) = run_start {
+        add_highlight(start, glyphs.len());
+    }
+}
+
+/// Tessellate either the color glyphs (e.g. emoji) or the other glyphs of the row.
+fn tessellate_glyphs(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    row: &Row,
+    color_glyphs: bool,
+    mesh: &mut Mesh,
+) {
+    for glyph in row.glyphs.iter().chain(&row.hyphen) {
         let uv_rect = glyph.uv_rect;
-        if !uv_rect.is_nothing() {
+        if !uv_rect.is_nothing() && uv_rect.is_color == color_glyphs {
             let mut left_top = glyph.pos + uv_rect.offset;
             left_top.x = point_scale.round_to_pixel(left_top.x);
             left_top.y = point_scale.round_to_pixel(left_top.y);
@@ -609,7 +1385,12 @@ fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh:
 
             let format = &job.sections[glyph.section_index as usize].format;
 
-            let color = format.color;
+            let color = if uv_rect.is_color {
+                // Keep the colors of the glyph, but fade it with the text:
+                Color32::from_white_alpha(format.color.a())
+            } else {
+                format.color
+            };
 
             if format.italics {
                 let idx = mesh.vertices.len() as u32;
@@ -645,36 +1426,42 @@ fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh:
     }
 }
 
-/// Add a horizontal line over a row of glyphs with a stroke and y decided by a callback.
+/// Add a horizontal line over a row of glyphs (in visual order) with a stroke, style and y decided by a callback.
 fn add_row_hline(
     point_scale: PointScale,
-    row: &Row,
+    glyphs: &[Glyph],
     mesh: &mut Mesh,
-    stroke_and_y: impl Fn(&Glyph) -> (Stroke, f32),
+    stroke_style_and_y: impl Fn(&Glyph) -> (Stroke, UnderlineStyle, f32),
 ) {
-    let mut end_line = |start: Option<(Stroke, Pos2)>, stop_x: f32| {
-        if let Some((stroke, start)) = start {
-            add_hline(point_scale, [start, pos2(stop_x, start.y)], stroke, mesh);
+    let mut end_line = |start: Option<(Stroke, UnderlineStyle, Pos2)>, stop_x: f32| {
+        if let Some((stroke, style, start)) = start {
+            add_hline(
+                point_scale,
+                [start, pos2(stop_x, start.y)],
+                stroke,
+                style,
+                mesh,
+            );
         }
     };
 
     let mut line_start = None;
     let mut last_right_x = f32::NAN;
 
-    for glyph in &row.glyphs {
-        let (stroke, y) = stroke_and_y(glyph);
+    for glyph in glyphs {
+        let (stroke, style, y) = stroke_style_and_y(glyph);
 
         if stroke == Stroke::none() {
             end_line(line_start.take(), last_right_x);
-        } else if let Some((existing_stroke, start)) = line_start {
-            if existing_stroke == stroke && start.y == y {
+        } else if let Some((existing_stroke, existing_style, start)) = line_start {
+            if existing_stroke == stroke && existing_style == style && start.y == y {
                 // continue the same line
             } else {
                 end_line(line_start.take(), last_right_x);
-                line_start = Some((stroke, pos2(glyph.pos.x, y)));
+                line_start = Some((stroke, style, pos2(glyph.pos.x, y)));
             }
         } else {
-            line_start = Some((stroke, pos2(glyph.pos.x, y)));
+            line_start = Some((stroke, style, pos2(glyph.pos.x, y)));
         }
 
         last_right_x = glyph.max_x();
@@ -683,10 +1470,44 @@ fn add_row_hline(
     end_line(line_start.take(), last_right_x);
 }
 
-fn add_hline(point_scale: PointScale, [start, stop]: [Pos2; 2], stroke: Stroke, mesh: &mut Mesh) {
+fn add_hline(
+    point_scale: PointScale,
+    [start, stop]: [Pos2; 2],
+    stroke: Stroke,
+    style: UnderlineStyle,
+    mesh: &mut Mesh,
+) {
     let antialiased = true;
 
-    if antialiased {
+    if style == UnderlineStyle::Dotted {
+        let feathering = 1.0 / point_scale.pixels_per_point();
+        let radius = 0.5 * stroke.width.max(feathering);
+        let mut path = crate::tessellator::Path::default();
+        let mut x = start.x + radius;
+        while x + radius <= stop.x {
+            path.clear();
+            path.add_circle(pos2(x, start.y), radius);
+            path.fill(feathering, stroke.color, mesh);
+            x += 4.0 * radius; // One dot every two diameters
+        }
+    } else if style == UnderlineStyle::Wavy {
+        let amplitude = 1.0 + 0.5 * stroke.width;
+        let wavelength = 4.0 * amplitude;
+        let step = wavelength / 8.0;
+        let num_steps = ((stop.x - start.x) / step).ceil().at_least(1.0) as usize;
+        let points: Vec<Pos2> = (0..=num_steps)
+            .map(|i| {
+                let x = (start.x + i as f32 * step).at_most(stop.x);
+                let phase = std::f32::consts::TAU * (x - start.x) / wavelength;
+                pos2(x, start.y + amplitude * phase.sin())
+            })
+            .collect();
+
+        let mut path = crate::tessellator::Path::default();
+        path.add_open_points(&points);
+        let feathering = 1.0 / point_scale.pixels_per_point();
+        path.stroke_open(feathering, stroke, mesh);
+    } else if antialiased {
         let mut path = crate::tessellator::Path::default(); // TODO(emilk): reuse this to avoid re-allocations.
         path.add_line_segment([start, stop]);
         let feathering = 1.0 / point_scale.pixels_per_point();
@@ -711,22 +1532,17 @@ fn add_hline(point_scale: PointScale, [start, stop]: [Pos2; 2], stroke: Stroke,
 // ----------------------------------------------------------------------------
 
 /// Keeps track of good places to break a long row of text.
-/// Will focus primarily on spaces, secondarily on things like `-`
+/// Will focus primarily on word boundaries, secondarily on things like `.`
 #[derive(Clone, Copy, Default)]
 struct RowBreakCandidates {
-    /// Breaking at ` ` or other whitespace
+    /// Breaking where the line breaking rules allow it
+    /// (after whitespace, between CJK ideographs, after a `-`, …)
     /// is always the primary candidate.
-    space: Option<usize>,
-
-    /// Logograms (single character representing a whole word) are good candidates for line break.
-    logogram: Option<usize>,
+    word_boundary: Option<usize>,
 
-    /// Kana (Japanese hiragana and katakana) may be line broken unless before a gyōtō kinsoku character.
-    kana: Option<usize>,
-
-    /// Breaking at a dash is a super-
-    /// good idea.
-    dash: Option<usize>,
+    /// Hyphenating a word that doesn't fit is also fine,
+    /// if the hyphen fits too.
+    hyphen: Option<usize>,
 
     /// This is nicer for things like URLs, e.g. www.
     /// example.com.
@@ -738,25 +1554,21 @@ struct RowBreakCandidates {
 }
 
 impl RowBreakCandidates {
-    fn add(&mut self, index: usize, glyphs: &[Glyph]) {
-        let chr = glyphs[0].chr;
-        const NON_BREAKING_SPACE: char = '\u{A0}';
-        if chr.is_whitespace() && chr != NON_BREAKING_SPACE {
-            self.space = Some(index);
-        } else if is_cjk_ideograph(chr) {
-            self.logogram = Some(index);
-        } else if chr == '-' {
-            self.dash = Some(index);
-        } else if chr.is_ascii_punctuation() {
-            self.punctuation = Some(index);
-        } else if is_kana(chr) && (glyphs.len() == 1 || !is_gyoto_kinsoku(glyphs[1].chr)) {
-            self.kana = Some(index);
+    fn add(&mut self, index: usize, chr: char, break_after: BreakAfter, hyphen_fits: bool) {
+        match break_after {
+            BreakAfter::WordBoundary => self.word_boundary = Some(index),
+            BreakAfter::Hyphen if hyphen_fits => self.hyphen = Some(index),
+            BreakAfter::Hyphen | BreakAfter::Never => {
+                if chr.is_ascii_punctuation() {
+                    self.punctuation = Some(index);
+                }
+            }
         }
         self.any = Some(index);
     }
 
     fn has_word_boundary(&self) -> bool {
-        self.space.is_some() || self.logogram.is_some()
+        self.word_boundary.is_some() || self.hyphen.is_some()
     }
 
     fn has_good_candidate(&self, break_anywhere: bool) -> bool {
@@ -767,47 +1579,578 @@ impl RowBreakCandidates {
         }
     }
 
-    fn get(&self, break_anywhere: bool) -> Option<usize> {
+    /// Where to break, and whether to add a hyphen there.
+    fn get(&self, break_anywhere: bool) -> Option<(usize, bool)> {
         if break_anywhere {
-            self.any
-        } else {
-            self.space
-                .or(self.kana)
-                .or(self.logogram)
-                .or(self.dash)
-                .or(self.punctuation)
-                .or(self.any)
+            return self.any.map(|index| (index, false));
+        }
+
+        match (self.word_boundary, self.hyphen) {
+            // Only hyphenate a word if that gets more of it on the row:
+            (Some(word_boundary), Some(hyphen)) if hyphen > word_boundary => Some((hyphen, true)),
+            (None, Some(hyphen)) => Some((hyphen, true)),
+            (Some(word_boundary), _) => Some((word_boundary, false)),
+            (None, None) => self.punctuation.or(self.any).map(|index| (index, false)),
         }
     }
 }
 
-#[inline]
-fn is_cjk_ideograph(c: char) -> bool {
-    ('\u{4E00}' <= c && c <= '\u{9FFF}')
-        || ('\u{3400}' <= c && c <= '\u{4DBF}')
-        || ('\u{2B740}' <= c && c <= '\u{2B81F}')
+// ----------------------------------------------------------------------------
+
+#[test]
+fn test_zero_max_width() {
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let mut layout_job = LayoutJob::single_section("W".into(), super::TextFormat::default());
+    layout_job.wrap.max_width = 0.0;
+    let galley = super::layout(&mut fonts, layout_job.into());
+    assert_eq!(galley.rows.len(), 1);
 }
 
-#[inline]
-fn is_kana(c: char) -> bool {
-    ('\u{3040}' <= c && c <= '\u{309F}') // Hiragana block
-        || ('\u{30A0}' <= c && c <= '\u{30FF}') // Katakana block
+#[cfg(feature = "shaping")]
+#[test]
+fn test_shaped_clusters() {
+    use super::cursor::CCursor;
+
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    // "fi" is a ligature in the proportional font, and "e\u{301}" is an e with a combining
+    // acute accent, which the monospace font has:
+    let mut layout_job = LayoutJob::default();
+    layout_job.append("fi", 0.0, super::TextFormat::default());
+    let monospace = super::TextFormat {
+        font_id: super::FontId::monospace(14.0),
+        ..Default::default()
+    };
+    layout_job.append("e\u{301}x", 0.0, monospace);
+    let text = layout_job.text.clone();
+    let galley = super::layout(&mut fonts, layout_job.into());
+    let glyphs = &galley.rows[0].glyphs;
+
+    // Still one glyph per character, so the cursor can be placed between all of them:
+    assert_eq!(
+        glyphs.iter().map(|glyph| glyph.chr).collect::<String>(),
+        text
+    );
+
+    // Each cluster is drawn by its first character, and its advance is split between them:
+    let drawn: Vec<bool> = glyphs
+        .iter()
+        .map(|glyph| !glyph.uv_rect.is_nothing())
+        .collect();
+    assert_eq!(drawn, [true, false, true, false, true]);
+    assert_eq!(glyphs[0].size.x, glyphs[1].size.x);
+    assert_eq!(glyphs[1].pos.x, glyphs[0].max_x());
+    assert_eq!(glyphs[3].pos.x, glyphs[2].max_x());
+
+    // The cursor moves through the clusters, left to right:
+    let cursor_x: Vec<f32> = (0..=text.chars().count())
+        .map(|index| {
+            let cursor = galley.from_ccursor(CCursor::new(index));
+            galley.pos_from_cursor(&cursor).min.x
+        })
+        .collect();
+    assert!(
+        cursor_x.windows(2).all(|pair| pair[0] < pair[1]),
+        "{:?}",
+        cursor_x
+    );
+    assert_eq!(
+        cursor_x[1], glyphs[1].pos.x,
+        "in the middle of the ligature"
+    );
+    assert_eq!(cursor_x[5], galley.rows[0].rect.max.x);
 }
 
-#[inline]
-fn is_gyoto_kinsoku(c: char) -> bool {
-    // Gyōtō (meaning "beginning of line") kinsoku characters in Japanese typesetting are characters that may not appear at the start of a line, according to kinsoku shori rules.
-    // The list of gyōtō kinsoku characters can be found at https://en.wikipedia.org/wiki/Line_breaking_rules_in_East_Asian_languages#Characters_not_permitted_on_the_start_of_a_line.
-    ")]｝〕〉》」』】〙〗〟'\"｠»ヽヾーァィゥェォッャュョヮヵヶぁぃぅぇぉっゃゅょゎゕゖㇰㇱㇲㇳㇴㇵㇶㇷㇸㇹㇺㇻㇼㇽㇾㇿ々〻‐゠–〜?!‼⁇⁈⁉・、:;,。.".contains(c)
+#[cfg(feature = "bidi")]
+#[test]
+fn test_bidi_reordering() {
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let mut visual_order = |text: &str| {
+        let layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+        let galley = super::layout(&mut fonts, layout_job.into());
+        let row = &galley.rows[0];
+
+        // The glyphs stay in text order, so that cursors map to characters,
+        // but are moved to their place on screen:
+        let chars: String = row.glyphs.iter().map(|glyph| glyph.chr).collect();
+        assert_eq!(chars, text);
+        let mut glyphs = row.glyphs.clone();
+        glyphs.sort_by(|a, b| a.pos.x.partial_cmp(&b.pos.x).unwrap());
+        assert!(
+            glyphs.windows(2).all(|g| g[0].max_x() <= g[1].pos.x + 1.0),
+            "glyphs overlap"
+        );
+        let chars: String = glyphs.iter().map(|glyph| glyph.chr).collect();
+        (chars, row.rtl)
+    };
+
+    // Left-to-right paragraph with a Hebrew word at the end:
+    assert_eq!(
+        visual_order("ab \u{5E9}\u{5DC}\u{5D5}\u{5DD}"),
+        ("ab \u{5DD}\u{5D5}\u{5DC}\u{5E9}".to_owned(), false)
+    );
+
+    // Right-to-left paragraph with a latin word embedded, which keeps its order:
+    assert_eq!(
+        visual_order("\u{5E9}\u{5DC} ab"),
+        ("ab \u{5DC}\u{5E9}".to_owned(), true)
+    );
+
+    // Plain left-to-right text is left alone:
+    assert_eq!(visual_order("abc"), ("abc".to_owned(), false));
 }
 
-// ----------------------------------------------------------------------------
+#[cfg(test)]
+fn break_points(text: &str, hyphenator: Option<super::Hyphenator>) -> Vec<(usize, BreakAfter)> {
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let mut layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+    layout_job.wrap.hyphenator = hyphenator.map(Arc::new);
+    let galley = super::layout(&mut fonts, layout_job.clone().into());
+    let glyphs = &galley.rows[0].glyphs;
+    break_opportunities(&layout_job, glyphs)
+        .into_iter()
+        .enumerate()
+        .take(glyphs.len() - 1) // after the last glyph doesn't matter
+        .filter(|(_, break_after)| *break_after != BreakAfter::Never)
+        .collect()
+}
+
+#[cfg(test)]
+fn wrapped_rows(layout_job: LayoutJob) -> Vec<String> {
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let galley = super::layout(&mut fonts, layout_job.into());
+    galley
+        .rows
+        .iter()
+        .map(|row| {
+            row.glyphs
+                .iter()
+                .chain(&row.hyphen)
+                .map(|glyph| glyph.chr)
+                .collect()
+        })
+        .collect()
+}
 
 #[test]
-fn test_zero_max_width() {
+fn test_break_opportunities() {
+    use BreakAfter::{Hyphen, WordBoundary};
+
+    assert_eq!(break_points("hello world", None), [(5, WordBoundary)]);
+    assert_eq!(break_points("non\u{A0}breaking", None), []);
+    assert_eq!(break_points("soft\u{AD}hyphen", None), [(4, Hyphen)]);
+
+    // Between CJK ideographs:
+    assert_eq!(
+        break_points("日本語", None),
+        [(0, WordBoundary), (1, WordBoundary)]
+    );
+
+    // Not before a small kana, which may not start a row:
+    assert_eq!(break_points("かっこ", None), [(1, WordBoundary)]);
+
+    // A hyphenator adds hyphens within words:
+    let hyphenator = super::Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n");
+    assert_eq!(
+        break_points("hyphenation rules", Some(hyphenator)),
+        [(1, Hyphen), (5, Hyphen), (11, WordBoundary)]
+    );
+}
+
+#[test]
+fn test_wrap_url() {
+    let text = "see https://example.com/some/long-path";
+    let mut layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+    layout_job.wrap.max_width = 80.0;
+    let rows = wrapped_rows(layout_job);
+    assert_eq!(rows.concat(), text);
+
+    // Broken after punctuation rather than within the words:
+    #[cfg(feature = "linebreak")]
+    assert_eq!(rows, ["see https://", "example.com/", "some/long-", "path"]);
+    #[cfg(not(feature = "linebreak"))]
+    assert_eq!(
+        rows,
+        ["see ", "https://", "example.com/", "some/long-", "path"]
+    );
+}
+
+#[test]
+fn test_wrap_cjk() {
+    // Two characters fit on a row:
+    let wrap = |text: &str| {
+        let mut layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+        layout_job.wrap.max_width = 30.0;
+        wrapped_rows(layout_job)
+    };
+
+    // Between any two characters:
+    assert_eq!(
+        wrap("日本語の文章を折り返す。"),
+        ["日本", "語の", "文章", "を折", "り返", "す。"]
+    );
+
+    // …but a small kana may not start a row:
+    assert_eq!(wrap("あいっう"), ["あ", "いっ", "う"]);
+}
+
+#[test]
+fn test_wrap_soft_hyphen() {
+    let text = "extra\u{AD}ordinary";
+    let mut layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+    layout_job.wrap.max_width = 50.0;
+    assert_eq!(
+        wrapped_rows(layout_job.clone()),
+        ["extra\u{AD}-", "ordinary"],
+        "broken at the soft hyphen, with a hyphen added"
+    );
+
+    // Invisible where the row isn't broken:
+    layout_job.wrap.max_width = f32::INFINITY;
     let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
-    let mut layout_job = LayoutJob::single_section("W".into(), super::TextFormat::default());
-    layout_job.wrap.max_width = 0.0;
     let galley = super::layout(&mut fonts, layout_job.into());
-    assert_eq!(galley.rows.len(), 1);
+    let soft_hyphen = galley.rows[0].glyphs[5];
+    assert_eq!(soft_hyphen.chr, '\u{AD}');
+    assert_eq!(soft_hyphen.size.x, 0.0);
+    assert!(soft_hyphen.uv_rect.is_nothing());
+}
+
+#[test]
+fn test_wrap_width_with_indent_and_letter_spacing() {
+    let text = "The quick brown fox jumps over the lazy dog, again and again and again.";
+    let max_width = 100.0;
+    let layout = |paragraph_indent: f32, extra_letter_spacing: f32| {
+        let mut layout_job = LayoutJob::single_section(
+            text.into(),
+            super::TextFormat {
+                extra_letter_spacing,
+                ..Default::default()
+            },
+        );
+        layout_job.paragraph_indent = paragraph_indent;
+        layout_job.wrap.max_width = max_width;
+        let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+        super::layout(&mut fonts, layout_job.into())
+    };
+
+    let plain = layout(0.0, 0.0);
+    let indented = layout(40.0, 0.0);
+    let spaced = layout(0.0, 3.0);
+    for galley in [&plain, &indented, &spaced] {
+        for row in &galley.rows {
+            let text_width = row.glyphs.last().unwrap().max_x() - row.rect.min.x;
+            assert!(text_width <= max_width, "{} > {}", text_width, max_width);
+        }
+    }
+
+    // The indent is part of the first row:
+    assert_eq!(indented.rows[0].rect.min.x, 0.0);
+    assert_eq!(indented.rows[0].glyphs[0].pos.x, 40.0);
+    assert!(indented.rows[0].glyphs.len() < plain.rows[0].glyphs.len());
+    assert_eq!(indented.rows[1].glyphs[0].pos.x, 0.0);
+
+    // Wider letters need more rows:
+    assert!(spaced.rows.len() > plain.rows.len());
+}
+
+#[test]
+fn test_line_height_factor() {
+    let layout = |line_height_factor: f32| {
+        let layout_job = LayoutJob::single_section(
+            "one\n\ntwo".into(),
+            super::TextFormat {
+                line_height_factor,
+                ..Default::default()
+            },
+        );
+        let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+        super::layout(&mut fonts, layout_job.into())
+    };
+
+    let plain = layout(1.0);
+    let tall = layout(2.0);
+    let height = plain.rows[0].height();
+    assert_eq!(plain.rows.len(), 3);
+    assert_eq!(tall.rows.len(), 3);
+    for (row, tall_row) in plain.rows.iter().zip(&tall.rows) {
+        assert_eq!(row.height(), height);
+        assert_eq!(tall_row.height(), 2.0 * height, "empty rows are taller too");
+    }
+    assert_eq!(tall.rect.height(), 3.0 * 2.0 * height);
+
+    // The extra space is split evenly above and below the text:
+    assert_eq!(plain.rows[0].glyphs[0].pos.y, 0.0);
+    assert_eq!(tall.rows[0].glyphs[0].pos.y, 0.5 * height);
+    assert_eq!(tall.rows[2].glyphs[0].pos.y, 4.0 * height + 0.5 * height);
+
+    // The tallest section decides the height of a row:
+    let mut layout_job = LayoutJob::default();
+    layout_job.append("short ", 0.0, super::TextFormat::default());
+    layout_job.append(
+        "tall",
+        0.0,
+        super::TextFormat {
+            line_height_factor: 2.0,
+            ..Default::default()
+        },
+    );
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let mixed = super::layout(&mut fonts, layout_job.into());
+    assert_eq!(mixed.rows.len(), 1);
+    assert_eq!(mixed.rows[0].height(), 2.0 * height);
+}
+
+#[test]
+fn test_paragraph_spacing() {
+    let layout = |paragraph_spacing: f32, max_width: f32| {
+        let mut layout_job = LayoutJob::single_section(
+            "The first paragraph is long enough to wrap.\nThe second.\nThe third.".into(),
+            super::TextFormat::default(),
+        );
+        layout_job.paragraph_spacing = paragraph_spacing;
+        layout_job.wrap.max_width = max_width;
+        let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+        super::layout(&mut fonts, layout_job.into())
+    };
+
+    let plain = layout(0.0, f32::INFINITY);
+    let spaced = layout(10.0, f32::INFINITY);
+    let height = plain.rows[0].height();
+    assert_eq!(spaced.rows.len(), 3);
+    assert_eq!(spaced.rows[1].rect.min.y, spaced.rows[0].rect.max.y + 10.0);
+    assert_eq!(spaced.rows[2].rect.min.y, spaced.rows[1].rect.max.y + 10.0);
+    assert_eq!(
+        spaced.rect.height(),
+        3.0 * height + 2.0 * 10.0,
+        "no spacing after the last paragraph"
+    );
+
+    // No spacing between the wrapped rows of a paragraph:
+    let wrapped = layout(10.0, 150.0);
+    assert!(wrapped.rows.len() > 3);
+    assert!(!wrapped.rows[0].ends_with_newline);
+    assert_eq!(wrapped.rows[1].rect.min.y, wrapped.rows[0].rect.max.y);
+    let last_row_of_first_paragraph = wrapped
+        .rows
+        .iter()
+        .position(|row| row.ends_with_newline)
+        .unwrap();
+    let rows = &wrapped.rows[last_row_of_first_paragraph..];
+    assert_eq!(rows[1].rect.min.y, rows[0].rect.max.y + 10.0);
+}
+
+#[test]
+fn test_extra_letter_spacing() {
+    let layout = |extra_letter_spacing: f32| {
+        let layout_job = LayoutJob::single_section(
+            "abc".into(),
+            super::TextFormat {
+                extra_letter_spacing,
+                ..Default::default()
+            },
+        );
+        let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+        super::layout(&mut fonts, layout_job.into())
+    };
+
+    let plain = layout(0.0);
+    let spaced = layout(3.0);
+    let tight = layout(-1.0);
+    for i in 1..3 {
+        let x = plain.rows[0].glyphs[i].pos.x;
+        assert_eq!(spaced.rows[0].glyphs[i].pos.x, x + 3.0 * i as f32);
+        assert_eq!(tight.rows[0].glyphs[i].pos.x, x - i as f32);
+    }
+}
+
+#[test]
+fn test_wrap_hyphenated() {
+    let hyphenator = super::Hyphenator::new("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n");
+    let mut layout_job =
+        LayoutJob::single_section("a hyphenation".into(), super::TextFormat::default());
+    layout_job.wrap.hyphenator = Some(Arc::new(hyphenator));
+    layout_job.wrap.max_width = 60.0;
+    assert_eq!(wrapped_rows(layout_job.clone()), ["a hyphen-", "ation"]);
+    layout_job.wrap.max_width = 40.0;
+    assert_eq!(wrapped_rows(layout_job), ["a hy-", "phen-", "ation"]);
+
+    // 'İ' lowercases to two characters, which must not move the hyphenation points:
+    let mut layout_job = LayoutJob::single_section("İİİİİİİİ".into(), super::TextFormat::default());
+    layout_job.wrap.hyphenator = Some(Arc::new(super::Hyphenator::new("1i")));
+    layout_job.wrap.max_width = 15.0;
+    let rows = wrapped_rows(layout_job);
+    assert!(rows.len() > 1, "{:?}", rows);
+    assert_eq!(rows.concat().replace('-', ""), "İİİİİİİİ");
+    let (last_row, rows) = rows.split_last().unwrap();
+    for row in rows {
+        assert!(row.ends_with('-'), "{:?}", row);
+        assert!(
+            row.chars().count() > 2,
+            "two letters and a hyphen: {:?}",
+            row
+        );
+    }
+    assert!(last_row.chars().count() >= 3, "{:?}", last_row);
+}
+
+/// The positions of the vertices of this color in the mesh of a row,
+/// i.e. the inside of lines and fills, but not their feathering.
+#[cfg(test)]
+fn row_vertices_of_color(row: &Row, color: Color32) -> Vec<Pos2> {
+    row.visuals
+        .mesh
+        .vertices
+        .iter()
+        .filter(|vertex| vertex.color == color)
+        .map(|vertex| vertex.pos)
+        .collect()
+}
+
+#[cfg(test)]
+fn underlined_galley(underline_style: super::UnderlineStyle) -> Arc<Galley> {
+    let layout_job = LayoutJob::single_section(
+        "underlined text".into(),
+        super::TextFormat {
+            underline: Stroke::new(1.0, Color32::RED),
+            underline_style,
+            ..Default::default()
+        },
+    );
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    super::layout(&mut fonts, layout_job.into()).into()
+}
+
+#[test]
+fn test_underline_styles() {
+    use super::UnderlineStyle;
+
+    let solid = underlined_galley(UnderlineStyle::Solid);
+    let row = &solid.rows[0];
+    let y = row.glyphs[0].logical_rect().bottom();
+    let (min_x, max_x) = (row.glyphs[0].pos.x, row.glyphs.last().unwrap().max_x());
+    let line = row_vertices_of_color(row, Color32::RED);
+    assert!(!line.is_empty());
+    for pos in &line {
+        assert!(
+            (pos.y - y).abs() <= 0.5,
+            "{:?} is not on the underline",
+            pos
+        );
+        assert!(min_x <= pos.x && pos.x <= max_x);
+    }
+
+    // Dots with a diameter of the stroke width, one every two diameters:
+    let dotted = underlined_galley(UnderlineStyle::Dotted);
+    let mut xs: Vec<f32> = row_vertices_of_color(&dotted.rows[0], Color32::RED)
+        .iter()
+        .map(|pos| {
+            assert!(
+                (pos.y - y).abs() <= 0.5,
+                "{:?} is not on the underline",
+                pos
+            );
+            pos.x
+        })
+        .collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let num_dots = 1 + xs.windows(2).filter(|pair| pair[1] - pair[0] > 0.5).count();
+    assert_eq!(num_dots, ((max_x - min_x - 1.0) / 2.0).floor() as usize + 1);
+    assert!(min_x <= xs[0] && *xs.last().unwrap() <= max_x);
+
+    // Waves with an amplitude of 1.5 around the underline:
+    let wavy = underlined_galley(UnderlineStyle::Wavy);
+    let wave = row_vertices_of_color(&wavy.rows[0], Color32::RED);
+    let bounds = Rect::from_points(&wave);
+    assert!(
+        bounds.top() < y - 1.0 && y + 1.0 < bounds.bottom(),
+        "{:?}",
+        bounds
+    );
+    assert!(
+        y - 2.0 <= bounds.top() && bounds.bottom() <= y + 2.0,
+        "{:?}",
+        bounds
+    );
+    assert!(min_x <= bounds.left() && bounds.right() <= max_x + 0.5);
+}
+
+#[test]
+fn test_overline_and_highlight() {
+    let layout_job = LayoutJob::single_section(
+        "highlighted".into(),
+        super::TextFormat {
+            overline: Stroke::new(1.0, Color32::BLUE),
+            highlight: Color32::YELLOW,
+            highlight_rounding: 4.0,
+            ..Default::default()
+        },
+    );
+    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let galley = super::layout(&mut fonts, layout_job.into());
+    let row = &galley.rows[0];
+    let glyph_rect = Rect::from_min_max(
+        row.glyphs[0].logical_rect().left_top(),
+        pos2(
+            row.glyphs.last().unwrap().max_x(),
+            row.glyphs[0].logical_rect().bottom(),
+        ),
+    );
+
+    let overline = row_vertices_of_color(row, Color32::BLUE);
+    assert!(!overline.is_empty());
+    for pos in &overline {
+        assert!((pos.y - glyph_rect.top()).abs() <= 0.5, "{:?}", pos);
+    }
+
+    // The highlight covers the text, with a margin of one point, with rounded corners:
+    let highlight = row_vertices_of_color(row, Color32::YELLOW);
+    let inside = glyph_rect.expand(0.5);
+    assert_eq!(Rect::from_points(&highlight), inside);
+    assert_eq!(highlight_corners(&highlight, inside), [false; 4]);
+}
+
+/// Which of the corners (`[left_top, right_top, left_bottom, right_bottom]`) of the `rect` are square,
+/// i.e. are in the `points`?
+#[cfg(test)]
+fn highlight_corners(points: &[Pos2], rect: Rect) -> [bool; 4] {
+    [
+        rect.left_top(),
+        rect.right_top(),
+        rect.left_bottom(),
+        rect.right_bottom(),
+    ]
+    .map(|corner| points.iter().any(|pos| pos.distance(corner) < 1e-3))
+}
+
+#[test]
+fn test_highlight_continues_across_rows() {
+    let layout = |text: &str| {
+        let mut layout_job = LayoutJob::single_section(
+            text.into(),
+            super::TextFormat {
+                highlight: Color32::YELLOW,
+                highlight_rounding: 4.0,
+                ..Default::default()
+            },
+        );
+        layout_job.wrap.max_width = 40.0;
+        let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+        super::layout(&mut fonts, layout_job.into())
+    };
+    let corners = |row: &Row| {
+        let highlight = row_vertices_of_color(row, Color32::YELLOW);
+        highlight_corners(&highlight, Rect::from_points(&highlight))
+    };
+
+    // Square where the highlight continues on the next row:
+    let wrapped = layout("aaaa bbbb cccc");
+    assert_eq!(wrapped.rows.len(), 3);
+    assert_eq!(corners(&wrapped.rows[0]), [false, true, false, true]);
+    assert_eq!(corners(&wrapped.rows[1]), [true; 4]);
+    assert_eq!(corners(&wrapped.rows[2]), [true, false, true, false]);
+
+    // Rounded where the paragraph ends:
+    let paragraphs = layout("aaaa\nbbbb");
+    assert_eq!(paragraphs.rows.len(), 2);
+    assert_eq!(corners(&paragraphs.rows[0]), [false; 4]);
+    assert_eq!(corners(&paragraphs.rows[1]), [false; 4]);
 }
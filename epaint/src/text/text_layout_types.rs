@@ -1,9 +1,9 @@
 #![allow(clippy::derive_hash_xor_eq)] // We need to impl Hash for f32, but we don't implement Eq, which is fine
 
-use std::ops::Range;
+use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
-use super::{cursor::*, font::UvRect};
+use super::{cursor::*, font::UvRect, Hyphenator};
 use crate::{Color32, FontId, Mesh, Stroke};
 use emath::*;
 
@@ -68,6 +68,16 @@ pub struct LayoutJob {
 
     /// Justify text so that word-wrapped rows fill the whole [`TextWrapping::max_width`]
     pub justify: bool,
+
+    /// Indent the first row of each paragraph by this much.
+    ///
+    /// In right-to-left paragraphs the indentation is on the right side.
+    /// Default: `0.0`.
+    pub paragraph_indent: f32,
+
+    /// Extra vertical space after each paragraph (i.e. after each `\n`).
+    /// Default: `0.0`.
+    pub paragraph_spacing: f32,
 }
 
 impl Default for LayoutJob {
@@ -81,6 +91,8 @@ impl Default for LayoutJob {
             break_on_newline: true,
             halign: Align::LEFT,
             justify: false,
+            paragraph_indent: 0.0,
+            paragraph_spacing: 0.0,
         }
     }
 }
@@ -174,6 +186,8 @@ impl std::hash::Hash for LayoutJob {
             break_on_newline,
             halign,
             justify,
+            paragraph_indent,
+            paragraph_spacing,
         } = self;
 
         text.hash(state);
@@ -183,6 +197,8 @@ impl std::hash::Hash for LayoutJob {
         break_on_newline.hash(state);
         halign.hash(state);
         justify.hash(state);
+        crate::f32_hash(state, *paragraph_indent);
+        crate::f32_hash(state, *paragraph_spacing);
     }
 }
 
@@ -214,20 +230,42 @@ impl std::hash::Hash for LayoutSection {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Clone, Debug, Hash, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TextFormat {
     pub font_id: FontId,
     /// Text color
     pub color: Color32,
     pub background: Color32,
+    /// Skew the glyphs of the font. For real italics, use [`FontId::style`] instead.
     pub italics: bool,
     pub underline: Stroke,
+    /// How [`Self::underline`] is drawn, e.g. wavy for spelling mistakes.
+    pub underline_style: UnderlineStyle,
+    /// A line above the text.
+    pub overline: Stroke,
     pub strikethrough: Stroke,
+    /// A background with rounded corners, which looks like one highlight across rows when wrapped.
+    ///
+    /// Painted on top of [`Self::background`].
+    pub highlight: Color32,
+    /// Rounding radius of the corners of [`Self::highlight`].
+    pub highlight_rounding: f32,
     /// If you use a small font and [`Align::TOP`] you
     /// can get the effect of raised text.
     pub valign: Align,
     // TODO(emilk): lowered
+    /// The height of the rows, as a factor of the row height of the font.
+    ///
+    /// The extra space (or lack of it) is split evenly above and below the text.
+    /// Default: `1.0`.
+    pub line_height_factor: f32,
+
+    /// Extra space added after each character (or cluster of characters, with the `shaping` feature).
+    ///
+    /// Can be negative to tighten the text.
+    /// Default: `0.0`.
+    pub extra_letter_spacing: f32,
 }
 
 impl Default for TextFormat {
@@ -239,12 +277,52 @@ impl Default for TextFormat {
             background: Color32::TRANSPARENT,
             italics: false,
             underline: Stroke::none(),
+            underline_style: UnderlineStyle::Solid,
+            overline: Stroke::none(),
             strikethrough: Stroke::none(),
+            highlight: Color32::TRANSPARENT,
+            highlight_rounding: 2.0,
             valign: Align::BOTTOM,
+            line_height_factor: 1.0,
+            extra_letter_spacing: 0.0,
         }
     }
 }
 
+impl std::hash::Hash for TextFormat {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let Self {
+            font_id,
+            color,
+            background,
+            italics,
+            underline,
+            underline_style,
+            overline,
+            strikethrough,
+            highlight,
+            highlight_rounding,
+            valign,
+            line_height_factor,
+            extra_letter_spacing,
+        } = self;
+        font_id.hash(state);
+        color.hash(state);
+        background.hash(state);
+        italics.hash(state);
+        underline.hash(state);
+        underline_style.hash(state);
+        overline.hash(state);
+        strikethrough.hash(state);
+        highlight.hash(state);
+        crate::f32_hash(state, *highlight_rounding);
+        valign.hash(state);
+        crate::f32_hash(state, *line_height_factor);
+        crate::f32_hash(state, *extra_letter_spacing);
+    }
+}
+
 impl TextFormat {
     #[inline]
     pub fn simple(font_id: FontId, color: Color32) -> Self {
@@ -256,6 +334,26 @@ impl TextFormat {
     }
 }
 
+/// How [`TextFormat::underline`] is drawn.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum UnderlineStyle {
+    /// A straight line.
+    Solid,
+
+    /// Round dots, as wide as the stroke.
+    Dotted,
+
+    /// A wave, like the ones under spelling mistakes.
+    Wavy,
+}
+
+impl Default for UnderlineStyle {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 #[derive(Clone, Debug, PartialEq)]
@@ -275,6 +373,13 @@ pub struct TextWrapping {
 
     /// Character to use to represent clipped text, `…` for example, which is the default.
     pub overflow_character: Option<char>,
+
+    /// Also break rows within words, where this says they can be hyphenated.
+    ///
+    /// A hyphen is then added at the end of the row (see [`Row::hyphen`]).
+    /// Soft hyphens (`\u{AD}`) in the text are always used, even without a [`Hyphenator`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hyphenator: Option<Arc<Hyphenator>>,
 }
 
 impl std::hash::Hash for TextWrapping {
@@ -285,11 +390,13 @@ impl std::hash::Hash for TextWrapping {
             max_rows,
             break_anywhere,
             overflow_character,
+            hyphenator,
         } = self;
         crate::f32_hash(state, *max_width);
         max_rows.hash(state);
         break_anywhere.hash(state);
         overflow_character.hash(state);
+        hyphenator.hash(state);
     }
 }
 
@@ -300,6 +407,7 @@ impl Default for TextWrapping {
             max_rows: 0,
             break_anywhere: false,
             overflow_character: Some('…'),
+            hyphenator: None,
         }
     }
 }
@@ -341,12 +449,22 @@ pub struct Galley {
 
     /// Total number of indices in all the row meshes.
     pub num_indices: usize,
+
+    /// The font atlas page the glyphs are on.
+    ///
+    /// Paint with its [`crate::AtlasPageRef::texture_id`].
+    /// The page is not cleared while the galley is alive.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub atlas_page: crate::AtlasPageRef,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Row {
-    /// One for each `char`.
+    /// One for each `char`, in logical order (the same order as the text).
+    ///
+    /// For right-to-left text this is not the same as the visual order,
+    /// so [`Glyph::pos`] is not always increasing.
     pub glyphs: Vec<Glyph>,
 
     /// Logical bounding rectangle based on font heights etc.
@@ -363,6 +481,17 @@ pub struct Row {
     /// so that text that ends with `\n` has an empty [`Row`] last.
     /// This also implies that the last [`Row`] in a [`Galley`] always has `ends_with_newline == false`.
     pub ends_with_newline: bool,
+
+    /// Is this [`Row`] part of a right-to-left paragraph (e.g. Hebrew or Arabic)?
+    ///
+    /// The start of a right-to-left row is on its right side.
+    pub rtl: bool,
+
+    /// If the row was broken within a word, this is the hyphen painted after it.
+    ///
+    /// It is not part of the text, so it is not in [`Self::glyphs`].
+    /// See [`TextWrapping::hyphenator`].
+    pub hyphen: Option<Glyph>,
 }
 
 /// The tessellated output of a row.
@@ -378,7 +507,8 @@ pub struct RowVisuals {
     pub mesh_bounds: Rect,
 
     /// The range of vertices in the mesh the contain glyphs.
-    /// Before comes backgrounds (if any), and after any underlines and strikethrough.
+    /// Before comes backgrounds (if any), and after any color glyphs (e.g. emoji),
+    /// underlines and strikethrough.
     pub glyph_vertex_range: Range<usize>,
 }
 
@@ -410,6 +540,11 @@ pub struct Glyph {
 
     /// Index into [`LayoutJob::sections`]. Decides color etc.
     pub section_index: u32,
+
+    /// The embedding level given by the Unicode Bidirectional Algorithm.
+    ///
+    /// Even levels are left-to-right, odd levels are right-to-left.
+    pub bidi_level: u8,
 }
 
 impl Glyph {
@@ -417,6 +552,32 @@ impl Glyph {
         self.pos.x + self.size.x
     }
 
+    /// Is this glyph part of right-to-left text?
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
+
+    /// Where a cursor before this character goes.
+    #[inline]
+    fn leading_x(&self) -> f32 {
+        if self.is_rtl() {
+            self.max_x()
+        } else {
+            self.pos.x
+        }
+    }
+
+    /// Where a cursor after this character goes.
+    #[inline]
+    fn trailing_x(&self) -> f32 {
+        if self.is_rtl() {
+            self.pos.x
+        } else {
+            self.max_x()
+        }
+    }
+
     /// Same y range for all characters with the same [`TextFormat`].
     #[inline]
     pub fn logical_rect(&self) -> Rect {
@@ -454,9 +615,28 @@ impl Row {
         self.rect.height()
     }
 
+    /// Does this row contain any right-to-left text?
+    ///
+    /// If not, the glyphs are in visual order.
+    #[inline]
+    pub fn is_bidi(&self) -> bool {
+        self.rtl || self.glyphs.iter().any(Glyph::is_rtl)
+    }
+
     /// Closest char at the desired x coordinate.
     /// Returns something in the range `[0, char_count_excluding_newline()]`.
     pub fn char_at(&self, desired_x: f32) -> usize {
+        if self.is_bidi() {
+            let mut best = (f32::INFINITY, 0);
+            for column in 0..=self.char_count_excluding_newline() {
+                let distance = (self.x_offset(column) - desired_x).abs();
+                if distance < best.0 {
+                    best = (distance, column);
+                }
+            }
+            return best.1;
+        }
+
         for (i, glyph) in self.glyphs.iter().enumerate() {
             if desired_x < glyph.logical_rect().center().x {
                 return i;
@@ -465,13 +645,66 @@ impl Row {
         self.char_count_excluding_newline()
     }
 
+    /// Where to put a cursor before the character at `column`.
+    ///
+    /// Between left-to-right and right-to-left text a cursor position could be in two places.
+    /// We then pick the edge of the character that goes in the direction of the paragraph.
     pub fn x_offset(&self, column: usize) -> f32 {
-        if let Some(glyph) = self.glyphs.get(column) {
-            glyph.pos.x
+        let next = self.glyphs.get(column);
+        let prev = column.checked_sub(1).and_then(|i| self.glyphs.get(i));
+        match (prev, next) {
+            (_, Some(next)) if next.is_rtl() == self.rtl => next.leading_x(),
+            (Some(prev), Some(_)) if prev.is_rtl() == self.rtl => prev.trailing_x(),
+            (_, Some(next)) => next.leading_x(),
+            (_, None) => self.end_x(),
+        }
+    }
+
+    /// Where to put a cursor after the last character of the row.
+    pub fn end_x(&self) -> f32 {
+        if self.rtl {
+            self.rect.left()
         } else {
             self.rect.right()
         }
     }
+
+    /// The x ranges covered by the characters in the range of columns, left to right.
+    ///
+    /// This is a single range, unless left-to-right and right-to-left text are mixed.
+    /// Useful for painting a selection.
+    pub fn x_ranges(&self, columns: Range<usize>) -> Vec<RangeInclusive<f32>> {
+        if !self.is_bidi() {
+            return vec![self.x_offset(columns.start)..=self.x_offset(columns.end)];
+        }
+
+        let end = columns.end.min(self.glyphs.len());
+        let start = columns.start.min(end);
+        let mut ranges: Vec<RangeInclusive<f32>> = self.glyphs[start..end]
+            .iter()
+            .map(|glyph| glyph.pos.x..=glyph.max_x())
+            .collect();
+        ranges.sort_by(|a, b| {
+            a.start()
+                .partial_cmp(b.start())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut merged: Vec<RangeInclusive<f32>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= *last.end() + 1.0 => {
+                    *last = *last.start()..=last.end().max(*range.end());
+                }
+                _ => merged.push(range),
+            }
+        }
+        if merged.is_empty() {
+            let x = self.x_offset(start);
+            merged.push(x..=x);
+        }
+        merged
+    }
 }
 
 impl Galley {
@@ -497,7 +730,7 @@ impl Galley {
     /// Zero-width rect past the last character.
     fn end_pos(&self) -> Rect {
         if let Some(row) = self.rows.last() {
-            let x = row.rect.right();
+            let x = row.end_x();
             Rect::from_min_max(pos2(x, row.min_y()), pos2(x, row.max_y()))
         } else {
             // Empty galley
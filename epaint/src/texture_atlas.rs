@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use emath::{remap_clamp, Rect};
 
-use crate::{textures::TextureFilter, FontImage, ImageDelta};
+use crate::{
+    mutex::Mutex, stats::AtlasStats, textures::TextureOptions, Color32, FontImage, ImageDelta,
+    TextureId,
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct Rectu {
@@ -52,13 +57,73 @@ pub struct PreparedDisc {
     pub uv: Rect,
 }
 
-/// Contains font data in an atlas, where each character occupied a small rectangle.
+/// On some low-precision GPUs (my old iPad) characters get muddled up
+/// if we don't add some empty pixels between the characters.
+/// On modern high-precision GPUs this is not needed.
+const PADDING: usize = 1;
+
+/// Which page of a [`TextureAtlas`] some text is on, and the texture to paint it with.
 ///
-/// More characters can be added, possibly expanding the texture.
-#[derive(Clone)]
-pub struct TextureAtlas {
+/// As long as any clone of it is alive, the page will not be cleared to make room for new glyphs.
+/// Each [`crate::Galley`] holds one.
+#[derive(Clone, Default)]
+pub struct AtlasPageRef(Arc<PageTexture>);
+
+#[derive(Default)]
+struct PageTexture {
+    page: usize,
+    texture_id: Mutex<TextureId>,
+}
+
+impl AtlasPageRef {
+    fn new(page: usize) -> Self {
+        Self(Arc::new(PageTexture {
+            page,
+            texture_id: Default::default(),
+        }))
+    }
+
+    /// The index of the page in the atlas.
+    pub fn page(&self) -> usize {
+        self.0.page
+    }
+
+    /// The texture showing the page.
+    pub fn texture_id(&self) -> TextureId {
+        *self.0.texture_id.lock()
+    }
+
+    /// Is there anything besides the atlas itself referring to this page?
+    fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+}
+
+impl PartialEq for AtlasPageRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.page() == other.page() && self.texture_id() == other.texture_id()
+    }
+}
+
+impl std::fmt::Debug for AtlasPageRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AtlasPageRef")
+            .field("page", &self.page())
+            .field("texture_id", &self.texture_id())
+            .finish()
+    }
+}
+
+/// One texture of a [`TextureAtlas`].
+struct AtlasPage {
     image: FontImage,
 
+    /// Handed out to the text using this page.
+    texture: AtlasPageRef,
+
+    /// Has [`TextureAtlas::set_texture_id`] been called for this page?
+    has_texture: bool,
+
     /// What part of the image that is dirty
     dirty: Rectu,
 
@@ -67,29 +132,106 @@ pub struct TextureAtlas {
 
     row_height: usize,
 
+    /// The top rows, with the white texel (and the discs), which are kept when the page is cleared.
+    reserved_height: usize,
+
+    /// The last frame any text used this page, for picking which page to clear.
+    last_used: u64,
+
+    /// Is there text using this page, so it must not be cleared?
+    in_use: bool,
+}
+
+impl Clone for AtlasPage {
+    /// The clone gets its own [`AtlasPageRef`], so that text using the original page doesn't keep the clone from being cleared.
+    fn clone(&self) -> Self {
+        let texture = AtlasPageRef::new(self.texture.page());
+        *texture.0.texture_id.lock() = self.texture.texture_id();
+        Self {
+            image: self.image.clone(),
+            texture,
+            ..*self
+        }
+    }
+}
+
+impl AtlasPage {
+    fn new(page: usize, mut image: FontImage) -> Self {
+        // Make the top left pixel fully white for `WHITE_UV`, i.e. painting something with solid color
+        // (or far inside, for a signed distance field):
+        image[(0, 0)] = 1.0;
+        Self {
+            image,
+            texture: AtlasPageRef::new(page),
+            // The first page is shown by `TextureId::default()`:
+            has_texture: page == 0,
+            dirty: Rectu::EVERYTHING,
+            cursor: (1 + PADDING, 0),
+            row_height: 1,
+            reserved_height: 1 + PADDING,
+            last_used: 0,
+            in_use: false,
+        }
+    }
+
+    fn used_height(&self) -> usize {
+        self.cursor.1 + self.row_height
+    }
+
+    /// Forget all glyphs on this page.
+    fn clear(&mut self) {
+        let start = self.reserved_height * self.image.width();
+        let end = self.image.pixels.len();
+        self.image.pixels[start.min(end)..].fill(0.0);
+        let end = self.image.colors.len();
+        self.image.colors[start.min(end)..].fill(Color32::TRANSPARENT);
+        self.cursor = (0, self.reserved_height);
+        self.row_height = 0;
+        self.dirty = Rectu::EVERYTHING;
+    }
+}
+
+/// Contains font data in an atlas, where each character occupied a small rectangle.
+///
+/// More characters can be added, possibly expanding the texture.
+///
+/// When the atlas is getting full, new glyphs are put on a new page
+/// (a separate texture, see [`Self::texture_id`]), up to [`Self::MAX_PAGES`] pages.
+/// After that, the page least recently used by any text is cleared and reused.
+/// A page is never cleared while some [`AtlasPageRef`] to it is alive.
+/// All pages have the same size.
+#[derive(Clone)]
+pub struct TextureAtlas {
+    /// Never empty.
+    pages: Vec<AtlasPage>,
+
+    /// New glyphs are put on this page.
+    current_page: usize,
+
+    /// Incremented by [`Self::begin_frame`].
+    frame: u64,
+
+    /// Set when [`Self::current_page`] changed, until [`Self::take_page_switch`].
+    page_switched: bool,
+
+    /// How many times a page has been cleared to make room for new glyphs.
+    num_evictions: usize,
+
     /// Set when someone requested more space than was available.
     overflowed: bool,
 
     /// pre-rasterized discs of radii `2^i`, where `i` is the index.
+    /// They are on the first page.
     discs: Vec<PrerasterizedDisc>,
 }
 
 impl TextureAtlas {
+    /// The most pages (textures) an atlas will use.
+    pub const MAX_PAGES: usize = 4;
+
     pub fn new(size: [usize; 2]) -> Self {
         assert!(size[0] >= 1024, "Tiny texture atlas");
-        let mut atlas = Self {
-            image: FontImage::new(size),
-            dirty: Rectu::EVERYTHING,
-            cursor: (0, 0),
-            row_height: 0,
-            overflowed: false,
-            discs: vec![], // will be filled in below
-        };
-
-        // Make the top left pixel fully white for `WHITE_UV`, i.e. painting something with solid color:
-        let (pos, image) = atlas.allocate((1, 1));
-        assert_eq!(pos, (0, 0));
-        image[pos] = 1.0;
+        let mut atlas = Self::with_first_page(FontImage::new(size));
 
         // Allocate a series of anti-aliased discs used to render small filled circles:
         // TODO(emilk): these circles can be packed A LOT better.
@@ -126,11 +268,93 @@ impl TextureAtlas {
             });
         }
 
+        let page = &mut atlas.pages[0];
+        page.reserved_height = page.used_height() + PADDING;
+
         atlas
     }
 
+    /// An atlas for glyphs as signed distance fields, see [`crate::text::GlyphRendering::Sdf`].
+    ///
+    /// It has no [`Self::prepared_discs`], since they are coverage and not distance.
+    pub fn new_sdf(size: [usize; 2]) -> Self {
+        assert!(size[0] >= 1024, "Tiny texture atlas");
+        let mut image = FontImage::new(size);
+        image.sdf = true;
+        Self::with_first_page(image)
+    }
+
+    fn with_first_page(image: FontImage) -> Self {
+        Self {
+            pages: vec![AtlasPage::new(0, image)],
+            current_page: 0,
+            frame: 0,
+            page_switched: false,
+            num_evictions: 0,
+            overflowed: false,
+            discs: vec![],
+        }
+    }
+
+    /// The texture of the given page.
+    ///
+    /// The first page is [`TextureId::default`].
+    /// The textures of the other pages are set with [`Self::set_texture_id`] when they are first used.
+    pub fn texture_id(&self, page: usize) -> TextureId {
+        self.pages[page].texture.texture_id()
+    }
+
+    /// Set which texture shows the given page.
+    ///
+    /// `egui` allocates these for you, see [`crate::Fonts::font_image_deltas`].
+    pub fn set_texture_id(&mut self, page: usize, texture_id: TextureId) {
+        let page = &mut self.pages[page];
+        *page.texture.0.texture_id.lock() = texture_id;
+        page.has_texture = true;
+    }
+
+    /// Has [`Self::set_texture_id`] been called for this page (or is it the first page)?
+    pub fn has_texture_id(&self, page: usize) -> bool {
+        self.pages[page].has_texture
+    }
+
+    /// The page shown by this texture, if any.
+    pub fn page_from_texture_id(&self, texture_id: TextureId) -> Option<usize> {
+        self.pages
+            .iter()
+            .position(|page| page.has_texture && page.texture.texture_id() == texture_id)
+    }
+
+    /// The size of each page.
     pub fn size(&self) -> [usize; 2] {
-        self.image.size
+        self.pages[0].image.size
+    }
+
+    /// The image of all glyphs allocated so far on the first page.
+    pub fn image(&self) -> &FontImage {
+        &self.pages[0].image
+    }
+
+    /// How many pages are in use.
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The image of the given page.
+    pub fn page_image(&self, page: usize) -> &FontImage {
+        &self.pages[page].image
+    }
+
+    /// The page new glyphs are put on.
+    pub fn current_page(&self) -> usize {
+        self.current_page
+    }
+
+    /// The page new glyphs are put on.
+    ///
+    /// The page will not be cleared as long as the returned value is alive.
+    pub fn current_page_ref(&self) -> AtlasPageRef {
+        self.pages[self.current_page].texture.clone()
     }
 
     /// Returns the locations and sizes of pre-rasterized discs (filled circles) in this atlas.
@@ -160,91 +384,279 @@ impl TextureAtlas {
 
     fn max_height(&self) -> usize {
         // the initial width is likely the max texture side size
-        self.image.width()
+        self.size()[0]
     }
 
-    /// When this get high, it might be time to clear and start over!
+    /// How full the current page is.
+    ///
+    /// When this get high, it might be time to switch page, or clear and start over!
     pub fn fill_ratio(&self) -> f32 {
         if self.overflowed {
             1.0
         } else {
-            (self.cursor.1 + self.row_height) as f32 / self.max_height() as f32
+            self.pages[self.current_page].used_height() as f32 / self.max_height() as f32
+        }
+    }
+
+    /// Did someone request more space than was available on any page?
+    ///
+    /// Then some glyphs have been overwritten, and the atlas needs to be recreated.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// How full the atlas is, for [`crate::stats::PaintStats`].
+    pub fn stats(&self) -> AtlasStats {
+        AtlasStats {
+            num_pages: self.pages.len(),
+            max_pages: Self::MAX_PAGES,
+            fill_ratio: self.fill_ratio(),
+            num_evictions: self.num_evictions,
+        }
+    }
+
+    /// Call at the start of each frame, after dropping the text that is no longer needed.
+    ///
+    /// The pages that some [`AtlasPageRef`] still refers to will not be cleared this frame.
+    pub(crate) fn begin_frame(&mut self) {
+        self.frame += 1;
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            page.in_use = index == self.current_page || page.texture.is_shared();
+            if page.in_use {
+                page.last_used = self.frame;
+            }
+        }
+    }
+
+    /// Put new glyphs on another page: the least recently used one that no text is using (after clearing it),
+    /// or a new page.
+    ///
+    /// Returns `false` if all pages are in use.
+    pub(crate) fn switch_page(&mut self) -> bool {
+        let unused_page = (0..self.pages.len())
+            .filter(|&page| !self.pages[page].in_use)
+            .min_by_key(|&page| self.pages[page].last_used);
+
+        if let Some(page) = unused_page {
+            self.pages[page].clear();
+            self.num_evictions += 1;
+            self.current_page = page;
+        } else if self.pages.len() < Self::MAX_PAGES {
+            let mut image = FontImage::new(self.size());
+            image.sdf = self.pages[0].image.sdf;
+            self.pages.push(AtlasPage::new(self.pages.len(), image));
+            self.current_page = self.pages.len() - 1;
+        } else {
+            return false;
+        }
+
+        let page = &mut self.pages[self.current_page];
+        page.in_use = true;
+        page.last_used = self.frame;
+        self.page_switched = true;
+        true
+    }
+
+    /// Has the current page changed since the last call?
+    ///
+    /// If so, all glyphs must be allocated again, so that text only uses one page.
+    pub(crate) fn take_page_switch(&mut self) -> bool {
+        std::mem::take(&mut self.page_switched)
+    }
+
+    /// Call to get the change to the pages since last call,
+    /// together with the texture of each page.
+    ///
+    /// `texture_id_of_page` is called for each new page, to pick the texture that shows it
+    /// (see [`Self::set_texture_id`]).
+    pub fn take_deltas(
+        &mut self,
+        mut texture_id_of_page: impl FnMut(usize) -> TextureId,
+    ) -> Vec<(TextureId, ImageDelta)> {
+        for index in 0..self.pages.len() {
+            if !self.pages[index].has_texture {
+                self.set_texture_id(index, texture_id_of_page(index));
+            }
         }
+
+        (0..self.pages.len())
+            .filter_map(|page| {
+                let delta = self.take_page_delta(page)?;
+                Some((self.texture_id(page), delta))
+            })
+            .collect()
     }
 
-    /// Call to get the change to the image since last call.
+    /// Call to get the change to the first page since last call.
+    #[deprecated = "Use take_deltas instead, which includes all pages"]
     pub fn take_delta(&mut self) -> Option<ImageDelta> {
-        let dirty = std::mem::replace(&mut self.dirty, Rectu::NOTHING);
+        self.take_page_delta(0)
+    }
+
+    /// The change to the given page since last call.
+    pub(crate) fn take_page_delta(&mut self, page: usize) -> Option<ImageDelta> {
+        let page = &mut self.pages[page];
+        let dirty = std::mem::replace(&mut page.dirty, Rectu::NOTHING);
         if dirty == Rectu::NOTHING {
             None
         } else if dirty == Rectu::EVERYTHING {
-            Some(ImageDelta::full(self.image.clone(), TextureFilter::Linear))
+            Some(ImageDelta::full(page.image.clone(), TextureOptions::LINEAR))
         } else {
             let pos = [dirty.min_x, dirty.min_y];
             let size = [dirty.max_x - dirty.min_x, dirty.max_y - dirty.min_y];
-            let region = self.image.region(pos, size);
-            Some(ImageDelta::partial(pos, region, TextureFilter::Linear))
+            let region = page.image.region(pos, size);
+            Some(ImageDelta::partial(pos, region, TextureOptions::LINEAR))
         }
     }
 
-    /// Returns the coordinates of where the rect ended up,
+    /// Returns the coordinates of where the rect ended up on the current page,
     /// and invalidates the region.
+    ///
+    /// If the current page is full, this may switch page.
     pub fn allocate(&mut self, (w, h): (usize, usize)) -> ((usize, usize), &mut FontImage) {
-        /// On some low-precision GPUs (my old iPad) characters get muddled up
-        /// if we don't add some empty pixels between the characters.
-        /// On modern high-precision GPUs this is not needed.
-        const PADDING: usize = 1;
-
         assert!(
-            w <= self.image.width(),
+            w <= self.size()[0],
             "Tried to allocate a {} wide glyph in a {} wide texture atlas",
             w,
-            self.image.width()
+            self.size()[0]
         );
-        if self.cursor.0 + w > self.image.width() {
+
+        let max_height = self.max_height();
+        let page = &mut self.pages[self.current_page];
+        let mut cursor = page.cursor;
+        let mut row_height = page.row_height;
+        if cursor.0 + w > page.image.width() {
             // New row:
-            self.cursor.0 = 0;
-            self.cursor.1 += self.row_height + PADDING;
-            self.row_height = 0;
+            cursor.0 = 0;
+            cursor.1 += row_height + PADDING;
+            row_height = 0;
         }
+        row_height = row_height.max(h);
 
-        self.row_height = self.row_height.max(h);
+        let required_height = cursor.1 + row_height;
 
-        let required_height = self.cursor.1 + self.row_height;
+        if required_height > max_height {
+            if !self.overflowed && self.switch_page() {
+                return self.allocate((w, h));
+            }
 
-        if required_height > self.max_height() {
             // This is a bad place to be - we need to start reusing space :/
 
             #[cfg(feature = "tracing")]
-            tracing::wan!("epaint texture atlas overflowed!");
+            tracing::warn!("epaint texture atlas overflowed!");
 
-            self.cursor = (0, self.image.height() / 3); // Restart a bit down - the top of the atlas has too many important things in it
+            let page = &mut self.pages[self.current_page];
+            cursor = (0, page.image.height() / 3); // Restart a bit down - the top of the atlas has too many important things in it
+            row_height = h;
             self.overflowed = true; // this will signal the user that we need to recreate the texture atlas next frame.
-        } else if resize_to_min_height(&mut self.image, required_height) {
-            self.dirty = Rectu::EVERYTHING;
+        } else if self.pages[self.current_page].image.height() <= required_height {
+            // All pages have the same size:
+            for page in &mut self.pages {
+                resize_to_min_height(&mut page.image, required_height);
+                page.dirty = Rectu::EVERYTHING;
+            }
         }
 
-        let pos = self.cursor;
-        self.cursor.0 += w + PADDING;
+        let page = &mut self.pages[self.current_page];
+        let pos = cursor;
+        page.cursor = (cursor.0 + w + PADDING, cursor.1);
+        page.row_height = row_height;
 
-        self.dirty.min_x = self.dirty.min_x.min(pos.0);
-        self.dirty.min_y = self.dirty.min_y.min(pos.1);
-        self.dirty.max_x = self.dirty.max_x.max(pos.0 + w);
-        self.dirty.max_y = self.dirty.max_y.max(pos.1 + h);
+        page.dirty.min_x = page.dirty.min_x.min(pos.0);
+        page.dirty.min_y = page.dirty.min_y.min(pos.1);
+        page.dirty.max_x = page.dirty.max_x.max(pos.0 + w);
+        page.dirty.max_y = page.dirty.max_y.max(pos.1 + h);
 
-        (pos, &mut self.image)
+        (pos, &mut page.image)
     }
 }
 
-fn resize_to_min_height(image: &mut FontImage, required_height: usize) -> bool {
+fn resize_to_min_height(image: &mut FontImage, required_height: usize) {
     while required_height >= image.height() {
         image.size[1] *= 2; // double the height
     }
 
     if image.width() * image.height() > image.pixels.len() {
         image.pixels.resize(image.width() * image.height(), 0.0);
-        true
-    } else {
-        false
+        if !image.colors.is_empty() {
+            image
+                .colors
+                .resize(image.width() * image.height(), Color32::TRANSPARENT);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evict_least_recently_used_page() {
+        let mut atlas = TextureAtlas::new([1024, 64]);
+        let mut pinned = vec![Some(atlas.current_page_ref())];
+        for page in 1..TextureAtlas::MAX_PAGES {
+            atlas.begin_frame();
+            assert!(atlas.switch_page());
+            assert_eq!(atlas.current_page(), page);
+            pinned.push(Some(atlas.current_page_ref()));
+        }
+        assert_eq!(atlas.num_pages(), TextureAtlas::MAX_PAGES);
+
+        // Every page has text on it:
+        atlas.begin_frame();
+        assert!(!atlas.switch_page());
+        assert_eq!(atlas.current_page(), TextureAtlas::MAX_PAGES - 1);
+
+        pinned[2] = None;
+        atlas.begin_frame();
+        pinned[1] = None;
+        atlas.begin_frame();
+
+        // Page 2 was used longer ago than page 1:
+        assert!(atlas.switch_page());
+        assert_eq!(atlas.current_page(), 2);
+        assert_eq!(atlas.stats().num_evictions, 1);
+
+        atlas.begin_frame();
+        assert!(atlas.switch_page());
+        assert_eq!(atlas.current_page(), 1);
+        assert_eq!(atlas.stats().num_evictions, 2);
+
+        // The current page is not cleared, and page 0 and 3 are still pinned:
+        atlas.begin_frame();
+        assert!(atlas.switch_page());
+        assert_eq!(atlas.current_page(), 2);
+        assert_eq!(atlas.num_pages(), TextureAtlas::MAX_PAGES);
+    }
+
+    #[test]
+    fn page_textures() {
+        let mut atlas = TextureAtlas::new([1024, 64]);
+        let first_page = atlas.current_page_ref();
+        atlas.begin_frame();
+        assert!(atlas.switch_page());
+        let second_page = atlas.current_page_ref();
+        assert_eq!(second_page.page(), 1);
+
+        let mut new_pages = vec![];
+        let deltas = atlas.take_deltas(|page| {
+            new_pages.push(page);
+            TextureId::Managed(10 + page as u64)
+        });
+        assert_eq!(new_pages, vec![1]);
+        let texture_ids: Vec<TextureId> = deltas.iter().map(|(id, _)| *id).collect();
+        assert_eq!(
+            texture_ids,
+            vec![TextureId::default(), TextureId::Managed(11)]
+        );
+
+        assert_eq!(first_page.texture_id(), TextureId::default());
+        assert_eq!(second_page.texture_id(), TextureId::Managed(11));
+        assert_eq!(atlas.page_from_texture_id(TextureId::Managed(11)), Some(1));
+        assert_eq!(atlas.page_from_texture_id(TextureId::User(11)), None);
+
+        // Nothing changed since last time, and both pages have a texture:
+        let deltas = atlas.take_deltas(|_| panic!("Asked for the texture of a page twice"));
+        assert!(deltas.is_empty());
     }
 }
@@ -27,7 +27,7 @@ impl TextureManager {
     /// MUST have a white pixel at (0,0) ([`crate::WHITE_UV`]).
     ///
     /// The texture is given a retain-count of `1`, requiring one call to [`Self::free`] to free it.
-    pub fn alloc(&mut self, name: String, image: ImageData, filter: TextureFilter) -> TextureId {
+    pub fn alloc(&mut self, name: String, image: ImageData, options: TextureOptions) -> TextureId {
         let id = TextureId::Managed(self.next_id);
         self.next_id += 1;
 
@@ -36,10 +36,10 @@ impl TextureManager {
             size: image.size(),
             bytes_per_pixel: image.bytes_per_pixel(),
             retain_count: 1,
-            filter,
+            options,
         });
 
-        self.delta.set.push((id, ImageDelta::full(image, filter)));
+        self.delta.set.push((id, ImageDelta::full(image, options)));
         id
     }
 
@@ -57,6 +57,7 @@ impl TextureManager {
                 // whole update
                 meta.size = delta.image.size();
                 meta.bytes_per_pixel = delta.image.bytes_per_pixel();
+                meta.options = delta.options;
                 // since we update the whole image, we can discard all old enqueued deltas
                 self.delta.set.retain(|(x, _)| x != &id);
             }
@@ -132,12 +133,87 @@ pub struct TextureMeta {
     /// Free when this reaches zero.
     pub retain_count: usize,
 
-    /// The texture filtering mode to use when rendering
-    pub filter: TextureFilter,
+    /// How the texture is sampled when rendering.
+    pub options: TextureOptions,
+}
+
+/// How the texture is sampled: how the texels are filtered, and what happens outside of it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TextureOptions {
+    /// How to filter when magnifying (when texels are larger than pixels).
+    pub magnification: TextureFilter,
+
+    /// How to filter when minifying (when texels are smaller than pixels).
+    pub minification: TextureFilter,
+
+    /// What to do with texture coordinates outside of `[0, 1]`.
+    pub wrap_mode: TextureWrapMode,
+
+    /// If `Some`, the backend creates mipmaps (smaller versions of the texture),
+    /// and this is how it filters between them when minifying.
+    ///
+    /// This makes zoomed-out images much less aliased, but uses a third more memory.
+    pub mipmap_mode: Option<TextureFilter>,
+}
+
+impl TextureOptions {
+    /// Linear magnification and minification, clamping to the edges, and no mipmaps.
+    pub const LINEAR: Self = Self {
+        magnification: TextureFilter::Linear,
+        minification: TextureFilter::Linear,
+        wrap_mode: TextureWrapMode::ClampToEdge,
+        mipmap_mode: None,
+    };
+
+    /// Nearest magnification and minification, clamping to the edges, and no mipmaps.
+    pub const NEAREST: Self = Self {
+        magnification: TextureFilter::Nearest,
+        minification: TextureFilter::Nearest,
+        wrap_mode: TextureWrapMode::ClampToEdge,
+        mipmap_mode: None,
+    };
+
+    /// What to do with texture coordinates outside of `[0, 1]`.
+    ///
+    /// For instance, use [`TextureWrapMode::Repeat`] to tile an image with the uv-coordinates
+    /// of an [`crate::Mesh`] (or `egui::Image::uv`).
+    #[inline]
+    pub fn with_wrap_mode(mut self, wrap_mode: TextureWrapMode) -> Self {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    /// Create mipmaps, and filter between them like this.
+    ///
+    /// `None` for no mipmaps.
+    #[inline]
+    pub fn with_mipmap_mode(mut self, mipmap_mode: Option<TextureFilter>) -> Self {
+        self.mipmap_mode = mipmap_mode;
+        self
+    }
+}
+
+impl Default for TextureOptions {
+    /// [`Self::LINEAR`].
+    fn default() -> Self {
+        Self::LINEAR
+    }
+}
+
+impl From<TextureFilter> for TextureOptions {
+    /// Filter like this both when magnifying and minifying.
+    fn from(filter: TextureFilter) -> Self {
+        Self {
+            magnification: filter,
+            minification: filter,
+            ..Self::LINEAR
+        }
+    }
 }
 
 /// How the texture texels are filtered.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum TextureFilter {
     /// Show the nearest pixel value.
@@ -158,6 +234,28 @@ impl Default for TextureFilter {
     }
 }
 
+/// What to do with texture coordinates outside of `[0, 1]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextureWrapMode {
+    /// Use the texel at the nearest edge.
+    ///
+    /// This is the default.
+    ClampToEdge,
+
+    /// Tile the texture.
+    Repeat,
+
+    /// Tile the texture, mirroring every other tile, so the edges match up.
+    MirroredRepeat,
+}
+
+impl Default for TextureWrapMode {
+    fn default() -> Self {
+        Self::ClampToEdge
+    }
+}
+
 impl TextureMeta {
     /// Size in bytes.
     /// width x height x [`Self::bytes_per_pixel`].
@@ -230,3 +328,78 @@ impl std::fmt::Debug for TexturesDelta {
         debug_struct.finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color32, ColorImage};
+
+    #[test]
+    fn texture_options() {
+        assert_eq!(TextureOptions::default(), TextureOptions::LINEAR);
+        assert_eq!(
+            TextureOptions::LINEAR.wrap_mode,
+            TextureWrapMode::ClampToEdge
+        );
+        assert_eq!(TextureOptions::NEAREST.mipmap_mode, None);
+
+        let options = TextureOptions::NEAREST
+            .with_wrap_mode(TextureWrapMode::Repeat)
+            .with_mipmap_mode(Some(TextureFilter::Linear));
+        assert_eq!(options.magnification, TextureFilter::Nearest);
+        assert_eq!(options.minification, TextureFilter::Nearest);
+        assert_eq!(options.wrap_mode, TextureWrapMode::Repeat);
+        assert_eq!(options.mipmap_mode, Some(TextureFilter::Linear));
+    }
+
+    #[test]
+    fn texture_options_from_filter() {
+        assert_eq!(
+            TextureOptions::from(TextureFilter::Nearest),
+            TextureOptions::NEAREST
+        );
+        assert_eq!(
+            TextureOptions::from(TextureFilter::Linear),
+            TextureOptions::LINEAR
+        );
+        assert_eq!(
+            ImageDelta::full(
+                ColorImage::new([1, 1], Color32::WHITE),
+                TextureFilter::Nearest
+            )
+            .options,
+            TextureOptions::NEAREST
+        );
+    }
+
+    #[test]
+    fn texture_manager_keeps_options() {
+        let mut manager = TextureManager::default();
+        let image = ColorImage::new([2, 2], Color32::WHITE);
+        let repeat = TextureOptions::LINEAR.with_wrap_mode(TextureWrapMode::Repeat);
+        let id = manager.alloc("test".to_owned(), image.clone().into(), repeat);
+        assert_eq!(manager.meta(id).unwrap().options, repeat);
+
+        let patch = ColorImage::new([1, 1], Color32::RED);
+        manager.set(
+            id,
+            ImageDelta::partial([1, 1], patch, TextureOptions::NEAREST),
+        );
+        assert_eq!(
+            manager.meta(id).unwrap().options,
+            repeat,
+            "partial updates don't change the options"
+        );
+
+        manager.set(id, ImageDelta::full(image, TextureOptions::NEAREST));
+        assert_eq!(manager.meta(id).unwrap().options, TextureOptions::NEAREST);
+
+        let delta = manager.take_delta();
+        assert_eq!(
+            delta.set.len(),
+            1,
+            "a whole update replaces the earlier ones"
+        );
+        assert_eq!(delta.set[0].1.options, TextureOptions::NEAREST);
+    }
+}